@@ -0,0 +1,251 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+#[cfg(test)]
+use std::{cell::RefCell, rc::Rc};
+
+/// Frecuencia de muestreo usada cuando no hay un dispositivo de audio
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+/// Trait que define un destino para el audio mezclado.
+///
+/// El mezclador escribe en cada tick muestras estéreo intercaladas (izquierda, derecha) en el rango -1.0 a 1.0.
+pub trait AudioBackend {
+    /// Obtiene la frecuencia de muestreo del destino.
+    ///
+    /// # Retorna
+    ///
+    /// Las muestras por segundo que espera el destino.
+    fn sample_rate(&self) -> u32;
+
+    /// Calcula cuántos fotogramas debe mezclar el mezclador en este tick.
+    ///
+    /// # Argumentos
+    ///
+    /// * `frames` - Fotogramas que corresponden a un tick de juego.
+    ///
+    /// # Retorna
+    ///
+    /// Los fotogramas a mezclar, por defecto los de un tick.
+    fn frames_needed(&self, frames: usize) -> usize {
+        frames
+    }
+
+    /// Envía las muestras mezcladas al destino.
+    ///
+    /// # Argumentos
+    ///
+    /// * `samples` - Muestras estéreo intercaladas.
+    fn write(&mut self, samples: &[f32]);
+}
+
+/// Destino de audio que descarta las muestras, usado cuando no hay dispositivo de audio.
+pub struct NullBackend {
+    /// Frecuencia de muestreo simulada
+    sample_rate: u32,
+}
+
+impl NullBackend {
+    /// Crea un destino silencioso.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sample_rate` - Frecuencia de muestreo simulada.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `NullBackend`.
+    pub fn new(sample_rate: u32) -> Self {
+        Self { sample_rate }
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn write(&mut self, _samples: &[f32]) {}
+}
+
+/// Destino de audio que guarda las muestras escritas para comprobarlas en las pruebas.
+///
+/// Las copias comparten el mismo búfer, así se puede seguir leyendo después de entregar el destino al mezclador.
+#[cfg(test)]
+#[derive(Clone)]
+pub struct RecordingBackend {
+    /// Frecuencia de muestreo simulada
+    sample_rate: u32,
+    /// Muestras escritas hasta el momento
+    samples: Rc<RefCell<Vec<f32>>>,
+}
+
+#[cfg(test)]
+impl RecordingBackend {
+    /// Crea un destino que graba las muestras.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sample_rate` - Frecuencia de muestreo simulada.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `RecordingBackend`.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            samples: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Obtiene las muestras grabadas.
+    ///
+    /// # Retorna
+    ///
+    /// Una copia de las muestras estéreo intercaladas escritas hasta el momento.
+    pub fn get_samples(&self) -> Vec<f32> {
+        self.samples.borrow().clone()
+    }
+
+    /// Borra las muestras grabadas.
+    pub fn clear(&self) {
+        self.samples.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+impl AudioBackend for RecordingBackend {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn write(&mut self, samples: &[f32]) {
+        self.samples.borrow_mut().extend_from_slice(samples);
+    }
+}
+
+/// Destino de audio que reproduce las muestras en el dispositivo de salida por defecto.
+///
+/// El juego mezcla en su propio hilo y deja las muestras en una cola que el hilo de audio va consumiendo.
+pub struct DeviceBackend {
+    /// Flujo de salida abierto, se cierra al soltarlo
+    _stream: cpal::Stream,
+    /// Muestras pendientes de reproducir
+    queue: Arc<Mutex<VecDeque<f32>>>,
+    /// Frecuencia de muestreo del dispositivo
+    sample_rate: u32,
+}
+
+impl DeviceBackend {
+    /// Segundos de audio que se intentan mantener en cola
+    const LATENCY: f64 = 0.05;
+
+    /// Abre el dispositivo de salida por defecto.
+    ///
+    /// # Retorna
+    ///
+    /// El destino de audio, o `None` si no hay dispositivo o su formato no está soportado.
+    pub fn new() -> Option<Self> {
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+        let device = cpal::default_host().default_output_device()?;
+        let config = device.default_output_config().ok()?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                let queue = queue.clone();
+                device.build_output_stream(
+                    &config.into(),
+                    move |data: &mut [f32], _| fill(data, channels, &queue, |sample| sample),
+                    |err| eprintln!("Error en el flujo de audio: {}", err),
+                    None,
+                )
+            }
+            cpal::SampleFormat::I16 => {
+                let queue = queue.clone();
+                device.build_output_stream(
+                    &config.into(),
+                    move |data: &mut [i16], _| {
+                        fill(data, channels, &queue, |sample| {
+                            (sample * i16::MAX as f32) as i16
+                        })
+                    },
+                    |err| eprintln!("Error en el flujo de audio: {}", err),
+                    None,
+                )
+            }
+            _ => return None,
+        }
+        .ok()?;
+        stream.play().ok()?;
+
+        Some(Self {
+            _stream: stream,
+            queue,
+            sample_rate,
+        })
+    }
+}
+
+impl AudioBackend for DeviceBackend {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn frames_needed(&self, _frames: usize) -> usize {
+        // Se rellena la cola hasta la latencia objetivo: si el juego va lento se mezclan más fotogramas para no cortar
+        // el sonido, y si va rápido no se mezcla nada mientras la cola esté llena
+        let target = (self.sample_rate as f64 * Self::LATENCY) as usize;
+        let queued = self.queue.lock().unwrap().len() / 2;
+        target.saturating_sub(queued)
+    }
+
+    fn write(&mut self, samples: &[f32]) {
+        self.queue.lock().unwrap().extend(samples);
+    }
+}
+
+/// Rellena el búfer del dispositivo con las muestras en cola, o silencio si no quedan.
+///
+/// # Argumentos
+///
+/// * `data` - Búfer del dispositivo.
+/// * `channels` - Canales del dispositivo.
+/// * `queue` - Cola de muestras estéreo.
+/// * `convert` - Conversión de cada muestra al formato del dispositivo.
+fn fill<T: Copy>(
+    data: &mut [T],
+    channels: usize,
+    queue: &Mutex<VecDeque<f32>>,
+    convert: impl Fn(f32) -> T,
+) {
+    let mut queue = queue.lock().unwrap();
+    for frame in data.chunks_mut(channels) {
+        let left = queue.pop_front().unwrap_or(0.0);
+        let right = queue.pop_front().unwrap_or(0.0);
+        for (channel, sample) in frame.iter_mut().enumerate() {
+            *sample = convert(match channel {
+                0 => left,
+                1 => right,
+                _ => 0.0,
+            });
+        }
+    }
+}
+
+/// Crea el destino de audio del juego.
+///
+/// # Retorna
+///
+/// El dispositivo de salida por defecto si se puede abrir, o un destino silencioso en caso contrario.
+pub fn default_backend() -> Box<dyn AudioBackend> {
+    if let Some(device) = DeviceBackend::new() {
+        return Box::new(device);
+    }
+    Box::new(NullBackend::new(DEFAULT_SAMPLE_RATE))
+}
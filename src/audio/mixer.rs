@@ -0,0 +1,570 @@
+use std::rc::Rc;
+
+use crate::player::character::snd::decoder::Sound;
+
+use super::backend::AudioBackend;
+
+/// Ticks de juego por segundo por defecto, el mezclador genera el audio de un tick en cada actualización
+pub const TICKS_PER_SECOND: u32 = 60;
+/// Número de voces que se pueden reproducir a la vez
+pub const MAX_VOICES: usize = 16;
+/// Segundos entre cada repetición del eco de KO
+const ECHO_DELAY: f64 = 0.12;
+/// Volumen de cada repetición del eco de KO
+const ECHO_GAINS: [f32; 3] = [0.5, 0.25, 0.125];
+/// Valor máximo de una muestra de 16 bits
+const SAMPLE_SCALE: f32 = 32768.0;
+
+/// Posición de un sonido en el panorama estéreo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pan {
+    /// Desplazamiento fijo respecto al centro de la pantalla, en píxeles
+    Absolute(f64),
+    /// Posición en la X dentro del escenario, se compara con la del oyente
+    Position(f64),
+}
+
+/// Parámetros con los que se reproduce un sonido.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundRequest {
+    /// Jugador que reproduce el sonido, cada uno tiene sus propios canales
+    pub owner: usize,
+    /// Canal del sonido, -1 usa cualquier voz libre
+    pub channel: i32,
+    /// Volumen (1.0 volumen original)
+    pub volume: f32,
+    /// Posición en el panorama estéreo
+    pub pan: Pan,
+    /// Un sonido de baja prioridad no interrumpe el de su canal y puede ser sustituido por otros
+    pub low_priority: bool,
+    /// Multiplicador de la frecuencia de reproducción
+    pub freq_mul: f32,
+    /// Indica si el sonido se repite hasta que se detenga
+    pub looping: bool,
+    /// Indica si se aplica el eco de KO
+    pub echo: bool,
+}
+
+/// Sonido que se está reproduciendo.
+struct Voice {
+    /// Sonido decodificado
+    sound: Rc<Sound>,
+    /// Jugador que reproduce el sonido
+    owner: usize,
+    /// Canal del sonido
+    channel: i32,
+    /// Prioridad baja
+    low_priority: bool,
+    /// Repetición del sonido
+    looping: bool,
+    /// Retardo del eco en fotogramas del sonido, 0 si no hay eco
+    echo_delay: f64,
+    /// Volumen de los canales izquierdo y derecho
+    gains: (f32, f32),
+    /// Fotogramas del sonido que avanza cada fotograma de salida
+    step: f64,
+    /// Posición actual en fotogramas del sonido
+    position: f64,
+    /// Orden en el que empezó a sonar, las voces más antiguas se sustituyen antes
+    age: u64,
+}
+
+/// Mezclador por software de los sonidos de la pelea.
+///
+/// Cada jugador tiene sus canales, un sonido nuevo en un canal ocupado sustituye al anterior. Cuando todas las voces
+/// están ocupadas se sustituye la voz de baja prioridad más antigua, o la más antigua si el sonido nuevo es importante.
+pub struct Mixer {
+    /// Destino del audio mezclado
+    backend: Box<dyn AudioBackend>,
+    /// Voces activas
+    voices: Vec<Voice>,
+    /// Número máximo de voces
+    max_voices: usize,
+    /// Centro de la pantalla en la X del escenario
+    listener_x: f64,
+    /// Distancia desde el centro a la que un sonido queda completamente a un lado
+    pan_width: f64,
+    /// Contador de sonidos reproducidos
+    played: u64,
+    /// Búfer de mezcla reutilizado entre ticks
+    buffer: Vec<f32>,
+    /// Ticks de juego por segundo, determinan los fotogramas que se mezclan en cada tick
+    ticks_per_second: u32,
+}
+
+impl Voice {
+    /// Obtiene la muestra del sonido en una posición, interpolando entre fotogramas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `position` - Posición en fotogramas del sonido.
+    ///
+    /// # Retorna
+    ///
+    /// Las muestras izquierda y derecha, silencio si la posición está fuera del sonido.
+    fn sample_at(&self, position: f64) -> (f32, f32) {
+        let frames = self.sound.frames();
+        if frames == 0 || position < 0.0 {
+            return (0.0, 0.0);
+        }
+        let index = position.floor() as usize;
+        let fraction = (position - index as f64) as f32;
+        let frame = |index: usize| -> (f32, f32) {
+            let index = if self.looping { index % frames } else { index };
+            if index >= frames {
+                return (0.0, 0.0);
+            }
+            let channels = self.sound.channels as usize;
+            let left = self.sound.samples[index * channels] as f32 / SAMPLE_SCALE;
+            let right = self.sound.samples[index * channels + channels - 1] as f32 / SAMPLE_SCALE;
+            (left, right)
+        };
+        let (left_a, right_a) = frame(index);
+        let (left_b, right_b) = frame(index + 1);
+        (
+            left_a + (left_b - left_a) * fraction,
+            right_a + (right_b - right_a) * fraction,
+        )
+    }
+
+    /// Indica si la voz ha terminado de sonar, incluyendo su eco.
+    fn is_finished(&self) -> bool {
+        let length = self.sound.frames() as f64 + self.echo_delay * ECHO_GAINS.len() as f64;
+        !self.looping && self.position >= length
+    }
+
+    /// Suma la voz a un búfer estéreo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `buffer` - Muestras estéreo intercaladas.
+    fn mix(&mut self, buffer: &mut [f32]) {
+        for frame in buffer.chunks_exact_mut(2) {
+            if self.is_finished() {
+                return;
+            }
+            let (mut left, mut right) = self.sample_at(self.position);
+            if self.echo_delay > 0.0 {
+                for (i, gain) in ECHO_GAINS.iter().enumerate() {
+                    let (echo_left, echo_right) =
+                        self.sample_at(self.position - self.echo_delay * (i + 1) as f64);
+                    left += echo_left * gain;
+                    right += echo_right * gain;
+                }
+            }
+            frame[0] += left * self.gains.0;
+            frame[1] += right * self.gains.1;
+            self.position += self.step;
+            if self.looping {
+                self.position %= self.sound.frames().max(1) as f64;
+            }
+        }
+    }
+}
+
+impl Mixer {
+    /// Crea un mezclador sobre un destino de audio.
+    ///
+    /// # Argumentos
+    ///
+    /// * `backend` - Destino del audio mezclado.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `Mixer` con `MAX_VOICES` voces.
+    pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+        Self::with_voices(backend, MAX_VOICES)
+    }
+
+    /// Crea un mezclador con un número de voces concreto.
+    ///
+    /// # Argumentos
+    ///
+    /// * `backend` - Destino del audio mezclado.
+    /// * `max_voices` - Número de sonidos que pueden sonar a la vez.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `Mixer`.
+    pub fn with_voices(backend: Box<dyn AudioBackend>, max_voices: usize) -> Self {
+        Self {
+            backend,
+            voices: Vec::with_capacity(max_voices),
+            max_voices,
+            listener_x: 256.0,
+            pan_width: 256.0,
+            played: 0,
+            buffer: Vec::new(),
+            ticks_per_second: TICKS_PER_SECOND,
+        }
+    }
+
+    /// Establece la velocidad del juego, para que cada tick mezcle el audio que dura.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ticks_per_second` - Ticks de juego por segundo.
+    pub fn set_ticks_per_second(&mut self, ticks_per_second: u32) {
+        self.ticks_per_second = ticks_per_second.max(1);
+    }
+
+    /// Establece la posición del oyente, usada para el panorama de los sonidos.
+    ///
+    /// # Argumentos
+    ///
+    /// * `x` - Centro de la pantalla en la X del escenario.
+    /// * `width` - Distancia desde el centro a la que un sonido queda completamente a un lado.
+    pub fn set_listener(&mut self, x: f64, width: f64) {
+        self.listener_x = x;
+        self.pan_width = width.max(1.0);
+    }
+
+    /// Reproduce un sonido.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sound` - Sonido decodificado.
+    /// * `request` - Parámetros de reproducción.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el sonido empieza a sonar, `false` si se descarta por su baja prioridad.
+    pub fn play(&mut self, sound: Rc<Sound>, request: SoundRequest) -> bool {
+        let index = if request.channel >= 0 {
+            self.voices
+                .iter()
+                .position(|voice| voice.owner == request.owner && voice.channel == request.channel)
+        } else {
+            None
+        };
+        let index = match index {
+            // Un sonido de baja prioridad no interrumpe el que ya suena en su canal
+            Some(_) if request.low_priority => return false,
+            Some(index) => Some(index),
+            None if self.voices.len() < self.max_voices => None,
+            None => match self.steal(request.low_priority) {
+                Some(index) => Some(index),
+                None => return false,
+            },
+        };
+
+        let pan = match request.pan {
+            Pan::Absolute(offset) => offset / self.pan_width,
+            Pan::Position(x) => (x - self.listener_x) / self.pan_width,
+        }
+        .clamp(-1.0, 1.0) as f32;
+        let volume = request.volume.max(0.0);
+        let output_rate = self.backend.sample_rate().max(1) as f64;
+        self.played += 1;
+        let voice = Voice {
+            owner: request.owner,
+            channel: request.channel,
+            low_priority: request.low_priority,
+            looping: request.looping,
+            echo_delay: if request.echo {
+                sound.sample_rate as f64 * ECHO_DELAY
+            } else {
+                0.0
+            },
+            gains: (volume * (1.0 - pan).min(1.0), volume * (1.0 + pan).min(1.0)),
+            step: sound.sample_rate as f64 / output_rate * request.freq_mul.max(0.0) as f64,
+            position: 0.0,
+            age: self.played,
+            sound,
+        };
+        match index {
+            Some(index) => self.voices[index] = voice,
+            None => self.voices.push(voice),
+        }
+        true
+    }
+
+    /// Busca la voz que se sustituye cuando todas están ocupadas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `low_priority` - Indica si el sonido nuevo es de baja prioridad.
+    ///
+    /// # Retorna
+    ///
+    /// El índice de la voz de baja prioridad más antigua, o de la más antigua si el sonido nuevo no es de baja
+    /// prioridad. `None` si no se puede sustituir ninguna.
+    fn steal(&self, low_priority: bool) -> Option<usize> {
+        let oldest = |only_low_priority: bool| {
+            self.voices
+                .iter()
+                .enumerate()
+                .filter(|(_, voice)| !only_low_priority || voice.low_priority)
+                .min_by_key(|(_, voice)| voice.age)
+                .map(|(index, _)| index)
+        };
+        oldest(true).or_else(|| if low_priority { None } else { oldest(false) })
+    }
+
+    /// Detiene los sonidos de un jugador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `owner` - Jugador que reproduce los sonidos.
+    /// * `channel` - Canal a detener, -1 detiene todos los del jugador.
+    pub fn stop(&mut self, owner: usize, channel: i32) {
+        self.voices
+            .retain(|voice| voice.owner != owner || (channel >= 0 && voice.channel != channel));
+    }
+
+    /// Mezcla el audio de un tick y lo envía al destino.
+    pub fn update(&mut self) {
+        let tick_frames = (self.backend.sample_rate() / self.ticks_per_second) as usize;
+        let frames = self.backend.frames_needed(tick_frames);
+        self.buffer.clear();
+        self.buffer.resize(frames * 2, 0.0);
+        for voice in &mut self.voices {
+            voice.mix(&mut self.buffer);
+        }
+        self.voices.retain(|voice| !voice.is_finished());
+        for sample in &mut self.buffer {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+        self.backend.write(&self.buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audio::backend::RecordingBackend;
+
+    use super::*;
+
+    /// Crea un sonido mono con todas las muestras al mismo valor.
+    fn sound(frames: usize, value: i16, sample_rate: u32) -> Rc<Sound> {
+        Rc::new(Sound {
+            channels: 1,
+            sample_rate,
+            samples: vec![value; frames],
+        })
+    }
+
+    /// Crea los parámetros de un sonido centrado a volumen original.
+    fn request(owner: usize, channel: i32) -> SoundRequest {
+        SoundRequest {
+            owner,
+            channel,
+            volume: 1.0,
+            pan: Pan::Absolute(0.0),
+            low_priority: false,
+            freq_mul: 1.0,
+            looping: false,
+            echo: false,
+        }
+    }
+
+    /// Indica si un canal de un jugador está sonando.
+    fn is_playing(mixer: &Mixer, owner: usize, channel: i32) -> bool {
+        mixer
+            .voices
+            .iter()
+            .any(|voice| voice.owner == owner && voice.channel == channel)
+    }
+
+    /// Crea un mezclador que graba a 600 muestras por segundo (10 fotogramas por tick).
+    fn mixer(max_voices: usize) -> (Mixer, RecordingBackend) {
+        let backend = RecordingBackend::new(600);
+        (
+            Mixer::with_voices(Box::new(backend.clone()), max_voices),
+            backend,
+        )
+    }
+
+    /// Prueba de mezcla, volumen y fin de los sonidos
+    #[test]
+    fn test_mix_volume() {
+        let (mut mixer, backend) = mixer(4);
+        mixer.update();
+        assert_eq!(backend.get_samples(), vec![0.0; 20]);
+
+        backend.clear();
+        mixer.play(sound(15, 16384, 600), request(0, 0));
+        mixer.play(
+            sound(15, 8192, 600),
+            SoundRequest {
+                volume: 0.5,
+                ..request(1, 0)
+            },
+        );
+        mixer.update();
+        assert!(backend.get_samples().iter().all(|&sample| sample == 0.625));
+        assert_eq!(mixer.voices.len(), 2);
+
+        backend.clear();
+        mixer.update();
+        let samples = backend.get_samples();
+        assert_eq!(samples[8], 0.625);
+        assert_eq!(samples[10], 0.0);
+        assert_eq!(mixer.voices.len(), 0);
+
+        backend.clear();
+        mixer.play(
+            sound(10, 16384, 600),
+            SoundRequest {
+                volume: 4.0,
+                ..request(0, 0)
+            },
+        );
+        mixer.update();
+        assert!(backend.get_samples().iter().all(|&sample| sample == 1.0));
+    }
+
+    /// Prueba del panorama según la posición del personaje
+    #[test]
+    fn test_pan() {
+        let (mut mixer, backend) = mixer(4);
+        mixer.set_listener(100.0, 100.0);
+        mixer.play(
+            sound(10, 16384, 600),
+            SoundRequest {
+                pan: Pan::Position(0.0),
+                ..request(0, 0)
+            },
+        );
+        mixer.update();
+        assert_eq!(&backend.get_samples()[0..2], &[0.5, 0.0]);
+
+        backend.clear();
+        mixer.play(
+            sound(10, 16384, 600),
+            SoundRequest {
+                pan: Pan::Position(150.0),
+                ..request(0, 0)
+            },
+        );
+        mixer.update();
+        assert_eq!(&backend.get_samples()[0..2], &[0.25, 0.5]);
+
+        backend.clear();
+        mixer.play(
+            sound(10, 16384, 600),
+            SoundRequest {
+                pan: Pan::Absolute(-500.0),
+                ..request(0, 0)
+            },
+        );
+        mixer.update();
+        assert_eq!(&backend.get_samples()[0..2], &[0.5, 0.0]);
+    }
+
+    /// Prueba de canales, prioridad y sustitución de voces
+    #[test]
+    fn test_channels() {
+        let (mut mixer, _) = mixer(2);
+        assert!(mixer.play(sound(100, 1, 600), request(0, 0)));
+        assert!(!mixer.play(
+            sound(100, 1, 600),
+            SoundRequest {
+                low_priority: true,
+                ..request(0, 0)
+            }
+        ));
+        assert!(mixer.play(sound(100, 1, 600), request(0, 0)));
+        assert_eq!(mixer.voices.len(), 1);
+
+        assert!(mixer.play(
+            sound(100, 1, 600),
+            SoundRequest {
+                low_priority: true,
+                ..request(1, 0)
+            }
+        ));
+        assert_eq!(mixer.voices.len(), 2);
+        // Sin voces libres se sustituye primero la de baja prioridad
+        assert!(mixer.play(sound(100, 1, 600), request(0, 1)));
+        assert!(!is_playing(&mixer, 1, 0));
+        assert!(is_playing(&mixer, 0, 0));
+        // Un sonido de baja prioridad no sustituye a uno importante
+        assert!(!mixer.play(
+            sound(100, 1, 600),
+            SoundRequest {
+                low_priority: true,
+                ..request(1, 3)
+            }
+        ));
+        // Pero uno importante sustituye al más antiguo
+        assert!(mixer.play(sound(100, 1, 600), request(1, -1)));
+        assert!(!is_playing(&mixer, 0, 0));
+        assert!(is_playing(&mixer, 0, 1));
+
+        mixer.stop(0, 5);
+        assert_eq!(mixer.voices.len(), 2);
+        mixer.stop(1, -1);
+        assert!(is_playing(&mixer, 0, 1));
+        assert_eq!(mixer.voices.len(), 1);
+        mixer.stop(0, 1);
+        assert_eq!(mixer.voices.len(), 0);
+    }
+
+    /// Prueba de que cada tick mezcla el audio que dura según la velocidad del juego
+    #[test]
+    fn test_ticks_per_second() {
+        let (mut mixer, backend) = mixer(4);
+        mixer.update();
+        assert_eq!(backend.get_samples().len(), 20);
+
+        backend.clear();
+        mixer.set_ticks_per_second(120);
+        mixer.update();
+        assert_eq!(backend.get_samples().len(), 10);
+    }
+
+    /// Prueba del cambio de frecuencia, repetición y eco
+    #[test]
+    fn test_resample_loop_echo() {
+        let (mut mixer, backend) = mixer(4);
+        let ramp = Rc::new(Sound {
+            channels: 2,
+            sample_rate: 300,
+            samples: vec![0, 0, 16384, 16384],
+        });
+        mixer.play(ramp, request(0, 0));
+        mixer.update();
+        assert_eq!(
+            &backend.get_samples()[0..8],
+            &[0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.25, 0.25]
+        );
+        assert_eq!(mixer.voices.len(), 0);
+
+        mixer.play(
+            sound(3, 16384, 600),
+            SoundRequest {
+                looping: true,
+                freq_mul: 2.0,
+                ..request(0, 0)
+            },
+        );
+        for _ in 0..10 {
+            mixer.update();
+        }
+        assert!(is_playing(&mixer, 0, 0));
+        mixer.stop(0, -1);
+
+        backend.clear();
+        mixer.play(
+            sound(10, 16384, 100),
+            SoundRequest {
+                echo: true,
+                freq_mul: 6.0,
+                ..request(0, 0)
+            },
+        );
+        mixer.update();
+        assert_eq!(mixer.voices.len(), 1);
+        mixer.update();
+        let samples = backend.get_samples();
+        assert_eq!(samples[0], 0.5);
+        assert_eq!(samples[20], 0.0);
+        assert_eq!(samples[24], 0.25);
+        assert_eq!(mixer.voices.len(), 1);
+        for _ in 0..3 {
+            mixer.update();
+        }
+        assert_eq!(mixer.voices.len(), 0);
+    }
+}
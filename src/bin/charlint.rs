@@ -0,0 +1,375 @@
+//! Herramienta de línea de comandos para revisar los archivos de un personaje.
+//!
+//! Revisa el DEF del personaje, las constantes de su CNS, su AIR, su CMD y su SND con los mismos
+//! lectores que usa el juego, y que las animaciones que usan los estados de su CNS estén en el AIR.
+//! Los mensajes están en inglés, como los de los lectores. A diferencia del juego,
+//! que se detiene en el primer error e ignora los avisos, muestra todos los problemas con el
+//! archivo, la línea y la columna donde están:
+//!
+//! ```text
+//! src/chars/kfm/kfm.cmd: error: Cmd file malformed on line 12, column 16: Undefined button: X
+//! ```
+//!
+//! También se puede revisar un único archivo AIR o CMD.
+//!
+//! ```text
+//! charlint <folder | file.def | file.air | file.cmd>
+//! ```
+
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    process,
+};
+
+use rust_fg::{
+    air::manager::check_animation_table,
+    cmd::manager::check_command_list,
+    cns::manager::parse_cns,
+    def::manager::{check_constants, check_def},
+    snd::decoder::Snd,
+};
+
+/// Un problema encontrado en un archivo.
+#[derive(Debug, PartialEq)]
+struct Diagnostic {
+    /// Archivo en el que está el problema
+    file: PathBuf,
+    /// Indica si el juego pasa el problema por alto
+    warning: bool,
+    /// Descripción del problema
+    message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    /// Muestra el problema como `archivo: error: mensaje` o `archivo: warning: mensaje`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `f` - El formateador donde se escribe.
+    ///
+    /// # Retorna
+    ///
+    /// Un resultado que indica si el formato fue exitoso o no.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.warning { "warning" } else { "error" };
+        write!(f, "{}: {}: {}", self.file.display(), severity, self.message)
+    }
+}
+
+/// Acumula los problemas de un personaje.
+#[derive(Default)]
+struct Linter {
+    /// Problemas encontrados
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+    /// Añade un error de un archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `file` - El archivo con el problema.
+    /// * `message` - La descripción del problema.
+    fn error(&mut self, file: &Path, message: impl fmt::Display) {
+        self.diagnostics.push(Diagnostic {
+            file: file.to_path_buf(),
+            warning: false,
+            message: message.to_string(),
+        });
+    }
+
+    /// Añade un aviso de un archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `file` - El archivo con el problema.
+    /// * `message` - La descripción del problema.
+    fn warning(&mut self, file: &Path, message: impl fmt::Display) {
+        self.diagnostics.push(Diagnostic {
+            file: file.to_path_buf(),
+            warning: true,
+            message: message.to_string(),
+        });
+    }
+
+    /// Añade los errores y los avisos que devolvió el lector de un archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `file` - El archivo leído.
+    /// * `errors` - Los errores del archivo.
+    /// * `warnings` - Los avisos del archivo.
+    fn extend<E: fmt::Display>(&mut self, file: &Path, errors: &[E], warnings: &[E]) {
+        for error in errors {
+            self.error(file, error);
+        }
+        for warning in warnings {
+            self.warning(file, warning);
+        }
+    }
+
+    /// Lee un archivo de texto, añadiendo un error si no se puede leer.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del archivo.
+    ///
+    /// # Retorna
+    ///
+    /// El contenido del archivo, admitiendo archivos que no están en UTF-8.
+    fn read(&mut self, path: &Path) -> Option<String> {
+        match std::fs::read(path) {
+            Ok(data) => Some(String::from_utf8_lossy(&data).into_owned()),
+            Err(err) => {
+                self.error(path, err);
+                None
+            }
+        }
+    }
+
+    /// Revisa un archivo AIR.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del archivo.
+    ///
+    /// # Retorna
+    ///
+    /// Los números de las acciones que lee el juego, o `None` si no se pudo leer el archivo.
+    fn air(&mut self, path: &Path) -> Option<Vec<i32>> {
+        let text = self.read(path)?;
+        let (table, report) = check_animation_table(&text);
+        self.extend(path, &report.errors, &report.warnings);
+        Some(table.get_animations().keys().copied().collect())
+    }
+
+    /// Revisa un archivo CMD.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del archivo.
+    fn cmd(&mut self, path: &Path) {
+        if let Some(text) = self.read(path) {
+            let report = check_command_list(&text);
+            self.extend(path, &report.errors, &report.warnings);
+        }
+    }
+
+    /// Revisa un archivo SND.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del archivo.
+    fn snd(&mut self, path: &Path) {
+        match Snd::load_snd(path) {
+            Ok(snd) => {
+                for id in snd.get_skipped() {
+                    self.warning(
+                        path,
+                        format!("Sound {},{} cannot be decoded", id.group, id.number),
+                    );
+                }
+            }
+            Err(err) => self.error(path, err),
+        }
+    }
+
+    /// Revisa un personaje a partir de su DEF.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del DEF.
+    fn def(&mut self, path: &Path) {
+        let Some(text) = self.read(path) else {
+            return;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let (mut def, report) = check_def(&text, &name);
+        self.extend(path, &report.errors, &report.warnings);
+        if !report.errors.is_empty() {
+            return;
+        }
+        let folder = path.parent().unwrap_or(Path::new("."));
+        let cns = def.files.cns.clone();
+        if let Some(text) = self.read(&folder.join(&cns)) {
+            let report = check_constants(&mut def, &text, &cns);
+            self.extend(&folder.join(&cns), &report.errors, &report.warnings);
+        }
+        let files = &def.files;
+        for file in [&files.sprite].into_iter().chain(&files.sound) {
+            if !folder.join(file).is_file() {
+                self.error(path, format!("File not found: {}", file));
+            }
+        }
+        if let Some(sound) = files.sound.as_ref().map(|sound| folder.join(sound)) {
+            if sound.is_file() {
+                self.snd(&sound);
+            }
+        }
+        self.cmd(&folder.join(&files.cmd));
+
+        let anim = folder.join(&files.anim);
+        let Some(actions) = self.air(&anim) else {
+            return;
+        };
+        let states = folder.join(def.get_states_file());
+        match parse_cns(&states.to_string_lossy()) {
+            Ok(table) => {
+                for (state, action) in table.animations() {
+                    if !actions.contains(&action) {
+                        self.error(
+                            &states,
+                            format!(
+                                "State {} uses animation {}, which is not in {}",
+                                state, action, files.anim
+                            ),
+                        );
+                    }
+                }
+            }
+            Err(err) => self.error(&states, err),
+        }
+    }
+}
+
+/// Revisa un personaje, o un único archivo AIR o CMD.
+///
+/// # Argumentos
+///
+/// * `path` - Carpeta del personaje, su DEF, o el archivo a revisar.
+///
+/// # Retorna
+///
+/// Los problemas encontrados, o un mensaje de error si la ruta no es algo que se pueda revisar.
+fn run(path: &Path) -> Result<Vec<Diagnostic>, String> {
+    let path = if path.is_dir() {
+        let mut defs: Vec<PathBuf> = std::fs::read_dir(path)
+            .map_err(|err| format!("{}: {}", path.display(), err))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| {
+                file.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("def"))
+            })
+            .collect();
+        defs.sort();
+        defs.into_iter()
+            .next()
+            .ok_or_else(|| format!("{} has no DEF file", path.display()))?
+    } else {
+        path.to_path_buf()
+    };
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let mut linter = Linter::default();
+    match extension.as_str() {
+        "air" => {
+            linter.air(&path);
+        }
+        "cmd" => linter.cmd(&path),
+        "def" => linter.def(&path),
+        _ => {
+            return Err(format!(
+                "{}: only DEF, AIR or CMD files can be checked",
+                path.display()
+            ))
+        }
+    }
+    Ok(linter.diagnostics)
+}
+
+/// Punto de entrada de la herramienta.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() != 1 {
+        eprintln!("Usage: charlint <folder | file.def | file.air | file.cmd>");
+        process::exit(2);
+    }
+
+    match run(Path::new(&args[0])) {
+        Ok(diagnostics) if diagnostics.is_empty() => println!("No problems found"),
+        Ok(diagnostics) => {
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic);
+            }
+            eprintln!("{} problems found", diagnostics.len());
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba que el personaje incluido no tenga problemas
+    #[test]
+    fn test_run_kfm() {
+        let diagnostics = run(Path::new("src/chars/kfm")).unwrap();
+        assert!(diagnostics.is_empty(), "{:#?}", diagnostics);
+        assert!(run(Path::new("Cargo.toml")).is_err());
+    }
+
+    /// Prueba que se muestren los problemas de todos los archivos de un personaje
+    #[test]
+    fn test_run_broken_char() {
+        let folder = env::temp_dir().join("rust_fg_test_charlint");
+        std::fs::create_dir_all(&folder).unwrap();
+        let files = [
+            (
+                "roto.def",
+                "[Info]\nversiondate = 1\n[Files]\ncmd = roto.cmd\ncns = roto.cns\nanim = roto.air\nsprite = roto.sff\n",
+            ),
+            ("roto.cmd", "[Command]\nname = \"a\"\ncommand = F, X\n"),
+            (
+                "roto.cns",
+                "[Data]\nlife = lots\n[Statedef 0]\nanim = 0\n[Statedef 10]\nanim = 11\n",
+            ),
+            ("roto.air", "[Begin Action 0]\n0,0, 0,0, -1, Z\n"),
+        ];
+        for (name, text) in files {
+            std::fs::write(folder.join(name), text).unwrap();
+        }
+
+        let diagnostics: Vec<String> = run(&folder)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        let path = |name: &str| folder.join(name).display().to_string();
+        assert_eq!(
+            diagnostics,
+            vec![
+                format!(
+                    "{}: warning: Def file roto.def malformed on line 2, column 1: Unknown key: versiondate",
+                    path("roto.def")
+                ),
+                format!(
+                    "{}: error: Def file roto.cns malformed on line 2, column 8: Invalid number: lots",
+                    path("roto.cns")
+                ),
+                format!("{}: error: File not found: roto.sff", path("roto.def")),
+                format!(
+                    "{}: error: Cmd file malformed on line 3, column 14: Undefined button: X",
+                    path("roto.cmd")
+                ),
+                format!(
+                    "{}: warning: Air file malformed on line 2, column 15: Invalid flip: Z",
+                    path("roto.air")
+                ),
+                format!(
+                    "{}: error: State 10 uses animation 11, which is not in roto.air",
+                    path("roto.cns")
+                ),
+            ]
+        );
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
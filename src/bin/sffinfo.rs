@@ -0,0 +1,315 @@
+//! Herramienta de línea de comandos para inspeccionar archivos SFF.
+//!
+//! Muestra la cabecera y la lista de sprites tal como los decodifica el juego, y permite
+//! exportar los sprites a PNG para comprobar que se ven bien.
+//!
+//! ```text
+//! sffinfo <archivo.sff> [--char]
+//! sffinfo <archivo.sff> --export <carpeta> [--group G | --sprite G,N] [--palette P] [--char]
+//! ```
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process,
+};
+
+use image::RgbaImage;
+use rust_fg::sff::decoder::{Sff, Sprite};
+
+/// Sprites que se exportan.
+#[derive(Debug, PartialEq)]
+enum Selection {
+    /// Todos los sprites del archivo
+    All,
+    /// Los sprites de un grupo
+    Group(i16),
+    /// Un único sprite identificado por su grupo y número
+    Sprite([i16; 2]),
+}
+
+/// Opciones leídas de la línea de comandos.
+#[derive(Debug, PartialEq)]
+struct Options {
+    /// Ruta al archivo SFF
+    path: PathBuf,
+    /// Indica si el archivo es de un personaje, cambia cómo se leen las paletas de la versión 1
+    char: bool,
+    /// Carpeta a la que exportar los sprites, si se pidió exportar
+    export: Option<PathBuf>,
+    /// Sprites a exportar
+    selection: Selection,
+    /// Paleta con la que pintar los sprites, o `None` para usar la de cada uno
+    palette: Option<usize>,
+}
+
+/// Lee las opciones de la línea de comandos.
+///
+/// # Argumentos
+///
+/// * `args` - Los argumentos sin el nombre del programa.
+///
+/// # Retorna
+///
+/// Las opciones, o un mensaje de error si los argumentos no son válidos.
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        path: PathBuf::new(),
+        char: false,
+        export: None,
+        selection: Selection::All,
+        palette: None,
+    };
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("Falta el valor de {}", name))
+        };
+        match arg.as_str() {
+            "--char" => options.char = true,
+            "--export" => options.export = Some(PathBuf::from(value(arg)?)),
+            "--group" => {
+                let group = value(arg)?;
+                options.selection = Selection::Group(
+                    group
+                        .parse()
+                        .map_err(|_| format!("Grupo inválido: {}", group))?,
+                );
+            }
+            "--sprite" => {
+                let sprite = value(arg)?;
+                let invalid = || format!("Sprite inválido, se espera grupo,número: {}", sprite);
+                let (group, number) = sprite.split_once(',').ok_or_else(invalid)?;
+                options.selection = Selection::Sprite([
+                    group.trim().parse().map_err(|_| invalid())?,
+                    number.trim().parse().map_err(|_| invalid())?,
+                ]);
+            }
+            "--palette" => {
+                let palette = value(arg)?;
+                options.palette = Some(
+                    palette
+                        .parse()
+                        .map_err(|_| format!("Paleta inválida: {}", palette))?,
+                );
+            }
+            _ if arg.starts_with("--") => return Err(format!("Opción desconocida: {}", arg)),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Argumento de más: {}", arg)),
+        }
+    }
+    options.path = path.ok_or("Falta el archivo SFF")?;
+    Ok(options)
+}
+
+/// Muestra la cabecera y la lista de sprites de un SFF.
+///
+/// # Argumentos
+///
+/// * `sff` - El SFF decodificado.
+fn print_info(sff: &Sff) {
+    println!("Versión: {}", sff.get_version());
+    println!("Sprites: {}", sff.get_order().len());
+    println!("Paletas: {}", sff.get_palette_count());
+    println!();
+    println!(
+        "{:>6} {:>6} {:>11} {:>13} {:>8} {:>7} {:>12}",
+        "Grupo", "Número", "Tamaño", "Eje", "Formato", "Paleta", "Enlace"
+    );
+    for key in sff.get_order() {
+        let sprite = &sff.sprites[key];
+        let link = match sprite.link {
+            Some([group, number]) => format!("{},{}", group, number),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:>6} {:>6} {:>11} {:>13} {:>8} {:>7} {:>12}",
+            sprite.group,
+            sprite.number,
+            format!("{}x{}", sprite.size[0], sprite.size[1]),
+            format!("{},{}", sprite.offset[0], sprite.offset[1]),
+            format!("{:?}", sprite.format),
+            sprite.pal_idx,
+            link
+        );
+    }
+}
+
+/// Exporta los sprites seleccionados a archivos PNG con el nombre `<grupo>-<número>.png`.
+///
+/// # Argumentos
+///
+/// * `sff` - El SFF decodificado.
+/// * `options` - Las opciones con la carpeta, los sprites y la paleta.
+/// * `folder` - Carpeta en la que se guardan los PNG.
+///
+/// # Retorna
+///
+/// La cantidad de sprites exportados, o un mensaje de error.
+fn export(sff: &Sff, options: &Options, folder: &Path) -> Result<usize, String> {
+    let palette = match options.palette {
+        Some(index) => Some(
+            sff.get_palette(index)
+                .filter(|palette| !palette.is_empty())
+                .ok_or_else(|| format!("La paleta {} no existe", index))?,
+        ),
+        None => None,
+    };
+    let sprites: Vec<&Sprite> = sff
+        .get_order()
+        .iter()
+        .map(|key| &sff.sprites[key])
+        .filter(|sprite| match options.selection {
+            Selection::All => true,
+            Selection::Group(group) => sprite.group == group,
+            Selection::Sprite(key) => [sprite.group, sprite.number] == key,
+        })
+        .collect();
+    if sprites.is_empty() {
+        return Err("No hay sprites que exportar".to_string());
+    }
+
+    std::fs::create_dir_all(folder).map_err(|err| err.to_string())?;
+    let mut exported = 0;
+    for sprite in sprites {
+        let image = match RgbaImage::from_raw(
+            sprite.size[0] as u32,
+            sprite.size[1] as u32,
+            sprite.to_rgba(palette),
+        ) {
+            Some(image) if !sprite.raw.is_empty() => image,
+            _ => {
+                eprintln!(
+                    "El sprite {},{} no tiene píxeles, se omite",
+                    sprite.group, sprite.number
+                );
+                continue;
+            }
+        };
+        let path = folder.join(format!("{}-{}.png", sprite.group, sprite.number));
+        image.save(&path).map_err(|err| err.to_string())?;
+        exported += 1;
+    }
+    Ok(exported)
+}
+
+/// Punto de entrada de la herramienta.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("Uso: sffinfo <archivo.sff> [--char]");
+            eprintln!(
+                "     sffinfo <archivo.sff> --export <carpeta> [--group G | --sprite G,N] [--palette P] [--char]"
+            );
+            process::exit(2);
+        }
+    };
+
+    let sff = match Sff::load_file(options.path.clone(), options.char) {
+        Ok(sff) => sff,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+
+    match &options.export {
+        Some(folder) => match export(&sff, &options, folder) {
+            Ok(count) => println!("Se exportaron {} sprites a {}", count, folder.display()),
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        },
+        None => print_info(&sff),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Convierte una lista de textos en argumentos.
+    ///
+    /// # Argumentos
+    ///
+    /// * `args` - Los argumentos.
+    ///
+    /// # Retorna
+    ///
+    /// Los argumentos como `String`.
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Prueba la lectura de las opciones para listar y exportar
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args(&["kfm.sff"])).unwrap();
+        assert_eq!(options.path, PathBuf::from("kfm.sff"));
+        assert_eq!(options.export, None);
+        assert_eq!(options.selection, Selection::All);
+
+        let options = parse_args(&args(&[
+            "--export",
+            "out",
+            "kfm.sff",
+            "--sprite",
+            "9000,1",
+            "--palette",
+            "3",
+            "--char",
+        ]))
+        .unwrap();
+        assert_eq!(options.export, Some(PathBuf::from("out")));
+        assert_eq!(options.selection, Selection::Sprite([9000, 1]));
+        assert_eq!(options.palette, Some(3));
+        assert!(options.char);
+
+        let options = parse_args(&args(&["kfm.sff", "--group", "-1"])).unwrap();
+        assert_eq!(options.selection, Selection::Group(-1));
+    }
+
+    /// Prueba que los argumentos inválidos dan error
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["a.sff", "b.sff"])).is_err());
+        assert!(parse_args(&args(&["a.sff", "--sprite", "9000"])).is_err());
+        assert!(parse_args(&args(&["a.sff", "--palette"])).is_err());
+        assert!(parse_args(&args(&["a.sff", "--scale", "2"])).is_err());
+    }
+
+    /// Prueba que se exporta un sprite de un SFF a PNG
+    #[test]
+    fn test_export_sprite() {
+        let path = PathBuf::from("src/chars/kfm/kfm.sff");
+        let sff = Sff::load_file(path.clone(), true).unwrap();
+        let folder = env::temp_dir().join("sffinfo_test_export");
+        let options = Options {
+            path,
+            char: true,
+            export: Some(folder.clone()),
+            selection: Selection::Sprite([0, 0]),
+            palette: Some(0),
+        };
+        assert_eq!(export(&sff, &options, &folder), Ok(1));
+
+        let image = image::open(folder.join("0-0.png")).unwrap();
+        let sprite = &sff.sprites[&[0, 0]];
+        assert_eq!(image.width(), sprite.size[0] as u32);
+        assert_eq!(image.height(), sprite.size[1] as u32);
+        std::fs::remove_dir_all(folder).unwrap();
+
+        let options = Options {
+            palette: Some(500),
+            ..options
+        };
+        assert!(export(&sff, &options, &env::temp_dir()).is_err());
+    }
+}
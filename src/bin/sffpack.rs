@@ -0,0 +1,755 @@
+//! Herramienta de línea de comandos para crear un SFF versión 2 a partir de una carpeta de PNG.
+//!
+//! Los sprites se describen en un manifiesto de texto, por defecto `sprites.txt` dentro de la carpeta,
+//! con una línea por sprite y comentarios que empiezan con `;`:
+//!
+//! ```text
+//! ; grupo, número, archivo, eje x, eje y, paleta
+//! 0, 0, stand-0.png, 18, 104
+//! 0, 1, stand-1.png, 18, 104, 1
+//! 9000, 0, retrato.png, 0, 0, 0
+//! ```
+//!
+//! Los sprites con la misma paleta (la 1 si no se indica) comparten los colores y se guardan con la
+//! paleta 1,N del SFF, así que se pueden cambiar de paleta juntos. Si alguno de sus PNG tiene paleta,
+//! todos los PNG con paleta deben tener la misma distribución de colores y esa es la paleta compartida;
+//! si no, se cuantizan los colores de todos a una paleta de 256. El índice 0 es el transparente.
+//! La paleta 0 indica un sprite de color verdadero, sin paleta.
+//!
+//! ```text
+//! sffpack <carpeta> <salida.sff> [--manifest archivo] [--format raw|rle8|rle5|lz5|png8]
+//! ```
+
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    process,
+};
+
+use rust_fg::sff::{
+    decoder::{Color, Sprite, SpriteFormat},
+    encoder::SffWriter,
+};
+
+/// Cantidad de colores de una paleta sin contar el transparente.
+const MAX_COLORS: usize = 255;
+
+/// Opciones leídas de la línea de comandos.
+#[derive(Debug, PartialEq)]
+struct Options {
+    /// Carpeta con los PNG
+    folder: PathBuf,
+    /// Ruta del SFF a crear
+    output: PathBuf,
+    /// Ruta del manifiesto, o `None` para usar `sprites.txt` dentro de la carpeta
+    manifest: Option<PathBuf>,
+    /// Formato de los sprites con paleta
+    format: SpriteFormat,
+}
+
+/// Sprite descrito en una línea del manifiesto.
+#[derive(Debug, PartialEq)]
+struct Entry {
+    /// Grupo del sprite
+    group: i16,
+    /// Número del sprite dentro del grupo
+    number: i16,
+    /// Archivo PNG, relativo a la carpeta
+    file: PathBuf,
+    /// Eje del sprite
+    axis: [i16; 2],
+    /// Número de la paleta compartida, 0 si el sprite es de color verdadero
+    palette: i16,
+}
+
+/// Imagen leída de un PNG.
+#[derive(Debug)]
+enum Frame {
+    /// PNG con paleta, con los índices de cada píxel
+    Indexed {
+        /// Ancho y alto
+        size: [u16; 2],
+        /// Índices de la paleta
+        indices: Vec<u8>,
+        /// Paleta del PNG, con el índice 0 transparente
+        palette: Vec<Color>,
+    },
+    /// PNG de color verdadero
+    Rgba {
+        /// Ancho y alto
+        size: [u16; 2],
+        /// Píxeles en RGBA
+        pixels: Vec<u8>,
+    },
+}
+
+impl Frame {
+    /// Obtiene el tamaño de la imagen.
+    ///
+    /// # Retorna
+    ///
+    /// El ancho y el alto.
+    fn size(&self) -> [u16; 2] {
+        match self {
+            Frame::Indexed { size, .. } | Frame::Rgba { size, .. } => *size,
+        }
+    }
+
+    /// Convierte la imagen a RGBA.
+    ///
+    /// # Retorna
+    ///
+    /// Los píxeles en RGBA, pintando los índices con la paleta del PNG.
+    fn to_rgba(&self) -> Vec<u8> {
+        match self {
+            Frame::Indexed {
+                indices, palette, ..
+            } => indices
+                .iter()
+                .flat_map(|&index| {
+                    palette
+                        .get(index as usize)
+                        .map_or(vec![0; 4], |color| color.get_vec())
+                })
+                .collect(),
+            Frame::Rgba { pixels, .. } => pixels.clone(),
+        }
+    }
+}
+
+/// Lee las opciones de la línea de comandos.
+///
+/// # Argumentos
+///
+/// * `args` - Los argumentos sin el nombre del programa.
+///
+/// # Retorna
+///
+/// Las opciones, o un mensaje de error si los argumentos no son válidos.
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut manifest = None;
+    let mut format = SpriteFormat::Rle8;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("Falta el valor de {}", name))
+        };
+        match arg.as_str() {
+            "--manifest" => manifest = Some(PathBuf::from(value(arg)?)),
+            "--format" => {
+                let name = value(arg)?;
+                format = match name.as_str() {
+                    "raw" => SpriteFormat::Raw,
+                    "rle8" => SpriteFormat::Rle8,
+                    "rle5" => SpriteFormat::Rle5,
+                    "lz5" => SpriteFormat::Lz5,
+                    "png8" => SpriteFormat::Png8,
+                    _ => return Err(format!("Formato desconocido: {}", name)),
+                };
+            }
+            _ if arg.starts_with("--") => return Err(format!("Opción desconocida: {}", arg)),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    match <[PathBuf; 2]>::try_from(paths) {
+        Ok([folder, output]) => Ok(Options {
+            folder,
+            output,
+            manifest,
+            format,
+        }),
+        Err(_) => Err("Se esperan la carpeta de los PNG y el SFF de salida".to_string()),
+    }
+}
+
+/// Lee el manifiesto con los sprites.
+///
+/// # Argumentos
+///
+/// * `text` - El contenido del manifiesto.
+///
+/// # Retorna
+///
+/// Los sprites en el orden del manifiesto, o un mensaje de error con la línea mal formada.
+fn parse_manifest(text: &str) -> Result<Vec<Entry>, String> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = |what: &str| format!("Línea {}: {}", line_number + 1, what);
+        let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+        if columns.len() != 5 && columns.len() != 6 {
+            return Err(invalid(
+                "se espera grupo, número, archivo, eje x, eje y y opcionalmente la paleta",
+            ));
+        }
+        let number = |index: usize, name: &str| -> Result<i16, String> {
+            columns[index]
+                .parse()
+                .map_err(|_| invalid(&format!("{} inválido: {}", name, columns[index])))
+        };
+        let entry = Entry {
+            group: number(0, "grupo")?,
+            number: number(1, "número")?,
+            file: PathBuf::from(columns[2]),
+            axis: [number(3, "eje x")?, number(4, "eje y")?],
+            palette: if columns.len() == 6 {
+                number(5, "paleta")?
+            } else {
+                1
+            },
+        };
+        if entry.palette < 0 {
+            return Err(invalid("la paleta no puede ser negativa"));
+        }
+        if entries
+            .iter()
+            .any(|other| [other.group, other.number] == [entry.group, entry.number])
+        {
+            return Err(invalid(&format!(
+                "el sprite {},{} está repetido",
+                entry.group, entry.number
+            )));
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Lee un PNG, conservando los índices si tiene paleta.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del PNG.
+///
+/// # Retorna
+///
+/// La imagen, o un mensaje de error si no se puede leer.
+fn load_frame(path: &Path) -> Result<Frame, String> {
+    let error = |err: &dyn std::fmt::Display| format!("{}: {}", path.display(), err);
+    let data = std::fs::read(path).map_err(|err| error(&err))?;
+    let mut decoder = png::Decoder::new(data.as_slice());
+    decoder.set_transformations(png::Transformations::IDENTITY);
+    let mut reader = decoder.read_info().map_err(|err| error(&err))?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(error(&"la imagen es demasiado grande"));
+    }
+    let size = [width as u16, height as u16];
+
+    if reader.info().color_type != png::ColorType::Indexed {
+        let image = image::load_from_memory(&data).map_err(|err| error(&err))?;
+        return Ok(Frame::Rgba {
+            size,
+            pixels: image.to_rgba8().into_raw(),
+        });
+    }
+
+    let palette = reader
+        .info()
+        .palette
+        .as_ref()
+        .map(|plte| {
+            plte.chunks_exact(3)
+                .enumerate()
+                .map(|(i, rgb)| {
+                    Color::from_rgba(rgb[0], rgb[1], rgb[2], if i == 0 { 0 } else { 255 })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).map_err(|err| error(&err))?;
+    // Los PNG de 1, 2 y 4 bits guardan varios índices en cada byte
+    let depth = frame.bit_depth as usize;
+    let mask = ((1u16 << depth) - 1) as u8;
+    let mut indices = Vec::with_capacity(width as usize * height as usize);
+    for row in buffer[..frame.buffer_size()].chunks(frame.line_size) {
+        for x in 0..width as usize {
+            let bit = x * depth;
+            let shift = 8 - depth - bit % 8;
+            indices.push((row[bit / 8] >> shift) & mask);
+        }
+    }
+    Ok(Frame::Indexed {
+        size,
+        indices,
+        palette,
+    })
+}
+
+/// Reduce una lista de colores con el algoritmo de corte por la mediana.
+///
+/// # Argumentos
+///
+/// * `counts` - Cantidad de píxeles de cada color.
+/// * `max` - Cantidad máxima de colores del resultado.
+///
+/// # Retorna
+///
+/// Los colores, los mismos de la entrada si no superan el máximo.
+fn quantise(counts: &HashMap<[u8; 3], usize>, max: usize) -> Vec<[u8; 3]> {
+    let mut colors: Vec<([u8; 3], usize)> = counts.iter().map(|(&rgb, &n)| (rgb, n)).collect();
+    colors.sort();
+    if colors.len() <= max {
+        return colors.into_iter().map(|(rgb, _)| rgb).collect();
+    }
+
+    // Se parte siempre la caja con el canal de mayor rango, por la mediana de los píxeles
+    let range = |colors: &[([u8; 3], usize)], channel: usize| {
+        let values = colors.iter().map(|(rgb, _)| rgb[channel]);
+        values.clone().max().unwrap_or(0) - values.min().unwrap_or(0)
+    };
+    let mut boxes = vec![colors];
+    while boxes.len() < max {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .flat_map(|(i, colors)| (0..3).map(move |channel| (i, channel, range(colors, channel))))
+            .max_by_key(|&(_, _, range)| range);
+        let (index, channel) = match widest {
+            Some((index, channel, _)) => (index, channel),
+            None => break,
+        };
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_by_key(|(rgb, _)| rgb[channel]);
+        let half = colors.iter().map(|(_, n)| n).sum::<usize>() / 2;
+        let mut pixels = 0;
+        let mut split = 1;
+        for (i, (_, n)) in colors.iter().enumerate() {
+            pixels += n;
+            if pixels >= half {
+                split = i + 1;
+                break;
+            }
+        }
+        let other = colors.split_off(split.clamp(1, colors.len() - 1));
+        boxes.push(colors);
+        boxes.push(other);
+    }
+
+    boxes
+        .iter()
+        .map(|colors| {
+            let total: usize = colors.iter().map(|(_, n)| n).sum();
+            let mut rgb = [0; 3];
+            for (channel, value) in rgb.iter_mut().enumerate() {
+                let sum: usize = colors.iter().map(|(c, n)| c[channel] as usize * n).sum();
+                *value = ((sum + total / 2) / total) as u8;
+            }
+            rgb
+        })
+        .collect()
+}
+
+/// Obtiene la paleta compartida por los sprites de una misma paleta del manifiesto.
+///
+/// # Argumentos
+///
+/// * `frames` - Los sprites de la paleta con sus imágenes.
+///
+/// # Retorna
+///
+/// La paleta de los PNG con paleta, o una cuantizada de los colores de todos si ninguno tiene.
+/// Un mensaje de error si dos PNG con paleta no tienen la misma distribución de colores.
+fn build_palette(frames: &[(&Entry, &Frame)]) -> Result<Vec<Color>, String> {
+    let mut shared: Option<(&Entry, &Vec<Color>)> = None;
+    for &(entry, frame) in frames {
+        let palette = match frame {
+            Frame::Indexed { palette, .. } => palette,
+            Frame::Rgba { .. } => continue,
+        };
+        match shared {
+            None => shared = Some((entry, palette)),
+            Some((first, colors)) => {
+                // Un PNG puede tener menos colores si el programa de dibujo recortó la paleta
+                let len = colors.len().min(palette.len());
+                if colors[..len] != palette[..len] {
+                    return Err(format!(
+                        "{} y {} no tienen la misma paleta, no se pueden compartir en la paleta {}",
+                        first.file.display(),
+                        entry.file.display(),
+                        entry.palette
+                    ));
+                }
+                if palette.len() > colors.len() {
+                    shared = Some((first, palette));
+                }
+            }
+        }
+    }
+    if let Some((_, colors)) = shared {
+        return Ok(colors.clone());
+    }
+
+    let mut counts = HashMap::new();
+    for (_, frame) in frames {
+        for rgba in frame.to_rgba().chunks_exact(4) {
+            if rgba[3] >= 128 {
+                *counts.entry([rgba[0], rgba[1], rgba[2]]).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut palette = vec![Color::from_rgba(0, 0, 0, 0)];
+    palette.extend(
+        quantise(&counts, MAX_COLORS)
+            .into_iter()
+            .map(|rgb| Color::from_rgba(rgb[0], rgb[1], rgb[2], 255)),
+    );
+    Ok(palette)
+}
+
+/// Convierte una imagen a índices de una paleta.
+///
+/// # Argumentos
+///
+/// * `frame` - La imagen.
+/// * `palette` - La paleta compartida, con el índice 0 transparente.
+///
+/// # Retorna
+///
+/// Los índices de la imagen. Los píxeles de color verdadero usan el color más cercano de la paleta, y
+/// los que son casi transparentes usan el índice 0.
+fn index_frame(frame: &Frame, palette: &[Color]) -> Vec<u8> {
+    if let Frame::Indexed { indices, .. } = frame {
+        return indices.clone();
+    }
+    let colors: Vec<Vec<u8>> = palette.iter().map(Color::get_vec).collect();
+    let mut nearest = HashMap::new();
+    frame
+        .to_rgba()
+        .chunks_exact(4)
+        .map(|rgba| {
+            if rgba[3] < 128 {
+                return 0;
+            }
+            *nearest
+                .entry([rgba[0], rgba[1], rgba[2]])
+                .or_insert_with(|| {
+                    (1..colors.len())
+                        .min_by_key(|&i| {
+                            (0..3)
+                                .map(|c| (colors[i][c] as i32 - rgba[c] as i32).pow(2))
+                                .sum::<i32>()
+                        })
+                        .unwrap_or(0) as u8
+                })
+        })
+        .collect()
+}
+
+/// Prepara el SFF con los sprites de un manifiesto.
+///
+/// # Argumentos
+///
+/// * `folder` - Carpeta con los PNG.
+/// * `entries` - Los sprites del manifiesto.
+/// * `format` - Formato de los sprites con paleta.
+///
+/// # Retorna
+///
+/// El codificador con las paletas y los sprites, o un mensaje de error.
+fn pack(folder: &Path, entries: &[Entry], format: SpriteFormat) -> Result<SffWriter, String> {
+    let frames = entries
+        .iter()
+        .map(|entry| load_frame(&folder.join(&entry.file)))
+        .collect::<Result<Vec<Frame>, String>>()?;
+
+    let mut numbers: Vec<i16> = entries
+        .iter()
+        .map(|entry| entry.palette)
+        .filter(|&palette| palette != 0)
+        .collect();
+    numbers.sort();
+    numbers.dedup();
+
+    let mut writer = SffWriter::new();
+    let mut palettes = HashMap::new();
+    for number in numbers {
+        let frames: Vec<(&Entry, &Frame)> = entries
+            .iter()
+            .zip(&frames)
+            .filter(|(entry, _)| entry.palette == number)
+            .collect();
+        let colors = build_palette(&frames)?;
+        let index = writer.add_palette([1, number], colors.clone());
+        palettes.insert(number, (index, colors));
+    }
+    // El juego necesita al menos una paleta aunque todos los sprites sean de color verdadero
+    if palettes.is_empty() {
+        writer.add_palette([1, 1], vec![Color::from_rgba(0, 0, 0, 0); 256]);
+    }
+
+    for (entry, frame) in entries.iter().zip(&frames) {
+        let mut sprite = Sprite::new();
+        sprite.group = entry.group;
+        sprite.number = entry.number;
+        sprite.size = frame.size();
+        sprite.offset = entry.axis;
+        match palettes.get(&entry.palette) {
+            Some((index, colors)) => {
+                sprite.raw = index_frame(frame, colors);
+                sprite.pal = colors.clone();
+                sprite.pal_idx = *index as i32;
+                sprite.col_depth = 8;
+                sprite.format = format;
+            }
+            None => {
+                sprite.raw = frame.to_rgba();
+                sprite.pal_idx = 0;
+                sprite.col_depth = 32;
+                sprite.format = SpriteFormat::Png32;
+            }
+        }
+        writer.add_sprite(sprite);
+    }
+    Ok(writer)
+}
+
+/// Lee el manifiesto y guarda el SFF.
+///
+/// # Argumentos
+///
+/// * `options` - Las opciones de la línea de comandos.
+///
+/// # Retorna
+///
+/// La cantidad de sprites guardados, o un mensaje de error.
+fn run(options: &Options) -> Result<usize, String> {
+    let manifest = options
+        .manifest
+        .clone()
+        .unwrap_or_else(|| options.folder.join("sprites.txt"));
+    let text = std::fs::read_to_string(&manifest)
+        .map_err(|err| format!("{}: {}", manifest.display(), err))?;
+    let entries =
+        parse_manifest(&text).map_err(|err| format!("{}: {}", manifest.display(), err))?;
+    if entries.is_empty() {
+        return Err(format!("{} no tiene sprites", manifest.display()));
+    }
+    let data = pack(&options.folder, &entries, options.format)?
+        .encode_v2()
+        .map_err(|err| err.to_string())?;
+    std::fs::write(&options.output, data).map_err(|err| err.to_string())?;
+    Ok(entries.len())
+}
+
+/// Punto de entrada de la herramienta.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!(
+                "Uso: sffpack <carpeta> <salida.sff> [--manifest archivo] [--format raw|rle8|rle5|lz5|png8]"
+            );
+            process::exit(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(count) => println!(
+            "Se guardaron {} sprites en {}",
+            count,
+            options.output.display()
+        ),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbaImage;
+    use rust_fg::sff::decoder::Sff;
+
+    use super::*;
+
+    /// Crea una carpeta temporal vacía para una prueba.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre de la carpeta.
+    ///
+    /// # Retorna
+    ///
+    /// La ruta de la carpeta.
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder = env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    /// Guarda un PNG con paleta de 8 bits.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del PNG.
+    /// * `size` - Ancho y alto.
+    /// * `plte` - Paleta en RGB.
+    /// * `indices` - Índices de los píxeles.
+    fn save_indexed(path: &Path, size: [u32; 2], plte: &[u8], indices: &[u8]) {
+        let file = std::fs::File::create(path).unwrap();
+        let mut encoder = png::Encoder::new(file, size[0], size[1]);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(plte.to_vec());
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(indices).unwrap();
+    }
+
+    /// Prueba la lectura del manifiesto con comentarios y paleta por defecto
+    #[test]
+    fn test_parse_manifest() {
+        let entries = parse_manifest(
+            "; grupo, número, archivo, eje x, eje y, paleta\n\
+             0, 0, stand-0.png, 18, 104\n\
+             \n\
+             9000, 1, retrato.png, -3, 0, 0 ; sin paleta\n",
+        )
+        .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    group: 0,
+                    number: 0,
+                    file: PathBuf::from("stand-0.png"),
+                    axis: [18, 104],
+                    palette: 1,
+                },
+                Entry {
+                    group: 9000,
+                    number: 1,
+                    file: PathBuf::from("retrato.png"),
+                    axis: [-3, 0],
+                    palette: 0,
+                },
+            ]
+        );
+
+        assert!(parse_manifest("0, 0, a.png, 1").is_err());
+        assert!(parse_manifest("0, x, a.png, 1, 2").is_err());
+        assert!(parse_manifest("0, 0, a.png, 1, 2, -1").is_err());
+        assert!(parse_manifest("0, 0, a.png, 1, 2\n0, 0, b.png, 1, 2").is_err());
+    }
+
+    /// Prueba la lectura de las opciones
+    #[test]
+    fn test_parse_args() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|a| a.to_string()).collect() };
+        let options = parse_args(&args(&["png", "out.sff", "--format", "lz5"])).unwrap();
+        assert_eq!(options.folder, PathBuf::from("png"));
+        assert_eq!(options.output, PathBuf::from("out.sff"));
+        assert_eq!(options.manifest, None);
+        assert_eq!(options.format, SpriteFormat::Lz5);
+
+        assert!(parse_args(&args(&["png"])).is_err());
+        assert!(parse_args(&args(&["png", "out.sff", "--format", "pcx"])).is_err());
+    }
+
+    /// Prueba que la cuantización conserva los colores si caben y reduce el resto al máximo
+    #[test]
+    fn test_quantise() {
+        let counts: HashMap<[u8; 3], usize> =
+            [([0, 0, 0], 5), ([255, 0, 0], 1)].into_iter().collect();
+        assert_eq!(quantise(&counts, 255), vec![[0, 0, 0], [255, 0, 0]]);
+
+        let counts: HashMap<[u8; 3], usize> = (0..=255u8)
+            .flat_map(|r| [([r, 0, 0], 1), ([r, 255, 0], 1)])
+            .collect();
+        let colors = quantise(&counts, 16);
+        assert_eq!(colors.len(), 16);
+        assert!(colors.iter().any(|rgb| rgb[1] < 128));
+        assert!(colors.iter().any(|rgb| rgb[1] >= 128));
+    }
+
+    /// Prueba que los PNG de color verdadero comparten una paleta y se decodifican con los mismos colores
+    #[test]
+    fn test_pack_true_color() {
+        let folder = temp_folder("sffpack_test_true_color");
+        let red = image::Rgba([255, 0, 0, 255]);
+        let blue = image::Rgba([0, 0, 255, 255]);
+        let clear = image::Rgba([0, 0, 0, 0]);
+        RgbaImage::from_fn(4, 2, |x, _| if x < 2 { red } else { clear })
+            .save(folder.join("a.png"))
+            .unwrap();
+        RgbaImage::from_fn(2, 3, |_, y| if y == 0 { blue } else { red })
+            .save(folder.join("b.png"))
+            .unwrap();
+        let entries =
+            parse_manifest("0, 0, a.png, 2, 2\n0, 1, b.png, 1, 3\n9000, 0, b.png, 0, 0, 0")
+                .unwrap();
+
+        let data = pack(&folder, &entries, SpriteFormat::Rle8)
+            .unwrap()
+            .encode_v2()
+            .unwrap();
+        let sff = Sff::decode(&data, true).unwrap();
+        assert_eq!(sff.get_palette_key(0), Some([1, 1]));
+        let (a, b) = (&sff.sprites[&[0, 0]], &sff.sprites[&[0, 1]]);
+        assert_eq!(a.offset, [2, 2]);
+        assert_eq!(a.pal_idx, b.pal_idx);
+        assert_eq!(a.raw[2], 0);
+        assert_eq!(
+            a.to_rgba(None),
+            [
+                [255, 0, 0, 255],
+                [255, 0, 0, 255],
+                [0, 0, 0, 0],
+                [0, 0, 0, 0]
+            ]
+            .repeat(2)
+            .concat()
+        );
+        assert_eq!(&b.to_rgba(None)[..4], &[0, 0, 255, 255]);
+        let portrait = &sff.sprites[&[9000, 0]];
+        assert!(portrait.is_true_color());
+        assert_eq!(portrait.format, SpriteFormat::Png32);
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    /// Prueba que los PNG con paleta conservan sus índices y que las paletas distintas dan error
+    #[test]
+    fn test_pack_palette_layout() {
+        let folder = temp_folder("sffpack_test_palette_layout");
+        let plte = [0, 0, 0, 10, 20, 30, 40, 50, 60];
+        save_indexed(&folder.join("a.png"), [2, 1], &plte, &[1, 2]);
+        save_indexed(&folder.join("b.png"), [2, 1], &plte[..6], &[0, 1]);
+        save_indexed(
+            &folder.join("c.png"),
+            [2, 1],
+            &[0, 0, 0, 60, 50, 40],
+            &[1, 1],
+        );
+
+        let entries = parse_manifest("0, 0, a.png, 0, 0\n0, 1, b.png, 0, 0").unwrap();
+        let data = pack(&folder, &entries, SpriteFormat::Lz5)
+            .unwrap()
+            .encode_v2()
+            .unwrap();
+        let sff = Sff::decode(&data, true).unwrap();
+        assert_eq!(sff.sprites[&[0, 0]].raw, vec![1, 2]);
+        assert_eq!(sff.sprites[&[0, 1]].raw, vec![0, 1]);
+        assert_eq!(
+            sff.sprites[&[0, 0]].to_rgba(None),
+            vec![10, 20, 30, 255, 40, 50, 60, 255]
+        );
+
+        let entries = parse_manifest("0, 0, a.png, 0, 0\n0, 1, c.png, 0, 0").unwrap();
+        assert!(pack(&folder, &entries, SpriteFormat::Lz5).is_err());
+        let entries = parse_manifest("0, 0, a.png, 0, 0\n0, 1, c.png, 0, 0, 2").unwrap();
+        assert!(pack(&folder, &entries, SpriteFormat::Lz5).is_ok());
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+}
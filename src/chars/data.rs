@@ -1,57 +1,15 @@
-use constants::constants::*;
-
-use crate::chars::Character;
+use crate::{
+    chars::Character,
+    player::character::{
+        cns::manager::{HitDef, SoundCommand, GROUND_Y, NUM_VARS},
+        def::manager::CharDef,
+    },
+};
 
 use super::State;
-const WINDOW_SIZE: f64 = 512.0;
+
 /// Implementación del rasgo `Character` para la estructura `CharData`.
 impl Character for CharData {
-    /// Crea un nuevo `CharData` con valores predeterminados.
-    ///
-    /// # Retorna
-    ///
-    /// Una nueva instancia de `CharData` con valores iniciales predeterminados.
-    fn new() -> Self {
-        Self {
-            //name: "KFM".to_string(),
-            life: LIFE,
-            power: 3000,
-            state: State::S,
-            attack: State::S,
-            ctrl: true,
-            state_no: -1,
-            air_time: 0,
-            double_jump: false,
-            run: false,
-            anim: 0,
-            //fx: 0,
-            action: "".to_string(),
-            time: 0,
-            anim_time: 0,
-            anim_elem: 0,
-            new_anim: true,
-            offset_x: 0.0,
-            offset_y: 0.0,
-            width: 0,
-            x: 50.0,
-            y: 500.0,
-            vel_x: 0.0,
-            vel_y: 0.0,
-            def: true,
-            direction: 5,
-            jumps: 1,
-            previous_flip: false,
-            current_flip: false,
-            flip_x: false,
-            distance: -1.0,
-            hit: 0,
-            fall: false,
-            win: false,
-            lose: false,
-            wall: false,
-        }
-    }
-
     /// Obtiene el número del estado que le ha impactado.
     ///
     /// # Retorna
@@ -67,7 +25,7 @@ impl Character for CharData {
     ///
     /// El porcentaje de vida del personaje.
     fn get_life_as_percentage(&self) -> f64 {
-        self.life as f64 / LIFE as f64 * 100.0
+        self.life as f64 / self.char_def.data.life as f64 * 100.0
     }
 
     /// Obtiene el poder del personaje como un porcentaje.
@@ -76,34 +34,16 @@ impl Character for CharData {
     ///
     /// El porcentaje de poder del personaje.
     fn get_power_as_percentage(&self) -> f64 {
-        self.power as f64 / POWER as f64 * 100.0
+        self.power as f64 / self.char_def.data.power as f64 * 100.0
     }
 
-    /// Obtiene el nombre del archivo AIR.
+    /// Obtiene la definición del personaje.
     ///
     /// # Retorna
     ///
-    /// El nombre del archivo AIR.
-    fn get_air_name(&self) -> &'static str {
-        AIR
-    }
-
-    /// Obtiene el nombre del archivo SFF.
-    ///
-    /// # Retorna
-    ///
-    /// El nombre del archivo SFF.
-    fn get_sff_name(&self) -> &'static str {
-        SFF
-    }
-
-    /// Obtiene el nombre del archivo CMD.
-    ///
-    /// # Retorna
-    ///
-    /// El nombre del archivo CMD.
-    fn get_cmd_name(&self) -> &'static str {
-        CMD
+    /// La definición leída de su archivo DEF.
+    fn get_def(&self) -> &CharDef {
+        &self.char_def
     }
 
     /// Establece la acción actual del personaje.
@@ -115,6 +55,15 @@ impl Character for CharData {
         self.action = action;
     }
 
+    /// Establece los comandos activos del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `commands` - Los nombres de los comandos activos en este frame.
+    fn set_commands(&mut self, commands: Vec<String>) {
+        self.commands = commands;
+    }
+
     /// Establece la dirección del personaje.
     ///
     /// # Argumentos
@@ -133,11 +82,13 @@ impl Character for CharData {
         self.direction
     }
 
-    /// Actualiza los datos del personaje en base a unas condiciones.
+    /// Actualiza los datos del personaje que dependen del paso del tiempo.
     ///
-    /// Esta función actualiza los datos del personaje según las condiciones actuales.
+    /// Se llama una vez por tick antes de ejecutar sus estados, y descuenta el tiempo de invulnerabilidad.
     fn update_data(&mut self) {
-        char::trigger(self);
+        if self.not_hit_by_time > 0 {
+            self.not_hit_by_time -= 1;
+        }
     }
 
     /// Establece la distancia del personaje respecto a otro.
@@ -149,13 +100,23 @@ impl Character for CharData {
         self.distance = distance;
     }
 
-    /// Establece el número de estado del personaje.
+    /// Establece el número de estado del personaje, que se vuelve a entrar aunque sea el mismo.
     ///
     /// # Argumentos
     ///
     /// * `number` - El número de estado del personaje.
     fn set_state_no(&mut self, number: i32) {
         self.state_no = number;
+        self.state_changed = true;
+    }
+
+    /// Indica si se ha cambiado de estado desde la última consulta y lo olvida.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si se ha establecido un estado con `set_state_no`.
+    fn take_state_changed(&mut self) -> bool {
+        std::mem::take(&mut self.state_changed)
     }
 
     /// Obtiene el número de estado actual del personaje.
@@ -212,6 +173,15 @@ impl Character for CharData {
         self.anim_elem = element;
     }
 
+    /// Establece el elemento de la animación que empieza a mostrarse en el siguiente tick.
+    ///
+    /// # Argumentos
+    ///
+    /// * `element` - El elemento que empieza, o -1 si sigue el mismo.
+    fn set_anim_elem_start(&mut self, element: i32) {
+        self.anim_elem_start = element;
+    }
+
     /// Obtiene si la animación es nueva.
     ///
     /// # Retorna
@@ -373,8 +343,8 @@ impl Character for CharData {
         if result < 0 {
             result = 0;
         }
-        if result > POWER as i32 {
-            result = POWER as i32;
+        if result > self.char_def.data.power {
+            result = self.char_def.data.power;
         }
         self.power = result as u32;
     }
@@ -385,11 +355,12 @@ impl Character for CharData {
     ///
     /// * `x` - La cantidad de posición en el eje `x` a añadir al personaje.
     ///
-    /// Se tiene en cuenta las colisiones con las paredes.
+    /// Se tiene en cuenta las colisiones con las paredes, que son los bordes de la pantalla.
     fn add_pos_x(&mut self, x: f64) {
         if self.current_flip {
             self.x -= x;
-            if x > 0.0 && self.x < self.offset_x || x < 0.0 && self.x > WINDOW_SIZE - self.offset_x
+            if x > 0.0 && self.x < self.bounds.0 + self.offset_x
+                || x < 0.0 && self.x > self.bounds.1 - self.offset_x
             {
                 self.wall = true;
                 self.x += x;
@@ -398,7 +369,8 @@ impl Character for CharData {
             }
         } else {
             self.x += x;
-            if x < 0.0 && self.x < self.offset_x || x > 0.0 && self.x > WINDOW_SIZE - self.offset_x
+            if x < 0.0 && self.x < self.bounds.0 + self.offset_x
+                || x > 0.0 && self.x > self.bounds.1 - self.offset_x
             {
                 self.wall = true;
                 self.x -= x;
@@ -427,32 +399,59 @@ impl Character for CharData {
     }
 
     /// Actualiza las posición del personaje. <br>
-    /// En base a la posición actual y estado, se le aplica un coeficiente de fricción con el suelo
-    /// a la vez que se detecta cuando choca contra una pared o cuanto tiempo está en el aire.
+    /// Se mueve según su velocidad y después, si está en el suelo, se le aplica la fricción de pie o agachado, a la
+    /// vez que se detecta cuando choca contra una pared o cuanto tiempo está en el aire.
     fn update_pos(&mut self) {
+        if self.wall && self.vel_x < 0.0 && self.state_no > 4999 {
+            self.vel_x = -self.vel_x;
+        }
+        self.add_pos_x(self.vel_x);
+        self.y += self.vel_y;
         if self.state != State::A {
             self.air_time = 0;
-            self.vel_x *= CROUCH_FRICTION;
-            if self.vel_x.abs() < CROUCH_FRICTION_THRESHOLD {
+            let movement = &self.char_def.movement;
+            let (friction, threshold) = if self.state == State::S {
+                (movement.stand_friction, movement.stand_friction_threshold)
+            } else {
+                (movement.crouch_friction, movement.crouch_friction_threshold)
+            };
+            self.vel_x *= friction;
+            if self.vel_x.abs() < threshold {
                 self.vel_x = 0.0;
             }
         } else {
             self.air_time += 1;
         }
-        if self.wall && self.vel_x < 0.0 && self.state_no > 4999 {
-            self.vel_x = -self.vel_x;
-        }
-        self.add_pos_x(self.vel_x);
-        self.y += self.vel_y;
     }
 
-    /// Maneja el impacto entre personajes.
+    /// Maneja el impacto entre personajes aplicando el golpe activo, que se consume al alcanzar al objetivo.
+    ///
+    /// El golpe no alcanza a un objetivo tirado en el suelo ni a uno invulnerable a los ataques del tipo de estado
+    /// del personaje. Si el objetivo no se defiende, el personaje pasa al estado indicado por el golpe.
     ///
     /// # Argumentos
     ///
     /// * `char_target` - El personaje objetivo del impacto.
-    fn hit_handler(&mut self, char_target: &mut dyn Character) {
-        char::hit_handler(self, char_target);
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el golpe ha alcanzado al objetivo, `false` en caso contrario.
+    fn hit_handler(&mut self, char_target: &mut dyn Character) -> bool {
+        if char_target.get_state() == &State::L || !char_target.is_hit_by(&self.attack) {
+            return false;
+        }
+        let Some(hit_def) = self.hit_def.take() else {
+            return false;
+        };
+        let blocking = hit_def.is_guarded(char_target);
+        hit_def.apply(self, char_target, blocking);
+        if let Some(sound) = hit_def.sound(blocking) {
+            self.sounds.push(sound);
+        }
+        if let (false, Some(state_no)) = (blocking, hit_def.p1_state_no()) {
+            self.set_state_no(state_no);
+        }
+        true
     }
 
     /// Obtiene el estado actual del personaje.
@@ -509,13 +508,13 @@ impl Character for CharData {
         self.anim_elem
     }
 
-    /// Obtiene si el personaje está volteado horizontalmente.
+    /// Obtiene el elemento de la animación que empieza a mostrarse en este tick.
     ///
     /// # Retorna
     ///
-    /// `true` si el personaje está volteado horizontalmente, `false` en caso contrario.
-    fn get_flip_x(&self) -> bool {
-        self.flip_x
+    /// El elemento que empieza, o -1 si sigue el mismo que en el tick anterior.
+    fn get_anim_elem_start(&self) -> i32 {
+        self.anim_elem_start
     }
 
     /// Establece si el personaje está en estado de caída.
@@ -527,6 +526,15 @@ impl Character for CharData {
         self.fall = fall;
     }
 
+    /// Obtiene si el personaje está en estado de caída.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el último golpe recibido derriba al personaje, `false` en caso contrario.
+    fn get_fall(&self) -> bool {
+        self.fall
+    }
+
     /// Establece el estado actual del personaje.
     ///
     /// # Argumentos
@@ -554,12 +562,12 @@ impl Character for CharData {
     /// Los límites de la vida van del 0 a la definida por el personaje. Si llega a 0 el personaje a perdido.
     fn add_life(&mut self, life: i32) {
         let tmp_life = self.life + life;
-        if tmp_life < LIFE && tmp_life > 0 {
+        if tmp_life < self.char_def.data.life && tmp_life > 0 {
             self.life = tmp_life;
             return;
         }
-        if tmp_life > LIFE {
-            self.life = LIFE;
+        if tmp_life > self.char_def.data.life {
+            self.life = self.char_def.data.life;
             return;
         }
         if tmp_life < 1 {
@@ -572,6 +580,17 @@ impl Character for CharData {
         }
     }
 
+    /// Multiplica la vida máxima del personaje y lo deja con la vida llena.
+    ///
+    /// # Argumentos
+    ///
+    /// * `multiplier` - El multiplicador de la vida definida por el personaje.
+    fn set_life_multiplier(&mut self, multiplier: f64) {
+        self.char_def.data.life =
+            ((self.char_def.data.life as f64 * multiplier).round() as i32).max(1);
+        self.life = self.char_def.data.life;
+    }
+
     /// Establece si el personaje está en estado defensivo.
     ///
     /// # Argumentos
@@ -599,6 +618,24 @@ impl Character for CharData {
         self.lose = lose;
     }
 
+    /// Obtiene si el personaje ha ganado.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el personaje ha ganado, `false` en caso contrario.
+    fn get_win(&self) -> bool {
+        self.win
+    }
+
+    /// Obtiene si el personaje ha perdido.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el personaje ha perdido, `false` en caso contrario.
+    fn get_lose(&self) -> bool {
+        self.lose
+    }
+
     /// Establece el ancho del personaje.
     ///
     /// # Argumentos
@@ -608,6 +645,16 @@ impl Character for CharData {
         self.width = width;
     }
 
+    /// Establece los límites en la X en los que se puede mover el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `left` - Límite izquierdo en coordenadas del escenario.
+    /// * `right` - Límite derecho en coordenadas del escenario.
+    fn set_bounds(&mut self, left: f64, right: f64) {
+        self.bounds = (left, right);
+    }
+
     /// Verifica si el personaje está en contacto con una pared.
     ///
     /// # Retorna
@@ -616,9 +663,188 @@ impl Character for CharData {
     fn get_wall(&self) -> bool {
         self.wall
     }
+
+    /// Establece la animación del personaje y la marca como nueva.
+    ///
+    /// # Argumentos
+    ///
+    /// * `anim` - El número de la animación.
+    ///
+    /// El tiempo restante de la animación se invalida hasta que se vuelva a calcular, y el elemento vuelve al primero.
+    fn set_anim(&mut self, anim: i32) {
+        self.anim = anim;
+        self.new_anim = true;
+        self.anim_time = -1;
+        self.anim_elem = 0;
+        self.anim_elem_start = 0;
+    }
+
+    /// Obtiene el tiempo que le queda a la animación.
+    ///
+    /// # Retorna
+    ///
+    /// Los ticks restantes de la animación.
+    fn get_anim_time(&self) -> i32 {
+        self.anim_time
+    }
+
+    /// Establece si el personaje tiene control.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ctrl` - Indica si el personaje tiene control.
+    fn set_ctrl(&mut self, ctrl: bool) {
+        self.ctrl = ctrl;
+    }
+
+    /// Obtiene la acción actual del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// El nombre de la acción actual.
+    fn get_action(&self) -> &str {
+        &self.action
+    }
+
+    /// Obtiene los comandos activos del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Los nombres de los comandos activos en este frame.
+    fn get_commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// Obtiene el tiempo que lleva el personaje en el estado actual.
+    ///
+    /// # Retorna
+    ///
+    /// Los ticks que lleva en el estado.
+    fn get_state_time(&self) -> i32 {
+        self.state_time
+    }
+
+    /// Establece el tiempo que lleva el personaje en el estado actual.
+    ///
+    /// # Argumentos
+    ///
+    /// * `time` - Los ticks que lleva en el estado.
+    fn set_state_time(&mut self, time: i32) {
+        self.state_time = time;
+    }
+
+    /// Establece el estado con el que ataca el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `attack` - El estado con el que ataca.
+    fn set_attack(&mut self, attack: State) {
+        self.attack = attack;
+    }
+
+    /// Obtiene el poder del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// El poder actual del personaje.
+    fn get_power(&self) -> i32 {
+        self.power as i32
+    }
+
+    /// Obtiene la velocidad en el eje `x` del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// La velocidad en el eje `x`.
+    fn get_vel_x(&self) -> f64 {
+        self.vel_x
+    }
+
+    /// Obtiene la velocidad en el eje `y` del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// La velocidad en el eje `y`.
+    fn get_vel_y(&self) -> f64 {
+        self.vel_y
+    }
+
+    /// Establece el golpe activo del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `hit_def` - El golpe activo, o `None` si no hay.
+    fn set_hit_def(&mut self, hit_def: Option<HitDef>) {
+        self.hit_def = hit_def;
+    }
+
+    /// Añade una orden de sonido pendiente de reproducir.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sound` - Orden de sonido.
+    fn push_sound(&mut self, sound: SoundCommand) {
+        self.sounds.push(sound);
+    }
+
+    /// Obtiene y vacía las órdenes de sonido pendientes.
+    ///
+    /// # Retorna
+    ///
+    /// Las órdenes de sonido en el orden en que se añadieron.
+    fn take_sounds(&mut self) -> Vec<SoundCommand> {
+        std::mem::take(&mut self.sounds)
+    }
+
+    /// Obtiene el valor de una variable del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `index` - El número de la variable.
+    ///
+    /// # Retorna
+    ///
+    /// El valor de la variable.
+    fn get_var(&self, index: usize) -> i32 {
+        self.vars[index]
+    }
+
+    /// Establece el valor de una variable del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `index` - El número de la variable.
+    /// * `value` - El nuevo valor.
+    fn set_var(&mut self, index: usize, value: i32) {
+        self.vars[index] = value;
+    }
+
+    /// Hace al personaje invulnerable a los ataques de ciertos tipos de estado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `state_types` - Los tipos de estado de los ataques que no le alcanzan.
+    /// * `time` - Los ticks que dura la invulnerabilidad.
+    fn set_not_hit_by(&mut self, state_types: Vec<State>, time: i32) {
+        self.not_hit_by = state_types;
+        self.not_hit_by_time = time;
+    }
+
+    /// Comprueba si al personaje le puede alcanzar un ataque.
+    ///
+    /// # Argumentos
+    ///
+    /// * `attack` - El tipo de estado con el que ataca el oponente.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el ataque le puede alcanzar, `false` si es invulnerable a él.
+    fn is_hit_by(&self, attack: &State) -> bool {
+        self.not_hit_by_time <= 0 || !self.not_hit_by.contains(attack)
+    }
 }
 
-/// Estructura que representa los datos de un personaje.
+/// Estructura que representa los datos de un personaje. Sus estados salen de sus archivos CNS, así que sirve para cualquier personaje.
 pub struct CharData {
     //name: String,
     /// Vida del personaje
@@ -635,21 +861,21 @@ pub struct CharData {
     state_no: i32,
     /// Tiempo en el aire
     air_time: i32,
-    /// Capacidad de doble salto
-    double_jump: bool,
-    /// Estado de correr
-    run: bool,
     /// Animación que anda reproduciendose
     anim: i32,
     //fx: u32,
     /// Nombre de la acción a realizar
     action: String,
+    /// Comandos activos en este frame
+    commands: Vec<String>,
     /// Tiempo total en un anim elemen
     time: i32,
     /// Tiempo total de la animación
     anim_time: i32,
     /// Elemento de la animación
     anim_elem: i32,
+    /// Elemento de la animación que empieza a mostrarse en este tick, -1 si sigue el mismo
+    anim_elem_start: i32,
     /// Estado de nueva animación
     new_anim: bool,
     /// Desplazamiento en la X del sprite relativa a la del escenario
@@ -670,14 +896,10 @@ pub struct CharData {
     def: bool,
     /// Dirección en la que se está movimiendo
     direction: u8,
-    /// Número de saltos realizados
-    jumps: i32,
     /// Estado anterior de giro horizontal
     previous_flip: bool,
     /// Estado actual de giro horizontal
     current_flip: bool,
-    /// Estado de giro horizontal que afecta solo al sprite
-    flip_x: bool,
     /// Distancia respecto al jugador 2
     distance: f64,
     /// Estado número con el que ha sido golpeado
@@ -690,7 +912,107 @@ pub struct CharData {
     lose: bool,
     /// Estado de choque con una pared
     wall: bool,
+    /// Límites izquierdo y derecho en la X, marcados por los bordes de la pantalla
+    bounds: (f64, f64),
+    /// Tiempo que lleva en el estado actual
+    state_time: i32,
+    /// Indica si se ha establecido un estado que la máquina de estados aún no ha empezado
+    state_changed: bool,
+    /// Golpe activo definido por el estado actual
+    hit_def: Option<HitDef>,
+    /// Órdenes de sonido pendientes de reproducir
+    sounds: Vec<SoundCommand>,
+    /// Variables enteras que usan sus estados
+    vars: [i32; NUM_VARS],
+    /// Tipos de estado de los ataques a los que es invulnerable
+    not_hit_by: Vec<State>,
+    /// Ticks que le quedan de invulnerabilidad
+    not_hit_by_time: i32,
+    /// Definición del personaje leída de su archivo DEF
+    char_def: CharDef,
+}
+
+impl CharData {
+    /// Crea un nuevo `CharData` a partir de la definición de un personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `def` - Definición del personaje con sus constantes.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `CharData` con la vida y el poder de la definición.
+    pub fn from_def(def: CharDef) -> Self {
+        Self {
+            life: def.data.life,
+            power: def.data.power as u32,
+            state: State::S,
+            attack: State::S,
+            ctrl: true,
+            state_no: 0,
+            air_time: 0,
+            anim: 0,
+            //fx: 0,
+            action: "".to_string(),
+            commands: Vec::new(),
+            time: 0,
+            anim_time: 0,
+            anim_elem: 0,
+            anim_elem_start: 0,
+            new_anim: true,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            width: 0,
+            x: 0.0,
+            y: GROUND_Y,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            def: true,
+            direction: 5,
+            previous_flip: false,
+            current_flip: false,
+            distance: -1.0,
+            hit: 0,
+            fall: false,
+            win: false,
+            lose: false,
+            wall: false,
+            bounds: (f64::NEG_INFINITY, f64::INFINITY),
+            state_time: 0,
+            state_changed: false,
+            hit_def: None,
+            sounds: Vec::new(),
+            vars: [0; NUM_VARS],
+            not_hit_by: Vec::new(),
+            not_hit_by_time: 0,
+            char_def: def,
+        }
+    }
 }
 
-pub mod char;
-pub mod constants;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba de la fricción de pie y agachado, aplicada después de mover al personaje
+    #[test]
+    fn test_friction() {
+        let mut char = CharData::from_def(CharDef::new());
+        let x = char.get_x();
+        char.set_state(State::S);
+        char.set_vel_x(4.0);
+        char.update_pos();
+        assert_eq!(char.get_x(), x + 4.0);
+        assert_eq!(char.get_vel_x(), 4.0 * 0.85);
+
+        // Por debajo del umbral de pie se detiene
+        char.set_vel_x(2.2);
+        char.update_pos();
+        assert_eq!(char.get_vel_x(), 0.0);
+
+        char.set_state(State::C);
+        char.set_vel_x(2.2);
+        char.update_pos();
+        assert_eq!(char.get_vel_x(), 2.2 * 0.82);
+    }
+}
@@ -1,131 +1,152 @@
+[Defaults]
+command.time = 15
+command.buffer.time = 1
+
 [Command]
 name = "TripleKFPalm"
-command = D, DF, F, D, DF, F, LP
+command = D, DF, F, D, DF, F, x
 time = 20
 
 [Command]
 name = "TripleKFPalm"
-command = D, DF, F, D, DF, F, MP
+command = D, DF, F, D, DF, F, y
 time = 20
 
 [Command]
 name = "SmashKFUpper"
-command = D, DB, B, D, DB, B, LP
+command = D, DB, B, D, DB, B, x
 time = 20
 
 [Command]
 name = "SmashKFUpper"
-command = F, D, DF, F, D, DF, LP
+command = F, D, DF, F, D, DF, x
 time = 20
 
 [Command]
 name = "SmashKFUpper"
-command = D, DB, B, D, DB, B, MP
+command = D, DB, B, D, DB, B, y
 time = 20
 
 [Command]
 name = "SmashKFUpper"
-command = F, D, DF, F, D, DF, MP
+command = F, D, DF, F, D, DF, y
 time = 20
 
 ;-| Special Motions |------------------------------------------------------
 [Command]
 name = "blocking"
-command = $F,LP
+command = $F,x
 time = 3
 
 [Command]
 name = "upper_x"
-command = F, D, DF, LP
+command = F, D, DF, x
 
 [Command]
 name = "upper_y"
-command = F, D, DF, MP
+command = F, D, DF, y
 
 [Command]
 name = "upper_xy"
-command = F, D, DF, LP+MP
+command = F, D, DF, x+y
 
 [Command]
 name = "QCF_x"
-command = D, DF, F, LP
+command = D, DF, F, x
 
 [Command]
 name = "QCF_y"
-command = D, DF, F, MP
+command = D, DF, F, y
 
 [Command]
 name = "QCF_xy"
-command = D, DF, F, LP+MP
+command = D, DF, F, x+y
 
 [Command]
 name = "QCB_x"
-command = D, DB, B, LP
+command = D, DB, B, x
 
 [Command]
 name = "QCB_y"
-command = D, DB, B, MP
+command = D, DB, B, y
 
 [Command]
 name = "QCB_xy"
-command = D, DB, B, LP+MP
+command = D, DB, B, x+y
 
 [Command]
 name = "QCF_a"
-command = D, DF, F, LK
+command = D, DF, F, a
 
 [Command]
 name = "QCF_b"
-command = D, DF, F, MK
+command = D, DF, F, b
 
 [Command]
 name = "QCF_ab"
-command = D, DF, F, LK+MK
+command = D, DF, F, a+b
 
 [Command]
 name = "FF_ab"
-command = F, F, LK+MK
+command = F, F, a+b
 
 [Command]
 name = "FF_a"
-command = F, F, LK
+command = F, F, a
 
 [Command]
 name = "FF_b"
-command = F, F, MK
+command = F, F, b
+
+;-| Double Tap |------------------------------------------------------------
+[Command]
+name = "FF"
+command = F, F
+time = 10
+
+[Command]
+name = "BB"
+command = B, B
+time = 10
+
+;-| Dir |-------------------------------------------------------------------
+[Command]
+name = "up"
+command = $U
+time = 1
 
 ;-| Single Button |---------------------------------------------------------
 [Command]
 name = "a"
-command = LK
+command = a
 time = 1
 
 [Command]
 name = "b"
-command = MK
+command = b
 time = 1
 
 [Command]
 name = "c"
-command = HK
+command = c
 time = 1
 
 [Command]
 name = "x"
-command = LP
+command = x
 time = 1
 
 [Command]
 name = "y"
-command = MP
+command = y
 time = 1
 
 [Command]
 name = "z"
-command = HP
+command = z
 time = 1
 
 [Command]
 name = "start"
-command = Start
+command = s
 time = 1
\ No newline at end of file
@@ -1,14 +1,15 @@
-pub mod kfm;
+pub mod data;
+
+use crate::{
+    error::def_error::DefError,
+    player::character::{
+        cns::manager::{HitDef, SoundCommand},
+        def::manager::{self, CharDef},
+    },
+};
 
 /// Rasgo que representa las operaciones básicas de un personaje.
 pub trait Character {
-    /// Crea un nuevo `CharData` con valores predeterminados.
-    ///
-    /// # Retorna
-    ///
-    /// Devuelve una instancia de `CharData`.
-    fn new() -> Self where Self: Sized;
-
     /// Obtiene el número del estado que le ha impactado.
     ///
     /// # Retorna
@@ -23,33 +24,19 @@ pub trait Character {
     /// Retorna la vida del personaje como un porcentaje (0.0 - 100.0).
     fn get_life_as_percentage(&self) -> f64;
 
-    /// Obtiene el poder del personaje como un porcentaje.
+    /// Obtiene la definición del personaje (archivos y constantes).
     ///
     /// # Retorna
     ///
-    /// Retorna el poder del personaje como un porcentaje (0.0 - 100.0).
-    fn get_power_as_percentage(&self) -> f64;
+    /// Retorna la definición leída de su archivo DEF.
+    fn get_def(&self) -> &CharDef;
 
-    /// Obtiene el nombre del archivo AIR.
-    ///
-    /// # Retorna
-    ///
-    /// Retorna el nombre del archivo AIR.
-    fn get_air_name(&self) -> &'static str;
-
-    /// Obtiene el nombre del archivo SFF.
-    ///
-    /// # Retorna
-    ///
-    /// Retorna el nombre del archivo SFF.
-    fn get_sff_name(&self) -> &'static str;
-
-    /// Obtiene el nombre del archivo CMD.
+    /// Obtiene el poder del personaje como un porcentaje.
     ///
     /// # Retorna
     ///
-    /// Retorna el nombre del archivo CMD.
-    fn get_cmd_name(&self) -> &'static str;
+    /// Retorna el poder del personaje como un porcentaje (0.0 - 100.0).
+    fn get_power_as_percentage(&self) -> f64;
 
     /// Establece la acción actual del personaje.
     ///
@@ -58,6 +45,13 @@ pub trait Character {
     /// * `action` - La acción que se establecerá.
     fn set_action(&mut self, action: String);
 
+    /// Establece los comandos activos del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `commands` - Los nombres de los comandos activos en este frame.
+    fn set_commands(&mut self, commands: Vec<String>);
+
     /// Establece la dirección del personaje.
     ///
     /// # Argumentos
@@ -82,13 +76,20 @@ pub trait Character {
     /// * `distance` - La distancia entre el personaje y otro.
     fn set_distance(&mut self, distance: f64);
 
-    /// Establece el número de estado del personaje.
+    /// Establece el número de estado del personaje, que se vuelve a entrar aunque sea el mismo.
     ///
     /// # Argumentos
     ///
     /// * `number` - El número de estado que se establecerá.
     fn set_state_no(&mut self, number: i32);
 
+    /// Indica si se ha cambiado de estado desde la última consulta y lo olvida.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna `true` si se ha establecido un estado con `set_state_no`.
+    fn take_state_changed(&mut self) -> bool;
+
     /// Obtiene el número de estado actual del personaje.
     ///
     /// # Retorna
@@ -131,6 +132,13 @@ pub trait Character {
     /// * `element` - El elemento de la animación.
     fn set_anim_element(&mut self, element: i32);
 
+    /// Establece el elemento de la animación que empieza a mostrarse en el siguiente tick.
+    ///
+    /// # Argumentos
+    ///
+    /// * `element` - El elemento que empieza, o -1 si sigue el mismo.
+    fn set_anim_elem_start(&mut self, element: i32);
+
     /// Obtiene si la animación es nueva.
     ///
     /// # Retorna
@@ -201,7 +209,7 @@ pub trait Character {
     /// Retorna true si el personaje está volteado horizontalmente, de lo contrario false.
     fn is_flipped(&self) -> bool;
 
-     /// Establece el desplazamiento `x` del sprite.
+    /// Establece el desplazamiento `x` del sprite.
     ///
     /// # Argumentos
     ///
@@ -282,7 +290,11 @@ pub trait Character {
     /// # Argumentos
     ///
     /// * `char_target` - El personaje objetivo del impacto.
-    fn hit_handler(&mut self, char_target: &mut dyn Character);
+    ///
+    /// # Retorna
+    ///
+    /// Retorna true si el golpe activo ha alcanzado al objetivo, de lo contrario false.
+    fn hit_handler(&mut self, char_target: &mut dyn Character) -> bool;
 
     /// Obtiene el estado actual del personaje.
     ///
@@ -326,12 +338,12 @@ pub trait Character {
     /// Retorna el elemento de la animación del personaje.
     fn get_anim_elem(&self) -> i32;
 
-    /// Obtiene si el personaje está volteado horizontalmente.
+    /// Obtiene el elemento de la animación que empieza a mostrarse en este tick.
     ///
     /// # Retorna
     ///
-    /// Retorna true si el personaje está volteado horizontalmente, de lo contrario false.
-    fn get_flip_x(&self) -> bool;
+    /// Retorna el elemento que empieza, o -1 si sigue el mismo que en el tick anterior.
+    fn get_anim_elem_start(&self) -> i32;
 
     /// Establece si el personaje está en estado de caída.
     ///
@@ -340,6 +352,13 @@ pub trait Character {
     /// * `fall` - Valor booleano que indica si el personaje está en estado de caída o no.
     fn set_fall(&mut self, fall: bool);
 
+    /// Obtiene si el personaje está en estado de caída.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna true si el último golpe recibido derriba al personaje, de lo contrario false.
+    fn get_fall(&self) -> bool;
+
     /// Establece el estado actual del personaje.<br>
     /// Puede ser de pie, en el aire, tirado y agachado.
     ///
@@ -362,7 +381,14 @@ pub trait Character {
     /// * `life` - La cantidad de vida que se sumará al personaje.
     fn add_life(&mut self, life: i32);
 
-/// Establece si el personaje está en estado defensivo.
+    /// Multiplica la vida máxima del personaje y lo deja con la vida llena.
+    ///
+    /// # Argumentos
+    ///
+    /// * `multiplier` - El multiplicador de la vida definida por el personaje.
+    fn set_life_multiplier(&mut self, multiplier: f64);
+
+    /// Establece si el personaje está en estado defensivo.
     ///
     /// # Argumentos
     ///
@@ -383,6 +409,20 @@ pub trait Character {
     /// * `lose` - Un booleano que indica si el personaje ha perdido (`true`) o no (`false`).
     fn set_lose(&mut self, lose: bool);
 
+    /// Obtiene si el personaje ha ganado.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna true si el personaje ha ganado el combate, de lo contrario false.
+    fn get_win(&self) -> bool;
+
+    /// Obtiene si el personaje ha perdido.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna true si el personaje ha perdido el combate, de lo contrario false.
+    fn get_lose(&self) -> bool;
+
     /// Establece el ancho del personaje.
     ///
     /// # Argumentos
@@ -390,15 +430,159 @@ pub trait Character {
     /// * `width` - El ancho del personaje como un entero sin signo de 16 bits.
     fn set_width(&mut self, width: u16);
 
+    /// Establece los límites en la X en los que se puede mover el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `left` - Límite izquierdo en coordenadas del escenario.
+    /// * `right` - Límite derecho en coordenadas del escenario.
+    fn set_bounds(&mut self, left: f64, right: f64);
+
     /// Verifica si el personaje está en contacto con una pared.
     ///
     /// # Retorna
     ///
     /// Un booleano que indica si el personaje está en contacto con una pared (`true`) o no (`false`).
     fn get_wall(&self) -> bool;
+
+    /// Establece la animación del personaje y la marca como nueva.
+    ///
+    /// # Argumentos
+    ///
+    /// * `anim` - El número de la animación.
+    fn set_anim(&mut self, anim: i32);
+
+    /// Obtiene el tiempo que le queda a la animación.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna los ticks restantes de la animación.
+    fn get_anim_time(&self) -> i32;
+
+    /// Establece si el personaje tiene control.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ctrl` - Indica si el personaje tiene control.
+    fn set_ctrl(&mut self, ctrl: bool);
+
+    /// Obtiene la acción actual del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna el nombre de la acción (comando) actual.
+    fn get_action(&self) -> &str;
+
+    /// Obtiene los comandos activos del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Los nombres de los comandos activos en este frame.
+    fn get_commands(&self) -> &[String];
+
+    /// Obtiene el tiempo que lleva el personaje en el estado actual.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna los ticks que lleva en el estado.
+    fn get_state_time(&self) -> i32;
+
+    /// Establece el tiempo que lleva el personaje en el estado actual.
+    ///
+    /// # Argumentos
+    ///
+    /// * `time` - Los ticks que lleva en el estado.
+    fn set_state_time(&mut self, time: i32);
+
+    /// Establece el estado con el que ataca el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `attack` - El estado con el que ataca.
+    fn set_attack(&mut self, attack: State);
+
+    /// Obtiene el poder del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna el poder actual del personaje.
+    fn get_power(&self) -> i32;
+
+    /// Obtiene la velocidad en el eje `x` del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna la velocidad en el eje `x`.
+    fn get_vel_x(&self) -> f64;
+
+    /// Obtiene la velocidad en el eje `y` del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna la velocidad en el eje `y`.
+    fn get_vel_y(&self) -> f64;
+
+    /// Establece el golpe activo del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `hit_def` - El golpe activo, o `None` si no hay.
+    fn set_hit_def(&mut self, hit_def: Option<HitDef>);
+
+    /// Añade una orden de sonido pendiente de reproducir.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sound` - Orden de sonido.
+    fn push_sound(&mut self, sound: SoundCommand);
+
+    /// Obtiene y vacía las órdenes de sonido pendientes.
+    ///
+    /// # Retorna
+    ///
+    /// Las órdenes de sonido en el orden en que se añadieron.
+    fn take_sounds(&mut self) -> Vec<SoundCommand>;
+
+    /// Obtiene el valor de una variable del personaje (`var(n)`).
+    ///
+    /// # Argumentos
+    ///
+    /// * `index` - El número de la variable, menor que `NUM_VARS`.
+    ///
+    /// # Retorna
+    ///
+    /// El valor de la variable.
+    fn get_var(&self, index: usize) -> i32;
+
+    /// Establece el valor de una variable del personaje (`var(n)`).
+    ///
+    /// # Argumentos
+    ///
+    /// * `index` - El número de la variable, menor que `NUM_VARS`.
+    /// * `value` - El nuevo valor.
+    fn set_var(&mut self, index: usize, value: i32);
+
+    /// Hace al personaje invulnerable a los ataques de ciertos tipos de estado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `state_types` - Los tipos de estado de los ataques que no le alcanzan.
+    /// * `time` - Los ticks que dura la invulnerabilidad.
+    fn set_not_hit_by(&mut self, state_types: Vec<State>, time: i32);
+
+    /// Comprueba si al personaje le puede alcanzar un ataque.
+    ///
+    /// # Argumentos
+    ///
+    /// * `attack` - El tipo de estado con el que ataca el oponente.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna true si el ataque le puede alcanzar, de lo contrario false.
+    fn is_hit_by(&self, attack: &State) -> bool;
 }
 
-/// Obtiene un personaje basado en su nombre.
+/// Obtiene un personaje basado en su nombre, leyendo el archivo DEF de su carpeta (`src/chars/<nombre>/<nombre>.def`).
 ///
 /// # Argumentos
 ///
@@ -406,22 +590,15 @@ pub trait Character {
 ///
 /// # Retorna
 ///
-/// Un `Option` que contiene una caja (`Box`) que envuelve a un rasgo (`trait`) de personaje (`Character`)
-/// si se encuentra un personaje con el nombre especificado, o `None` si no se encuentra ningún personaje.
-pub fn get_char(char_name: &str) -> Option<Box<dyn Character>> {
-    match char_name {
-        "kfm" => {
-            let kfm: kfm::CharData = Character::new();
-            Some(Box::new(kfm))
-        }
-        _ => {
-            None
-        }
-    }
+/// Un `Result` que contiene una caja (`Box`) que envuelve a un rasgo (`trait`) de personaje (`Character`)
+/// creado con la definición leída, o un `DefError` si no se encuentra o está malformada.
+pub fn get_char(char_name: &str) -> Result<Box<dyn Character>, DefError> {
+    let def = manager::parse_def(&format!("src/chars/{}/{}.def", char_name, char_name))?;
+    Ok(Box::new(data::CharData::from_def(def)))
 }
 
 /// Estados posibles de un personaje.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum State {
     /// Stand (de pie)
     S, 
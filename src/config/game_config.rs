@@ -0,0 +1,340 @@
+use std::{fs, ops::RangeInclusive, path::Path};
+
+use crate::error::{config_error::ConfigError, pop_up::show_error_popup};
+
+/// Ruta del archivo de configuración del juego.
+pub const GAME_CFG: &str = "config/mugen.cfg";
+
+/// Lado de la ventana sin escalar, en píxeles.
+pub const WINDOW_SIZE: f64 = 512.0;
+
+/// Rondas que se pueden pedir para ganar la pelea.
+pub const ROUNDS_TO_WIN: RangeInclusive<u32> = 1..=9;
+/// Duraciones posibles de una ronda, en segundos.
+pub const ROUND_TIME: RangeInclusive<u32> = 10..=99;
+/// Duraciones posibles de la cuenta regresiva, en segundos.
+pub const COUNTDOWN: RangeInclusive<u32> = 0..=9;
+/// Velocidades posibles del juego, en frames por segundo.
+pub const GAME_SPEED: RangeInclusive<u32> = 30..=120;
+/// Porcentajes posibles de vida.
+pub const LIFE: RangeInclusive<u32> = 10..=300;
+/// Escalas posibles de la ventana.
+pub const WINDOW_SCALE: RangeInclusive<u32> = 1..=4;
+/// Muestras de antialiasing (MSAA) posibles.
+pub const SAMPLES: [u8; 5] = [0, 2, 4, 8, 16];
+
+/// Configuración del juego: reglas de la pelea, velocidad y video.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    /// Rondas que hay que ganar para ganar la pelea
+    pub rounds_to_win: u32,
+    /// Duración de cada ronda, en segundos
+    pub round_time: u32,
+    /// Duración de la cuenta regresiva antes de cada ronda, en segundos
+    pub countdown: u32,
+    /// Actualizaciones de la lógica del juego por segundo
+    pub game_speed: u32,
+    /// Vida de los personajes, en porcentaje de la definida en su archivo
+    pub life: u32,
+    /// Vida del jugador que pelea solo contra un equipo, en porcentaje (se guarda para cuando haya equipos)
+    pub team_1vs2_life: u32,
+    /// Indica si un equipo pierde cuando cae cualquiera de sus miembros (se guarda para cuando haya equipos)
+    pub team_lose_on_ko: bool,
+    /// Escala de la ventana
+    pub window_scale: u32,
+    /// Indica si se sincroniza el dibujo con el refresco del monitor
+    pub vsync: bool,
+    /// Muestras de antialiasing (MSAA)
+    pub samples: u8,
+}
+
+impl Default for GameConfig {
+    /// Crea la configuración por defecto.
+    ///
+    /// # Retorna
+    ///
+    /// Una configuración con los valores por defecto de MUGEN.
+    fn default() -> Self {
+        Self {
+            rounds_to_win: 2,
+            round_time: 99,
+            countdown: 3,
+            game_speed: 60,
+            life: 100,
+            team_1vs2_life: 150,
+            team_lose_on_ko: false,
+            window_scale: 1,
+            vsync: false,
+            samples: 8,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Carga la configuración del juego, usando la configuración por defecto si el archivo no
+    /// existe o no se puede leer. Los errores de lectura se muestran en una ventana emergente.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del archivo de configuración.
+    ///
+    /// # Retorna
+    ///
+    /// La configuración cargada o la de por defecto.
+    pub fn load_or_default(path: &str) -> Self {
+        match load_game_config(path) {
+            Ok(config) => config,
+            Err(ConfigError::NotFound(_)) => Self::default(),
+            Err(err) => {
+                show_error_popup(&err);
+                Self::default()
+            }
+        }
+    }
+
+    /// Obtiene el tamaño de la ventana con la escala configurada.
+    ///
+    /// # Retorna
+    ///
+    /// El ancho y el alto de la ventana en píxeles.
+    pub fn window_size(&self) -> [f64; 2] {
+        [WINDOW_SIZE * self.window_scale as f64; 2]
+    }
+
+    /// Asigna una opción leída del archivo de configuración.
+    ///
+    /// # Argumentos
+    ///
+    /// * `section` - La sección de la opción, en minúsculas.
+    /// * `name` - El nombre de la opción, en minúsculas.
+    /// * `value` - El valor de la opción.
+    ///
+    /// # Retorna
+    ///
+    /// `false` si el valor no es válido; las opciones desconocidas se ignoran.
+    fn set_option(&mut self, section: &str, name: &str, value: &str) -> bool {
+        match (section, name) {
+            ("options", "match.wins") => {
+                parse_in_range(value, ROUNDS_TO_WIN).map(|wins| self.rounds_to_win = wins)
+            }
+            ("options", "time") => {
+                parse_in_range(value, ROUND_TIME).map(|time| self.round_time = time)
+            }
+            ("options", "countdown") => {
+                parse_in_range(value, COUNTDOWN).map(|countdown| self.countdown = countdown)
+            }
+            ("options", "life") => parse_in_range(value, LIFE).map(|life| self.life = life),
+            ("options", "team.1vs2life") => {
+                parse_in_range(value, LIFE).map(|life| self.team_1vs2_life = life)
+            }
+            ("options", "team.loseonko") => {
+                parse_bool(value).map(|lose_on_ko| self.team_lose_on_ko = lose_on_ko)
+            }
+            ("config", "gamespeed") => {
+                parse_in_range(value, GAME_SPEED).map(|speed| self.game_speed = speed)
+            }
+            ("video", "scale") => {
+                parse_in_range(value, WINDOW_SCALE).map(|scale| self.window_scale = scale)
+            }
+            ("video", "vretrace") => parse_bool(value).map(|vsync| self.vsync = vsync),
+            ("video", "samples") => value
+                .parse()
+                .ok()
+                .filter(|samples| SAMPLES.contains(samples))
+                .map(|samples| self.samples = samples),
+            _ => Some(()),
+        }
+        .is_some()
+    }
+}
+
+/// Lee un número dentro de un rango.
+///
+/// # Argumentos
+///
+/// * `value` - El texto a leer.
+/// * `range` - Los valores aceptados.
+///
+/// # Retorna
+///
+/// El número, o `None` si no es un número o está fuera del rango.
+fn parse_in_range(value: &str, range: RangeInclusive<u32>) -> Option<u32> {
+    value.parse().ok().filter(|number| range.contains(number))
+}
+
+/// Lee un valor booleano escrito como `0` o `1`.
+///
+/// # Argumentos
+///
+/// * `value` - El texto a leer.
+///
+/// # Retorna
+///
+/// El valor, o `None` si no es `0` ni `1`.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+/// Carga la configuración del juego.
+///
+/// El archivo sigue el formato de `mugen.cfg`: secciones `[Options]`, `[Config]` y `[Video]` con
+/// una línea `<opción> = <valor>` por opción; `;` inicia un comentario. Las opciones que no
+/// aparecen conservan su valor por defecto y las desconocidas se ignoran.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo de configuración.
+///
+/// # Retorna
+///
+/// La configuración, o un error si el archivo no existe o tiene un valor no válido.
+pub fn load_game_config(path: &str) -> Result<GameConfig, ConfigError> {
+    if !Path::new(path).exists() {
+        return Err(ConfigError::NotFound(path.to_string()));
+    }
+    let content = fs::read_to_string(path)?;
+    let mut config = GameConfig::default();
+    let mut section = String::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            section = line
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim()
+                .to_lowercase();
+            continue;
+        }
+        let (name, value) = line.split_once('=').ok_or(ConfigError::Malformed(i + 1))?;
+        if !config.set_option(&section, &name.trim().to_lowercase(), value.trim()) {
+            return Err(ConfigError::Malformed(i + 1));
+        }
+    }
+    Ok(config)
+}
+
+/// Guarda la configuración del juego, creando la carpeta si no existe.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo de configuración.
+/// * `config` - La configuración a guardar.
+///
+/// # Retorna
+///
+/// Un error si no se pudo escribir el archivo.
+pub fn save_game_config(path: &str, config: &GameConfig) -> Result<(), ConfigError> {
+    let content = format!(
+        "; Configuración del juego\n\
+         \n\
+         [Options]\n\
+         Life = {}\n\
+         Time = {}\n\
+         Match.Wins = {}\n\
+         Countdown = {}\n\
+         Team.1VS2Life = {}\n\
+         Team.LoseOnKO = {}\n\
+         \n\
+         [Config]\n\
+         GameSpeed = {}\n\
+         \n\
+         [Video]\n\
+         Scale = {}\n\
+         VRetrace = {}\n\
+         Samples = {}\n",
+        config.life,
+        config.round_time,
+        config.rounds_to_win,
+        config.countdown,
+        config.team_1vs2_life,
+        config.team_lose_on_ko as u8,
+        config.game_speed,
+        config.window_scale,
+        config.vsync as u8,
+        config.samples,
+    );
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba de guardar y volver a cargar la configuración
+    #[test]
+    fn test_save_and_load_game_config() {
+        let path = std::env::temp_dir()
+            .join("rust_fg_test_config")
+            .join("mugen.cfg");
+        let path = path.to_str().unwrap();
+        let config = GameConfig {
+            rounds_to_win: 3,
+            round_time: 60,
+            countdown: 0,
+            game_speed: 90,
+            life: 150,
+            team_1vs2_life: 200,
+            team_lose_on_ko: true,
+            window_scale: 2,
+            vsync: true,
+            samples: 4,
+        };
+        save_game_config(path, &config).unwrap();
+        assert_eq!(load_game_config(path).unwrap(), config);
+
+        std::fs::remove_dir_all(Path::new(path).parent().unwrap()).unwrap();
+        assert!(matches!(
+            load_game_config(path),
+            Err(ConfigError::NotFound(_))
+        ));
+    }
+
+    /// Prueba de la lectura de un archivo con opciones parciales, desconocidas y no válidas
+    #[test]
+    fn test_load_game_config() {
+        let path = std::env::temp_dir().join("rust_fg_test_load_config.cfg");
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "[Options]\nDifficulty = 4 ; sin CPU\nTIME = 45\n\n[P1 Keys]\nJump = Up\n\n[Video]\nVRetrace = 1\n",
+        )
+        .unwrap();
+        let config = load_game_config(path).unwrap();
+        assert_eq!(
+            config,
+            GameConfig {
+                round_time: 45,
+                vsync: true,
+                ..GameConfig::default()
+            }
+        );
+        assert_eq!(config.window_size(), [512.0; 2]);
+
+        std::fs::write(path, "[Options]\nMatch.Wins = 0\n").unwrap();
+        assert!(matches!(
+            load_game_config(path),
+            Err(ConfigError::Malformed(2))
+        ));
+        std::fs::write(path, "[Video]\nScale = 2\nSamples = 3\n").unwrap();
+        assert!(matches!(
+            load_game_config(path),
+            Err(ConfigError::Malformed(3))
+        ));
+        std::fs::write(path, "[Config]\nGameSpeed\n").unwrap();
+        assert!(matches!(
+            load_game_config(path),
+            Err(ConfigError::Malformed(2))
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
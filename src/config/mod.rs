@@ -0,0 +1 @@
+pub mod game_config;
\ No newline at end of file
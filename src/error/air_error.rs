@@ -1,6 +1,8 @@
 use std::{fmt, io};
 use std::error::Error;
 
+use crate::player::character::span::Span;
+
 /// Errores relacionados con la carga y manipulación de archivos AIR (Animation Information Report).
 #[derive(Debug)]
 pub enum AirError {
@@ -10,6 +12,32 @@ pub enum AirError {
     NotFound(String),
     /// Error que indica un formato incorrecto al intentar formatear un archivo AIR.
     BadFormat(fmt::Error),
+    /// Error que indica que el archivo está malformado, con la línea, la columna y el problema
+    Malformed {
+        line: u32,
+        column: usize,
+        message: String,
+    },
+}
+
+impl AirError {
+    /// Crea un error de formato en la posición de un texto del archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `span` - El texto con el problema.
+    /// * `message` - La descripción del problema.
+    ///
+    /// # Retorna
+    ///
+    /// Un `AirError::Malformed` con la línea y la columna del texto.
+    pub fn malformed(span: &Span, message: String) -> Self {
+        AirError::Malformed {
+            line: span.location_line(),
+            column: span.get_utf8_column(),
+            message,
+        }
+    }
 }
 
 impl fmt::Display for AirError {
@@ -28,6 +56,11 @@ impl fmt::Display for AirError {
             AirError::InvalidData(err) => err.fmt(f),
             AirError::BadFormat(err) => err.fmt(f),
             AirError::NotFound(file) =>  write!(f, "Air file not found: {}", file),
+            AirError::Malformed {
+                line,
+                column,
+                message,
+            } => write!(f, "Air file malformed on line {}, column {}: {}", line, column, message),
         }
     }
 }
@@ -1,14 +1,39 @@
 use std::fmt;
 use std::error::Error;
 
+use crate::player::character::span::Span;
 
 /// Errores relacionados con la gestión de comandos en el juego.
 #[derive(Debug)]
 pub enum CmdError {
     /// Error que indica que no se pudo encontrar el archivo de comando especificado.
     NotFound(String),
-    /// Error que indica que el archivo está malformado y en que línea
-    Malformed(usize),
+    /// Error que indica que el archivo está malformado, con la línea, la columna y el problema
+    Malformed {
+        line: u32,
+        column: usize,
+        message: String,
+    },
+}
+
+impl CmdError {
+    /// Crea un error de formato en la posición de un texto del archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `span` - El texto con el problema.
+    /// * `message` - La descripción del problema.
+    ///
+    /// # Retorna
+    ///
+    /// Un `CmdError::Malformed` con la línea y la columna del texto.
+    pub fn malformed(span: &Span, message: String) -> Self {
+        CmdError::Malformed {
+            line: span.location_line(),
+            column: span.get_utf8_column(),
+            message,
+        }
+    }
 }
 
 impl fmt::Display for CmdError {
@@ -24,7 +49,11 @@ impl fmt::Display for CmdError {
     /// Un resultado que indica si el formato fue exitoso o no.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            CmdError::Malformed(line) => write!(f, "Cmd command malformed on line {:?}", line),
+            CmdError::Malformed {
+                line,
+                column,
+                message,
+            } => write!(f, "Cmd file malformed on line {}, column {}: {}", line, column, message),
             CmdError::NotFound(file) =>  write!(f, "Cmd file not found: {}", file),
         }
     }
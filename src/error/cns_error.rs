@@ -0,0 +1,55 @@
+use std::fmt;
+use std::error::Error;
+
+/// Errores relacionados con la carga de archivos CNS (definición de estados del personaje).
+#[derive(Debug)]
+pub enum CnsError {
+    /// Error que indica que no se pudo encontrar el archivo CNS especificado.
+    NotFound(String),
+    /// Error que indica que el archivo está malformado y en que línea
+    Malformed(usize),
+    /// Error que indica que un controlador es desconocido y en que línea
+    UnknownController(String, usize),
+    /// Error que indica que la expresión de un disparador no es válida y en que línea
+    InvalidExpression(String, usize),
+}
+
+impl fmt::Display for CnsError {
+    /// Implementación de formato para mostrar los errores CNS.
+    ///
+    /// # Argumentos
+    ///
+    /// * `self` - La referencia al error CNS.
+    /// * `f` - El formateador utilizado para escribir el mensaje de error.
+    ///
+    /// # Retorna
+    ///
+    /// Un resultado que indica si el formato fue exitoso o no.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CnsError::NotFound(file) => write!(f, "Cns file not found: {}", file),
+            CnsError::Malformed(line) => write!(f, "Cns state malformed on line {:?}", line),
+            CnsError::UnknownController(name, line) => {
+                write!(f, "Cns unknown controller {} on line {:?}", name, line)
+            }
+            CnsError::InvalidExpression(err, line) => {
+                write!(f, "Cns invalid expression on line {:?}: {}", line, err)
+            }
+        }
+    }
+}
+
+impl Error for CnsError {
+    /// Implementación de método para obtener la fuente del error CNS.
+    ///
+    /// # Argumentos
+    ///
+    /// * `self` - La referencia al error CNS.
+    ///
+    /// # Retorna
+    ///
+    /// `None` porque no hay una fuente de error específica para los errores CNS.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
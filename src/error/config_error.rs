@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::{fmt, io};
+
+/// Errores relacionados con la carga y el guardado de la configuración.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Archivo de configuración no encontrado.
+    NotFound(String),
+    /// Error al leer o escribir el archivo.
+    Io(io::Error),
+    /// Línea malformada del archivo.
+    Malformed(usize),
+    /// Tecla asignada a más de una acción.
+    KeyConflict(String),
+}
+
+impl fmt::Display for ConfigError {
+    /// Formatea el error para visualización.
+    ///
+    /// # Argumentos
+    ///
+    /// * `f` - Formateador de texto.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna un resultado de formato.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NotFound(file) => write!(f, "Archivo de configuración no encontrado: {}", file),
+            ConfigError::Io(err) => err.fmt(f),
+            ConfigError::Malformed(line) => write!(f, "Configuración malformada en la línea {}", line),
+            ConfigError::KeyConflict(key) => write!(f, "La tecla {} está asignada más de una vez", key),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    /// Devuelve la fuente del error.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna una opción que contiene una referencia al error subyacente.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    /// Convierte un error de E/S en un error de configuración.
+    ///
+    /// # Argumentos
+    ///
+    /// * `error` - Error de E/S a convertir.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna el error de configuración resultante.
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+use std::error::Error;
+
+use crate::player::character::span::Span;
+
+/// Errores relacionados con la carga de archivos DEF (definición del personaje) y sus constantes.
+#[derive(Debug)]
+pub enum DefError {
+    /// Error que indica que no se pudo encontrar el archivo especificado.
+    NotFound(String),
+    /// Error que indica que el archivo está malformado, con el archivo, la línea, la columna y el problema
+    Malformed {
+        file: String,
+        line: u32,
+        column: usize,
+        message: String,
+    },
+    /// Error que indica que falta una entrada obligatoria en `[Files]`
+    MissingFile(String),
+}
+
+impl DefError {
+    /// Crea un error de formato en la posición de un texto del archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `file` - El nombre del archivo.
+    /// * `span` - El texto con el problema.
+    /// * `message` - La descripción del problema.
+    ///
+    /// # Retorna
+    ///
+    /// Un `DefError::Malformed` con la línea y la columna del texto.
+    pub fn malformed(file: &str, span: &Span, message: String) -> Self {
+        DefError::Malformed {
+            file: file.to_string(),
+            line: span.location_line(),
+            column: span.get_utf8_column(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for DefError {
+    /// Implementación de formato para mostrar los errores DEF.
+    ///
+    /// # Argumentos
+    ///
+    /// * `self` - La referencia al error DEF.
+    /// * `f` - El formateador utilizado para escribir el mensaje de error.
+    ///
+    /// # Retorna
+    ///
+    /// Un resultado que indica si el formato fue exitoso o no.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefError::NotFound(file) => write!(f, "Def file not found: {}", file),
+            DefError::Malformed {
+                file,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Def file {} malformed on line {}, column {}: {}",
+                file, line, column, message
+            ),
+            DefError::MissingFile(key) => write!(f, "Def file is missing the {} entry in [Files]", key),
+        }
+    }
+}
+
+impl Error for DefError {
+    /// Implementación de método para obtener la fuente del error DEF.
+    ///
+    /// # Argumentos
+    ///
+    /// * `self` - La referencia al error DEF.
+    ///
+    /// # Retorna
+    ///
+    /// `None` porque no hay una fuente de error específica para los errores DEF.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
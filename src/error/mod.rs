@@ -2,4 +2,9 @@ pub mod sff_error;
 pub mod pop_up;
 pub mod air_error;
 pub mod cmd_error;
-pub mod preload_error;
\ No newline at end of file
+pub mod preload_error;
+pub mod cns_error;
+pub mod def_error;
+pub mod snd_error;
+pub mod stage_error;
+pub mod config_error;
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 use std::{fmt, io};
 use std::error::Error;
 
-use crate::player::character::sff::decoder::Version;
+use crate::player::character::sff::decoder::{SpriteFormat, Version};
 
 /// Errores relacionados con la carga y manipulación de archivos SFF (Sprite File Format).
 #[derive(Debug)]
@@ -19,6 +19,14 @@ pub enum SffError {
     UnsupportedHeaderVersion(u8),
     /// Firma inválida.
     InvalidSignature,
+    /// Datos PNG de un sprite inválidos.
+    InvalidPng(String),
+    /// Archivo de paleta ACT inválido.
+    InvalidAct(PathBuf),
+    /// Sprite que no se puede guardar en el formato pedido.
+    UnencodableSprite([i16; 2], SpriteFormat),
+    /// Sprite con más o menos píxeles de los que indica su tamaño.
+    SizeMismatch([i16; 2]),
 }
 
 impl fmt::Display for SffError {
@@ -38,6 +46,10 @@ impl fmt::Display for SffError {
             SffError::UnsupportedVersion(v) => write!(f, "Versión no soportada: {:?}", v),
             SffError::InvalidData(err) => err.fmt(f),
             SffError::InvalidSignature => write!(f, "Firma inválida"),
+            SffError::InvalidPng(err) => write!(f, "PNG inválido: {}", err),
+            SffError::InvalidAct(file) => write!(f, "Paleta ACT inválida: {}", file.to_string_lossy()),
+            SffError::UnencodableSprite([group, number], format) => write!(f, "El sprite {},{} no se puede guardar en formato {:?}", group, number, format),
+            SffError::SizeMismatch([group, number]) => write!(f, "Los píxeles del sprite {},{} no coinciden con su tamaño", group, number),
             SffError::NotFound(file) =>  write!(f, "Archivo SFF no encontrado: {}", file.to_string_lossy()),
         }
     }
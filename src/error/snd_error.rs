@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+use std::{fmt, io};
+use std::error::Error;
+
+/// Errores relacionados con la carga de archivos SND (contenedor de sonidos).
+#[derive(Debug)]
+pub enum SndError {
+    /// Datos inválidos.
+    InvalidData(io::Error),
+    /// Archivo no encontrado.
+    NotFound(PathBuf),
+    /// Firma inválida.
+    InvalidSignature,
+    /// Sonido que no es un archivo WAV válido.
+    InvalidWav,
+    /// Formato de audio no soportado (solo PCM).
+    UnsupportedFormat(u16),
+    /// Bits por muestra no soportados (solo 8 y 16).
+    UnsupportedBitDepth(u16),
+    /// Número de canales no soportado (solo mono y estéreo).
+    UnsupportedChannels(u16),
+}
+
+impl fmt::Display for SndError {
+    /// Formatea el error para visualización.
+    ///
+    /// # Argumentos
+    ///
+    /// * `f` - Formateador de texto.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna un resultado de formato.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SndError::InvalidData(err) => err.fmt(f),
+            SndError::NotFound(file) => write!(f, "Archivo SND no encontrado: {}", file.to_string_lossy()),
+            SndError::InvalidSignature => write!(f, "Firma inválida"),
+            SndError::InvalidWav => write!(f, "Sonido WAV inválido"),
+            SndError::UnsupportedFormat(format) => write!(f, "Formato de audio no soportado: {}", format),
+            SndError::UnsupportedBitDepth(bits) => write!(f, "Bits por muestra no soportados: {}", bits),
+            SndError::UnsupportedChannels(channels) => write!(f, "Número de canales no soportado: {}", channels),
+        }
+    }
+}
+
+impl Error for SndError {
+    /// Devuelve la fuente del error.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna una opción que contiene una referencia al error subyacente.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SndError::InvalidData(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SndError {
+    /// Convierte un error de E/S en un error de SND.
+    ///
+    /// # Argumentos
+    ///
+    /// * `error` - Error de E/S a convertir.
+    ///
+    /// # Retorna
+    ///
+    /// Retorna el error de SND resultante.
+    fn from(error: io::Error) -> Self {
+        SndError::InvalidData(error)
+    }
+}
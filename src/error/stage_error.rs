@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt;
+
+use super::{air_error::AirError, sff_error::SffError};
+
+/// Errores relacionados con la carga de escenarios (archivo DEF del escenario y sus sprites).
+#[derive(Debug)]
+pub enum StageError {
+    /// Error que indica que no se pudo encontrar el archivo DEF del escenario.
+    NotFound(String),
+    /// Error que indica que un valor del archivo está malformado y en que línea
+    Malformed(String, usize),
+    /// Error que indica que falta el archivo de sprites en `[BGdef]`
+    MissingFile(String),
+    /// Error al leer las acciones de los elementos animados
+    Animation(AirError),
+    /// Error al cargar los sprites del escenario
+    Sprites(SffError),
+}
+
+impl fmt::Display for StageError {
+    /// Implementación de formato para mostrar los errores del escenario.
+    ///
+    /// # Argumentos
+    ///
+    /// * `self` - La referencia al error del escenario.
+    /// * `f` - El formateador utilizado para escribir el mensaje de error.
+    ///
+    /// # Retorna
+    ///
+    /// Un resultado que indica si el formato fue exitoso o no.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageError::NotFound(file) => write!(f, "Stage file not found: {}", file),
+            StageError::Malformed(file, line) => {
+                write!(f, "Stage file {} malformed on line {:?}", file, line)
+            }
+            StageError::MissingFile(key) => {
+                write!(f, "Stage file is missing the {} entry in [BGdef]", key)
+            }
+            StageError::Animation(err) => write!(f, "Stage animation error: {}", err),
+            StageError::Sprites(err) => write!(f, "Stage sprites error: {}", err),
+        }
+    }
+}
+
+impl Error for StageError {
+    /// Implementación de método para obtener la fuente del error del escenario.
+    ///
+    /// # Argumentos
+    ///
+    /// * `self` - La referencia al error del escenario.
+    ///
+    /// # Retorna
+    ///
+    /// El error de las animaciones o de los sprites si lo hay, o `None` en caso contrario.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StageError::Animation(err) => Some(err),
+            StageError::Sprites(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<AirError> for StageError {
+    /// Implementación de conversión desde `AirError` a `StageError`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `error` - El error de las acciones que se está convirtiendo a `StageError`.
+    ///
+    /// # Retorna
+    ///
+    /// Un `StageError` que encapsula el error de las acciones.
+    fn from(error: AirError) -> Self {
+        StageError::Animation(error)
+    }
+}
+
+impl From<SffError> for StageError {
+    /// Implementación de conversión desde `SffError` a `StageError`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `error` - El error de los sprites que se está convirtiendo a `StageError`.
+    ///
+    /// # Retorna
+    ///
+    /// Un `StageError` que encapsula el error de los sprites.
+    fn from(error: SffError) -> Self {
+        StageError::Sprites(error)
+    }
+}
@@ -6,9 +6,14 @@
 //! diferentes pantallas del juego.
 mod player;
 mod chars;
+pub mod config;
 pub mod views;
 mod preloader;
 mod error;
+mod audio;
+mod stage;
+
+pub use player::character::{air, cmd, cns, def, sff, snd};
 
 /// Enumaración de teclas de comandos que se pueden pulsar
 #[derive(Hash, Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
@@ -7,16 +7,15 @@ use std::path::Path;
 
 use piston_window::*;
 use winit::window::{Icon, WindowButtons};
+use rust_fg::config::game_config::{GameConfig, GAME_CFG};
 use rust_fg::views::screen_manager::{ScreenManager, ScreenType};
 
-/// Máximo de frames por segundo (FPS) para la ventana del juego.
-const FPS: u64 = 60;
-
-/// Función para crear un ventana por defecto tipo Piston
-fn new_window(title: &str, size: [f64;2]) -> PistonWindow{
-    WindowSettings::new(title, size)
+/// Función para crear un ventana tipo Piston con el tamaño y el video de la configuración
+fn new_window(title: &str, config: &GameConfig) -> PistonWindow{
+    WindowSettings::new(title, config.window_size())
         .resizable(false)
-        .samples(8)
+        .samples(config.samples)
+        .vsync(config.vsync)
         .build()
         .unwrap()
 }
@@ -34,17 +33,20 @@ fn load_icon(path: &Path) -> Icon {
 
 /// Punto de entrada de la aplicación.
 fn main() {
-    let mut window: PistonWindow = new_window("Rust_FG", [512.0;2]);
+    let game_config = GameConfig::load_or_default(GAME_CFG);
+    let mut window: PistonWindow = new_window("Rust_FG", &game_config);
     let icon_path = Path::new("assets/icon/icon.png");
     let window_icon = load_icon(icon_path);
 
     let conf_window: &winit::window::Window = &window.window.window;
     conf_window.set_enabled_buttons(WindowButtons::CLOSE | WindowButtons::MINIMIZE);
     conf_window.set_window_icon(Some(window_icon));
-    window.events.set_max_fps(FPS);
-    window.events.set_ups(FPS);
+    window.events.set_max_fps(game_config.game_speed as u64);
+    window.events.set_ups(game_config.game_speed as u64);
 
-    let mut screen_manager = ScreenManager::new(ScreenType::Main, &mut window);
+    // Las pantallas se dibujan en un área de `WINDOW_SIZE` y se escalan al tamaño de la ventana
+    let scale = game_config.window_scale as f64;
+    let mut screen_manager = ScreenManager::new(ScreenType::Main, &mut window, game_config);
 
     while let Some(e) = window.next() {
         if let Some(_) = e.update_args() {
@@ -60,6 +62,11 @@ fn main() {
         }
 
         window.draw_2d(&e, |c, g, device| {
+            let c = Context {
+                view: c.view.scale(scale, scale),
+                transform: c.transform.scale(scale, scale),
+                ..c
+            };
             screen_manager.draw(c, g, device);
         });
     }
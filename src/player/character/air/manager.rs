@@ -1,16 +1,28 @@
 use std::{
-    collections::HashMap,
+    cell::{RefCell, RefMut},
+    collections::{BTreeMap, HashMap},
     fmt::Error,
-    sync::{Arc, Mutex, MutexGuard},
+    rc::Rc,
+    str::FromStr,
 };
 
 use crate::{
     chars::Character,
     error::{air_error::AirError, pop_up::show_error_popup},
-    player::character::sff::decoder::Sff,
+    player::character::{
+        sff::texture::{subtract_blend, SffTextures},
+        span::{
+            content, fields, float, integer, key_value, section, split_lines, trim, Report, Span,
+        },
+    },
 };
 use gfx_device_gl::Resources;
-use piston_window::{G2dTextureContext, Texture};
+use graphics::{draw_state::Blend, math::Matrix2d, DrawState, Image, ImageSize, Transformed};
+use nom::{
+    bytes::complete::tag, character::complete::digit1, combinator::all_consuming, sequence::tuple,
+    IResult, Slice,
+};
+use piston_window::{G2d, G2dTextureContext, Texture};
 use sprite::Sprite;
 
 /// Struct que representa una caja de colisiones (incluye "HitBox" y "HurtBox").
@@ -44,12 +56,14 @@ pub struct Clsn {
 pub struct AnimationTable {
     /// Mapa de animación identificaod por su ID
     animations: HashMap<i32, Animation>,
-    /// Referencia mutable del manejador del archivo SFF
-    sff: Option<Arc<Mutex<Sff>>>,
+    /// Referencia mutable a los sprites del archivo SFF y sus texturas
+    sff: Option<Rc<RefCell<SffTextures>>>,
     /// Referencia del sprite del jugador
-    spr: Option<Arc<Mutex<Sprite<Texture<Resources>>>>>,
+    spr: Option<Rc<RefCell<Sprite<Texture<Resources>>>>>,
     /// Vector con las colisiones activas del jugador
     clsns: Vec<Clsn>,
+    /// Mezcla, escala y rotación con las que se dibuja el fotograma actual
+    render: FrameRender,
 }
 
 /// Struct que representa un fotograma de una animación, incluyendo las colisiones.
@@ -73,21 +87,34 @@ pub struct AnimFrame {
     h: i8,
     /// Rotación vertical del sprite
     v: i8,
-    /// Colisiones del frame
+    /// Hurtboxes, hitboxes y escala en X, escala en Y y ángulo del frame
     ex: Vec<Vec<f64>>,
-    /// Hurtboxes por defecto en el frame
+    /// Si el frame usa las hurtboxes por defecto
     def1: bool,
-    /// Hitboxes por defecto en el frame
+    /// Si el frame usa las hitboxes por defecto
     def2: bool,
 }
 
+/// Struct con los valores con los que se dibuja el fotograma actual, ya interpolados con el siguiente.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameRender {
+    /// Desplazamiento del fotograma en la X e Y
+    pub offset: (f64, f64),
+    /// Escala en la X e Y
+    pub scale: (f64, f64),
+    /// Ángulo en grados, en sentido antihorario
+    pub angle: f64,
+    /// Alpha del sprite y del fondo sobre el que se dibuja
+    pub alpha: (u8, u8),
+}
+
 /// Struct que representa una animación.
 #[derive(Clone)]
 pub struct Animation {
-    /// Referencia mutable del gestor del archivo SFF
-    sff: Option<Arc<Mutex<Sff>>>,
+    /// Referencia mutable a los sprites del archivo SFF y sus texturas
+    sff: Option<Rc<RefCell<SffTextures>>>,
     /// Refenrecia mutable del sprite del jugador
-    spr: Option<Arc<Mutex<Sprite<Texture<Resources>>>>>,
+    spr: Option<Rc<RefCell<Sprite<Texture<Resources>>>>>,
     /// Vector de los frames del jugador
     frames: Vec<AnimFrame>,
     /// Indicador del frame donde inicia un bucle
@@ -140,6 +167,88 @@ impl AnimFrame {
             def2: false,
         }
     }
+
+    /// Obtiene el sprite del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// El grupo y número del sprite, como se indexan en el SFF.
+    pub fn get_sprite(&self) -> [i16; 2] {
+        [self.group, self.number]
+    }
+
+    /// Obtiene el desplazamiento del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// El desplazamiento en la X e Y.
+    pub fn get_offset(&self) -> (i16, i16) {
+        (self.x, self.y)
+    }
+
+    /// Obtiene la duración del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// Los ticks que dura el fotograma, -1 si dura para siempre.
+    pub fn get_time(&self) -> i32 {
+        self.time
+    }
+
+    /// Obtiene el volteo del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// Si se voltea horizontal y verticalmente.
+    pub fn get_flip(&self) -> (bool, bool) {
+        (self.h < 0, self.v < 0)
+    }
+
+    /// Obtiene la escala del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// La escala en la X e Y, 1.0 si el fotograma no la indica.
+    pub fn get_scale(&self) -> (f64, f64) {
+        match self.ex.get(2) {
+            Some(ex) if ex.len() >= 2 => (ex[0], ex[1]),
+            _ => (1.0, 1.0),
+        }
+    }
+
+    /// Obtiene el ángulo del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// El ángulo en grados, 0.0 si el fotograma no lo indica.
+    pub fn get_angle(&self) -> f64 {
+        match self.ex.get(2) {
+            Some(ex) if ex.len() >= 3 => ex[2],
+            _ => 0.0,
+        }
+    }
+
+    /// Obtiene los valores con los que se dibuja el fotograma, sin interpolar.
+    fn get_render(&self) -> FrameRender {
+        FrameRender {
+            offset: (self.x as f64, self.y as f64),
+            scale: self.get_scale(),
+            angle: self.get_angle(),
+            alpha: (self.src_alpha, self.dst_alpha),
+        }
+    }
+}
+
+impl Default for FrameRender {
+    /// Crea los valores de un fotograma sin desplazamiento, escala, rotación ni transparencia.
+    fn default() -> Self {
+        Self {
+            offset: (0.0, 0.0),
+            scale: (1.0, 1.0),
+            angle: 0.0,
+            alpha: (255, 0),
+        }
+    }
 }
 
 impl Clsn {
@@ -236,12 +345,30 @@ impl Animation {
         }
     }
 
+    /// Obtiene los fotogramas de la animación.
+    ///
+    /// # Retorna
+    ///
+    /// Los fotogramas en orden.
+    pub fn get_frames(&self) -> &Vec<AnimFrame> {
+        &self.frames
+    }
+
+    /// Obtiene el fotograma donde empieza el bucle.
+    ///
+    /// # Retorna
+    ///
+    /// El índice del fotograma al que se vuelve al terminar la animación.
+    pub fn get_loopstart(&self) -> i32 {
+        self.loopstart
+    }
+
     /// Se le asigna una referencia al archivo SFF.
     ///
     /// # Argumentos
     ///
-    /// * `sff` - Una referencia al SFF cargado.
-    fn set_sff(&mut self, sff: Arc<Mutex<Sff>>) {
+    /// * `sff` - Una referencia al SFF cargado con sus texturas.
+    fn set_sff(&mut self, sff: Rc<RefCell<SffTextures>>) {
         self.sff = Some(sff);
     }
 
@@ -259,6 +386,55 @@ impl Animation {
         self.totaltime - self.sumtime
     }
 
+    /// Avanza el tiempo de la animación un tick, pasando al siguiente frame cuando se acaba el actual.
+    ///
+    /// # Retorna
+    ///
+    /// True si la animación había terminado y vuelve a empezar.
+    fn advance(&mut self) -> bool {
+        let restart = self.totaltime > 1 && self.delta_time() == 0 && self.loopstart == 0;
+        if restart {
+            self.reset();
+        }
+        let frame_time = self.frames[self.current as usize].time;
+        if self.time == -1 {
+            self.time = frame_time;
+        }
+        if self.time == 0 && frame_time != -1 {
+            self.current = (self.current + 1) % self.frames.len() as i32;
+            if self.loopstart > 0 && self.current == 0 {
+                self.current = self.loopstart;
+                self.time = self.looptime;
+            }
+            self.time = self.frames[self.current as usize].time;
+        }
+        self.sumtime += 1;
+        self.time -= 1;
+        restart
+    }
+
+    /// Obtiene el frame que se mostrará en el siguiente paso de la animación.
+    ///
+    /// # Retorna
+    ///
+    /// El índice del frame y si empieza a mostrarse en ese paso.
+    pub fn next_element(&self) -> (i32, bool) {
+        if self.totaltime > 1 && self.delta_time() == 0 && self.loopstart == 0 {
+            return (0, true);
+        }
+        if self.time == -1 {
+            return (self.current, true);
+        }
+        if self.time == 0 && self.frames[self.current as usize].time != -1 {
+            let next = (self.current + 1) % self.frames.len() as i32;
+            if self.loopstart > 0 && next == 0 {
+                return (self.loopstart, true);
+            }
+            return (next, true);
+        }
+        (self.current, false)
+    }
+
     /// Avanza un paso dentro de la animación, actualizando el tiempo o el frame por el que va.
     ///
     /// # Argumentos
@@ -272,38 +448,25 @@ impl Animation {
     /// - Cuando se finaliza un frame, se pasa al siguiente. Si se finaliza el tiempo de animación, reiniciamos.
     /// - Mientras, el sprite se actualiza con la imagen del frame actual.
     pub fn step(&mut self, char: &mut Box<dyn Character>) {
-        if self.totaltime > 1 && self.delta_time() == 0 && self.loopstart == 0 {
-            self.reset();
+        if self.advance() {
             char.set_time(-1);
         }
-        let mut frame = &self.frames[self.current as usize];
-        if self.time == -1 {
-            self.time = frame.time;
-        }
-        if self.time == 0 && frame.time != -1 {
-            self.current = (self.current + 1) % self.frames.len() as i32;
-            if self.loopstart > 0 && self.current == 0 {
-                self.current = self.loopstart;
-                self.time = self.looptime;
-            }
-            frame = &self.frames[self.current as usize];
-            self.time = frame.time;
-        }
-        self.sumtime += 1;
-        self.time -= 1;
+        let frame = &self.frames[self.current as usize];
 
         let key = [frame.group, frame.number];
+        let render = self.get_render();
 
-        if let Some(spr) = self.sff.as_ref().unwrap().lock().unwrap().sprites.get(&key) {
-            let texture = spr.tex.clone().unwrap();
+        let sff = self.sff.as_ref().unwrap().borrow();
+        if let (Some(spr), Some(texture)) = (sff.get_sff().sprites.get(&key), sff.get_texture(&key))
+        {
+            let texture = Rc::clone(texture);
             let offset = spr.offset;
             char.set_width(spr.size[0]);
-            char.set_offset_x(offset[0] as f64);
-            char.set_offset_y(offset[1] as f64);
-            let mut sprite = self.spr.as_ref().unwrap().lock().unwrap();
+            char.set_offset_x(offset[0] as f64 - render.offset.0);
+            char.set_offset_y(offset[1] as f64 - render.offset.1);
+            let mut sprite = self.spr.as_ref().unwrap().borrow_mut();
             sprite.set_texture(texture);
-            if frame.h == -1 || char.get_flip_x() {
-                char.is_flipped();
+            if frame.h == -1 {
                 sprite.set_flip_x(!char.is_flipped());
             } else {
                 sprite.set_flip_x(char.is_flipped());
@@ -316,6 +479,57 @@ impl Animation {
         }
     }
 
+    /// Obtiene los valores con los que se dibuja el fotograma actual.
+    ///
+    /// Si hay una línea `Interpolate` antes del siguiente fotograma, el desplazamiento, la escala,
+    /// el ángulo o la mezcla avanzan hacia los del siguiente según el tiempo que lleva el actual.
+    ///
+    /// # Retorna
+    ///
+    /// El desplazamiento, la escala, el ángulo y la mezcla del fotograma.
+    pub fn get_render(&self) -> FrameRender {
+        let current = self.current as usize;
+        let frame = &self.frames[current];
+        let mut render = frame.get_render();
+        let next = if current + 1 < self.frames.len() {
+            current + 1
+        } else {
+            self.loopstart as usize
+        };
+        let Some(to) = self.frames.get(next).map(AnimFrame::get_render) else {
+            return render;
+        };
+        if frame.time <= 0 {
+            return render;
+        }
+
+        let ratio = ((frame.time - self.time - 1) as f64 / frame.time as f64).clamp(0.0, 1.0);
+        let lerp = |from: f64, to: f64| from + (to - from) * ratio;
+        let index = self.current + 1;
+        if self.interpolate_offset.contains(&index) {
+            render.offset = (
+                lerp(render.offset.0, to.offset.0),
+                lerp(render.offset.1, to.offset.1),
+            );
+        }
+        if self.interpolate_scale.contains(&index) {
+            render.scale = (
+                lerp(render.scale.0, to.scale.0),
+                lerp(render.scale.1, to.scale.1),
+            );
+        }
+        if self.interpolate_angle.contains(&index) {
+            render.angle = lerp(render.angle, to.angle);
+        }
+        if self.interpolate_blend.contains(&index) {
+            render.alpha = (
+                lerp(render.alpha.0 as f64, to.alpha.0 as f64).round() as u8,
+                lerp(render.alpha.1 as f64, to.alpha.1 as f64).round() as u8,
+            );
+        }
+        render
+    }
+
     /// Actualiza un vector de colisiones en base a las colisiones del frame actual.
     ///
     /// # Argumentos
@@ -336,36 +550,21 @@ impl Animation {
             let mut i = start_index;
             for clsn in data.chunks(4) {
                 let new_clsn = Clsn::new(clsn[0], clsn[1], clsn[2], clsn[3], hitbox);
-                if i < clsns.len() && clsns[i] != new_clsn {
-                    clsns[i] = new_clsn;
-                } else {
+                if i >= clsns.len() {
                     clsns.push(new_clsn);
+                } else if clsns[i] != new_clsn {
+                    clsns[i] = new_clsn;
                 }
                 i += 1;
             }
             i
         }
 
-        if !frame.def2 {
-            if let Some(clsns2) = frame.ex.get(1) {
-                i = process_clsns(clsns, clsns2, true, i);
-            }
-        } else {
-            if let Some(clsns2) = frame.ex.get(1) {
-                let clsns2_len = clsns2.len() / 4;
-                i += clsns2_len;
-            }
+        if let Some(clsns2) = frame.ex.get(1) {
+            i = process_clsns(clsns, clsns2, true, i);
         }
-
-        if !frame.def1 {
-            if let Some(clsns1) = frame.ex.get(0) {
-                i = process_clsns(clsns, clsns1, false, i);
-            }
-        } else {
-            if let Some(clsns1) = frame.ex.get(0) {
-                let clsns1_len = clsns1.len() / 4;
-                i += clsns1_len;
-            }
+        if let Some(clsns1) = frame.ex.first() {
+            i = process_clsns(clsns, clsns1, false, i);
         }
 
         let mut size = clsns.len();
@@ -380,7 +579,7 @@ impl Animation {
     /// # Argumentos
     ///
     /// * `spr` - Una referencia al sprite del personaje.
-    pub fn set_sprite(&mut self, spr: Option<Arc<Mutex<Sprite<Texture<Resources>>>>>) {
+    pub fn set_sprite(&mut self, spr: Option<Rc<RefCell<Sprite<Texture<Resources>>>>>) {
         self.spr = spr;
     }
 }
@@ -395,65 +594,67 @@ impl AnimationTable {
             sff: None,
             spr: None,
             clsns: Vec::new(),
+            render: FrameRender::default(),
         }
     }
 
-    /// Le asigna una paleta a la animación utilizando un índice válido.
+    /// Le asigna a la animación la paleta de uno de los colores del personaje.
     ///
     /// # Argumentos
     ///
-    /// * `palette` - El índice de la paleta que se desea asignar a la animación.
+    /// * `palette` - El número del color, de 1 a 12.
     pub fn set_palette(&mut self, palette: usize) {
-        self.sff
-            .as_mut()
-            .unwrap()
-            .lock()
-            .unwrap()
-            .set_palette(palette);
+        self.sff.as_ref().unwrap().borrow_mut().set_palette(palette);
     }
 
-    /// Obtiene el sprite para trabajar con él, desbloqueando su Mutex.
+    /// Obtiene el sprite para trabajar con él, tomándolo prestado de su `RefCell`.
     ///
     /// Retorna una referencia mutable al sprite.
-    pub fn get_sprite(&self) -> MutexGuard<Sprite<Texture<Resources>>> {
-        self.spr.as_ref().unwrap().lock().unwrap()
+    pub fn get_sprite(&self) -> RefMut<'_, Sprite<Texture<Resources>>> {
+        self.spr.as_ref().unwrap().borrow_mut()
     }
 
     /// Lee las líneas de un archivo en formato AIR y devuelve una animación.
     ///
     /// # Argumentos
     ///
-    /// * `lines` - Vector de referencias de texto que representan las líneas del archivo AIR.
+    /// * `lines` - Las líneas del archivo AIR con su posición.
     /// * `i` - Índice de la línea actual que se está leyendo.
+    /// * `report` - El informe donde se añaden los problemas encontrados.
     ///
     /// # Retorna
     ///
     /// Una opción que contiene la animación leída, o None si no se encontró ninguna animación.
     fn read_action(
         &mut self,
-        lines: &Vec<&str>,
+        lines: &[Span],
         i: &mut usize,
-    ) -> Result<Option<Animation>, AirError> {
+        report: &mut Report<AirError>,
+    ) -> Option<Animation> {
         while *i < lines.len() {
             let mut a;
-            if let Some(a1) = read_action(&lines, i)? {
-                a = a1.1.clone();
-                if let Some(tmp) = self.animations.get(&a1.0) {
-                    return Ok(Some(tmp.clone()));
+            if let Some((name, number, animation)) = read_action(lines, i, report) {
+                a = animation.clone();
+                if let Some(tmp) = self.animations.get(&number) {
+                    report.warnings.push(AirError::malformed(
+                        &name,
+                        format!("Action {} is already defined", number),
+                    ));
+                    return Some(tmp.clone());
                 }
-                self.animations.insert(a1.0, a1.1.clone());
-                while a1.1.frames.is_empty() && *i < lines.len() {
-                    if let Ok(Some(a2)) = self.read_action(lines, i) {
+                self.animations.insert(number, animation.clone());
+                while animation.frames.is_empty() && *i < lines.len() {
+                    if let Some(a2) = self.read_action(lines, i, report) {
                         a = a2;
                         break;
                     }
                     *i += 1;
                 }
-                return Ok(Some(a));
+                return Some(a);
             }
             *i += 1;
         }
-        Ok(None)
+        None
     }
 
     /// Le asigna un sprite a la tabla de animación y envía su referencia a sus animaciones.
@@ -462,7 +663,7 @@ impl AnimationTable {
     ///
     /// * `spr` - El sprite que se desea asignar a la tabla de animación.
     pub fn set_sprite(&mut self, spr: sprite::Sprite<Texture<Resources>>) {
-        let sprite = Arc::new(Mutex::new(spr));
+        let sprite = Rc::new(RefCell::new(spr));
 
         self.spr = Some(sprite);
         self.set_animation_sprite();
@@ -475,23 +676,24 @@ impl AnimationTable {
         }
     }
 
-    /// Se crea en base a los parámetros un SFF y se le asigna. A su vez, este SFF se le asigna a todas sus animaciones.
+    /// Se carga el SFF del personaje con sus texturas y se le asigna, compartiéndolo si otro jugador ya lo cargó.
+    /// A su vez, este SFF se le asigna a todas sus animaciones.
     ///
     /// # Argumentos
     ///
     /// * `char_name` - Nombre del personaje.
     /// * `filename` - Nombre del archivo que contiene el SFF.
-    /// * `char` - Indica si se trata de un personaje (true) o no (false).
+    /// * `palettes` - Archivos ACT de los colores del personaje.
     /// * `context` - Contexto de textura G2d.
     pub fn set_sff(
         &mut self,
         char_name: &str,
         filename: String,
-        char: bool,
+        palettes: &BTreeMap<usize, String>,
         context: G2dTextureContext,
     ) {
-        match Sff::load_sff(char_name, filename, char, context) {
-            Ok(sff) => self.sff = Some(Arc::new(Mutex::new(sff))),
+        match SffTextures::load_char(char_name, filename, palettes, context) {
+            Ok(sff) => self.sff = Some(Rc::new(RefCell::new(sff))),
             Err(err) => {
                 show_error_popup(&err);
                 std::process::exit(1);
@@ -508,7 +710,7 @@ impl AnimationTable {
         }
         let sff = self.sff.as_ref().unwrap();
         for animation in self.animations.values_mut() {
-            animation.set_sff(Arc::clone(sff));
+            animation.set_sff(Rc::clone(sff));
         }
     }
 
@@ -526,7 +728,7 @@ impl AnimationTable {
             char.set_new_anim(false);
             char.set_hit(-1);
             char.set_time(-1);
-            let mut spr = self.spr.as_mut().unwrap().lock().unwrap();
+            let mut spr = self.spr.as_ref().unwrap().borrow_mut();
             spr.set_flip_x(flip);
             spr.set_anchor(if flip { 1.0 } else { 0.0 }, 0.0);
         } else {
@@ -536,6 +738,7 @@ impl AnimationTable {
             }
         }
         animation.step(char);
+        self.render = animation.get_render();
 
         animation.update_clsns(&mut self.clsns);
 
@@ -544,6 +747,8 @@ impl AnimationTable {
         }
 
         char.set_anim_element(animation.current);
+        let (element, starts) = animation.next_element();
+        char.set_anim_elem_start(if starts { element } else { -1 });
         char.set_anim_time(animation.delta_time());
         char.set_time(animation.time);
     }
@@ -554,95 +759,172 @@ impl AnimationTable {
     pub fn get_clsns(&self) -> &Vec<Clsn> {
         &self.clsns
     }
+
+    /// Dibuja el sprite del personaje con la mezcla, la escala y la rotación del fotograma actual.
+    ///
+    /// La escala y la rotación se aplican alrededor del eje del personaje, como en MUGEN.
+    ///
+    /// # Argumentos
+    ///
+    /// * `axis` - Posición del eje del personaje.
+    /// * `transform` - Transformación del escenario a la pantalla.
+    /// * `g` - El contexto de gráficos.
+    pub fn draw(&self, axis: (f64, f64), transform: Matrix2d, g: &mut G2d) {
+        let spr = self.get_sprite();
+        if !spr.get_visible() {
+            return;
+        }
+        let texture = spr.get_texture();
+        let (width, height) = texture.get_size();
+        let (width, height) = (width as f64, height as f64);
+        let (x, y) = spr.get_position();
+        let anchor = spr.get_anchor();
+        let anchor = (anchor.0 * width, anchor.1 * height);
+
+        // La rotación es antihoraria, y se invierte al mirar a la izquierda
+        let angle = if spr.get_flip_x() {
+            self.render.angle
+        } else {
+            -self.render.angle
+        };
+        let mut model = transform
+            .trans(axis.0, axis.1)
+            .rot_deg(angle)
+            .scale(self.render.scale.0, self.render.scale.1)
+            .trans(x - axis.0, y - axis.1);
+        if spr.get_flip_x() {
+            model = model.trans(width - 2.0 * anchor.0, 0.0).flip_h();
+        }
+        if spr.get_flip_y() {
+            model = model.trans(0.0, height - 2.0 * anchor.1).flip_v();
+        }
+
+        let (draw_state, color) = frame_blend(self.render.alpha);
+        Image::new_color(color)
+            .rect([-anchor.0, -anchor.1, width, height])
+            .draw(texture.as_ref(), &draw_state, model, g);
+    }
+
+    /// Obtiene las animaciones de la tabla.
+    ///
+    /// Retorna el mapa de animaciones identificadas por su número.
+    pub fn get_animations(&self) -> &HashMap<i32, Animation> {
+        &self.animations
+    }
+}
+
+/// Obtiene el modo de mezcla y el color con los que se dibuja un fotograma.
+///
+/// Solo hay mezclas normales y aditivas, así que el resto se aproxima: la resta usa
+/// `subtract_blend` y la suma ignora el alpha del fondo.
+///
+/// # Argumentos
+///
+/// * `alpha` - Alpha del sprite y del fondo, (1, 255) es una resta.
+///
+/// # Retorna
+///
+/// El estado de dibujo y el color que multiplica al sprite.
+fn frame_blend(alpha: (u8, u8)) -> (DrawState, [f32; 4]) {
+    let draw_state = DrawState::default();
+    let (src, dst) = alpha;
+    let opacity = src as f32 / 255.0;
+    match (src, dst) {
+        (1, 255) => subtract_blend(),
+        (_, 0) => (draw_state.blend(Blend::Alpha), [1.0, 1.0, 1.0, opacity]),
+        // Los sprites translúcidos reparten el color entre el sprite y el fondo
+        _ if dst < 255 && (254..=256).contains(&(src as u16 + dst as u16)) => {
+            (draw_state.blend(Blend::Alpha), [1.0, 1.0, 1.0, opacity])
+        }
+        _ => (draw_state.blend(Blend::Lighter), [1.0, 1.0, 1.0, opacity]),
+    }
 }
 
 /// Lee línea por línea de un archivo con formato AIR y devuelve la animación y su número,
 /// si es que se cumplen las condiciones del formato.
 ///
+/// Las líneas anteriores a la sección se saltan y, si no están vacías, se añaden como avisos.
+///
 /// # Argumentos
 ///
-/// * `lines` - Un vector de referencias a cadenas que representan las líneas del archivo AIR.
+/// * `lines` - Las líneas del archivo AIR con su posición.
 /// * `i` - Un contador de línea mutable que se utiliza para rastrear la posición actual en el vector `lines`.
+/// * `report` - El informe donde se añaden los problemas encontrados.
 ///
 /// # Retorna
 ///
-/// Una tupla que contiene el número de la animación y la propia animación, envueltos en `Some`, si se cumplen las condiciones del formato AIR.
-/// Si no se encuentra una animación válida, devuelve `None`. En caso de error en el formato del archivo, devuelve un `Result` que encapsula un `AirError`.
+/// Una tupla que contiene el nombre de la sección, el número de la animación y la propia animación, envueltos en `Some`, si se cumplen las condiciones del formato AIR.
+/// Si no se encuentra una animación válida, devuelve `None` y añade la sección al informe.
 ///
 /// # Ejemplo de formato
 ///
 /// El formato esperado para la línea que indica el comienzo de una animación debe ser similar a `[Begin Action 5]`.
 /// Esto implica que la cadena debe comenzar con `[Begin`, seguido de un espacio y la palabra `Action`, seguido de otro espacio y el número de la animación, y finalmente `]`.
-fn read_action(lines: &Vec<&str>, i: &mut usize) -> Result<Option<(i32, Animation)>, AirError> {
-    let length = lines.len();
-
-    let mut name = String::new();
-    let mut subname = String::new();
-
-    while *i < length {
-        let sec = &lines[*i];
-        if sec.is_empty() || !sec.starts_with('[') {
-            name = "".to_string();
-            subname = "".to_string();
-        } else {
-            let sec = sec.splitn(2, ';').next().unwrap().trim();
-            if !sec.ends_with(']') {
-                name = "".to_string();
-                subname = "".to_string();
-            } else {
-                let sec = &sec[1..sec.rfind(']').unwrap()];
-                if let Some(j) = sec.find(' ') {
-                    name = sec[..=j].to_string();
-                    subname = sec[j + 1..].to_string();
-                } else {
-                    name = sec.to_string();
-                    subname = "".to_string();
-                }
-            }
-        }
-        if !name.is_empty() {
+fn read_action<'a>(
+    lines: &[Span<'a>],
+    i: &mut usize,
+    report: &mut Report<AirError>,
+) -> Option<(Span<'a>, i32, Animation)> {
+    let mut name = None;
+    while *i < lines.len() {
+        let line = content(lines[*i]);
+        name = section(line);
+        if name.is_some() {
             break;
         }
+        if !line.fragment().is_empty() {
+            report.warnings.push(AirError::malformed(
+                &line,
+                "Line outside of an action".to_string(),
+            ));
+        }
         *i += 1;
     }
-
-    name = name.to_lowercase();
-    subname = subname.to_lowercase();
-
-    if name.to_lowercase() != "begin " {
-        return Ok(None);
-    }
-
-    let spi;
-    match subname.find(" ") {
-        Some(pos) => {
-            spi = pos;
-        }
-        None => return Ok(None),
+    let name = name?;
+
+    const BEGIN_ACTION: &str = "begin action ";
+    if !name.fragment().to_lowercase().starts_with(BEGIN_ACTION) {
+        report.warnings.push(AirError::malformed(
+            &name,
+            format!("Unknown section: {}", name.fragment()),
+        ));
+        return None;
     }
-
-    if &subname[..spi] != "action" {
-        return Ok(None);
+    let number = trim(name.slice(BEGIN_ACTION.len()..));
+    let Ok(action) = atoi(number.fragment()) else {
+        report.errors.push(AirError::malformed(
+            &number,
+            format!("Action number out of range: {}", number.fragment()),
+        ));
+        return None;
+    };
+    if integer(number).is_none() {
+        report.warnings.push(AirError::malformed(
+            &number,
+            format!("Invalid action number: {}", number.fragment()),
+        ));
     }
 
     *i += 1;
 
-    match read_animation(lines, i) {
-        Ok(animation) => {
-            return Ok(Some((
-                atoi(&subname[spi + 1..].to_string()).unwrap(),
-                animation,
-            )))
-        }
-        Err(err) => return Err(err),
+    let animation = read_animation(lines, i, report);
+    if animation.frames.is_empty() {
+        report.warnings.push(AirError::malformed(
+            &name,
+            format!("Action {} has no frames", action),
+        ));
     }
+    Some((name, action, animation))
 }
 
 /// Lee una línea de un archivo con formato tipo AIR, intentando interpretarla como un fotograma de animación.
 ///
+/// Los valores que no se pueden leer se añaden como avisos y toman su valor por defecto.
+///
 /// # Argumentos
 ///
-/// * `line` - Una referencia a una cadena que representa una línea del archivo con formato AIR.
+/// * `line` - El contenido de una línea del archivo con formato AIR, con su posición.
+/// * `report` - El informe donde se añaden los problemas encontrados.
 ///
 /// # Retorna
 ///
@@ -655,7 +937,7 @@ fn read_action(lines: &Vec<&str>, i: &mut usize) -> Result<Option<(i32, Animatio
 /// - Los primeros cinco valores son obligatorios: `group`, `number`, `x`, `y` y `time`.
 /// - Luego, puede haber un sexto valor opcional para indicar espejado o volteado horizontal y/o verticalmente.
 /// - Después, puede haber un séptimo valor opcional para indicar transparencia.
-/// - Finalmente, puede haber hasta tres valores adicionales opcionales para datos extras.
+/// - Finalmente, puede haber hasta tres valores opcionales para la escala en X, la escala en Y y el ángulo.
 ///
 /// Ejemplo de formato:
 /// ```text
@@ -669,36 +951,55 @@ fn read_action(lines: &Vec<&str>, i: &mut usize) -> Result<Option<(i32, Animatio
 /// Clsn2[1] = -4,-105, 9,-89
 /// 41,7, 0,0, 4
 /// 41,8, 0,0, 4
-fn read_anim_frame(line: &String) -> Option<AnimFrame> {
-    if line.is_empty() || !line.starts_with(|c: char| c.is_digit(10) || c == '-') {
+/// 41,9, 0,0, 4, H, A, 1.5,1.5, 45
+/// ```
+fn read_anim_frame(line: Span, report: &mut Report<AirError>) -> Option<AnimFrame> {
+    if !line
+        .fragment()
+        .starts_with(|c: char| c.is_ascii_digit() || c == '-')
+    {
         return None;
     }
 
-    let mut ary = line
-        .splitn(10, ',')
-        .map(|s| s.trim())
-        .collect::<Vec<&str>>();
+    let values = fields(line, ',');
 
-    if ary.len() < 5 {
+    if values.len() < 5 {
+        report.warnings.push(AirError::malformed(
+            &line,
+            "A frame needs group, number, x, y and time".to_string(),
+        ));
         return None;
     }
 
     let mut af = AnimFrame::new();
-    af.group = ary[0].parse().unwrap_or_default();
-    af.number = ary[1].parse().unwrap_or_default();
-    af.x = ary[2].parse().unwrap_or_default();
-    af.y = ary[3].parse().unwrap_or_default();
-    af.time = ary[4].parse().unwrap_or_default();
+    af.group = frame_value(values[0], "group", report);
+    af.number = frame_value(values[1], "number", report);
+    af.x = frame_value(values[2], "x", report);
+    af.y = frame_value(values[3], "y", report);
+    af.time = frame_value(values[4], "time", report);
+    if af.time < -1 {
+        report.warnings.push(AirError::malformed(
+            &values[4],
+            format!("The time must be -1 or greater: {}", af.time),
+        ));
+    }
 
-    if ary.len() < 6 {
+    if values.len() < 6 {
         return Some(af);
     }
 
-    for c in ary[5].chars() {
+    let flip = values[5];
+    for c in flip.fragment().chars() {
         match c {
             'H' | 'h' => af.h *= -1,
             'V' | 'v' => af.v *= -1,
-            _ => (),
+            _ => {
+                report.warnings.push(AirError::malformed(
+                    &flip,
+                    format!("Invalid flip: {}", flip.fragment()),
+                ));
+                break;
+            }
         }
     }
 
@@ -710,15 +1011,22 @@ fn read_anim_frame(line: &String) -> Option<AnimFrame> {
         af.y *= -1;
     }
 
-    if ary.len() < 7 {
+    if values.len() < 7 {
         return Some(af);
     }
 
-    if let Some(ia) = ary[6].find(|c: char| c == 'A' || c == 'S' || c == 'a' || c == 's') {
-        ary[6] = &ary[6][ia..];
+    let trans = values[6];
+    if !is_trans(trans.fragment()) {
+        report.warnings.push(AirError::malformed(
+            &trans,
+            format!("Invalid transparency: {}", trans.fragment()),
+        ));
+    }
+    let mut a = trans.fragment().to_lowercase();
+    if let Some(ia) = a.find(['a', 's']) {
+        a = a[ia..].to_string();
     }
 
-    let a = ary[6].split(',').next().unwrap_or_default().to_lowercase();
     match a.as_str() {
         "a1" => {
             af.src_alpha = 255;
@@ -728,38 +1036,38 @@ fn read_anim_frame(line: &String) -> Option<AnimFrame> {
             af.src_alpha = 1;
             af.dst_alpha = 255;
         }
-        a if a.len() >= 2 && a.starts_with("as") => {
-            if a.len() > 2 && a.chars().nth(2).unwrap_or_default().is_digit(10) {
-                let mut i = 2;
-                let mut alp = 0;
-                while i < a.len() && a.chars().nth(i).unwrap_or_default().is_digit(10) {
-                    alp = alp * 10 + (a.chars().nth(i).unwrap_or_default() as u8 - b'0') as i32;
-                    i += 1;
-                }
-                alp &= 0x3FFF;
-                if alp >= 255 {
-                    af.src_alpha = 255;
-                } else {
-                    af.src_alpha = alp as u8;
-                }
-                if i < a.len() && a.chars().nth(i).unwrap_or_default() == 'd' {
-                    i += 1;
-                    if i < a.len() && a.chars().nth(i).unwrap_or_default().is_digit(10) {
-                        alp = 0;
-                        while i < a.len() && a.chars().nth(i).unwrap_or_default().is_digit(10) {
-                            alp = alp * 10
-                                + (a.chars().nth(i).unwrap_or_default() as u8 - b'0') as i32;
-                            i += 1;
-                        }
-                        alp &= 0x3FFF;
-                        if alp >= 255 {
-                            af.dst_alpha = 255;
-                        } else {
-                            af.dst_alpha = alp as u8;
-                        }
-                        if af.src_alpha == 1 && af.dst_alpha == 255 {
-                            af.src_alpha = 0;
-                        }
+        a if a.len() > 2
+            && a.starts_with("as")
+            && a.chars().nth(2).unwrap_or_default().is_ascii_digit() =>
+        {
+            let mut i = 2;
+            let mut alp = 0;
+            while i < a.len() && a.chars().nth(i).unwrap_or_default().is_ascii_digit() {
+                alp = alp * 10 + (a.chars().nth(i).unwrap_or_default() as u8 - b'0') as i32;
+                i += 1;
+            }
+            alp &= 0x3FFF;
+            if alp >= 255 {
+                af.src_alpha = 255;
+            } else {
+                af.src_alpha = alp as u8;
+            }
+            if i < a.len() && a.chars().nth(i).unwrap_or_default() == 'd' {
+                i += 1;
+                if i < a.len() && a.chars().nth(i).unwrap_or_default().is_ascii_digit() {
+                    alp = 0;
+                    while i < a.len() && a.chars().nth(i).unwrap_or_default().is_ascii_digit() {
+                        alp = alp * 10 + (a.chars().nth(i).unwrap_or_default() as u8 - b'0') as i32;
+                        i += 1;
+                    }
+                    alp &= 0x3FFF;
+                    if alp >= 255 {
+                        af.dst_alpha = 255;
+                    } else {
+                        af.dst_alpha = alp as u8;
+                    }
+                    if af.src_alpha == 1 && af.dst_alpha == 255 {
+                        af.src_alpha = 0;
                     }
                 }
             }
@@ -770,22 +1078,75 @@ fn read_anim_frame(line: &String) -> Option<AnimFrame> {
         }
         _ => (),
     }
-    if ary.len() < 8 || !ary[7].parse::<f32>().is_ok() {
+    if values.len() < 8 {
         return Some(af);
     }
-    af.ex = Vec::with_capacity(3);
-    af.ex[2].push(atof(&ary[7]) as f64);
-    if ary.len() < 9 || !ary[8].parse::<f32>().is_ok() {
-        return Some(af);
+    af.ex = vec![vec![]; 3];
+    af.ex[2] = vec![1.0, 1.0, 0.0];
+    for (n, (value, name)) in values[7..]
+        .iter()
+        .zip(["X scale", "Y scale", "angle"])
+        .enumerate()
+    {
+        if !value.fragment().is_empty() {
+            if float(*value).is_none() {
+                report.warnings.push(AirError::malformed(
+                    value,
+                    format!("Invalid {}: {}", name, value.fragment()),
+                ));
+            }
+            af.ex[2][n] = atof(value.fragment());
+        }
     }
-    af.ex[2].push(atof(&ary[8]) as f64);
-    if ary.len() < 10 || !ary[9].parse::<f32>().is_ok() {
-        return Some(af);
+    if let Some(extra) = values.get(10) {
+        report.warnings.push(AirError::malformed(
+            extra,
+            "Too many values in the frame".to_string(),
+        ));
     }
-    af.ex[2].push(atof(&ary[9]) as f64);
     Some(af)
 }
 
+/// Lee un valor numérico de un fotograma.
+///
+/// # Argumentos
+///
+/// * `value` - El texto del valor.
+/// * `name` - El nombre del valor, para el aviso.
+/// * `report` - El informe donde se añade el aviso si no es un número.
+///
+/// # Retorna
+///
+/// El número, o el valor por defecto si el texto no es un número.
+fn frame_value<T: FromStr + Default>(value: Span, name: &str, report: &mut Report<AirError>) -> T {
+    value.fragment().parse().unwrap_or_else(|_| {
+        report.warnings.push(AirError::malformed(
+            &value,
+            format!("Invalid {}: {}", name, value.fragment()),
+        ));
+        T::default()
+    })
+}
+
+/// Comprueba si un texto es una transparencia de fotograma: `A`, `A1`, `S` o `AS###D###`.
+///
+/// # Argumentos
+///
+/// * `trans` - La transparencia.
+///
+/// # Retorna
+///
+/// True si es válida o está vacía.
+fn is_trans(trans: &str) -> bool {
+    let lower = trans.to_lowercase();
+    if matches!(lower.as_str(), "" | "a" | "a1" | "s") {
+        return true;
+    }
+    let result: IResult<&str, _> =
+        all_consuming(tuple((tag("as"), digit1, tag("d"), digit1)))(lower.as_str());
+    result.is_ok()
+}
+
 /// Convierte una cadena de texto en un número de punto flotante (f64) y lo devuelve.
 ///
 /// # Argumentos
@@ -818,7 +1179,7 @@ fn atof(s: &str) -> f64 {
                 m = 1;
                 continue;
             }
-            if !c.is_digit(10) {
+            if !c.is_ascii_digit() {
                 break;
             }
             f = f * 10.0 + (c as u32 - '0' as u32) as f64;
@@ -835,7 +1196,7 @@ fn atof(s: &str) -> f64 {
             {
                 j += 1;
             }
-            while j < a.len() && a.chars().nth(j).unwrap_or_default().is_digit(10) {
+            while j < a.len() && a.chars().nth(j).unwrap_or_default().is_ascii_digit() {
                 e = e * 10.0 + (a.chars().nth(j).unwrap_or_default() as u32 - '0' as u32) as f64;
                 j += 1;
             }
@@ -865,16 +1226,18 @@ fn atof(s: &str) -> f64 {
 /// - Seguidamente se lee línea por línea del archivo y se procesan los frames o las colisiones.
 /// - Si se encuentra un frame, se almacena en la animación.
 /// - Si no es un frame, se actualizan parámetros extras como si la animación tiene un bucle o el tiempo que dura.
+/// - Las líneas desconocidas se ignoran y se añaden como avisos.
 ///
 /// # Argumentos
 ///
-/// * `lines` - Una referencia a un vector de cadenas de texto que representan las líneas del archivo.
+/// * `lines` - Las líneas del archivo AIR con su posición.
 /// * `i` - Un mutable usize que representa el índice actual dentro del vector de líneas.
+/// * `report` - El informe donde se añaden los problemas encontrados.
 ///
 /// # Retorna
 ///
-/// Un resultado que contiene una animación si la lectura fue exitosa, o un error `AirError` si hubo un problema de formato.
-fn read_animation(lines: &Vec<&str>, i: &mut usize) -> Result<Animation, AirError> {
+/// La animación leída.
+fn read_animation(lines: &[Span], i: &mut usize, report: &mut Report<AirError>) -> Animation {
     let mut a = Animation::new();
 
     a.mask = 0;
@@ -882,36 +1245,26 @@ fn read_animation(lines: &Vec<&str>, i: &mut usize) -> Result<Animation, AirErro
     let (mut clsn1, mut clsn1d, mut clsn2, mut clsn2d): (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) =
         (Vec::new(), Vec::new(), Vec::new(), Vec::new());
     let (mut def1, mut def2) = (true, true);
-    let mut first = true;
 
     while *i < lines.len() {
-        if !lines[*i].is_empty() && lines[*i].starts_with('[') {
+        let line = content(lines[*i]);
+        if line.fragment().starts_with('[') {
             break;
         }
 
-        let line = lines[*i]
-            .splitn(2, ';')
-            .next()
-            .unwrap()
-            .trim()
-            .to_lowercase();
-
-        let af = read_anim_frame(&line);
+        let lower = line.fragment().to_lowercase();
 
-        match af {
+        match read_anim_frame(line, report) {
             Some(mut af) => {
                 ols = a.loopstart;
-                af.def1 = if first { false } else { def1 };
-                af.def2 = if first { false } else { def2 };
-                if def1 || def2 {
-                    first = false
-                };
                 if def1 {
                     clsn1 = clsn1d.clone();
                 }
                 if def2 {
                     clsn2 = clsn2d.clone();
                 }
+                af.def1 = def1;
+                af.def2 = def2;
                 if !clsn1.is_empty() || !clsn2.is_empty() {
                     if af.ex.len() < 2 {
                         af.ex = vec![vec![]; 2];
@@ -923,112 +1276,74 @@ fn read_animation(lines: &Vec<&str>, i: &mut usize) -> Result<Animation, AirErro
                 def1 = true;
                 def2 = true;
             }
-            None => {
-                if line.len() >= 9 && &line[..9] == "loopstart" {
-                    a.loopstart = a.frames.len() as i32;
-                }
-                if line.len() >= 18 && &line[..18] == "interpolate offset" {
-                    a.interpolate_offset.push(a.frames.len() as i32);
-                }
-                if line.len() >= 17 && &line[..17] == "interpolate scale" {
-                    a.interpolate_scale.push(a.frames.len() as i32);
-                }
-                if line.len() >= 17 && &line[..17] == "interpolate angle" {
-                    a.interpolate_angle.push(a.frames.len() as i32);
-                }
-                if line.len() >= 17 && &line[..17] == "interpolate blend" {
-                    a.interpolate_blend.push(a.frames.len() as i32);
-                }
-                if line.len() >= 5 && &line[..4] == "clsn" {
-                    if let Some(ii) = line.find(":") {
-                        let size;
-                        match atoi(&line[ii + 1..].to_string()) {
-                            Ok(result) => {
-                                size = result;
-                            }
-                            Err(err) => {
-                                return Err(AirError::BadFormat(err));
-                            }
-                        }
-
-                        if size < 0 {
-                            break;
-                        }
-
-                        let mut clsn;
-                        let is_clsn1;
-                        if &line[4..5] == "1" {
-                            clsn1 = vec![0.0; size as usize * 4];
-                            clsn = clsn1.clone();
-                            def1 = line.len() >= 12 && &line[5..12] == "default";
-                            is_clsn1 = true;
-                            first = true;
-                        } else if &line[4..5] == "2" {
-                            clsn2 = vec![0.0; size as usize * 4];
-                            clsn = clsn2.clone();
-                            def2 = line.len() >= 12 && &line[5..12] == "default";
-                            is_clsn1 = false;
-                            first = true;
-                        } else {
-                            break;
-                        }
-                        if size == 0 {
-                            break;
-                        }
-                        *i += 1;
-                        for n in 0..size {
-                            if *i >= lines.len() {
-                                break;
-                            }
-                            let line = lines[*i]
-                                .splitn(2, ';')
-                                .next()
-                                .unwrap()
-                                .trim()
-                                .to_lowercase();
-                            if line.is_empty() {
-                                continue;
-                            }
-                            if line.len() < 4 || !line.starts_with("clsn") {
-                                break;
-                            }
-
-                            let ii = line.find("=").unwrap_or_default();
-
-                            let ary: Vec<_> = line[ii + 1..].split(',').collect();
-                            if ary.len() < 4 {
-                                break;
-                            }
-
-                            let (l, t, r, b) = (
-                                atoi(&ary[0].to_string()).unwrap(),
-                                atoi(&ary[1].to_string()).unwrap(),
-                                atoi(&ary[2].to_string()).unwrap(),
-                                atoi(&ary[3].to_string()).unwrap(),
-                            );
-                            let (l, r) = if l > r { (r, l) } else { (l, r) };
-                            let (t, b) = if t > b { (b, t) } else { (t, b) };
-                            clsn[n as usize * 4] = l as f64;
-                            clsn[n as usize * 4 + 1] = t as f64;
-                            clsn[n as usize * 4 + 2] = r as f64;
-                            clsn[n as usize * 4 + 3] = b as f64;
-                            *i += 1;
+            None if lower.starts_with("loopstart") => {
+                a.loopstart = a.frames.len() as i32;
+            }
+            None if lower.starts_with("interpolate offset") => {
+                a.interpolate_offset.push(a.frames.len() as i32);
+            }
+            None if lower.starts_with("interpolate scale") => {
+                a.interpolate_scale.push(a.frames.len() as i32);
+            }
+            None if lower.starts_with("interpolate angle") => {
+                a.interpolate_angle.push(a.frames.len() as i32);
+            }
+            None if lower.starts_with("interpolate blend") => {
+                a.interpolate_blend.push(a.frames.len() as i32);
+            }
+            None if lower.starts_with("clsn") => match line.fragment().find(':') {
+                Some(ii) => {
+                    let count = trim(line.slice(ii + 1..));
+                    let size = match atoi(count.fragment()) {
+                        Ok(size) => size.max(0) as usize,
+                        Err(_) => {
+                            report.errors.push(AirError::malformed(
+                                &count,
+                                format!("Number out of range: {}", count.fragment()),
+                            ));
+                            0
                         }
-                        if is_clsn1 {
-                            clsn1 = clsn.clone();
+                    };
+                    if !matches!(integer(count), Some(0..)) {
+                        report.warnings.push(AirError::malformed(
+                            &count,
+                            format!("Invalid number of boxes: {}", count.fragment()),
+                        ));
+                    }
+                    let kind = line.fragment()[4..ii].trim().to_lowercase();
+                    match kind.as_str() {
+                        "1" | "1default" => {
+                            def1 = kind == "1default";
+                            clsn1 = read_clsns(lines, i, size, report);
                             if def1 {
-                                clsn1d = clsn;
+                                clsn1d = clsn1.clone();
                             }
-                        } else {
-                            clsn2 = clsn.clone();
+                        }
+                        "2" | "2default" => {
+                            def2 = kind == "2default";
+                            clsn2 = read_clsns(lines, i, size, report);
                             if def2 {
-                                clsn2d = clsn;
+                                clsn2d = clsn2.clone();
                             }
                         }
-                        *i -= 1;
+                        _ => report.warnings.push(AirError::malformed(
+                            &line,
+                            format!("Unknown line: {}", line.fragment()),
+                        )),
                     }
                 }
-            }
+                None => report.warnings.push(AirError::malformed(
+                    &line,
+                    "Collision box outside of a Clsn1: or Clsn2: block".to_string(),
+                )),
+            },
+            // Los fotogramas mal formados ya se añadieron al informe
+            None if lower.is_empty()
+                || lower.starts_with(|c: char| c.is_ascii_digit() || c == '-') => {}
+            None => report.warnings.push(AirError::malformed(
+                &line,
+                format!("Unknown line: {}", line.fragment()),
+            )),
         }
         *i += 1;
     }
@@ -1058,7 +1373,85 @@ fn read_animation(lines: &Vec<&str>, i: &mut usize) -> Result<Animation, AirErro
             a.nazotime = 0;
         }
     }
-    Ok(a)
+    a
+}
+
+/// Lee las cajas de colisión que siguen a una línea `Clsn1:` o `Clsn2:`.
+///
+/// # Argumentos
+///
+/// * `lines` - Las líneas del archivo AIR con su posición.
+/// * `i` - El índice de la línea `Clsn`, al terminar apunta a la última caja leída.
+/// * `size` - La cantidad de cajas que indica la línea.
+/// * `report` - El informe donde se añaden los problemas encontrados.
+///
+/// # Retorna
+///
+/// Las coordenadas izquierda, arriba, derecha y abajo de cada caja leída.
+fn read_clsns(
+    lines: &[Span],
+    i: &mut usize,
+    size: usize,
+    report: &mut Report<AirError>,
+) -> Vec<f64> {
+    let header = content(lines[*i]);
+    let mut clsn = Vec::with_capacity(size * 4);
+    while clsn.len() < size * 4 && *i + 1 < lines.len() {
+        let line = content(lines[*i + 1]);
+        if line.fragment().is_empty() {
+            *i += 1;
+            continue;
+        }
+        if !line.fragment().to_lowercase().starts_with("clsn") {
+            break;
+        }
+        let Some((_, value)) = key_value(line) else {
+            break;
+        };
+        let values = fields(value, ',');
+        if values.len() != 4 {
+            report.warnings.push(AirError::malformed(
+                &value,
+                "A box needs left, top, right and bottom".to_string(),
+            ));
+        }
+        if values.len() < 4 {
+            *i += 1;
+            break;
+        }
+
+        let mut coords = [0; 4];
+        for (coord, value) in coords.iter_mut().zip(&values) {
+            *coord = match atoi(value.fragment()) {
+                Ok(coord) => coord,
+                Err(_) => {
+                    report.errors.push(AirError::malformed(
+                        value,
+                        format!("Number out of range: {}", value.fragment()),
+                    ));
+                    0
+                }
+            };
+            if integer(*value).is_none() {
+                report.warnings.push(AirError::malformed(
+                    value,
+                    format!("Invalid number: {}", value.fragment()),
+                ));
+            }
+        }
+        let [l, t, r, b] = coords;
+        let (l, r) = if l > r { (r, l) } else { (l, r) };
+        let (t, b) = if t > b { (b, t) } else { (t, b) };
+        clsn.extend([l as f64, t as f64, r as f64, b as f64]);
+        *i += 1;
+    }
+    if clsn.len() < size * 4 {
+        report.warnings.push(AirError::malformed(
+            &header,
+            format!("{} boxes declared but {} found", size, clsn.len() / 4),
+        ));
+    }
+    clsn
 }
 
 /// Método para convertir una cadena de texto en un i32 y devolverlo.
@@ -1070,7 +1463,7 @@ fn read_animation(lines: &Vec<&str>, i: &mut usize) -> Result<Animation, AirErro
 /// # Retorna
 ///
 /// Un resultado que contiene el i32 convertido si la conversión fue exitosa, o un error si hubo un problema.
-fn atoi(string: &String) -> Result<i32, Error> {
+fn atoi(string: &str) -> Result<i32, Error> {
     let mut n = 0;
     let trimmed_str = string.trim();
 
@@ -1094,7 +1487,7 @@ fn atoi(string: &String) -> Result<i32, Error> {
                 if trimmed_str.starts_with('-') {
                     return Err(Error);
                 }
-                return Ok(0 as i32 >> 1);
+                return Ok(0_i32 >> 1);
             }
         }
 
@@ -1114,13 +1507,44 @@ fn atoi(string: &String) -> Result<i32, Error> {
 ///
 /// # Retorna
 ///
-/// Un resultado que contiene la tabla de animación si la lectura fue exitosa, o un error `AirError` si hubo un problema de formato.
-fn read_animation_table(air: &str) -> Result<AnimationTable, AirError> {
+/// Un resultado que contiene la tabla de animación si la lectura fue exitosa, o el primer error `AirError` con la posición del problema.
+pub fn read_animation_table(air: &str) -> Result<AnimationTable, AirError> {
+    let mut report = Report::default();
+    let at = read_air(air, &mut report);
+    report.into_result(at)
+}
+
+/// Revisa el contenido de un archivo AIR, informando de todos sus problemas.
+///
+/// # Argumentos
+///
+/// * `air` - Una cadena de texto que representa el contenido del archivo AIR.
+///
+/// # Retorna
+///
+/// La tabla de animación que usaría el juego y los errores y avisos del archivo, con su posición.
+pub fn check_animation_table(air: &str) -> (AnimationTable, Report<AirError>) {
+    let mut report = Report::default();
+    let at = read_air(air, &mut report);
+    (at, report)
+}
+
+/// Lee todas las acciones de un archivo AIR.
+///
+/// # Argumentos
+///
+/// * `air` - Una cadena de texto que representa el contenido del archivo AIR.
+/// * `report` - El informe donde se añaden los problemas encontrados.
+///
+/// # Retorna
+///
+/// La tabla de animación con las acciones leídas.
+fn read_air(air: &str, report: &mut Report<AirError>) -> AnimationTable {
     let mut i = 0;
     let mut at = AnimationTable::new();
-    let lines = air.lines().map(|line| line.trim()).collect();
-    while let Some(_) = at.read_action(&lines, &mut i)? {}
-    Ok(at)
+    let lines = split_lines(air);
+    while at.read_action(&lines, &mut i, report).is_some() {}
+    at
 }
 
 /// Método que busca un archivo AIR y devuelve una tabla de animación.
@@ -1137,15 +1561,16 @@ pub fn parse_air(air: &str) -> Result<AnimationTable, AirError> {
         Ok(content) => content,
         Err(_) => return Err(AirError::NotFound(air.to_string())),
     };
-    match read_animation_table(&content.as_str()) {
-        Ok(at) => return Ok(at),
-        Err(err) => return Err(err),
-    }
+    read_animation_table(content.as_str())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        chars::data::CharData,
+        player::character::{cns::manager::read_cns_file, def::manager::CharDef},
+    };
 
     /// Función para comparar vectores f64.
     ///
@@ -1158,13 +1583,118 @@ mod tests {
     /// # Retorna
     ///
     /// True si ambos vectores son iguales, false si son distintos.
-    fn approx_eq_vec(a: &Vec<f64>, b: &Vec<f64>, epsilon: f64) -> bool {
+    fn approx_eq_vec(a: &[f64], b: &[f64], epsilon: f64) -> bool {
         if a.len() != b.len() {
             return false;
         }
         a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < epsilon)
     }
 
+    /// Lee la primera acción de unas líneas.
+    ///
+    /// # Argumentos
+    ///
+    /// `lines` - Las líneas del archivo AIR.
+    ///
+    /// # Retorna
+    ///
+    /// El número de la acción y su animación, o `None` si no hay ninguna acción.
+    fn read_lines(lines: &[&str]) -> Option<(i32, Animation)> {
+        let text = lines.join("\n");
+        let lines = split_lines(&text);
+        read_action(&lines, &mut 0, &mut Report::default())
+            .map(|(_, number, animation)| (number, animation))
+    }
+
+    /// Lee un fotograma de una línea.
+    ///
+    /// # Argumentos
+    ///
+    /// `line` - La línea del fotograma.
+    ///
+    /// # Retorna
+    ///
+    /// El fotograma leído.
+    fn frame(line: &str) -> AnimFrame {
+        read_anim_frame(Span::new(line), &mut Report::default()).unwrap()
+    }
+
+    /// Resume los problemas de un informe en línea, columna y mensaje para compararlos.
+    ///
+    /// # Argumentos
+    ///
+    /// `problems` - Los errores o los avisos del informe.
+    ///
+    /// # Retorna
+    ///
+    /// La línea, la columna y el mensaje de cada problema.
+    fn summary(problems: &[AirError]) -> Vec<(u32, usize, &str)> {
+        problems
+            .iter()
+            .filter_map(|problem| match problem {
+                AirError::Malformed {
+                    line,
+                    column,
+                    message,
+                } => Some((*line, *column, message.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Prueba que se informe de todos los problemas de un archivo con su posición
+    #[test]
+    fn test_check_animation_table() {
+        let (table, report) = check_animation_table(
+            "\
+[Begin Action 0]
+Clsn2Default: 2
+ Clsn2[0] = -10, 0, 10, -80
+0,0, 0,0, x
+Loopstart
+0,1, 0,0, 5, Z, A2, 1.5, big
+[Begin Action 0]
+Interpolate Size
+[Begin Action 5]
+Clsn1: 1
+ Clsn1[0] = 1, 2, 3, 4
+ Clsn1[1] = 1, 2, 3, 4
+5,0, 0,0, -1
+[End]
+[Begin Action -99999999999]
+0,0, 0,0, 1
+",
+        );
+        let mut actions: Vec<_> = table.get_animations().keys().copied().collect();
+        actions.sort();
+        assert_eq!(actions, vec![0, 5]);
+        assert_eq!(
+            summary(&report.errors),
+            vec![(15, 15, "Action number out of range: -99999999999")]
+        );
+        assert_eq!(
+            summary(&report.warnings),
+            vec![
+                (2, 1, "2 boxes declared but 1 found"),
+                (4, 11, "Invalid time: x"),
+                (6, 14, "Invalid flip: Z"),
+                (6, 17, "Invalid transparency: A2"),
+                (6, 26, "Invalid Y scale: big"),
+                (8, 1, "Unknown line: Interpolate Size"),
+                (7, 2, "Action 0 has no frames"),
+                (7, 2, "Action 0 is already defined"),
+                (12, 2, "Collision box outside of a Clsn1: or Clsn2: block"),
+                (14, 2, "Unknown section: End"),
+                (16, 1, "Line outside of an action"),
+            ]
+        );
+
+        let kfm = std::fs::read_to_string("src/chars/kfm/kfm.air").unwrap();
+        let (_, report) = check_animation_table(&kfm);
+        assert!(report.is_empty(), "{:#?}", report);
+        assert!(read_animation_table("[Begin Action -99999999999]").is_err());
+    }
+
     #[test]
     /// Prueba si un archivo air bien formado lo crea y si uno que ni existe falla
     fn test_parse_air() {
@@ -1186,11 +1716,7 @@ mod tests {
             "Loopstart",
             "1,10, 0,8, 5",
         ];
-        let mut index = 0;
-        let result = read_action(&lines, &mut index);
-
-        assert!(result.is_ok());
-        let animation = result.unwrap();
+        let animation = read_lines(&lines);
 
         assert!(animation.is_some());
         let (number, animation) = animation.unwrap();
@@ -1204,7 +1730,7 @@ mod tests {
         assert!(
             approx_eq_vec(
                 &animation.frames[0].ex[0],
-                &vec![16.0, -80.0, 61.0, -71.0],
+                &[16.0, -80.0, 61.0, -71.0],
                 1e-6
             ),
             "Clsn1 are not equal"
@@ -1212,7 +1738,7 @@ mod tests {
         assert!(
             approx_eq_vec(
                 &animation.frames[0].ex[1],
-                &vec![-13.0, -79.0, 16.0, 0.0, -7.0, -93.0, 5.0, -79.0],
+                &[-13.0, -79.0, 16.0, 0.0, -7.0, -93.0, 5.0, -79.0],
                 1e-6
             ),
             "Clsn2 are not equal"
@@ -1225,13 +1751,13 @@ mod tests {
         assert_eq!(animation.frames[1].y, 8);
         assert_eq!(animation.frames[1].time, 5);
         assert!(
-            approx_eq_vec(&animation.frames[1].ex[0], &vec![], 1e-6),
+            approx_eq_vec(&animation.frames[1].ex[0], &[], 1e-6),
             "Clsn1 are not equal"
         );
         assert!(
             approx_eq_vec(
                 &animation.frames[1].ex[1],
-                &vec![-13.0, -79.0, 16.0, 0.0, -7.0, -93.0, 5.0, -79.0],
+                &[-13.0, -79.0, 16.0, 0.0, -7.0, -93.0, 5.0, -79.0],
                 1e-6
             ),
             "Clsn2 are not equal"
@@ -1242,10 +1768,7 @@ mod tests {
     #[test]
     fn test_read_action_invalid_format() {
         let lines = vec!["[Begin Action]", "10,20, 13,8, 7", "1,10, 0,8, 5"];
-        let mut index = 0;
-        let result = read_action(&lines, &mut index);
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_none());
+        assert!(read_lines(&lines).is_none());
     }
 
     /// Prueba unitaria cuando se envía una acción sin animación.
@@ -1259,11 +1782,7 @@ mod tests {
             "Clsn2[0] = -13,  0, 16,-79",
             "Clsn2[1] =   5,-79, -7,-93",
         ];
-        let mut index = 0;
-        let result = read_action(&lines, &mut index);
-        assert!(result.is_ok());
-
-        let animation = result.unwrap();
+        let animation = read_lines(&lines);
         assert!(animation.is_some());
         let (number, animation) = animation.unwrap();
 
@@ -1275,11 +1794,7 @@ mod tests {
     #[test]
     fn test_read_action_invalid_line_format() {
         let lines = vec!["[Begin Action 5]", "Invalid Line", "10,20, 13,8, 7"];
-        let mut index = 0;
-        let result = read_action(&lines, &mut index);
-
-        assert!(result.is_ok());
-        let animation = result.unwrap();
+        let animation = read_lines(&lines);
 
         assert!(animation.is_some());
         let (number, animation) = animation.unwrap();
@@ -1301,11 +1816,7 @@ mod tests {
             "Clsn1[0] =  16,-80, 61,-71",
             "10,20, 13,8, 7",
         ];
-        let mut index = 0;
-        let result = read_action(&lines, &mut index);
-
-        assert!(result.is_ok());
-        let animation = result.unwrap();
+        let animation = read_lines(&lines);
 
         assert!(animation.is_some());
         let (number, animation) = animation.unwrap();
@@ -1318,26 +1829,252 @@ mod tests {
         assert_eq!(animation.frames[0].time, 7);
     }
 
+    /// Prueba unitaria de las colisiones por defecto y las que solo valen para un fotograma.
+    #[test]
+    fn test_read_action_clsn_defaults() {
+        let lines = vec![
+            "[Begin Action 200]",
+            "Clsn1Default: 1",
+            " Clsn1[0] = 10,-60, 30,-50",
+            "Clsn2Default: 1",
+            " Clsn2[0] = -10,0, 10,-80",
+            "200,0, 0,0, 3",
+            "Clsn1: 1",
+            " Clsn1[0] = 20,-70, 60,-60",
+            "Clsn2: 0",
+            "200,1, 0,0, 3",
+            "200,2, 0,0, 3",
+        ];
+        let (_, animation) = read_lines(&lines).unwrap();
+
+        assert_eq!(animation.frames.len(), 3);
+        let frames = &animation.frames;
+        assert_eq!(frames[0].ex[0], vec![10.0, -60.0, 30.0, -50.0]);
+        assert_eq!(frames[0].ex[1], vec![-10.0, -80.0, 10.0, 0.0]);
+        assert!(frames[0].def1 && frames[0].def2);
+
+        assert_eq!(frames[1].ex[0], vec![20.0, -70.0, 60.0, -60.0]);
+        assert!(frames[1].ex[1].is_empty());
+        assert!(!frames[1].def1 && !frames[1].def2);
+
+        assert_eq!(frames[2].ex[0], vec![10.0, -60.0, 30.0, -50.0]);
+        assert_eq!(frames[2].ex[1], vec![-10.0, -80.0, 10.0, 0.0]);
+        assert!(frames[2].def1 && frames[2].def2);
+
+        let mut clsns = Vec::new();
+        let mut animation = animation;
+        animation.current = 1;
+        animation.update_clsns(&mut clsns);
+        assert_eq!(clsns.len(), 1);
+        assert!(!clsns[0].is_hitbox());
+        animation.current = 2;
+        animation.update_clsns(&mut clsns);
+        assert_eq!(clsns.len(), 2);
+        assert_eq!(clsns[0].get_rectangle(), [-10.0, -80.0, 20.0, 80.0]);
+        assert_eq!(clsns[1].get_rectangle(), [10.0, -60.0, 20.0, 10.0]);
+    }
+
+    /// Prueba unitaria de la línea Loopstart y los tiempos del bucle.
+    #[test]
+    fn test_read_action_loopstart() {
+        let lines = vec![
+            "[Begin Action 20]",
+            "20,0, 0,0, 4",
+            "20,1, 0,0, 5",
+            "LoopStart",
+            "20,2, 0,0, 6",
+            "20,3, 0,0, 7",
+        ];
+        let (_, animation) = read_lines(&lines).unwrap();
+
+        assert_eq!(animation.get_loopstart(), 2);
+        assert_eq!(animation.totaltime, 22);
+        assert_eq!(animation.nazotime, 9);
+        assert_eq!(animation.looptime, 13);
+
+        let lines = vec!["[Begin Action 21]", "21,0, 0,0, 4", "Loopstart"];
+        let (_, animation) = read_lines(&lines).unwrap();
+        assert_eq!(animation.get_loopstart(), 0);
+    }
+
+    /// Prueba que `AnimElem` solo se cumpla en el primer tick de un elemento
+    #[test]
+    fn test_anim_elem_trigger() {
+        let lines = vec![
+            "[Begin Action 200]",
+            "200,0, 0,0, 2",
+            "200,1, 0,0, 3",
+            "200,2, 0,0, 2",
+        ];
+        let (_, mut animation) = read_lines(&lines).unwrap();
+        let cns = [
+            "[Statedef 0]",
+            "anim = 200",
+            "[State 0, Count]",
+            "type = VarAdd",
+            "trigger1 = AnimElem = 2",
+            "v = 0",
+            "value = 1",
+        ];
+        let mut table = read_cns_file(&cns).unwrap();
+        let mut char: Box<dyn Character> = Box::new(CharData::from_def(CharDef::new()));
+
+        let elements = [0, 0, 1, 1, 1, 2, 2];
+        let mut fired = Vec::new();
+        for (tick, &element) in elements.iter().enumerate() {
+            let count = char.get_var(0);
+            table.run(char.as_mut(), None);
+            if char.get_var(0) != count {
+                fired.push(tick);
+            }
+            // Lo mismo que hace `update_sprite` después de los estados, sin dibujar
+            if char.get_new_anim() {
+                animation.reset();
+                char.set_new_anim(false);
+            }
+            animation.advance();
+            assert_eq!(animation.current, element);
+            char.set_anim_element(animation.current);
+            let (element, starts) = animation.next_element();
+            char.set_anim_elem_start(if starts { element } else { -1 });
+        }
+        assert_eq!(fired, vec![2]);
+    }
+
+    /// Prueba unitaria de las líneas de interpolación.
+    #[test]
+    fn test_read_action_interpolate() {
+        let lines = vec![
+            "[Begin Action 30]",
+            "30,0, 0,0, 5",
+            "Interpolate Offset",
+            "Interpolate Scale",
+            "30,0, 20,0, 5, , , 2,2",
+            "Interpolate Angle ; comentario",
+            "Interpolate Blend",
+            "30,0, 40,0, 5, , AS0D256, 1,1, 90",
+            "Interpolate Blend",
+            "30,0, 60,0, 5",
+        ];
+        let (_, animation) = read_lines(&lines).unwrap();
+
+        assert_eq!(animation.frames.len(), 4);
+        assert_eq!(animation.interpolate_offset, vec![1]);
+        assert_eq!(animation.interpolate_scale, vec![1]);
+        assert_eq!(animation.interpolate_angle, vec![2]);
+        assert_eq!(animation.interpolate_blend, vec![2, 3]);
+    }
+
+    /// Prueba unitaria de las columnas opcionales de escala y ángulo.
+    #[test]
+    fn test_read_anim_frame_scale_angle() {
+        let af = frame("0,0, 0,0, 5");
+        assert!(af.ex.is_empty());
+
+        let af = frame("0,0, 0,0, 5, h, a, 1.5, 2, 45");
+        assert_eq!(af.get_flip(), (true, false));
+        assert_eq!((af.src_alpha, af.dst_alpha), (255, 255));
+        assert_eq!(af.ex[2], vec![1.5, 2.0, 45.0]);
+
+        let af = frame("0,0, 0,0, 5, , , 2");
+        assert_eq!(af.ex[2], vec![2.0, 1.0, 0.0]);
+
+        let af = frame("0,0, 0,0, 5, , , , , -30");
+        assert_eq!(af.ex[2], vec![1.0, 1.0, -30.0]);
+
+        let lines = vec![
+            "[Begin Action 40]",
+            "Clsn2Default: 1",
+            " Clsn2[0] = -10,0, 10,-80",
+            "40,0, 0,0, 5, , , 0.5,0.5, 180",
+        ];
+        let (_, animation) = read_lines(&lines).unwrap();
+        assert_eq!(animation.frames[0].ex[1], vec![-10.0, -80.0, 10.0, 0.0]);
+        assert_eq!(animation.frames[0].ex[2], vec![0.5, 0.5, 180.0]);
+    }
+
+    /// Prueba la interpolación de los valores de dibujado entre un fotograma y el siguiente.
+    #[test]
+    fn test_get_render_interpolate() {
+        let lines = vec![
+            "[Begin Action 50]",
+            "50,0, 0,0, 4, , AS255D0, 1,1, 0",
+            "Interpolate Offset",
+            "Interpolate Angle",
+            "Interpolate Blend",
+            "50,0, 40,-20, 4, , AS55D200, 2,2, 90",
+            "50,0, 0,0, 4, H, A, 0.5,1",
+        ];
+        let (_, mut animation) = read_lines(&lines).unwrap();
+
+        animation.current = 0;
+        animation.time = 3;
+        assert_eq!(animation.get_render(), animation.frames[0].get_render());
+
+        animation.time = 1;
+        let render = animation.get_render();
+        assert_eq!(render.offset, (20.0, -10.0));
+        assert_eq!(render.angle, 45.0);
+        assert_eq!(render.alpha, (155, 100));
+        assert_eq!(render.scale, (1.0, 1.0));
+
+        animation.current = 1;
+        animation.time = 1;
+        let render = animation.get_render();
+        assert_eq!(render.offset, (40.0, -20.0));
+        assert_eq!(render.scale, (2.0, 2.0));
+        assert_eq!(render.alpha, (55, 200));
+
+        animation.current = 2;
+        let render = animation.get_render();
+        assert_eq!(render.offset, (0.0, 0.0));
+        assert_eq!(render.scale, (0.5, 1.0));
+        assert_eq!(render.alpha, (255, 255));
+    }
+
+    /// Prueba los modos de mezcla con los que se dibujan las transparencias de los fotogramas.
+    #[test]
+    fn test_frame_blend() {
+        let (state, color) = frame_blend((255, 0));
+        assert_eq!(state.blend, Some(Blend::Alpha));
+        assert_eq!(color, [1.0; 4]);
+
+        let (state, color) = frame_blend((255, 255));
+        assert_eq!(state.blend, Some(Blend::Lighter));
+        assert_eq!(color, [1.0; 4]);
+
+        let (state, _) = frame_blend((255, 128));
+        assert_eq!(state.blend, Some(Blend::Lighter));
+
+        let (state, color) = frame_blend((128, 128));
+        assert_eq!(state.blend, Some(Blend::Alpha));
+        assert_eq!(color[3], 128.0 / 255.0);
+
+        let (state, color) = frame_blend((1, 255));
+        assert_eq!(state.blend, Some(Blend::Alpha));
+        assert_eq!(color, [0.0, 0.0, 0.0, 0.5]);
+    }
+
     /// Prueba unitaria para la función atoi.
     #[test]
     fn test_atoi() {
         // Casos de prueba válidos
-        assert_eq!(atoi(&"123".to_string()), Ok(123));
-        assert_eq!(atoi(&"-123".to_string()), Ok(-123));
-        assert_eq!(atoi(&"+123".to_string()), Ok(123));
-        assert_eq!(atoi(&"   123   ".to_string()), Ok(123));
-        assert_eq!(atoi(&"0000123".to_string()), Ok(123));
+        assert_eq!(atoi("123"), Ok(123));
+        assert_eq!(atoi("-123"), Ok(-123));
+        assert_eq!(atoi("+123"), Ok(123));
+        assert_eq!(atoi("   123   "), Ok(123));
+        assert_eq!(atoi("0000123"), Ok(123));
 
         // Casos de prueba de entrada no válida
-        assert_eq!(atoi(&"abc".to_string()), Ok(0));
-        assert_eq!(atoi(&"123abc".to_string()), Ok(123));
-        assert_eq!(atoi(&"-123abc".to_string()), Ok(-123));
-        assert_eq!(atoi(&"".to_string()), Ok(0));
-        assert_eq!(atoi(&"   ".to_string()), Ok(0));
+        assert_eq!(atoi("abc"), Ok(0));
+        assert_eq!(atoi("123abc"), Ok(123));
+        assert_eq!(atoi("-123abc"), Ok(-123));
+        assert_eq!(atoi(""), Ok(0));
+        assert_eq!(atoi("   "), Ok(0));
 
         // Casos de prueba fuera del rango de int32
-        assert_eq!(atoi(&"2147483648".to_string()), Ok(0));
-        assert_eq!(atoi(&"-2147483649".to_string()), Err(Error));
+        assert_eq!(atoi("2147483648"), Ok(0));
+        assert_eq!(atoi("-2147483649"), Err(Error));
     }
 
     /// Prueba unitaria para la función atof.
@@ -1366,16 +2103,14 @@ mod tests {
     /// Prueba unitaria para la actualización de colisiones
     #[test]
     fn test_update_clsns() {
-
         let air = parse_air("src/chars/kfm/kfm.air");
         assert!(air.is_ok());
         let mut animation_table = air.unwrap();
         let clsns = &mut animation_table.clsns;
         if let Some(animation) = animation_table.animations.get(&0) {
-            
             // Llamar al método update_clsns para actualizar el vector de colisiones
             animation.update_clsns(clsns);
-            
+
             // [Begin Action 0]
             // Clsn2Default: 2
             //  Clsn2[0] = -13,  0, 16,-79
@@ -1404,7 +2139,7 @@ mod tests {
             assert_eq!(clsns[0].ofs_y, expected_clsn0.ofs_y);
             assert_eq!(clsns[0].x, expected_clsn0.x);
             assert_eq!(clsns[0].y, expected_clsn0.y);
-            
+
             assert_eq!(clsns[1].height, expected_clsn1.height);
             assert_eq!(clsns[1].width, expected_clsn1.width);
             assert_eq!(clsns[1].hitbox, expected_clsn1.hitbox);
@@ -1,18 +1,21 @@
-use std::{rc::Rc, sync::MutexGuard};
+use std::{path::Path, rc::Rc};
 
-use gfx_device_gl::Resources;
+use graphics::math::Matrix2d;
 use image::RgbaImage;
-use piston_window::{G2dTextureContext, Texture, TextureSettings};
+use piston_window::{G2d, G2dTextureContext, TextureSettings};
 use sprite::Sprite;
 
 use crate::{
+    audio::mixer::{Mixer, Pan, SoundRequest},
     chars::{self, State},
     error::pop_up::show_error_popup,
 };
 
 use super::{
     air::{self, manager::AnimationTable},
-    cmd::{self, manager::CommandNode},
+    cmd::{self, manager::CommandList},
+    cns::{self, manager::{SoundCommand, StateTable, GROUND_Y}},
+    snd::decoder::Snd,
 };
 
 /// Estructura que representa un personaje en el juego.
@@ -21,8 +24,12 @@ pub struct Character {
     char: Box<dyn chars::Character>,
     /// Tabla de animaciones del personaje
     at: AnimationTable,
-    /// Árbol de comandos del personaje
-    cmd: CommandNode,
+    /// Comandos del personaje
+    cmd: CommandList,
+    /// Tabla de estados del personaje
+    states: StateTable,
+    /// Sonidos del personaje
+    snd: Snd,
 }
 
 impl Character {
@@ -48,42 +55,72 @@ impl Character {
         empty_sprite.set_anchor(0.0, 0.0);
         empty_sprite.set_scale(1.0, 1.0);
 
-        let char = chars::get_char(char_name).unwrap();
+        let char = match chars::get_char(char_name) {
+            Ok(char) => char,
+            Err(err) => {
+                show_error_popup(&err);
+                std::process::exit(1);
+            }
+        };
+        let def = char.get_def();
         let char_path = "src/chars/".to_string() + char_name + "/";
-        let mut at =
-            match air::manager::parse_air(&(char_path.clone() + char.get_air_name() + ".air")) {
-                Ok(at) => at,
-                Err(err) => {
-                    show_error_popup(&err);
-                    std::process::exit(1);
-                }
-            };
+        let mut at = match air::manager::parse_air(&(char_path.clone() + &def.files.anim)) {
+            Ok(at) => at,
+            Err(err) => {
+                show_error_popup(&err);
+                std::process::exit(1);
+            }
+        };
+
+        let cmd = match cmd::manager::create_command_list(&(char_path.clone() + &def.files.cmd)) {
+            Ok(at) => at,
+            Err(err) => {
+                show_error_popup(&err);
+                std::process::exit(1);
+            }
+        };
+
+        let states = match cns::manager::parse_cns(&(char_path.clone() + def.get_states_file())) {
+            Ok(states) => states,
+            Err(err) => {
+                show_error_popup(&err);
+                std::process::exit(1);
+            }
+        };
 
-        let cmd =
-            match cmd::manager::create_command_tree(&(char_path + char.get_cmd_name() + ".cmd")) {
-                Ok(at) => at,
+        let snd = match &def.files.sound {
+            Some(sound) => match Snd::load_snd(Path::new(&(char_path + sound))) {
+                Ok(snd) => snd,
                 Err(err) => {
                     show_error_popup(&err);
                     std::process::exit(1);
                 }
-            };
+            },
+            None => Snd::default(),
+        };
 
         at.set_sff(
             char_name,
-            char.get_sff_name().to_string() + ".sff",
-            true,
+            def.files.sprite.clone(),
+            &def.files.pal,
             context,
         );
         at.set_sprite(empty_sprite);
 
-        Self { char, at, cmd }
+        Self {
+            char,
+            at,
+            cmd,
+            states,
+            snd,
+        }
     }
 
-    /// Establece el índice de la paleta del personaje.
+    /// Establece el color del personaje.
     ///
     /// # Argumentos
     ///
-    /// * `palette_index` - Índice de la paleta.
+    /// * `palette_index` - Número del color, de 1 a 12.
     pub fn set_palette(&mut self, palette_index: usize) {
         self.at.set_palette(palette_index);
     }
@@ -154,6 +191,24 @@ impl Character {
         self.char.set_action(action);
     }
 
+    /// Obtiene la acción que está realizando el personaje.
+    ///
+    /// # Retorna
+    ///
+    /// El nombre de la acción, vacío si no hay ninguna.
+    pub fn get_action(&self) -> &str {
+        self.char.get_action()
+    }
+
+    /// Establece los comandos activos del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `commands` - Nombres de los comandos activos en este frame.
+    pub fn set_commands(&mut self, commands: Vec<String>) {
+        self.char.set_commands(commands);
+    }
+
     /// Establece la dirección del personaje.
     ///
     /// # Argumentos
@@ -172,13 +227,15 @@ impl Character {
         &self.at
     }
 
-    /// Obtiene el sprite del personaje.
-    /// 
-    /// # Retorna
-    /// 
-    /// Retorna una referencia mutable del sprite del personaje.
-    pub fn get_sprite(&mut self) -> MutexGuard<Sprite<Texture<Resources>>> {
-        self.at.get_sprite()
+    /// Dibuja el sprite del personaje en su posición.
+    ///
+    /// # Argumentos
+    ///
+    /// * `transform` - Transformación del escenario a la pantalla.
+    /// * `g` - El contexto de gráficos.
+    pub fn draw(&self, transform: Matrix2d, g: &mut G2d) {
+        self.at
+            .draw((self.char.get_x(), self.char.get_y()), transform, g);
     }
 
     /// Verifica si el personaje está volteado.
@@ -212,24 +269,53 @@ impl Character {
         self.char.has_control()
     }
 
-    /// Obtiene el árbol de comandos del personaje.
+    /// Obtiene los comandos del personaje.
     /// 
     /// # Retorna
     /// 
-    /// El nodo de comandos desde el nivel 0.
-    pub fn get_cmd(&self) -> &CommandNode {
+    /// La lista de comandos leída de su CMD.
+    pub fn get_cmd(&self) -> &CommandList {
         &self.cmd
     }
 
     /// Configura el personaje como segundo jugador.
     pub fn set_as_second_player(&mut self) {
-        self.char.set_x(450.0);
         self.at.get_sprite().set_anchor(1.0, 0.0);
         self.char.set_current_flip(true);
         self.char.set_previous_flip();
         self.char.set_distance(1.0);
     }
 
+    /// Coloca al personaje en su posición inicial.
+    ///
+    /// # Argumentos
+    ///
+    /// * `x` - Posición en la X en coordenadas del escenario.
+    /// * `y` - Altura respecto al suelo, negativa hacia arriba.
+    pub fn set_start_position(&mut self, x: f64, y: f64) {
+        self.char.set_x(x);
+        self.char.set_y(GROUND_Y + y);
+    }
+
+    /// Obtiene la posición del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// La posición en coordenadas del escenario.
+    pub fn get_position(&self) -> (f64, f64) {
+        (self.char.get_x(), self.char.get_y())
+    }
+
+    /// Establece los límites en la X en los que se puede mover el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `left` - Límite izquierdo en coordenadas del escenario.
+    /// * `right` - Límite derecho en coordenadas del escenario.
+    pub fn set_bounds(&mut self, left: f64, right: f64) {
+        self.char.set_bounds(left, right);
+    }
+
     /// Obtiene la vida del personaje.
     /// 
     /// # Retorna
@@ -239,6 +325,24 @@ impl Character {
         self.char.get_life()
     }
 
+    /// Obtiene las frases de victoria del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Las frases de la sección `[Quotes]` de sus constantes, en orden.
+    pub fn get_quotes(&self) -> &[String] {
+        &self.char.get_def().quotes
+    }
+
+    /// Multiplica la vida máxima del personaje y lo deja con la vida llena.
+    /// 
+    /// # Argumentos
+    /// 
+    /// * `multiplier` - El multiplicador de la vida definida por el personaje.
+    pub fn set_life_multiplier(&mut self, multiplier: f64) {
+        self.char.set_life_multiplier(multiplier);
+    }
+
     /// Establece si el personaje ganó.
     ///
     /// # Argumentos
@@ -276,8 +380,14 @@ impl Character {
     }
 
     /// Actualiza el estado del personaje.
-    pub fn update(&mut self) {
+    ///
+    /// # Argumentos
+    ///
+    /// * `opponent` - Personaje rival, usado al evaluar los disparadores de los estados.
+    pub fn update(&mut self, opponent: Option<&Character>) {
         self.char.update_data();
+        let p2 = opponent.map(|opponent| opponent.char.as_ref());
+        self.states.run(self.char.as_mut(), p2);
         self.char.update_pos();
         self.at.update_sprite(&mut self.char);
         let x;
@@ -291,16 +401,57 @@ impl Character {
             .set_position(x, self.char.get_y() - self.char.get_offset_y());
     }
 
+    /// Envía al mezclador los sonidos que han pedido los estados del personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `mixer` - Mezclador de audio de la pelea.
+    /// * `owner` - Índice del jugador, cada uno tiene sus propios canales.
+    /// * `common` - Sonidos comunes, usados por los sonidos con prefijo `F`.
+    pub fn play_sounds(&mut self, mixer: &mut Mixer, owner: usize, common: &Snd) {
+        for command in self.char.take_sounds() {
+            match command {
+                SoundCommand::Play(play_snd) => {
+                    let snd = if play_snd.common { common } else { &self.snd };
+                    let Some(sound) = snd.get_sound(play_snd.sound) else {
+                        continue;
+                    };
+                    let facing = if self.char.is_flipped() { -1.0 } else { 1.0 };
+                    let pan = if play_snd.abs_pan {
+                        Pan::Absolute(play_snd.pan)
+                    } else {
+                        Pan::Position(self.char.get_x() + play_snd.pan * facing)
+                    };
+                    mixer.play(
+                        sound.clone(),
+                        SoundRequest {
+                            owner,
+                            channel: play_snd.channel,
+                            volume: (play_snd.volume_scale / 100.0) as f32,
+                            pan,
+                            low_priority: play_snd.low_priority,
+                            freq_mul: play_snd.freq_mul as f32,
+                            looping: play_snd.looping,
+                            echo: self.char.get_def().data.ko_echo && self.char.get_life() <= 0,
+                        },
+                    );
+                }
+                SoundCommand::Stop(channel) => mixer.stop(owner, channel),
+            }
+        }
+    }
+
     /// Maneja las colisiones entre personajes donde haya una hurtbox.
     ///
+    /// Un elemento de la animación solo puede alcanzar una vez al objetivo.
+    ///
     /// # Argumentos
     ///
     /// * `player_target` - Personaje objetivo para la colisión.
     pub fn collision_handler(&mut self, player_target: &mut Character) {
         let current_frame = self.char.get_anim_elem();
-        if self.char.get_hit() != current_frame {
+        if self.char.get_hit() != current_frame && self.char.hit_handler(&mut *player_target.char) {
             self.char.set_hit(current_frame);
-            self.char.hit_handler(&mut *player_target.char);
         }
     }
 }
@@ -1,14 +1,58 @@
-use crate::{error::cmd_error::CmdError, player::input::manager::CommandInput, CK};
+use crate::{
+    error::cmd_error::CmdError,
+    player::{
+        character::span::{content, fields, key_value, section, split_lines, Report, Span},
+        input::manager::InputFrame,
+    },
+    CK,
+};
 
+use nom::Slice;
 use regex::Regex;
 use std::collections::HashSet;
 
+/// Tiempo por defecto entre elementos de un comando, si no hay `[Defaults]`.
+const DEFAULT_TIME: u16 = 15;
+/// Tiempo por defecto que un comando sigue activo tras completarse, si no hay `[Defaults]`.
+const DEFAULT_BUFFER_TIME: u16 = 1;
+
+/// Nombres de los botones y direcciones que se pueden usar en un comando.
+///
+/// Además de los nombres propios se aceptan los de MUGEN: `x`, `y`, `z` para los puños,
+/// `a`, `b`, `c` para las patadas y `s` para el inicio.
+const BUTTONS: [(&str, CK); 22] = [
+    ("LP", CK::LP),
+    ("MP", CK::MP),
+    ("HP", CK::HP),
+    ("LK", CK::LK),
+    ("MK", CK::MK),
+    ("HK", CK::HK),
+    ("U", CK::U),
+    ("F", CK::F),
+    ("D", CK::D),
+    ("B", CK::B),
+    ("UF", CK::UF),
+    ("UB", CK::UB),
+    ("DF", CK::DF),
+    ("DB", CK::DB),
+    ("Start", CK::Start),
+    ("x", CK::LP),
+    ("y", CK::MP),
+    ("z", CK::HP),
+    ("a", CK::LK),
+    ("b", CK::MK),
+    ("c", CK::HK),
+    ("s", CK::Start),
+];
+
 /// Representa un comando, que es una secuencia de elementos que forman una ejecución de movimiento.
 /// Contiene la lista de elementos de comando y el nombre del comando.
 #[derive(Debug, Clone)]
-struct Command {
+pub struct Command {
     cmd_elements: Vec<CommandElement>,
     name: String,
+    time: u16,
+    buffer_time: u16,
 }
 
 /// Representa un elemento de comando, que es la unidad más pequeña de un comando, y contiene las teclas presionadas y el tiempo mantenido.
@@ -17,24 +61,18 @@ struct CommandElement {
     elements: HashSet<CK>,
     sensitive: bool,
     time: u16,
+    hold: Option<u16>,
+    release: Option<u16>,
+    diagonals: bool,
 }
 
-/// Representa un nodo en un árbol de comandos.
-/// Contiene información sobre los elementos de comando, nombre del comando, sensibilidad, ventana de entrada y sub-nodos.
+/// Lista de comandos de un personaje.
+///
+/// Los comandos se comprueban todos en cada frame contra el historial de entrada del jugador.
 #[derive(Debug, Clone)]
-pub struct CommandNode {
-    /// Nivel dentro del árbol
-    level: usize,
-    /// Elemento para seguir la secuencia de comandos
-    cmd_elements: Option<Vec<CK>>,
-    /// Nombre de la hoja
-    name: Option<String>,
-    /// Sensibilidad con la que se debe ejecutar el comando
-    sensitive: bool,
-    /// Ventana o franja para introducir un comando entre este y el anterior nodo
-    input_window: Vec<u16>,
-    /// Nodos subsiguientes a este
-    sub_nodes: Vec<CommandNode>,
+pub struct CommandList {
+    /// Comandos en el orden del archivo, con los `$` ya expandidos
+    commands: Vec<Command>,
 }
 
 impl Command {
@@ -47,7 +85,106 @@ impl Command {
         Self {
             cmd_elements: Vec::new(),
             name: String::new(),
+            time: DEFAULT_TIME,
+            buffer_time: DEFAULT_BUFFER_TIME,
+        }
+    }
+
+    /// Obtiene el nombre del comando.
+    ///
+    /// # Retorna
+    ///
+    /// El nombre del comando.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Obtiene los frames que el comando sigue activo tras completarse.
+    ///
+    /// # Retorna
+    ///
+    /// El `buffer.time` del comando.
+    pub fn get_buffer_time(&self) -> u16 {
+        self.buffer_time
+    }
+
+    /// Obtiene el número de elementos del comando.
+    ///
+    /// # Retorna
+    ///
+    /// La cantidad de elementos de la secuencia.
+    pub fn len(&self) -> usize {
+        self.cmd_elements.len()
+    }
+
+    /// Indica si el comando no tiene elementos.
+    ///
+    /// # Retorna
+    ///
+    /// True si la secuencia está vacía.
+    pub fn is_empty(&self) -> bool {
+        self.cmd_elements.is_empty()
+    }
+
+    /// Indica si el comando se completa en el último frame del historial.
+    ///
+    /// Se buscan los elementos desde el último hacia atrás, cada uno en el frame más reciente
+    /// posible antes del siguiente. El último elemento tiene que ocurrir en el frame actual y el
+    /// primero como mucho `time` frames antes.
+    ///
+    /// # Argumentos
+    ///
+    /// * `history` - Las teclas de cada frame, del más antiguo al actual.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el comando se ha completado en este frame, de lo contrario `false`.
+    fn matches(&self, history: &[InputFrame]) -> bool {
+        let Some(now) = history.len().checked_sub(1) else {
+            return false;
+        };
+        let earliest = now.saturating_sub(self.time as usize);
+        // Frame del elemento siguiente y si es sensible
+        let mut next: Option<(usize, &CommandElement)> = None;
+        for cmd_element in self.cmd_elements.iter().rev() {
+            let (latest, oldest) = match next {
+                None => (now, now),
+                Some((frame, next_element)) => {
+                    let latest = if cmd_element.is_zero_width() {
+                        frame
+                    } else if let Some(latest) = frame.checked_sub(1) {
+                        latest
+                    } else {
+                        return false;
+                    };
+                    let oldest = match next_element.time {
+                        // Las teclas mantenidas tienen que estarlo al ocurrir el siguiente
+                        _ if cmd_element.hold.is_some() => frame,
+                        0 => earliest,
+                        time => earliest.max(frame.saturating_sub(time as usize)),
+                    };
+                    (latest, oldest)
+                }
+            };
+            if latest < oldest {
+                return false;
+            }
+            let Some(frame) = (oldest..=latest)
+                .rev()
+                .find(|frame| cmd_element.matches(history, *frame))
+            else {
+                return false;
+            };
+            if let Some((next_frame, next_element)) = next {
+                if next_element.sensitive
+                    && has_other_keys(history, frame + 1, next_frame, cmd_element)
+                {
+                    return false;
+                }
+            }
+            next = Some((frame, cmd_element));
         }
+        next.is_some()
     }
 }
 
@@ -68,7 +205,7 @@ impl CommandElement {
         inputs: HashSet<String>,
         time: u16,
         sensitive: bool,
-        directions_and_actions: &[(&str, CK); 15],
+        directions_and_actions: &[(&str, CK)],
     ) -> Self {
         let mut elements = HashSet::new();
         for input in inputs {
@@ -82,446 +219,469 @@ impl CommandElement {
             elements,
             sensitive,
             time,
+            hold: None,
+            release: None,
+            diagonals: false,
         }
     }
-}
 
-impl CommandNode {
-    /// Crea una nueva instancia de `CommandNode` con valores por defecto.
+    /// Indica si el elemento puede ocurrir en el mismo frame que el siguiente.
     ///
-    /// # Retorna
-    ///
-    /// Una nueva instancia de `CommandNode` con valores iniciales predeterminados.
-    fn new() -> Self {
-        Self {
-            level: 0,
-            cmd_elements: None,
-            name: None,
-            sensitive: false,
-            input_window: Vec::new(),
-            sub_nodes: Vec::new(),
-        }
+    /// Mantener (`/`) o soltar (`~`) teclas no necesita un frame propio: `/D, a` es pulsar `a`
+    /// con abajo mantenido y `~D, DF` es pasar de abajo a abajo-adelante.
+    fn is_zero_width(&self) -> bool {
+        self.hold.is_some() || self.release.is_some()
     }
 
-    /// Inserta un comando en el árbol de comandos a partir de él mismo.
-    /// Recorre recursivamente el árbol e inserta los elementos de comando en las posiciones apropiadas.
+    /// Indica si el elemento ocurre en un frame del historial.
     ///
     /// # Argumentos
     ///
-    /// * `command` - Una referencia al comando que se va a insertar.
-    /// * `pos` - La posición donde debería insertarse el elemento de comando.
-    fn insert(&mut self, command: &Command, pos: usize) {
-        if let Some(cmd_element) = command.cmd_elements.get(pos) {
-            if let Some(node) = self.sub_nodes.iter_mut().find(|node| {
-                node.cmd_elements == Some(cmd_element.elements.clone().into_iter().collect())
-            }) {
-                node.input_window.push(cmd_element.time);
-                node.sensitive = cmd_element.sensitive;
-                node.level = pos;
-                node.insert(command, pos + 1);
-            } else {
-                let mut new_command = CommandNode::new();
-                new_command.cmd_elements = Some(cmd_element.elements.clone().into_iter().collect());
-                new_command.input_window.push(cmd_element.time);
-                new_command.sensitive = cmd_element.sensitive;
-                new_command.level = pos;
-                if command.cmd_elements.len() - 1 <= pos {
-                    new_command.name = Some(command.name.clone());
-                }
-                self.sub_nodes.push(new_command);
-                self.sub_nodes.last_mut().unwrap().insert(command, pos + 1);
+    /// * `history` - Las teclas de cada frame, del más antiguo al actual.
+    /// * `frame` - El frame a comprobar.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si en ese frame se mantienen, sueltan o presionan las teclas del elemento.
+    fn matches(&self, history: &[InputFrame], frame: usize) -> bool {
+        let empty = InputFrame::default();
+        let current = &history[frame];
+        let previous = frame
+            .checked_sub(1)
+            .map_or(&empty, |previous| &history[previous]);
+        let diagonals = self.diagonals;
+        match (self.hold, self.release) {
+            (_, Some(time)) => self.elements.iter().all(|key| {
+                current.was_released(key, diagonals)
+                    && previous.get_charge(key, diagonals) >= time as u32
+            }),
+            (Some(time), None) => self
+                .elements
+                .iter()
+                .all(|key| current.get_charge(key, diagonals) >= (time as u32).max(1)),
+            (None, None) => {
+                self.elements
+                    .iter()
+                    .all(|key| current.holds(key, diagonals) || current.was_pressed(key, diagonals))
+                    && self
+                        .elements
+                        .iter()
+                        .any(|key| current.was_pressed(key, diagonals))
             }
         }
     }
+}
 
-    /// Busca un comando que coincida con el búfer de entrada comenzando desde la posición especificada.
-    /// Busca de forma recursiva en el árbol de comandos un comando que coincida y devuelve su nombre si lo encuentra.
+impl CommandList {
+    /// Busca los comandos que se completan en el último frame del historial.
     ///
     /// # Argumentos
     ///
-    /// * `input_buffer` - Una referencia al búfer de entrada que contiene los inputs de comando.
-    /// * `pos` - La posición en el búfer de entrada desde la cual comenzar la búsqueda.
+    /// * `history` - Las teclas de cada frame, del más antiguo al actual.
     ///
     /// # Retorna
     ///
-    /// Una referencia opcional al `nombre` del comando que coincide, de lo contrario `None`.
-    pub fn search(&self, input_buffer: &Vec<CommandInput>, pos: usize) -> Option<&String> {
-        let sub_nodes = &self.sub_nodes;
-        let ib_len = input_buffer.len();
-        if let Some(input) = input_buffer.get(pos) {
-            for sub_node in sub_nodes.iter().filter(|sub_node| {
-                sub_node
-                    .input_window
-                    .iter()
-                    .all(|time| *time == 0 || input.get_input_window_ref() <= time)
-                    && sub_node.level < ib_len
-            }) {
-                let matched = if sub_node.sensitive {
-                    sub_node.cmd_elements.iter().all(|commands| {
-                        input.get_keys_ref().len() == commands.len()
-                            && commands.iter().all(|command| {
-                                input
-                                    .get_keys_ref()
-                                    .iter()
-                                    .any(|input_key| input_key.get_cmd_key_ref() == command)
-                            })
-                    })
-                } else {
-                    sub_node.cmd_elements.iter().all(|commands| {
-                        commands.iter().all(|command| {
-                            input
-                                .get_keys_ref()
-                                .iter()
-                                .any(|input_key| input_key.get_cmd_key_ref() == command)
-                        })
-                    })
-                };
-                if matched {
-                    if let Some(name) = &sub_node.name {
-                        return Some(name);
-                    } else {
-                        if let Some(result) = sub_node.search(input_buffer, pos + 1) {
-                            return Some(result);
-                        } else {
-                            return sub_node.search(input_buffer, pos + 2);
-                        }
-                    }
-                }
-            }
-        }
-        None
+    /// Los comandos completados, en el orden del archivo.
+    pub fn search(&self, history: &[InputFrame]) -> Vec<&Command> {
+        self.commands
+            .iter()
+            .filter(|command| command.matches(history))
+            .collect()
     }
+}
 
-    /// Prints the command tree starting from himself with proper indentation.
-    ///
-    /// # Arguments
-    ///
-    /// * `level` - The current level of indentation and depth in the command tree.
-    // pub fn print(&self, level: usize) {
-    //     if let Some(command) = &self.cmd_elements {
-    //         for _ in 1..level {
-    //             print!("  ");
-    //         }
-    //         if let Some(name) = &self.name {
-    //             println!("{:?} - {}", command, name);
-    //         } else {
-    //             println!("{:?}", command);
-    //         }
-    //     }
-    //     for sub_command in &self.sub_nodes {
-    //         sub_command.print(level + 1);
-    //     }
-    // }
-
-    /// Ordena el árbol de comandos dando prioridad a las ramas más cortas.
-    fn sort(&mut self) {
-        let priorities = vec![CK::LK, CK::MK, CK::HK, CK::LP, CK::HP, CK::MP];
-
-        self.sub_nodes.sort_by(|a, b| {
-            let a_priority = a
-                .cmd_elements
-                .as_ref()
-                .and_then(|elems| elems.iter().position(|x| priorities.contains(x)))
-                .unwrap_or(usize::MAX);
-
-            let b_priority = b
-                .cmd_elements
-                .as_ref()
-                .and_then(|elems| elems.iter().position(|x| priorities.contains(x)))
-                .unwrap_or(usize::MAX);
-
-            if let (Some(a_elems), Some(b_elems)) =
-                (a.cmd_elements.as_ref(), b.cmd_elements.as_ref())
-            {
-                if a_elems.len() > b_elems.len() {
-                    return std::cmp::Ordering::Less;
-                } else if b_elems.len() > a_elems.len() {
-                    return std::cmp::Ordering::Greater;
-                }
-            }
-
-            a_priority.cmp(&b_priority)
-        });
-
-        for sub_node in &mut self.sub_nodes {
-            sub_node.sort();
-        }
-    }
+/// Indica si entre dos elementos se presionó una tecla o se soltó una que no es del primero.
+///
+/// Es lo que impide un elemento sensible (`>`): soltar las teclas del elemento anterior sí se
+/// permite, para poder volver a presionarlas como en `F, >F`.
+///
+/// # Argumentos
+///
+/// * `history` - Las teclas de cada frame, del más antiguo al actual.
+/// * `from` - Primer frame del rango.
+/// * `to` - Frame siguiente al último del rango.
+/// * `previous` - El elemento anterior al sensible.
+///
+/// # Retorna
+///
+/// `true` si en algún frame del rango se presionó una tecla o se soltó otra tecla.
+fn has_other_keys(
+    history: &[InputFrame],
+    from: usize,
+    to: usize,
+    previous: &CommandElement,
+) -> bool {
+    (from..to).any(|frame| {
+        let frame = &history[frame];
+        frame.has_presses() || frame.has_other_releases(&previous.elements, previous.diagonals)
+    })
 }
 
 /// Lee un archivo de comandos y analiza su contenido para crear un vector de comandos.
 ///
-/// Esta función lee un archivo de comandos especificado por el parámetro de nombre de archivo. Analiza el contenido del archivo
-/// para extraer los comandos y sus detalles asociados, como nombres, secuencias de entrada e información de temporización.
-/// El formato del archivo de comandos debe adherirse a las siguientes convenciones:
-/// - El archivo debe contener secciones delimitadas por marcadores '#commands' y '#endcommands'.
-/// - Cada comando debe especificarse en el siguiente formato:
-///   - 'name=<nombre_del_comando>' para especificar el nombre del comando.
-///   - 'command=<secuencia_de_entrada>' para especificar la secuencia de entrada para el comando.
-///   - Opcionalmente, se puede usar 'time=<valores_de_tiempo>' para especificar los valores de temporización para el comando.
+/// Analiza el contenido del archivo para extraer los comandos y sus detalles asociados, como
+/// nombres, secuencias de entrada e información de temporización, con la sintaxis de MUGEN:
+/// - Cada comando va en una sección `[Command]` con las claves, en cualquier orden:
+///   - 'name = "<nombre_del_comando>"' para especificar el nombre del comando.
+///   - 'command = <secuencia_de_entrada>' para especificar la secuencia de entrada para el comando.
+///   - Opcionalmente, 'time = <valores_de_tiempo>' con los frames máximos del primer al último
+///     elemento, o una lista con los frames máximos entre cada elemento y el anterior.
+///   - Opcionalmente, 'buffer.time = <frames>' con los frames que el comando sigue activo.
+/// - La sección `[Defaults]` puede cambiar los valores por defecto con `command.time` y
+///   `command.buffer.time`.
 /// - La secuencia de entrada debe consistir en inputs separados por comas.
-/// - Los inputs pueden incluir caracteres especiales como '$' para múltiples inputs, '>' para sensibilidad y '/' para retención.
+/// - Los inputs pueden incluir caracteres especiales como '$' para la dirección o sus diagonales, '>' para sensibilidad,
+///   '/' para retención y '~' para soltar, ambos con un número opcional de frames que se tiene
+///   que mantener la tecla (`/20a`, `~30$B`).
 /// - Los valores de temporización se pueden especificar como una lista separada por comas de enteros.
+/// - Se ignoran los comentarios tras ';' y las secciones `[Remap]` y de estados.
+///
+/// Los comandos mal formados se añaden como errores al informe y no se leen; las claves, líneas y
+/// secciones desconocidas se ignoran y se añaden como avisos.
 ///
 /// # Argumentos
 ///
-/// * `filename` - Una referencia opcional a una cadena que contiene el nombre del archivo de comandos a leer.
+/// * `lines` - Las líneas del archivo de comandos.
+/// * `report` - El informe donde se añaden los problemas encontrados.
 ///
 /// # Retorna
 ///
-/// Un `io::Result` que contiene un vector de instancias de `Command` si el archivo se lee y analiza correctamente,
-/// de lo contrario, un `io::Error` indicando el problema encontrado.
-fn read_command_file(lines: &Vec<&str>) -> Result<Vec<Command>, CmdError> {
+/// Un vector con los comandos bien formados.
+fn read_command_file(lines: &[Span], report: &mut Report<CmdError>) -> Vec<Command> {
+    /// Sección en la que está cada línea.
+    enum Section<'a> {
+        /// Antes de la primera sección
+        None,
+        /// Un comando en lectura
+        Command(CommandSection<'a>),
+        /// Valores por defecto de los comandos
+        Defaults,
+        /// Secciones que no tienen que ver con los comandos, como los estados
+        Other,
+    }
+
+    /// Sección `[Command]` en lectura.
+    struct CommandSection<'a> {
+        /// Cabecera de la sección
+        header: Span<'a>,
+        name: Option<String>,
+        command: Option<Span<'a>>,
+        time: Option<Vec<u16>>,
+        buffer_time: Option<u16>,
+    }
+
     let mut commands: Vec<Command> = Vec::new();
+    let mut default_time = vec![DEFAULT_TIME];
+    let mut default_buffer_time = DEFAULT_BUFFER_TIME;
+    let mut current = Section::None;
+    let name_re = Regex::new(r#""([^"]*)""#).unwrap();
 
-    let mut i = 0;
-
-    while i < lines.len() {
-        let line = lines[i];
-        i += 1;
-
-        match line {
-            s if s.starts_with("name") => {
-                let parts: Vec<&str> = line.split('=').collect();
-                commands.push(Command::new());
-                if let Some(command) = commands.last_mut() {
-                    command.name = parts[1].trim().to_string();
-                    let re = Regex::new(r#""([^"]*)""#).unwrap();
-                    if let Some(captures) = re.captures(parts[1].trim()) {
-                        if let Some(matched) = captures.get(1) {
-                            command.name = matched.as_str().to_string();
-                        }
-                    }
+    let close = |section: Section,
+                 commands: &mut Vec<Command>,
+                 time: &Vec<u16>,
+                 buffer_time: u16,
+                 report: &mut Report<CmdError>| {
+        let Section::Command(section) = section else {
+            return;
+        };
+        let missing = |message: &str| CmdError::malformed(&section.header, message.to_string());
+        if section.name.is_none() {
+            report.errors.push(missing("Missing command name"));
+        }
+        let Some(sequence) = section.command else {
+            report.errors.push(missing("Missing command sequence"));
+            return;
+        };
+        let mut command = Command::new();
+        command.buffer_time = section.buffer_time.unwrap_or(buffer_time);
+        let time = section.time.unwrap_or_else(|| time.clone());
+        let command = parse_command(sequence, command, &time, report);
+        if let (Some(mut command), Some(name)) = (command, section.name) {
+            command.name = name;
+            commands.push(command);
+        }
+    };
+
+    for line in lines {
+        let line = content(*line);
+        if line.fragment().is_empty() {
+            continue;
+        }
+        if let Some(name) = section(line) {
+            let previous = std::mem::replace(&mut current, Section::Other);
+            close(
+                previous,
+                &mut commands,
+                &default_time,
+                default_buffer_time,
+                report,
+            );
+            let header = name.fragment().to_lowercase();
+            current = match header.split_whitespace().next().unwrap_or_default() {
+                "command" if header == "command" => Section::Command(CommandSection {
+                    header: line,
+                    name: None,
+                    command: None,
+                    time: None,
+                    buffer_time: None,
+                }),
+                "defaults" if header == "defaults" => Section::Defaults,
+                "remap" | "statedef" | "state" => Section::Other,
+                _ => {
+                    report.warnings.push(CmdError::malformed(
+                        &name,
+                        format!("Unknown section: {}", name.fragment()),
+                    ));
+                    Section::Other
                 }
+            };
+            continue;
+        }
+        let Some((key, value)) = key_value(line) else {
+            if !matches!(current, Section::Other) {
+                report.warnings.push(CmdError::malformed(
+                    &line,
+                    format!("Unknown line: {}", line.fragment()),
+                ));
             }
-            s if s.starts_with("command") => {
-                let mut time: Vec<u16> = vec![15];
-
-                if lines[i].starts_with("time") {
-                    let parts: Vec<&str> = lines[i].split('=').collect();
-                    let time_gotten: Vec<u16> = parts[1]
-                        .split(',')
-                        .map(|e| e.trim().parse().unwrap())
-                        .collect();
-                    time = time_gotten;
-                }
+            continue;
+        };
+        let unknown_key = || CmdError::malformed(&key, format!("Unknown key: {}", key.fragment()));
 
-                let parts: Vec<&str> = line.split('=').collect();
-                match parse_command(
-                    parts[1].split(',').map(|e| e.trim()).collect(),
-                    commands.len() - 1,
-                    &mut commands,
-                    &time,
-                ) {
-                    Ok(_) => {}
-                    Err(_) => return Err(CmdError::Malformed(i)),
-                };
-            }
-            _ => {}
+        match &mut current {
+            Section::None => report.warnings.push(CmdError::malformed(
+                &line,
+                "Line outside of a section".to_string(),
+            )),
+            Section::Defaults => match key.fragment().to_lowercase().as_str() {
+                "command.time" => {
+                    if let Some(time) = parse_times(value, report) {
+                        default_time = time;
+                    }
+                }
+                "command.buffer.time" => {
+                    if let Some(time) = parse_time(value, report) {
+                        default_buffer_time = time;
+                    }
+                }
+                _ => report.warnings.push(unknown_key()),
+            },
+            Section::Command(section) => match key.fragment().to_lowercase().as_str() {
+                "name" => {
+                    let name = value.fragment();
+                    if !(name.len() > 2 && name.starts_with('"') && name.ends_with('"')) {
+                        report.warnings.push(CmdError::malformed(
+                            &value,
+                            "The command name must be quoted".to_string(),
+                        ));
+                    }
+                    section.name = Some(match name_re.captures(name).and_then(|c| c.get(1)) {
+                        Some(matched) => matched.as_str().to_string(),
+                        None => name.to_string(),
+                    });
+                }
+                "command" => section.command = Some(value),
+                "time" => section.time = parse_times(value, report),
+                "buffer.time" => section.buffer_time = parse_time(value, report),
+                _ => report.warnings.push(unknown_key()),
+            },
+            Section::Other => (),
         }
     }
-    Ok(commands)
+    close(
+        current,
+        &mut commands,
+        &default_time,
+        default_buffer_time,
+        report,
+    );
+    commands
+}
+
+/// Lee un tiempo en frames.
+///
+/// # Argumentos
+///
+/// * `value` - El texto del tiempo.
+/// * `report` - El informe donde se añade el error si no es un tiempo válido.
+///
+/// # Retorna
+///
+/// El tiempo, o `None` si no es un número válido.
+fn parse_time(value: Span, report: &mut Report<CmdError>) -> Option<u16> {
+    let time = value.fragment().parse().ok();
+    if time.is_none() {
+        report.errors.push(CmdError::malformed(
+            &value,
+            format!("Invalid time: {}", value.fragment()),
+        ));
+    }
+    time
+}
+
+/// Analiza una lista de tiempos separados por comas.
+///
+/// # Argumentos
+///
+/// * `value` - El valor de la clave `time`.
+/// * `report` - El informe donde se añaden los tiempos que no son válidos.
+///
+/// # Retorna
+///
+/// Los tiempos, o `None` si alguno no es un número válido.
+fn parse_times(value: Span, report: &mut Report<CmdError>) -> Option<Vec<u16>> {
+    let times: Vec<Option<u16>> = fields(value, ',')
+        .into_iter()
+        .map(|time| parse_time(time, report))
+        .collect();
+    times.into_iter().collect()
 }
 
-/// Analiza una secuencia de comandos y completa el comando correspondiente con los detalles analizados.
+/// Analiza una secuencia de comandos y crea los comandos correspondientes.
 ///
-/// Esta función toma un vector de elementos de comando, una posición, un vector de comandos y la información de temporización como entrada.
-/// Itera sobre cada elemento en la secuencia de comandos, lo analiza y completa el comando con los detalles analizados.
-/// Los detalles analizados incluyen los inputs, el tiempo, la sensibilidad y cualquier caracter especial presente en la secuencia de comandos.
+/// Cada elemento puede llevar los modificadores `>` (sensible), `/` (mantener), `~` (soltar),
+/// estos dos con los frames mínimos que se tiene que mantener la tecla delante, y `$` (la dirección o cualquiera de sus diagonales),
+/// y varias teclas unidas con `+`.
 ///
 /// # Argumentos
 ///
-/// * `elements` - Un vector de fragmentos de cadena que representan los elementos de comando.
-/// * `pos` - La posición en el vector de comandos donde deben completarse los detalles del comando.
-/// * `commands` - Una referencia mutable a un vector de instancias de `Command`.
+/// * `sequence` - La secuencia del comando, con los elementos separados por comas.
+/// * `command` - Comando con el nombre y el tiempo de búfer, al que se añaden los elementos.
 /// * `time` - Una referencia a un vector que contiene la información de temporización para el comando.
+/// * `report` - El informe donde se añaden los elementos mal formados.
+///
+/// # Retorna
+///
+/// El comando creado, o `None` si algún elemento está mal formado.
 fn parse_command(
-    elements: Vec<&str>,
-    pos: usize,
-    commands: &mut Vec<Command>,
-    time: &Vec<u16>,
-) -> Result<(), ()> {
-    let mut hold_element = String::new();
-    let mut hold = false;
-
-    let directions_and_actions = [
-        ("LP", CK::LP),
-        ("MP", CK::MP),
-        ("HP", CK::HP),
-        ("LK", CK::LK),
-        ("MK", CK::MK),
-        ("HK", CK::HK),
-        ("U", CK::U),
-        ("F", CK::F),
-        ("D", CK::D),
-        ("B", CK::B),
-        ("UF", CK::UF),
-        ("UB", CK::UB),
-        ("DF", CK::DF),
-        ("DB", CK::DB),
-        ("Start", CK::Start),
-    ];
-
-    for (index, element) in elements.iter().enumerate() {
+    sequence: Span,
+    mut command: Command,
+    time: &[u16],
+    report: &mut Report<CmdError>,
+) -> Option<Command> {
+    let errors = report.errors.len();
+    for (index, element) in fields(sequence, ',').into_iter().enumerate() {
         let mut sensitive = false;
-        let mut inputs = HashSet::new();
-        let mut modified_element = element.to_string();
-        if modified_element.contains('$') {
-            if let Some(dollar_pos) = modified_element.find('$') {
-                if let Some(command) = commands.get(pos).cloned() {
-                    commands.push(command.clone());
-                    commands.push(command);
-                }
-                let length = commands.len();
-                match &modified_element.as_str()[dollar_pos + 1..dollar_pos + 2] {
-                    "F" | "U" | "D" | "B" | "P" | "K" => {
-                        let base_key = &modified_element.as_str()[dollar_pos..dollar_pos + 2];
-                        let variations =
-                            match &modified_element.as_str()[dollar_pos + 1..dollar_pos + 2] {
-                                "F" => ["F", "UF", "DF"],
-                                "U" => ["U", "UF", "UB"],
-                                "D" => ["D", "DF", "DB"],
-                                "B" => ["B", "UB", "DB"],
-                                "P" => ["LP", "MP", "HP"],
-                                "K" => ["LK", "MK", "HK"],
-                                _ => ["", "", ""],
-                            };
-                        let repeated = elements[index].matches(base_key).count();
-                        match elements[index].matches(base_key).count() {
-                            1 | 2 => {
-                                for (i, variation) in variations.iter().enumerate() {
-                                    if !elements[index].contains(variation)
-                                        || base_key.contains(variation)
-                                    {
-                                        let mut modified_elements = elements.clone();
-                                        let mut replaced_str = modified_elements[index]
-                                            .replacen(base_key, variation, 1);
-                                        if repeated == 2 {
-                                            replaced_str = replaced_str.replacen(
-                                                base_key,
-                                                variations[(i + 1) % 3],
-                                                1,
-                                            )
-                                        };
-                                        modified_elements[index] = &replaced_str;
-                                        let offset = if i == 0 { pos } else { length - i };
-                                        parse_command(
-                                            modified_elements.clone()[index..].to_vec(),
-                                            offset,
-                                            commands,
-                                            &time,
-                                        )?;
-                                    }
-                                }
-                            }
-                            3 => {
-                                let mut modified_elements = elements.clone();
-                                let mut replaced_str = modified_elements[index].to_owned();
-                                for variation in variations {
-                                    replaced_str = replaced_str.replacen(base_key, variation, 1);
-                                }
-                                modified_elements[index] = &replaced_str;
-                                parse_command(
-                                    modified_elements.clone()[index..].to_vec(),
-                                    pos,
-                                    commands,
-                                    &time,
-                                )?;
-                            }
-
-                            _ => {}
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            return Ok(());
-        }
-        if modified_element.contains('>') {
-            modified_element = modified_element.replace('>', "");
-            sensitive = true;
-        }
-        if modified_element.contains('/') {
-            modified_element = modified_element.replace('/', "");
-            hold = true;
-        }
-
-        if modified_element.contains('+') {
-            inputs = modified_element
-                .split('+')
-                .map(|e| e.trim().to_string())
-                .collect();
-        } else {
-            inputs.insert(modified_element.clone());
-        }
-
-        if hold {
-            if hold_element.len() > 0 {
-                if index == elements.len() - 1 {
-                    inputs.insert(hold_element);
-                    hold_element = String::new();
+        let mut hold = None;
+        let mut release = None;
+        let mut dollar = false;
+        let mut rest = element;
+        loop {
+            let fragment = *rest.fragment();
+            if fragment.starts_with('>') {
+                sensitive = true;
+            } else if fragment.starts_with('$') {
+                dollar = true;
+            } else if fragment.starts_with(['/', '~']) {
+                let after = &fragment[1..];
+                let digits =
+                    after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                let frames = rest.slice(1..digits + 1);
+                let frames = match digits {
+                    0 => Some(0),
+                    _ => parse_time(frames, report),
+                };
+                if fragment.starts_with('/') {
+                    hold = frames;
                 } else {
-                    hold_element = String::new();
-                    hold = false;
+                    release = frames;
                 }
+                rest = rest.slice(digits + 1..);
+                continue;
             } else {
-                hold_element = modified_element;
+                break;
             }
+            rest = rest.slice(1..);
         }
 
-        if let Some(command) = commands.get_mut(pos) {
-            let time_value;
-            let size = inputs.len();
-
-            if command.cmd_elements.is_empty() {
-                time_value = 0;
-            } else {
-                time_value = time[(command.cmd_elements.len() - 1) % time.len()]
-            }
-            if let Some(cmd_element) = Some(CommandElement::new(
-                inputs,
-                time_value,
-                sensitive,
-                &directions_and_actions,
-            )) {
-                if cmd_element.elements.len() < size {
-                    return Err(());
-                }
-                command.cmd_elements.push(cmd_element);
+        let mut inputs: HashSet<String> = HashSet::new();
+        for key in fields(rest, '+') {
+            let name = *key.fragment();
+            if name.is_empty() {
+                report
+                    .errors
+                    .push(CmdError::malformed(&key, "Missing button".to_string()));
+            } else if !BUTTONS.iter().any(|(button, _)| *button == name) {
+                report.errors.push(CmdError::malformed(
+                    &key,
+                    format!("Undefined button: {}", name),
+                ));
             }
+            inputs.insert(name.to_string());
         }
+
+        let time_value = if index == 0 {
+            0
+        } else {
+            time[(index - 1) % time.len()]
+        };
+        let mut cmd_element = CommandElement::new(inputs, time_value, sensitive, &BUTTONS);
+        cmd_element.hold = hold;
+        cmd_element.release = release;
+        cmd_element.diagonals = dollar;
+        command.cmd_elements.push(cmd_element);
+    }
+    if report.errors.len() > errors {
+        return None;
     }
-    Ok(())
+    command.time = match time {
+        [time] => *time,
+        _ => command
+            .cmd_elements
+            .iter()
+            .map(|cmd_element| cmd_element.time)
+            .fold(0, u16::saturating_add),
+    };
+    Some(command)
 }
 
-/// Crea un árbol de comandos para un personaje dado leyendo el archivo de comandos correspondiente.
+/// Crea la lista de comandos de un personaje leyendo su archivo de comandos.
 ///
-/// Esta función construye un árbol de comandos para un personaje especificado leyendo el archivo de comandos correspondiente.
 /// Analiza el contenido del archivo de comandos para extraer los comandos y sus detalles asociados.
-/// El archivo de comandos debe estar ubicado en el directorio 'src/assets' y tener el nombre '<character_name>.cmd'.
 ///
 /// # Argumentos
 ///
-/// * `char_name` - Una porción de cadena que contiene el nombre del personaje para el cual se creará el árbol de comandos.
+/// * `cmd` - Ruta del archivo de comandos del personaje.
 ///
 /// # Retorna
 ///
-/// Un `CommandNode` que representa la raíz del árbol de comandos con sus ramas para el personaje especificado.
-pub fn create_command_tree(cmd: &str) -> Result<CommandNode, CmdError> {
+/// Un `CommandList` con todos los comandos del personaje.
+pub fn create_command_list(cmd: &str) -> Result<CommandList, CmdError> {
     let content = match std::fs::read_to_string(cmd) {
         Ok(content) => content,
         Err(_) => return Err(CmdError::NotFound(cmd.to_string())),
     };
-    let lines: Vec<&str> = content.lines().map(|line| line.trim()).collect();
-    let mut tree = CommandNode::new();
+    parse_command_list(&content)
+}
 
-    let commands = read_command_file(&lines)?;
+/// Crea la lista de comandos a partir del contenido de un archivo de comandos.
+///
+/// # Argumentos
+///
+/// * `content` - El texto del archivo de comandos.
+///
+/// # Retorna
+///
+/// Un `CommandList` con todos los comandos, o el primer `CmdError` con la posición del problema.
+pub(crate) fn parse_command_list(content: &str) -> Result<CommandList, CmdError> {
+    let mut report = Report::default();
+    let commands = read_command_file(&split_lines(content), &mut report);
+    report.into_result(CommandList { commands })
+}
 
-    for command in &commands {
-        tree.insert(command, 0);
-    }
-    tree.sort();
-    return Ok(tree);
+/// Revisa el contenido de un archivo de comandos sin crear la lista de comandos.
+///
+/// # Argumentos
+///
+/// * `content` - El texto del archivo de comandos.
+///
+/// # Retorna
+///
+/// Los errores y avisos del archivo, con su posición.
+pub fn check_command_list(content: &str) -> Report<CmdError> {
+    let mut report = Report::default();
+    read_command_file(&split_lines(content), &mut report);
+    report
 }
 
 #[cfg(test)]
@@ -562,25 +722,6 @@ mod tests {
         assert_eq!(cmd_element.time, 10);
     }
 
-    /// Prueba para la isercción de un commando
-    #[test]
-    fn test_insert_command_node() {
-        let mut root = CommandNode::new();
-        let mut command = Command::new();
-        command.name = "test_command".to_string();
-        command.cmd_elements.push(CommandElement::new(
-            ["LP".to_string()].iter().cloned().collect(),
-            15,
-            true,
-            &DIRANDACT,
-        ));
-
-        root.insert(&command, 0);
-
-        assert_eq!(root.sub_nodes.len(), 1);
-        assert_eq!(root.sub_nodes[0].name, Some("test_command".to_string()));
-    }
-
     // Prueba de lectura de archivo de comandos
     #[test]
     fn test_read_command_file() {
@@ -590,7 +731,7 @@ mod tests {
             "command = F, MP+LP",
             "time = 15",
         ];
-        let commands = read_command_file(&lines).unwrap();
+        let commands = read_lines(&lines).unwrap();
 
         assert_eq!(commands.len(), 1);
         assert_eq!(commands[0].name, "Prueba");
@@ -613,44 +754,327 @@ mod tests {
             "command = F, K",
             "time = 15",
         ];
-        let result = read_command_file(&lines);
+        let result = read_lines(&lines);
 
         assert!(result.is_err());
     }
 
-    // Prueba de creación de árbol
-    #[test]
-    fn test_create_command_tree() {
-        let content = "
-        [Command]
-        name = \"Prueba\"
-        command = F, MP+LP
-        time = 15
-    ";
-        let lines: Vec<&str> = content.lines().map(|line| line.trim()).collect();
-        let mut tree = CommandNode::new();
-
-        let commands = read_command_file(&lines).unwrap();
-
-        for command in &commands {
-            tree.insert(command, 0);
+    /// Lee los comandos de unas líneas.
+    fn read_lines(lines: &[&str]) -> Result<Vec<Command>, CmdError> {
+        parse_command_list(&lines.join("\n")).map(|list| list.commands)
+    }
+
+    /// Crea la lista de comandos a partir de unas líneas.
+    fn list_from(lines: &Vec<&str>) -> CommandList {
+        CommandList {
+            commands: read_lines(lines).unwrap(),
         }
-        tree.sort();
+    }
 
-        assert_eq!(tree.sub_nodes.len(), 1);
-        assert_eq!(tree.sub_nodes[0].cmd_elements, Some(vec![CK::F]));
-        assert_eq!(tree.sub_nodes[0].input_window, [0]);
-        assert_eq!(tree.sub_nodes[0].name, None);
-        assert_eq!(tree.sub_nodes[0].sensitive, false);
-        assert!(
-            tree.sub_nodes[0].sub_nodes[0].cmd_elements == Some(vec![CK::MP, CK::LP])
-                || tree.sub_nodes[0].sub_nodes[0].cmd_elements == Some(vec![CK::LP, CK::MP])
+    /// Crea un historial a partir de las teclas de cada frame.
+    fn history_from(frames: &[&[CK]]) -> Vec<InputFrame> {
+        let mut history: Vec<InputFrame> = Vec::new();
+        for keys in frames {
+            history.push(InputFrame::new(keys.to_vec(), history.last()));
+        }
+        history
+    }
+
+    /// Nombres de los comandos que se completan en el último frame.
+    fn found(list: &CommandList, frames: &[&[CK]]) -> Vec<String> {
+        list.search(&history_from(frames))
+            .iter()
+            .map(|command| command.get_name().to_string())
+            .collect()
+    }
+
+    /// Prueba de búsqueda de una secuencia con una combinación de botones
+    #[test]
+    fn test_search_sequence() {
+        let list = list_from(&vec![
+            "[Command]",
+            "name = \"Prueba\"",
+            "command = F, MP+LP",
+            "time = 15",
+        ]);
+        assert_eq!(list.commands[0].time, 15);
+
+        assert_eq!(
+            found(&list, &[&[], &[CK::F], &[], &[CK::LP, CK::MP]]),
+            ["Prueba"]
         );
-        assert_eq!(tree.sub_nodes[0].sub_nodes[0].input_window, [15]);
+        // Un botón mantenido desde antes también vale para la combinación
         assert_eq!(
-            tree.sub_nodes[0].sub_nodes[0].name,
-            Some("Prueba".to_string())
+            found(&list, &[&[CK::F, CK::LP], &[CK::LP, CK::MP]]),
+            ["Prueba"]
         );
-        assert_eq!(tree.sub_nodes[0].sub_nodes[0].sensitive, false);
+        // El último elemento tiene que ocurrir en el frame actual
+        assert!(found(&list, &[&[CK::F], &[CK::LP, CK::MP], &[CK::LP, CK::MP]]).is_empty());
+        // Mantener la dirección no es volver a presionarla
+        assert!(found(&list, &[&[CK::LP, CK::MP]]).is_empty());
+    }
+
+    /// Prueba de la ventana de tiempo, del primer al último elemento
+    #[test]
+    fn test_search_time_window() {
+        let list = list_from(&vec![
+            "[Defaults]",
+            "command.time = 4",
+            "[Command]",
+            "name = \"QCF_x\"",
+            "command = D, DF, F, x",
+            "[Command]",
+            "name = \"gaps\"",
+            "command = D, DF, F, x",
+            "time = 1, 1, 4",
+        ]);
+        assert_eq!(list.commands[1].time, 6);
+
+        let quick: [&[CK]; 4] = [&[CK::D], &[CK::DF], &[CK::F], &[CK::F, CK::LP]];
+        assert_eq!(found(&list, &quick), ["QCF_x", "gaps"]);
+
+        // Cinco frames del primer al último elemento, cada hueco dentro de su tiempo
+        let slow: [&[CK]; 6] = [
+            &[CK::D],
+            &[CK::DF],
+            &[CK::F],
+            &[CK::F],
+            &[CK::F],
+            &[CK::F, CK::LP],
+        ];
+        assert_eq!(found(&list, &slow), ["gaps"]);
+
+        // Dos frames entre abajo y abajo-adelante
+        let late: [&[CK]; 5] = [&[CK::D], &[CK::D], &[CK::DF], &[CK::F], &[CK::F, CK::LP]];
+        assert_eq!(found(&list, &late), ["QCF_x"]);
+    }
+
+    /// Prueba de que varios comandos pueden completarse en el mismo frame
+    #[test]
+    fn test_search_multiple_commands() {
+        let list = list_from(&vec![
+            "[Command]",
+            "name = \"QCF_x\"",
+            "command = D, DF, F, x",
+            "[Command]",
+            "name = \"x\"",
+            "command = x",
+            "time = 1",
+            "[Command]",
+            "name = \"y\"",
+            "command = y",
+        ]);
+        let frames: [&[CK]; 4] = [&[CK::D], &[CK::DF], &[CK::F], &[CK::F, CK::LP]];
+        assert_eq!(found(&list, &frames), ["QCF_x", "x"]);
+    }
+
+    /// Prueba de los elementos sensibles, sin otras teclas entre medias
+    #[test]
+    fn test_search_sensitive() {
+        let list = list_from(&vec!["[Command]", "name = \"FF\"", "command = F, >F"]);
+        assert_eq!(found(&list, &[&[CK::F], &[], &[CK::F]]), ["FF"]);
+        assert!(found(&list, &[&[CK::F], &[CK::LK], &[CK::F]]).is_empty());
+
+        // Soltar otra tecla entre los dos elementos también lo impide
+        let held: [&[CK]; 4] = [&[CK::LK], &[CK::F, CK::LK], &[CK::LK], &[CK::F, CK::LK]];
+        assert_eq!(found(&list, &held), ["FF"]);
+        let released: [&[CK]; 4] = [&[CK::LK], &[CK::F, CK::LK], &[], &[CK::F]];
+        assert!(found(&list, &released).is_empty());
+    }
+    /// Prueba de lectura de un archivo con la sintaxis de MUGEN
+    #[test]
+    fn test_read_mugen_command_file() {
+        let lines = vec![
+            "[Defaults]",
+            "command.time = 20",
+            "command.buffer.time = 3",
+            "[Command] ; comentario",
+            "time = 25",
+            "command = ~D, DF, F, x",
+            "name = \"QCF_x\"",
+            "[Command]",
+            "name = \"charge_a\"",
+            "command = ~30$B, F, a",
+            "buffer.time = 5",
+            "[Statedef -1]",
+            "[State -1, QCF]",
+            "type = ChangeState",
+        ];
+        let commands = read_lines(&lines).unwrap();
+
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].name, "QCF_x");
+        assert_eq!(commands[0].buffer_time, 3);
+        assert_eq!(commands[0].cmd_elements[0].release, Some(0));
+        assert!(commands[0].cmd_elements[0].elements.contains(&CK::D));
+        assert_eq!(commands[0].cmd_elements[1].time, 25);
+        assert!(commands[0].cmd_elements[3].elements.contains(&CK::LP));
+        assert_eq!(commands[0].cmd_elements[3].release, None);
+
+        assert_eq!(commands[1].name, "charge_a");
+        assert_eq!(commands[1].buffer_time, 5);
+        assert_eq!(commands[1].cmd_elements[0].release, Some(30));
+        assert!(commands[1].cmd_elements[0].diagonals);
+        assert!(commands[1].cmd_elements[0].elements.contains(&CK::B));
+        assert_eq!(commands[1].cmd_elements[1].time, 20);
+        assert!(commands[1].cmd_elements[2].elements.contains(&CK::LK));
+    }
+
+    /// Prueba de que los comandos incompletos o con tiempos inválidos son errores
+    #[test]
+    fn test_read_incomplete_command_file() {
+        let lines = vec![
+            "[Command]",
+            "command = F, x",
+            "",
+            "[Command]",
+            "name = \"a\"",
+        ];
+        assert!(matches!(
+            read_lines(&lines),
+            Err(CmdError::Malformed { line: 1, .. })
+        ));
+
+        let lines = vec!["[Command]", "name = \"a\"", "command = a", "time = rápido"];
+        assert!(matches!(
+            read_lines(&lines),
+            Err(CmdError::Malformed { line: 4, .. })
+        ));
+
+        let lines = vec!["[Command]", "name = \"a\"", "command = F, , a"];
+        assert!(matches!(
+            read_lines(&lines),
+            Err(CmdError::Malformed { line: 3, .. })
+        ));
+    }
+
+    /// Resume los problemas de un informe en línea, columna y mensaje para compararlos.
+    fn summary(problems: &[CmdError]) -> Vec<(u32, usize, &str)> {
+        problems
+            .iter()
+            .filter_map(|problem| match problem {
+                CmdError::Malformed {
+                    line,
+                    column,
+                    message,
+                } => Some((*line, *column, message.as_str())),
+                CmdError::NotFound(_) => None,
+            })
+            .collect()
+    }
+
+    /// Prueba que se informe de todos los problemas de un archivo con su posición
+    #[test]
+    fn test_check_command_list() {
+        let report = check_command_list(
+            "\
+[Defaults]
+command.time = 15
+[Command]
+name = \"QCF_x\"
+command = D, DF, F, X+LP
+time = 20
+[Command]
+command = $Q, /B, , >F, ~99999a, /20x
+time = fast
+[Command]
+name = Sin comillas
+timer = 1
+[Statedef -1]
+[State -1, Taunt]
+type = ChangeState
+[End]
+",
+        );
+        assert_eq!(
+            summary(&report.errors),
+            vec![
+                (5, 21, "Undefined button: X"),
+                (9, 8, "Invalid time: fast"),
+                (7, 1, "Missing command name"),
+                (8, 12, "Undefined button: Q"),
+                (8, 19, "Missing button"),
+                (8, 26, "Invalid time: 99999"),
+                (10, 1, "Missing command sequence"),
+            ]
+        );
+        assert_eq!(
+            summary(&report.warnings),
+            vec![
+                (11, 8, "The command name must be quoted"),
+                (12, 1, "Unknown key: timer"),
+                (16, 2, "Unknown section: End"),
+            ]
+        );
+        assert!(
+            check_command_list(&std::fs::read_to_string("src/chars/kfm/kfm.cmd").unwrap())
+                .is_empty()
+        );
+    }
+
+    /// Prueba de la búsqueda de comandos que sueltan una tecla tras cargarla
+    #[test]
+    fn test_search_charge_release() {
+        let list = list_from(&vec![
+            "[Command]",
+            "name = \"charge_a\"",
+            "command = ~3$B, F, a",
+        ]);
+        let charged: [&[CK]; 6] = [
+            &[],
+            &[CK::B],
+            &[CK::DB],
+            &[CK::B],
+            &[CK::F],
+            &[CK::F, CK::LK],
+        ];
+        assert_eq!(found(&list, &charged), ["charge_a"]);
+
+        let short: [&[CK]; 4] = [&[CK::B], &[CK::B], &[CK::F], &[CK::F, CK::LK]];
+        assert!(found(&list, &short).is_empty());
+    }
+
+    /// Prueba de la búsqueda de comandos con teclas mantenidas
+    #[test]
+    fn test_search_hold() {
+        let list = list_from(&vec![
+            "[Command]",
+            "name = \"hold_a\"",
+            "command = /D, a",
+            "buffer.time = 4",
+        ]);
+        assert_eq!(list.commands[0].get_buffer_time(), 4);
+        assert_eq!(found(&list, &[&[CK::D], &[CK::D, CK::LK]]), ["hold_a"]);
+        assert!(found(&list, &[&[CK::D], &[CK::LK]]).is_empty());
+    }
+
+    /// Prueba de la búsqueda de comandos que mantienen una tecla un mínimo de frames
+    #[test]
+    fn test_search_minimum_hold() {
+        let list = list_from(&vec![
+            "[Command]",
+            "name = \"hold_a\"",
+            "command = /3$D, a",
+            "[Command]",
+            "name = \"charge_x\"",
+            "command = /4x",
+        ]);
+        assert_eq!(list.commands[0].cmd_elements[0].hold, Some(3));
+
+        let held: [&[CK]; 3] = [&[CK::DB], &[CK::D], &[CK::DF, CK::LK]];
+        assert_eq!(found(&list, &held), ["hold_a"]);
+        assert!(found(&list, &held[1..]).is_empty());
+
+        let pressed: [&[CK]; 4] = [&[CK::LP], &[CK::LP], &[CK::LP], &[CK::LP]];
+        assert_eq!(found(&list, &pressed), ["charge_x"]);
+        assert!(found(&list, &pressed[1..]).is_empty());
+    }
+
+    /// Prueba de la búsqueda de comandos que sueltan un botón tras mantenerlo
+    #[test]
+    fn test_search_button_release_after_hold() {
+        let list = list_from(&vec!["[Command]", "name = \"release_x\"", "command = ~2x"]);
+        assert_eq!(found(&list, &[&[CK::LP], &[CK::LP], &[]]), ["release_x"]);
+        assert!(found(&list, &[&[], &[CK::LP], &[]]).is_empty());
     }
 }
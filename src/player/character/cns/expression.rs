@@ -0,0 +1,1227 @@
+use crate::chars::{Character, State};
+
+use super::manager::{GROUND_Y, NUM_VARS};
+
+/// Valor resultante de evaluar una expresión.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    /// Número entero
+    Int(i32),
+    /// Número decimal
+    Float(f64),
+}
+
+/// Símbolos en los que se divide el texto de una expresión.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Número entero
+    Int(i32),
+    /// Número decimal
+    Float(f64),
+    /// Nombre de un disparador o función (en minúsculas)
+    Ident(String),
+    /// Texto entre comillas
+    Str(String),
+    /// Operador
+    Op(&'static str),
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+    /// `[`
+    LBracket,
+    /// `]`
+    RBracket,
+    /// `,`
+    Comma,
+}
+
+/// Personaje sobre el que se evalúa una parte de la expresión.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Redirection {
+    /// El oponente
+    P2,
+    /// El personaje raíz
+    Root,
+}
+
+/// Disparadores con valor numérico.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trigger {
+    Time,
+    AnimTime,
+    AnimElem,
+    Anim,
+    StateNo,
+    Ctrl,
+    Life,
+    Power,
+    VelX,
+    VelY,
+    PosX,
+    PosY,
+    P2DistX,
+    P2DistY,
+    P2BodyDistX,
+    P2BodyDistY,
+    Facing,
+    Alive,
+    Win,
+    Lose,
+    HitFall,
+}
+
+/// Constantes del personaje (`const(nombre)`), leídas de su archivo de constantes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+    Life,
+    Power,
+    Attack,
+    Defence,
+    XScale,
+    YScale,
+    GroundBack,
+    GroundFront,
+    AirBack,
+    AirFront,
+    Height,
+    WalkFwdX,
+    WalkBackX,
+    RunFwdX,
+    RunFwdY,
+    RunBackX,
+    RunBackY,
+    JumpNeuX,
+    JumpNeuY,
+    JumpBackX,
+    JumpFwdX,
+    RunJumpBackX,
+    RunJumpBackY,
+    RunJumpFwdX,
+    RunJumpFwdY,
+    AirJumpNum,
+    YAccel,
+    StandFriction,
+    CrouchFriction,
+    StandFrictionThreshold,
+    CrouchFrictionThreshold,
+}
+
+/// Funciones admitidas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Abs,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+    IfElse,
+}
+
+/// Operadores unarios.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    /// `!`
+    Not,
+    /// `~`
+    BitNot,
+    /// `-`
+    Neg,
+}
+
+/// Operadores binarios.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Or,
+    Xor,
+    And,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+}
+
+/// Árbol de una expresión de disparador.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Número entero
+    Int(i32),
+    /// Número decimal
+    Float(f64),
+    /// Disparador numérico
+    Trigger(Trigger),
+    /// `command = "nombre"`
+    Command(String),
+    /// `statetype = S`
+    StateType(State),
+    /// `p2, ...` o `root, ...`
+    Redirect(Redirection, Box<Expr>),
+    /// Operación unaria
+    Unary(UnaryOp, Box<Expr>),
+    /// Operación binaria
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// `valor = [min, max]`, los extremos pueden ser abiertos con `(` y `)`
+    Range {
+        value: Box<Expr>,
+        negate: bool,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        low_open: bool,
+        high_open: bool,
+    },
+    /// Llamada a una función
+    Function(Function, Vec<Expr>),
+    /// `var(n)`, variable entera del personaje
+    Var(usize),
+    /// `const(nombre)`, constante del personaje
+    Const(Constant),
+}
+
+/// Contexto de evaluación de una expresión.
+#[derive(Clone, Copy)]
+pub struct Context<'a> {
+    /// Personaje sobre el que se evalúa
+    char: &'a dyn Character,
+    /// Oponente del personaje, si se conoce
+    p2: Option<&'a dyn Character>,
+    /// Personaje raíz
+    root: &'a dyn Character,
+}
+
+/// Analizador de una lista de símbolos.
+struct Parser {
+    /// Símbolos de la expresión
+    tokens: Vec<Token>,
+    /// Posición del siguiente símbolo
+    pos: usize,
+}
+
+impl Value {
+    /// Convierte el valor a decimal.
+    ///
+    /// # Retorna
+    ///
+    /// El valor como `f64`.
+    pub fn as_float(self) -> f64 {
+        match self {
+            Value::Int(value) => value as f64,
+            Value::Float(value) => value,
+        }
+    }
+
+    /// Convierte el valor a entero, truncando los decimales.
+    ///
+    /// # Retorna
+    ///
+    /// El valor como `i32`.
+    pub fn as_int(self) -> i32 {
+        match self {
+            Value::Int(value) => value,
+            Value::Float(value) => value as i32,
+        }
+    }
+
+    /// Indica si el valor es verdadero (distinto de 0).
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el valor no es 0, `false` en caso contrario.
+    pub fn is_true(self) -> bool {
+        self.as_float() != 0.0
+    }
+
+    /// Crea un valor a partir de un booleano.
+    ///
+    /// # Argumentos
+    ///
+    /// * `value` - El booleano.
+    ///
+    /// # Retorna
+    ///
+    /// `1` si es verdadero, `0` si es falso.
+    fn from_bool(value: bool) -> Self {
+        Value::Int(value as i32)
+    }
+}
+
+impl<'a> Context<'a> {
+    /// Crea un contexto de evaluación.
+    ///
+    /// # Argumentos
+    ///
+    /// * `char` - Personaje sobre el que se evalúa.
+    /// * `p2` - Oponente del personaje, si se conoce.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo `Context` donde el personaje es también la raíz.
+    pub fn new(char: &'a dyn Character, p2: Option<&'a dyn Character>) -> Self {
+        Self {
+            char,
+            p2,
+            root: char,
+        }
+    }
+
+    /// Obtiene el contexto redirigido a otro personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `redirection` - Personaje al que se redirige.
+    ///
+    /// # Retorna
+    ///
+    /// El contexto redirigido, o `None` si el personaje no existe.
+    fn redirect(&self, redirection: Redirection) -> Option<Self> {
+        match redirection {
+            Redirection::P2 => {
+                let p2 = self.p2?;
+                Some(Self {
+                    char: p2,
+                    p2: Some(self.char),
+                    root: p2,
+                })
+            }
+            Redirection::Root => Some(Self {
+                char: self.root,
+                p2: self.p2,
+                root: self.root,
+            }),
+        }
+    }
+
+    /// Obtiene la distancia al oponente en la X, positiva si está delante.
+    ///
+    /// Si no se conoce el oponente se usa la distancia calculada durante las colisiones.
+    fn p2_dist_x(&self) -> f64 {
+        let facing = if self.char.is_flipped() { -1.0 } else { 1.0 };
+        match self.p2 {
+            Some(p2) => (p2.get_x() - self.char.get_x()) * facing,
+            None => -self.char.get_distance() * facing,
+        }
+    }
+}
+
+impl Trigger {
+    /// Obtiene el disparador a partir de su nombre y argumento.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre del disparador en minúsculas.
+    /// * `axis` - Eje del disparador (`x` o `y`), si lo tiene.
+    ///
+    /// # Retorna
+    ///
+    /// El disparador, o `None` si es desconocido.
+    fn from_name(name: &str, axis: Option<&str>) -> Option<Self> {
+        let trigger = match (name, axis) {
+            ("time", None) | ("statetime", None) => Trigger::Time,
+            ("animtime", None) => Trigger::AnimTime,
+            ("animelem", None) => Trigger::AnimElem,
+            ("anim", None) => Trigger::Anim,
+            ("stateno", None) => Trigger::StateNo,
+            ("ctrl", None) => Trigger::Ctrl,
+            ("life", None) => Trigger::Life,
+            ("power", None) => Trigger::Power,
+            ("facing", None) => Trigger::Facing,
+            ("alive", None) => Trigger::Alive,
+            ("win", None) => Trigger::Win,
+            ("lose", None) => Trigger::Lose,
+            ("hitfall", None) => Trigger::HitFall,
+            ("vel", Some("x")) => Trigger::VelX,
+            ("vel", Some("y")) => Trigger::VelY,
+            ("pos", Some("x")) => Trigger::PosX,
+            ("pos", Some("y")) => Trigger::PosY,
+            ("p2dist", Some("x")) => Trigger::P2DistX,
+            ("p2dist", Some("y")) => Trigger::P2DistY,
+            ("p2bodydist", Some("x")) => Trigger::P2BodyDistX,
+            ("p2bodydist", Some("y")) => Trigger::P2BodyDistY,
+            _ => return None,
+        };
+        Some(trigger)
+    }
+
+    /// Indica si el disparador necesita un eje (`Vel X`, `Pos Y`...).
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre del disparador en minúsculas.
+    fn has_axis(name: &str) -> bool {
+        matches!(name, "vel" | "pos" | "p2dist" | "p2bodydist")
+    }
+
+    /// Obtiene el valor del disparador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ctx` - Contexto de evaluación.
+    ///
+    /// # Retorna
+    ///
+    /// El valor del disparador.
+    fn eval(self, ctx: &Context) -> Value {
+        let char = ctx.char;
+        match self {
+            Trigger::Time => Value::Int(char.get_state_time()),
+            Trigger::AnimTime => Value::Int(-char.get_anim_time()),
+            Trigger::AnimElem => Value::Int(char.get_anim_elem_start() + 1),
+            Trigger::Anim => Value::Int(*char.get_anim()),
+            Trigger::StateNo => Value::Int(char.get_state_no()),
+            Trigger::Ctrl => Value::from_bool(char.has_control()),
+            Trigger::Life => Value::Int(char.get_life()),
+            Trigger::Power => Value::Int(char.get_power()),
+            Trigger::VelX => Value::Float(char.get_vel_x()),
+            Trigger::VelY => Value::Float(char.get_vel_y()),
+            Trigger::PosX => Value::Float(char.get_x()),
+            Trigger::PosY => Value::Float(char.get_y() - GROUND_Y),
+            Trigger::P2DistX => Value::Float(ctx.p2_dist_x()),
+            Trigger::P2BodyDistX => {
+                let p2_front = ctx.p2.map_or(0.0, body_front);
+                Value::Float(ctx.p2_dist_x() - body_front(char) - p2_front)
+            }
+            Trigger::P2DistY | Trigger::P2BodyDistY => Value::Float(match ctx.p2 {
+                Some(p2) => p2.get_y() - char.get_y(),
+                None => 0.0,
+            }),
+            Trigger::Facing => Value::Int(if char.is_flipped() { -1 } else { 1 }),
+            Trigger::Alive => Value::from_bool(char.get_life() > 0),
+            Trigger::Win => Value::from_bool(char.get_win()),
+            Trigger::Lose => Value::from_bool(char.get_lose()),
+            Trigger::HitFall => Value::from_bool(char.get_fall()),
+        }
+    }
+}
+
+impl Constant {
+    /// Obtiene la constante a partir de su nombre.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre de la constante en minúsculas (`velocity.walk.fwd.x`).
+    ///
+    /// # Retorna
+    ///
+    /// La constante, o `None` si es desconocida.
+    fn from_name(name: &str) -> Option<Self> {
+        let constant = match name {
+            "data.life" => Constant::Life,
+            "data.power" => Constant::Power,
+            "data.attack" => Constant::Attack,
+            "data.defence" => Constant::Defence,
+            "size.xscale" => Constant::XScale,
+            "size.yscale" => Constant::YScale,
+            "size.ground.back" => Constant::GroundBack,
+            "size.ground.front" => Constant::GroundFront,
+            "size.air.back" => Constant::AirBack,
+            "size.air.front" => Constant::AirFront,
+            "size.height" => Constant::Height,
+            "velocity.walk.fwd.x" => Constant::WalkFwdX,
+            "velocity.walk.back.x" => Constant::WalkBackX,
+            "velocity.run.fwd.x" => Constant::RunFwdX,
+            "velocity.run.fwd.y" => Constant::RunFwdY,
+            "velocity.run.back.x" => Constant::RunBackX,
+            "velocity.run.back.y" => Constant::RunBackY,
+            "velocity.jump.neu.x" => Constant::JumpNeuX,
+            "velocity.jump.neu.y" | "velocity.jump.y" => Constant::JumpNeuY,
+            "velocity.jump.back.x" => Constant::JumpBackX,
+            "velocity.jump.fwd.x" => Constant::JumpFwdX,
+            "velocity.runjump.back.x" => Constant::RunJumpBackX,
+            "velocity.runjump.back.y" => Constant::RunJumpBackY,
+            "velocity.runjump.fwd.x" => Constant::RunJumpFwdX,
+            "velocity.runjump.fwd.y" | "velocity.runjump.y" => Constant::RunJumpFwdY,
+            "movement.airjump.num" => Constant::AirJumpNum,
+            "movement.yaccel" => Constant::YAccel,
+            "movement.stand.friction" => Constant::StandFriction,
+            "movement.crouch.friction" => Constant::CrouchFriction,
+            "movement.stand.friction.threshold" => Constant::StandFrictionThreshold,
+            "movement.crouch.friction.threshold" => Constant::CrouchFrictionThreshold,
+            _ => return None,
+        };
+        Some(constant)
+    }
+
+    /// Obtiene el valor de la constante.
+    ///
+    /// # Argumentos
+    ///
+    /// * `char` - Personaje del que se lee la constante.
+    ///
+    /// # Retorna
+    ///
+    /// El valor de la constante.
+    fn eval(self, char: &dyn Character) -> Value {
+        let def = char.get_def();
+        let (data, size, velocity, movement) = (&def.data, &def.size, &def.velocity, &def.movement);
+        match self {
+            Constant::Life => Value::Int(data.life),
+            Constant::Power => Value::Int(data.power),
+            Constant::Attack => Value::Int(data.attack),
+            Constant::Defence => Value::Int(data.defence),
+            Constant::XScale => Value::Float(size.xscale),
+            Constant::YScale => Value::Float(size.yscale),
+            Constant::GroundBack => Value::Int(size.ground_back),
+            Constant::GroundFront => Value::Int(size.ground_front),
+            Constant::AirBack => Value::Int(size.air_back),
+            Constant::AirFront => Value::Int(size.air_front),
+            Constant::Height => Value::Int(size.height),
+            Constant::WalkFwdX => Value::Float(velocity.walk_fwd),
+            Constant::WalkBackX => Value::Float(velocity.walk_back),
+            Constant::RunFwdX => Value::Float(velocity.run_fwd.0),
+            Constant::RunFwdY => Value::Float(velocity.run_fwd.1),
+            Constant::RunBackX => Value::Float(velocity.run_back.0),
+            Constant::RunBackY => Value::Float(velocity.run_back.1),
+            Constant::JumpNeuX => Value::Float(velocity.jump_neu.0),
+            Constant::JumpNeuY => Value::Float(velocity.jump_neu.1),
+            Constant::JumpBackX => Value::Float(velocity.jump_back),
+            Constant::JumpFwdX => Value::Float(velocity.jump_fwd),
+            Constant::RunJumpBackX => Value::Float(velocity.runjump_back.0),
+            Constant::RunJumpBackY => Value::Float(velocity.runjump_back.1),
+            Constant::RunJumpFwdX => Value::Float(velocity.runjump_fwd.0),
+            Constant::RunJumpFwdY => Value::Float(velocity.runjump_fwd.1),
+            Constant::AirJumpNum => Value::Int(movement.airjump_num),
+            Constant::YAccel => Value::Float(movement.yaccel),
+            Constant::StandFriction => Value::Float(movement.stand_friction),
+            Constant::CrouchFriction => Value::Float(movement.crouch_friction),
+            Constant::StandFrictionThreshold => Value::Float(movement.stand_friction_threshold),
+            Constant::CrouchFrictionThreshold => Value::Float(movement.crouch_friction_threshold),
+        }
+    }
+}
+
+impl Function {
+    /// Obtiene la función a partir de su nombre.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre de la función en minúsculas.
+    ///
+    /// # Retorna
+    ///
+    /// La función y su número de argumentos, o `None` si es desconocida.
+    fn from_name(name: &str) -> Option<(Self, usize)> {
+        match name {
+            "abs" => Some((Function::Abs, 1)),
+            "floor" => Some((Function::Floor, 1)),
+            "ceil" => Some((Function::Ceil, 1)),
+            "min" => Some((Function::Min, 2)),
+            "max" => Some((Function::Max, 2)),
+            "ifelse" => Some((Function::IfElse, 3)),
+            _ => None,
+        }
+    }
+}
+
+impl BinaryOp {
+    /// Obtiene el operador binario y su precedencia a partir de su símbolo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `symbol` - Símbolo del operador.
+    ///
+    /// # Retorna
+    ///
+    /// El operador y su precedencia (mayor se evalúa antes), o `None` si no es binario.
+    fn from_symbol(symbol: &str) -> Option<(Self, u8)> {
+        let op = match symbol {
+            "||" => (BinaryOp::Or, 1),
+            "^^" => (BinaryOp::Xor, 2),
+            "&&" => (BinaryOp::And, 3),
+            "|" => (BinaryOp::BitOr, 4),
+            "^" => (BinaryOp::BitXor, 5),
+            "&" => (BinaryOp::BitAnd, 6),
+            "=" => (BinaryOp::Eq, 7),
+            "!=" => (BinaryOp::Ne, 7),
+            "<" => (BinaryOp::Lt, 8),
+            "<=" => (BinaryOp::Le, 8),
+            ">" => (BinaryOp::Gt, 8),
+            ">=" => (BinaryOp::Ge, 8),
+            "+" => (BinaryOp::Add, 9),
+            "-" => (BinaryOp::Sub, 9),
+            "*" => (BinaryOp::Mul, 10),
+            "/" => (BinaryOp::Div, 10),
+            "%" => (BinaryOp::Mod, 10),
+            "**" => (BinaryOp::Pow, 11),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Aplica el operador a dos valores.
+    ///
+    /// # Argumentos
+    ///
+    /// * `a` - Operando izquierdo.
+    /// * `b` - Operando derecho.
+    ///
+    /// # Retorna
+    ///
+    /// El resultado, o `None` si no está definido (división entre 0).
+    fn apply(self, a: Value, b: Value) -> Option<Value> {
+        let value = match self {
+            BinaryOp::Or => Value::from_bool(a.is_true() || b.is_true()),
+            BinaryOp::Xor => Value::from_bool(a.is_true() != b.is_true()),
+            BinaryOp::And => Value::from_bool(a.is_true() && b.is_true()),
+            BinaryOp::BitOr => Value::Int(a.as_int() | b.as_int()),
+            BinaryOp::BitXor => Value::Int(a.as_int() ^ b.as_int()),
+            BinaryOp::BitAnd => Value::Int(a.as_int() & b.as_int()),
+            BinaryOp::Eq => Value::from_bool(a.as_float() == b.as_float()),
+            BinaryOp::Ne => Value::from_bool(a.as_float() != b.as_float()),
+            BinaryOp::Lt => Value::from_bool(a.as_float() < b.as_float()),
+            BinaryOp::Le => Value::from_bool(a.as_float() <= b.as_float()),
+            BinaryOp::Gt => Value::from_bool(a.as_float() > b.as_float()),
+            BinaryOp::Ge => Value::from_bool(a.as_float() >= b.as_float()),
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                match (a, b) {
+                    (Value::Int(a), Value::Int(b)) => Value::Int(match self {
+                        BinaryOp::Add => a.wrapping_add(b),
+                        BinaryOp::Sub => a.wrapping_sub(b),
+                        BinaryOp::Mul => a.wrapping_mul(b),
+                        BinaryOp::Div => a.checked_div(b)?,
+                        _ => a.checked_rem(b)?,
+                    }),
+                    _ => {
+                        let (a, b) = (a.as_float(), b.as_float());
+                        match self {
+                            BinaryOp::Add => Value::Float(a + b),
+                            BinaryOp::Sub => Value::Float(a - b),
+                            BinaryOp::Mul => Value::Float(a * b),
+                            _ if b == 0.0 => return None,
+                            BinaryOp::Div => Value::Float(a / b),
+                            _ => Value::Float(a % b),
+                        }
+                    }
+                }
+            }
+            BinaryOp::Pow => match (a, b) {
+                (Value::Int(a), Value::Int(b)) if b >= 0 => Value::Int(a.wrapping_pow(b as u32)),
+                _ => Value::Float(a.as_float().powf(b.as_float())),
+            },
+        };
+        Some(value)
+    }
+}
+
+impl Expr {
+    /// Evalúa la expresión.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ctx` - Contexto de evaluación.
+    ///
+    /// # Retorna
+    ///
+    /// El valor de la expresión, o `None` si no está definido (redirección a un personaje
+    /// inexistente, división entre 0...).
+    pub fn eval(&self, ctx: &Context) -> Option<Value> {
+        let value = match self {
+            Expr::Int(value) => Value::Int(*value),
+            Expr::Float(value) => Value::Float(*value),
+            Expr::Trigger(trigger) => trigger.eval(ctx),
+            Expr::Command(name) => Value::from_bool(is_command_active(ctx.char, name)),
+            Expr::StateType(state) => Value::from_bool(ctx.char.get_state() == state),
+            Expr::Var(index) => Value::Int(ctx.char.get_var(*index)),
+            Expr::Const(constant) => constant.eval(ctx.char),
+            Expr::Redirect(redirection, expr) => expr.eval(&ctx.redirect(*redirection)?)?,
+            Expr::Unary(op, expr) => {
+                let value = expr.eval(ctx)?;
+                match op {
+                    UnaryOp::Not => Value::from_bool(!value.is_true()),
+                    UnaryOp::BitNot => Value::Int(!value.as_int()),
+                    UnaryOp::Neg => match value {
+                        Value::Int(value) => Value::Int(value.wrapping_neg()),
+                        Value::Float(value) => Value::Float(-value),
+                    },
+                }
+            }
+            Expr::Binary(op, a, b) => match op {
+                BinaryOp::And if !a.eval(ctx)?.is_true() => Value::Int(0),
+                BinaryOp::Or if a.eval(ctx)?.is_true() => Value::Int(1),
+                _ => op.apply(a.eval(ctx)?, b.eval(ctx)?)?,
+            },
+            Expr::Range {
+                value,
+                negate,
+                low,
+                high,
+                low_open,
+                high_open,
+            } => {
+                let value = value.eval(ctx)?.as_float();
+                let low = low.eval(ctx)?.as_float();
+                let high = high.eval(ctx)?.as_float();
+                let above = if *low_open { value > low } else { value >= low };
+                let below = if *high_open { value < high } else { value <= high };
+                Value::from_bool((above && below) != *negate)
+            }
+            Expr::Function(function, args) => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(arg.eval(ctx)?);
+                }
+                match function {
+                    Function::Abs => match values[0] {
+                        Value::Int(value) => Value::Int(value.wrapping_abs()),
+                        Value::Float(value) => Value::Float(value.abs()),
+                    },
+                    Function::Floor => Value::Int(values[0].as_float().floor() as i32),
+                    Function::Ceil => Value::Int(values[0].as_float().ceil() as i32),
+                    Function::Min | Function::Max => {
+                        let (a, b) = (values[0], values[1]);
+                        let first = (a.as_float() < b.as_float()) == (*function == Function::Min);
+                        if first {
+                            a
+                        } else {
+                            b
+                        }
+                    }
+                    Function::IfElse => {
+                        if values[0].is_true() {
+                            values[1]
+                        } else {
+                            values[2]
+                        }
+                    }
+                }
+            }
+        };
+        Some(value)
+    }
+
+    /// Evalúa la expresión como condición.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ctx` - Contexto de evaluación.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el valor está definido y no es 0, `false` en caso contrario.
+    pub fn is_true(&self, ctx: &Context) -> bool {
+        self.eval(ctx).is_some_and(Value::is_true)
+    }
+}
+
+impl Parser {
+    /// Obtiene el siguiente símbolo sin consumirlo.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume el siguiente símbolo.
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Consume el siguiente símbolo comprobando que sea el esperado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `expected` - Símbolo esperado.
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected {:?}, found {:?}", expected, token)),
+            None => Err(format!("expected {:?}", expected)),
+        }
+    }
+
+    /// Lee una expresión cuyos operadores tengan al menos la precedencia indicada.
+    ///
+    /// # Argumentos
+    ///
+    /// * `min_prec` - Precedencia mínima.
+    fn expression(&mut self, min_prec: u8) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(symbol)) = self.peek() {
+            let Some((op, prec)) = BinaryOp::from_symbol(symbol) else {
+                break;
+            };
+            if prec < min_prec {
+                break;
+            }
+            self.pos += 1;
+            if matches!(op, BinaryOp::Eq | BinaryOp::Ne) {
+                if let Some(range) = self.range(&left, op == BinaryOp::Ne)? {
+                    left = range;
+                    continue;
+                }
+            }
+            // `**` es asociativo por la derecha
+            let next_prec = if op == BinaryOp::Pow { prec } else { prec + 1 };
+            let right = self.expression(next_prec)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// Intenta leer un rango (`[min, max]`) tras un `=` o `!=`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `value` - Expresión que se compara con el rango.
+    /// * `negate` - Indica si la comparación es `!=`.
+    ///
+    /// # Retorna
+    ///
+    /// El rango si lo hay, `None` si lo que sigue no es un rango.
+    fn range(&mut self, value: &Expr, negate: bool) -> Result<Option<Expr>, String> {
+        let low_open = match self.peek() {
+            Some(Token::LBracket) => false,
+            Some(Token::LParen) => true,
+            _ => return Ok(None),
+        };
+        let start = self.pos;
+        self.pos += 1;
+        let low = self.expression(1)?;
+        if self.peek() != Some(&Token::Comma) {
+            if low_open {
+                self.pos = start;
+                return Ok(None);
+            }
+            return Err("expected , in range".to_string());
+        }
+        self.pos += 1;
+        let high = self.expression(1)?;
+        let high_open = match self.next() {
+            Some(Token::RBracket) => false,
+            Some(Token::RParen) => true,
+            _ => return Err("unterminated range".to_string()),
+        };
+        Ok(Some(Expr::Range {
+            value: Box::new(value.clone()),
+            negate,
+            low: Box::new(low),
+            high: Box::new(high),
+            low_open,
+            high_open,
+        }))
+    }
+
+    /// Lee una expresión unaria (`!`, `~`, `-`) o un término.
+    fn unary(&mut self) -> Result<Expr, String> {
+        let op = match self.peek() {
+            Some(Token::Op("!")) => UnaryOp::Not,
+            Some(Token::Op("~")) => UnaryOp::BitNot,
+            Some(Token::Op("-")) => UnaryOp::Neg,
+            _ => return self.primary(),
+        };
+        self.pos += 1;
+        let expr = self.unary()?;
+        Ok(match (op, expr) {
+            (UnaryOp::Neg, Expr::Int(value)) => Expr::Int(-value),
+            (UnaryOp::Neg, Expr::Float(value)) => Expr::Float(-value),
+            (op, expr) => Expr::Unary(op, Box::new(expr)),
+        })
+    }
+
+    /// Lee un término: número, paréntesis, función, disparador o redirección.
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Int(value)) => Ok(Expr::Int(value)),
+            Some(Token::Float(value)) => Ok(Expr::Float(value)),
+            Some(Token::LParen) => {
+                let expr = self.expression(1)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) => self.identifier(&name),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    /// Lee un término que empieza por un nombre.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre ya consumido, en minúsculas.
+    fn identifier(&mut self, name: &str) -> Result<Expr, String> {
+        let redirection = match name {
+            "p2" => Some(Redirection::P2),
+            "root" | "parent" => Some(Redirection::Root),
+            _ => None,
+        };
+        if let Some(redirection) = redirection {
+            self.expect(Token::Comma)?;
+            let expr = self.unary()?;
+            return Ok(Expr::Redirect(redirection, Box::new(expr)));
+        }
+        if let Some(trigger) = name.strip_prefix("p2") {
+            if matches!(trigger, "stateno" | "life" | "statetype") {
+                let expr = self.identifier(trigger)?;
+                return Ok(Expr::Redirect(Redirection::P2, Box::new(expr)));
+            }
+        }
+        if let Some((function, arity)) = Function::from_name(name) {
+            self.expect(Token::LParen)?;
+            let mut args = Vec::with_capacity(arity);
+            for i in 0..arity {
+                if i > 0 {
+                    self.expect(Token::Comma)?;
+                }
+                args.push(self.expression(1)?);
+            }
+            self.expect(Token::RParen)?;
+            return Ok(Expr::Function(function, args));
+        }
+        match name {
+            "var" => {
+                self.expect(Token::LParen)?;
+                let index = match self.next() {
+                    Some(Token::Int(index)) if (0..NUM_VARS as i32).contains(&index) => {
+                        index as usize
+                    }
+                    _ => return Err(format!("expected variable number below {}", NUM_VARS)),
+                };
+                self.expect(Token::RParen)?;
+                Ok(Expr::Var(index))
+            }
+            "const" => {
+                self.expect(Token::LParen)?;
+                let constant = match self.next() {
+                    Some(Token::Ident(name)) => Constant::from_name(&name)
+                        .ok_or_else(|| format!("unknown constant {}", name))?,
+                    _ => return Err("expected constant name".to_string()),
+                };
+                self.expect(Token::RParen)?;
+                Ok(Expr::Const(constant))
+            }
+            "command" => {
+                let negate = self.equality()?;
+                match self.next() {
+                    Some(Token::Str(command)) => {
+                        let expr = Expr::Command(command);
+                        Ok(if negate {
+                            Expr::Unary(UnaryOp::Not, Box::new(expr))
+                        } else {
+                            expr
+                        })
+                    }
+                    _ => Err("expected command name".to_string()),
+                }
+            }
+            "statetype" => {
+                let negate = self.equality()?;
+                let state = match self.next() {
+                    Some(Token::Ident(state)) => match state.as_str() {
+                        "s" => State::S,
+                        "c" => State::C,
+                        "a" => State::A,
+                        "l" => State::L,
+                        _ => return Err(format!("unknown state type {}", state)),
+                    },
+                    _ => return Err("expected state type".to_string()),
+                };
+                let expr = Expr::StateType(state);
+                Ok(if negate {
+                    Expr::Unary(UnaryOp::Not, Box::new(expr))
+                } else {
+                    expr
+                })
+            }
+            _ => {
+                let axis = if Trigger::has_axis(name) {
+                    match self.next() {
+                        Some(Token::Ident(axis)) => Some(axis),
+                        _ => return Err(format!("expected axis after {}", name)),
+                    }
+                } else {
+                    None
+                };
+                Trigger::from_name(name, axis.as_deref())
+                    .map(Expr::Trigger)
+                    .ok_or_else(|| format!("unknown trigger {}", name))
+            }
+        }
+    }
+
+    /// Consume un `=` o `!=`.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si es `!=`, `false` si es `=`.
+    fn equality(&mut self) -> Result<bool, String> {
+        match self.next() {
+            Some(Token::Op("=")) => Ok(false),
+            Some(Token::Op("!=")) => Ok(true),
+            _ => Err("expected = or !=".to_string()),
+        }
+    }
+}
+
+/// Divide el texto de una expresión en símbolos.
+///
+/// # Argumentos
+///
+/// * `text` - Texto de la expresión.
+///
+/// # Retorna
+///
+/// Los símbolos de la expresión, o un mensaje de error si hay algún carácter inválido.
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    const OPERATORS: [&str; 22] = [
+        "**", "&&", "||", "^^", "!=", ">=", "<=", "!", "~", "-", "+", "*", "/", "%", ">", "<",
+        "=", "&", "|", "^", ":", "$",
+    ];
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            if number.contains('.') {
+                let value = number.parse().map_err(|_| format!("bad number {}", number))?;
+                tokens.push(Token::Float(value));
+            } else {
+                let value = number.parse().map_err(|_| format!("bad number {}", number))?;
+                tokens.push(Token::Int(value));
+            }
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            tokens.push(Token::Ident(name.to_lowercase()));
+            continue;
+        }
+        if c == '"' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("unterminated string".to_string());
+            }
+            tokens.push(Token::Str(chars[start..i].iter().collect()));
+            i += 1;
+            continue;
+        }
+        let token = match c {
+            '(' => Some(Token::LParen),
+            ')' => Some(Token::RParen),
+            '[' => Some(Token::LBracket),
+            ']' => Some(Token::RBracket),
+            ',' => Some(Token::Comma),
+            _ => None,
+        };
+        if let Some(token) = token {
+            tokens.push(token);
+            i += 1;
+            continue;
+        }
+        let op = OPERATORS.iter().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(j, op_c)| chars.get(i + j) == Some(&op_c))
+        });
+        match op {
+            Some(op) if *op != ":" && *op != "$" => {
+                tokens.push(Token::Op(op));
+                i += op.len();
+            }
+            _ => return Err(format!("unexpected character {}", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Lee una expresión de disparador.
+///
+/// # Argumentos
+///
+/// * `text` - Texto de la expresión (`AnimTime = 0 && Vel X > 0`).
+///
+/// # Retorna
+///
+/// El árbol de la expresión, o un mensaje de error si no es válida.
+pub fn parse_expression(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    let expr = parser.expression(1)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {:?}", token)),
+    }
+}
+
+/// Obtiene el ancho del cuerpo de un personaje por delante según esté en el suelo o en el aire.
+///
+/// # Argumentos
+///
+/// * `char` - Personaje del que se obtiene el ancho.
+///
+/// # Retorna
+///
+/// El valor de `ground.front` o `air.front` de sus constantes.
+fn body_front(char: &dyn Character) -> f64 {
+    let size = &char.get_def().size;
+    if char.get_state() == &State::A {
+        size.air_front as f64
+    } else {
+        size.ground_front as f64
+    }
+}
+
+/// Comprueba si un comando está activo en el personaje.
+///
+/// # Argumentos
+///
+/// * `char` - Personaje sobre el que se comprueba.
+/// * `name` - Nombre del comando, incluidos los predefinidos `holdfwd`, `holdback`, `holdup` y `holddown`.
+///
+/// # Retorna
+///
+/// `true` si el comando está activo, `false` en caso contrario.
+fn is_command_active(char: &dyn Character, name: &str) -> bool {
+    match name {
+        "holdfwd" => matches!(char.get_direction(), 6 | 66 | 3 | 9),
+        "holdback" => matches!(char.get_direction(), 4 | 44 | 1 | 7),
+        "holdup" => matches!(char.get_direction(), 7..=9),
+        "holddown" => matches!(char.get_direction(), 1..=3),
+        _ => char.get_action() == name || char.get_commands().iter().any(|command| command == name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chars::data::CharData, player::character::def::manager::CharDef};
+
+    /// Evalúa una expresión sin personajes relevantes.
+    fn eval(text: &str) -> Option<Value> {
+        let char = CharData::from_def(CharDef::new());
+        parse_expression(text).unwrap().eval(&Context::new(&char, None))
+    }
+
+    /// Prueba de operaciones aritméticas con enteros y decimales
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(eval("1 + 2 * 3"), Some(Value::Int(7)));
+        assert_eq!(eval("(1 + 2) * 3"), Some(Value::Int(9)));
+        assert_eq!(eval("7 / 2"), Some(Value::Int(3)));
+        assert_eq!(eval("7 / 2.0"), Some(Value::Float(3.5)));
+        assert_eq!(eval("7 % 4 - -1"), Some(Value::Int(4)));
+        assert_eq!(eval("2 ** 3 ** 2"), Some(Value::Int(512)));
+        assert_eq!(eval(".5 + 1"), Some(Value::Float(1.5)));
+        assert_eq!(eval("1 / 0"), None);
+    }
+
+    /// Prueba de operadores lógicos, de bits y de comparación
+    #[test]
+    fn test_logical_and_bitwise() {
+        assert_eq!(eval("1 && 0 || 1"), Some(Value::Int(1)));
+        assert_eq!(eval("1 ^^ 1"), Some(Value::Int(0)));
+        assert_eq!(eval("!0"), Some(Value::Int(1)));
+        assert_eq!(eval("6 & 3 | 8"), Some(Value::Int(10)));
+        assert_eq!(eval("6 ^ 3"), Some(Value::Int(5)));
+        assert_eq!(eval("~0"), Some(Value::Int(-1)));
+        assert_eq!(eval("1 + 1 = 2 && 3 >= 4 = 0"), Some(Value::Int(1)));
+        assert_eq!(eval("ifelse(2 > 1, abs(-3), 0)"), Some(Value::Int(3)));
+        assert_eq!(eval("max(1, 2.5) + floor(1.7)"), Some(Value::Float(3.5)));
+    }
+
+    /// Prueba de comparación con rangos abiertos y cerrados
+    #[test]
+    fn test_ranges() {
+        assert_eq!(eval("3 = [1, 3]"), Some(Value::Int(1)));
+        assert_eq!(eval("3 = [1, 3)"), Some(Value::Int(0)));
+        assert_eq!(eval("1 = (1, 3]"), Some(Value::Int(0)));
+        assert_eq!(eval("5 != [1, 3]"), Some(Value::Int(1)));
+        assert_eq!(eval("2 = (1 + 1)"), Some(Value::Int(1)));
+        assert_eq!(eval("2 = [1, 3] && 0"), Some(Value::Int(0)));
+    }
+
+    /// Prueba de disparadores sobre el personaje
+    #[test]
+    fn test_triggers() {
+        let mut char = CharData::from_def(CharDef::new());
+        char.set_state_time(4);
+        char.set_anim_time(0);
+        char.set_action("x".to_string());
+        char.set_state(State::C);
+        char.set_var(3, 7);
+        char.set_fall(true);
+        let ctx = Context::new(&char, None);
+        let check = |text: &str| parse_expression(text).unwrap().is_true(&ctx);
+
+        assert!(check("Time = 4 && AnimTime = 0"));
+        assert!(check("StateNo = 0 && Life = 1000 && ctrl"));
+        assert!(check("var(3) = 7 && var(0) = 0"));
+        assert!(check("HitFall && !Win && !Lose"));
+        assert!(check("Vel X = 0 && Pos Y = 0"));
+        assert!(check("command = \"x\" && command != \"y\""));
+        assert!(check("statetype = C && StateType != S"));
+        assert!(!check("p2, Life > 0"));
+        assert!(check("root, Time = 4"));
+        assert!(check("const(velocity.walk.fwd.x) = 2.4"));
+        assert!(check("const(movement.airjump.num) = 1"));
+        assert!(check("const(data.life) = Life"));
+        assert!(check("const(Velocity.Jump.Y) < 0"));
+    }
+
+    /// Prueba de redirecciones al oponente
+    #[test]
+    fn test_redirection() {
+        let char = CharData::from_def(CharDef::new());
+        let mut p2 = CharData::from_def(CharDef::new());
+        p2.set_x(char.get_x() + 40.0);
+        p2.add_life(-100);
+        p2.set_state(State::A);
+        let ctx = Context::new(&char, Some(&p2));
+        let check = |text: &str| parse_expression(text).unwrap().is_true(&ctx);
+
+        assert!(check("p2, Life = 900"));
+        assert!(check("P2Life = 900 && Life = 1000"));
+        assert!(check("p2, statetype = A && P2StateType = A"));
+        assert!(check("P2Dist X = 40"));
+        assert!(check("P2BodyDist X = 12"));
+        assert!(check("p2, P2Dist X = -40"));
+        assert!(check("p2, root, Life = 900"));
+    }
+
+    /// Prueba de expresiones inválidas
+    #[test]
+    fn test_invalid_expression() {
+        assert!(parse_expression("").is_err());
+        assert!(parse_expression("Time = (1").is_err());
+        assert!(parse_expression("Time = [1, 2").is_err());
+        assert!(parse_expression("Explode > 1").is_err());
+        assert!(parse_expression("Vel Z > 1").is_err());
+        assert!(parse_expression("command = x").is_err());
+        assert!(parse_expression("statetype = Q").is_err());
+        assert!(parse_expression("var(60) = 0").is_err());
+        assert!(parse_expression("const(velocity.fly.x) = 0").is_err());
+        assert!(parse_expression("const 1").is_err());
+        assert!(parse_expression("1 2").is_err());
+        assert!(parse_expression("Time @ 2").is_err());
+    }
+}
@@ -0,0 +1,1618 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    chars::{Character, State},
+    error::cns_error::CnsError,
+};
+
+use super::{
+    super::snd::decoder::SoundId,
+    expression::{self, Context, Expr, Value},
+};
+
+/// Posición en la Y del suelo del escenario
+pub const GROUND_Y: f64 = 0.0;
+/// Número máximo de cambios de estado encadenados en un mismo tick
+const MAX_STATE_CHANGES: usize = 8;
+/// Número de variables enteras (`var(0)` a `var(59)`) de un personaje
+pub const NUM_VARS: usize = 60;
+
+/// Tipos de golpe de un `HitDef`, determinan el estado que recibe el objetivo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HitType {
+    /// Golpe alto
+    High,
+    /// Golpe bajo
+    Low,
+    /// Golpe que derriba al objetivo
+    Trip,
+}
+
+/// Estados en los que el objetivo puede defenderse de un golpe (`guardflag`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GuardFlags {
+    /// De pie (`H`)
+    high: bool,
+    /// Agachado (`L`)
+    low: bool,
+    /// En el aire (`A`)
+    air: bool,
+}
+
+/// Struct que representa la definición de un golpe (`HitDef`) de un estado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitDef {
+    /// Daño que recibe el objetivo
+    damage: i32,
+    /// Daño que recibe el objetivo si se defiende
+    guard_damage: i32,
+    /// Fuerza del golpe (0 ligero, 1 medio, 2 fuerte)
+    level: i32,
+    /// Tipo de golpe
+    ground_type: HitType,
+    /// Estados en los que el objetivo puede defenderse, sin ninguno el golpe no se puede bloquear
+    guard_flags: GuardFlags,
+    /// Indica si el golpe derriba a un objetivo en el suelo
+    fall: bool,
+    /// Indica si el golpe derriba a un objetivo en el aire
+    air_fall: bool,
+    /// Velocidad del objetivo en el suelo, con una Y distinta de 0 lo levanta del suelo
+    ground_velocity: (f64, f64),
+    /// Velocidad del objetivo en el aire
+    air_velocity: (f64, f64),
+    /// Velocidad en la X del objetivo al defenderse en el suelo
+    guard_velocity: f64,
+    /// Velocidad del objetivo al defenderse en el aire
+    airguard_velocity: (f64, f64),
+    /// Estado al que pasa el atacante si el golpe no se bloquea
+    p1_state_no: Option<i32>,
+    /// Estado al que pasa el objetivo si el golpe no se bloquea, en lugar del de su tipo
+    p2_state_no: Option<i32>,
+    /// Distancia delante del atacante a la que se coloca el objetivo
+    snap: Option<f64>,
+    /// Sonido al golpear
+    hit_sound: Option<(bool, SoundId)>,
+    /// Sonido al golpear a un objetivo que se defiende
+    guard_sound: Option<(bool, SoundId)>,
+}
+
+/// Struct que representa los parámetros de un controlador `PlaySnd`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaySnd {
+    /// Indica si el sonido es de los sonidos comunes (prefijo `F`) en vez de los del personaje
+    pub common: bool,
+    /// Grupo y número del sonido
+    pub sound: SoundId,
+    /// Volumen en porcentaje
+    pub volume_scale: f64,
+    /// Canal del sonido, -1 usa cualquier voz libre
+    pub channel: i32,
+    /// Un sonido de baja prioridad no interrumpe el de su canal
+    pub low_priority: bool,
+    /// Multiplicador de la frecuencia de reproducción
+    pub freq_mul: f64,
+    /// Indica si el sonido se repite hasta que se detenga
+    pub looping: bool,
+    /// Desplazamiento del sonido en píxeles, hacia delante del personaje o desde el centro si es absoluto
+    pub pan: f64,
+    /// Indica si el desplazamiento es respecto al centro de la pantalla
+    pub abs_pan: bool,
+}
+
+/// Órdenes de sonido que los controladores dejan al personaje para que las reproduzca el mezclador.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SoundCommand {
+    /// Reproduce un sonido
+    Play(PlaySnd),
+    /// Detiene un canal, -1 detiene todos los del personaje
+    Stop(i32),
+}
+
+/// Controladores de estado admitidos.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Controller {
+    /// Cambia de estado
+    ChangeState { value: Expr, ctrl: Option<Expr> },
+    /// Cambia la animación
+    ChangeAnim { value: Expr },
+    /// Establece la velocidad
+    VelSet { x: Option<Expr>, y: Option<Expr> },
+    /// Suma velocidad
+    VelAdd { x: Option<Expr>, y: Option<Expr> },
+    /// Suma posición
+    PosAdd { x: Option<Expr>, y: Option<Expr> },
+    /// Establece la posición (la Y es relativa al suelo)
+    PosSet { x: Option<Expr>, y: Option<Expr> },
+    /// Establece el control
+    CtrlSet { value: Expr },
+    /// Suma poder
+    PowerAdd { value: Expr },
+    /// Cambia el tipo de estado
+    StateTypeSet { state_type: State },
+    /// Gira al personaje
+    Turn,
+    /// Establece una variable
+    VarSet { var: usize, value: Expr },
+    /// Suma a una variable
+    VarAdd { var: usize, value: Expr },
+    /// Hace al personaje invulnerable a los ataques de ciertos tipos de estado durante unos ticks
+    NotHitBy { state_types: Vec<State>, time: Expr },
+    /// Indica si el último golpe recibido derriba al personaje
+    HitFallSet { value: bool },
+    /// Activa un golpe
+    HitDef(HitDef),
+    /// Reproduce un sonido
+    PlaySnd(PlaySnd),
+    /// Detiene los sonidos de un canal
+    StopSnd { channel: i32 },
+    /// No hace nada
+    Null,
+}
+
+/// Struct que representa un controlador de estado (`[State N, ...]`) con sus disparadores.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateController {
+    /// Controlador a ejecutar
+    controller: Controller,
+    /// Expresiones que se deben cumplir siempre
+    trigger_all: Vec<Expr>,
+    /// Grupos de expresiones, basta con que se cumplan todas las de uno
+    triggers: Vec<Vec<Expr>>,
+}
+
+/// Struct que representa la definición de un estado (`[Statedef N]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDef {
+    /// Tipo de estado (de pie, agachado, en el aire, tirado)
+    state_type: Option<State>,
+    /// Indica si el estado es de ataque
+    attack: bool,
+    /// Física aplicada en el estado
+    physics: Option<State>,
+    /// Animación inicial
+    anim: Option<i32>,
+    /// Control inicial
+    ctrl: Option<bool>,
+    /// Velocidad inicial
+    vel_set: Option<(f64, f64)>,
+    /// Poder que se suma al entrar
+    power_add: i32,
+    /// Controladores del estado
+    controllers: Vec<StateController>,
+}
+
+/// Struct que representa la tabla de estados de un personaje leída de un archivo CNS.
+///
+/// El estado especial `-1` se ejecuta en cada tick antes que el estado en el que se encuentra el personaje, el resto
+/// solo cuando el personaje se encuentra en él.
+pub struct StateTable {
+    /// Mapa de estados identificados por su número
+    states: HashMap<i32, StateDef>,
+    /// Estado cuya definición ya se ha aplicado
+    current: i32,
+}
+
+impl GuardFlags {
+    /// Interpreta las letras de un `guardflag`: `H` de pie, `L` agachado, `M` ambos y `A` en el aire.
+    ///
+    /// # Argumentos
+    ///
+    /// * `value` - Texto del parámetro.
+    ///
+    /// # Retorna
+    ///
+    /// Los estados en los que se puede defender el golpe, o `None` si hay alguna letra desconocida.
+    fn parse(value: &str) -> Option<Self> {
+        let mut flags = Self::default();
+        for letter in value.trim().chars() {
+            match letter.to_ascii_uppercase() {
+                'H' => flags.high = true,
+                'L' => flags.low = true,
+                'M' => (flags.high, flags.low) = (true, true),
+                'A' => flags.air = true,
+                _ => return None,
+            }
+        }
+        Some(flags)
+    }
+}
+
+impl HitDef {
+    /// Crea un `HitDef` a partir de los parámetros de un controlador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `params` - Parámetros del controlador.
+    ///
+    /// # Retorna
+    ///
+    /// El `HitDef` leído, o `None` si algún parámetro es incorrecto.
+    fn from_params(params: &HashMap<String, String>) -> Option<Self> {
+        let (damage, guard_damage) = match params.get("damage") {
+            Some(damage) => {
+                let (hit, guard) = parse_pair(damage)?;
+                (hit.unwrap_or(0.0) as i32, guard.unwrap_or(0.0) as i32)
+            }
+            None => (0, 0),
+        };
+        let level = match params.get("animtype").map(|s| s.to_lowercase()) {
+            None => 0,
+            Some(animtype) => match animtype.as_str() {
+                "light" => 0,
+                "medium" => 1,
+                "hard" | "heavy" => 2,
+                _ => return None,
+            },
+        };
+        let ground_type = match params.get("ground.type").map(|s| s.to_lowercase()) {
+            None => HitType::High,
+            Some(ground_type) => match ground_type.as_str() {
+                "high" => HitType::High,
+                "low" => HitType::Low,
+                "trip" => HitType::Trip,
+                _ => return None,
+            },
+        };
+        let guard_flags = match params.get("guardflag") {
+            Some(flags) => GuardFlags::parse(flags)?,
+            None => GuardFlags::default(),
+        };
+        let flag = |key: &str| match params.get(key) {
+            Some(value) => parse_number(value).map(|value| Some(value != 0.0)),
+            None => Some(None),
+        };
+        let fall = flag("fall")?.unwrap_or(false);
+        let air_fall = flag("air.fall")?.unwrap_or(fall);
+        let ground_velocity = match params.get("ground.velocity") {
+            Some(velocity) => {
+                let (x, y) = parse_pair(velocity)?;
+                (x.unwrap_or(0.0), y.unwrap_or(0.0))
+            }
+            None => (0.0, 0.0),
+        };
+        let air_velocity = match params.get("air.velocity") {
+            Some(velocity) => {
+                let (x, y) = parse_pair(velocity)?;
+                (x.unwrap_or(ground_velocity.0), y.unwrap_or(0.0))
+            }
+            None => (ground_velocity.0, 0.0),
+        };
+        let guard_velocity = match params.get("guard.velocity") {
+            Some(velocity) => parse_pair(velocity)?.0.unwrap_or(ground_velocity.0),
+            None => ground_velocity.0,
+        };
+        let airguard_velocity = match params.get("airguard.velocity") {
+            Some(velocity) => {
+                let (x, y) = parse_pair(velocity)?;
+                (x.unwrap_or(air_velocity.0), y.unwrap_or(air_velocity.1))
+            }
+            None => air_velocity,
+        };
+        let number = |key: &str| match params.get(key) {
+            Some(value) => parse_number(value).map(Some),
+            None => Some(None),
+        };
+        let hit_sound = match params.get("hitsound") {
+            Some(sound) => Some(parse_sound(sound)?),
+            None => None,
+        };
+        let guard_sound = match params.get("guardsound") {
+            Some(sound) => Some(parse_sound(sound)?),
+            None => None,
+        };
+        Some(Self {
+            damage,
+            guard_damage,
+            level,
+            ground_type,
+            guard_flags,
+            fall,
+            air_fall,
+            ground_velocity,
+            air_velocity,
+            guard_velocity,
+            airguard_velocity,
+            p1_state_no: number("p1stateno")?.map(|state_no| state_no as i32),
+            p2_state_no: number("p2stateno")?.map(|state_no| state_no as i32),
+            snap: number("snap")?,
+            hit_sound,
+            guard_sound,
+        })
+    }
+
+    /// Obtiene el sonido que se reproduce al conectar el golpe.
+    ///
+    /// # Argumentos
+    ///
+    /// * `blocking` - Indica si el objetivo se ha defendido.
+    ///
+    /// # Retorna
+    ///
+    /// La orden para reproducir el sonido, o `None` si el golpe no tiene sonido.
+    pub fn sound(&self, blocking: bool) -> Option<SoundCommand> {
+        let (common, sound) = if blocking { self.guard_sound } else { self.hit_sound }?;
+        Some(SoundCommand::Play(PlaySnd {
+            common,
+            ..PlaySnd::new(sound)
+        }))
+    }
+
+    /// Obtiene el estado al que pasa el atacante cuando el golpe no se bloquea.
+    ///
+    /// # Retorna
+    ///
+    /// El número del estado, o `None` si el atacante sigue en su estado.
+    pub fn p1_state_no(&self) -> Option<i32> {
+        self.p1_state_no
+    }
+
+    /// Comprueba si el objetivo se defiende del golpe.
+    ///
+    /// El objetivo se defiende manteniendo atrás (o atrás y arriba) de pie o en el aire, o atrás y abajo agachado o en
+    /// el aire, siempre que el golpe se pueda bloquear en ese estado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `target` - Personaje que recibe el golpe.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si el objetivo bloquea el golpe, `false` en caso contrario.
+    pub fn is_guarded(&self, target: &dyn Character) -> bool {
+        let air = target.get_state() == &State::A;
+        match target.get_direction() {
+            4 | 7 | 1 if air => self.guard_flags.air,
+            4 | 7 => self.guard_flags.high,
+            1 => self.guard_flags.low,
+            _ => false,
+        }
+    }
+
+    /// Aplica el golpe sobre el objetivo, que se gira hacia el atacante.
+    ///
+    /// # Argumentos
+    ///
+    /// * `attacker` - Personaje que golpea, su ataque multiplica el daño y la defensa del objetivo lo divide.
+    /// * `target` - Personaje que recibe el golpe.
+    /// * `blocking` - Indica si el objetivo se está defendiendo.
+    ///
+    /// Si se defiende pasa al estado de defensa (1310 de pie, 1330 agachado, 1350 en el aire). Si no, al de golpe según
+    /// su tipo y fuerza a partir de 5000 (de pie o en el aire) o 5020 (agachado), salvo que el golpe indique otro.
+    pub fn apply(&self, attacker: &dyn Character, target: &mut dyn Character, blocking: bool) {
+        if (target.get_distance() > 0.0) != target.get_flip() {
+            target.set_current_flip(target.get_distance() > 0.0);
+        }
+        if let Some(snap) = self.snap {
+            let facing = if attacker.is_flipped() { -1.0 } else { 1.0 };
+            target.set_x(attacker.get_x() + snap * facing);
+        }
+        let air = target.get_state() == &State::A;
+        let attack = attacker.get_def().data.attack;
+        let defence = target.get_def().data.defence.max(1);
+        target.set_def(blocking);
+        if blocking {
+            if air {
+                target.set_vel_x(self.airguard_velocity.0);
+                target.set_vel_y(self.airguard_velocity.1);
+            } else {
+                target.set_vel_x(self.guard_velocity);
+            }
+            target.add_life(-self.guard_damage * attack / defence);
+            target.set_state_no(match target.get_direction() {
+                _ if air => 1350,
+                1 => 1330,
+                _ => 1310,
+            });
+            return;
+        }
+        if air {
+            target.set_vel_x(self.air_velocity.0);
+            target.set_vel_y(self.air_velocity.1);
+            target.set_fall(self.air_fall || target.get_fall());
+        } else {
+            target.set_vel_x(self.ground_velocity.0);
+            if self.ground_velocity.1 != 0.0 {
+                target.set_vel_y(self.ground_velocity.1);
+                target.set_state(State::A);
+            }
+            target.set_fall(self.fall);
+        }
+        target.add_life(-self.damage * attack / defence);
+        let state_no = if target.get_state() == &State::C {
+            5020
+        } else {
+            5000
+        };
+        target.set_state_no(match (self.p2_state_no, self.ground_type) {
+            (Some(state_no), _) => state_no,
+            (None, HitType::Trip) => 5070,
+            (None, HitType::Low) if state_no == 5000 => state_no + 10 + self.level,
+            (None, _) => state_no + self.level,
+        });
+    }
+}
+
+impl PlaySnd {
+    /// Crea los parámetros para reproducir un sonido del personaje con los valores por defecto.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sound` - Grupo y número del sonido.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo `PlaySnd` a volumen original en cualquier canal.
+    pub fn new(sound: SoundId) -> Self {
+        Self {
+            common: false,
+            sound,
+            volume_scale: 100.0,
+            channel: -1,
+            low_priority: false,
+            freq_mul: 1.0,
+            looping: false,
+            pan: 0.0,
+            abs_pan: false,
+        }
+    }
+
+    /// Crea un `PlaySnd` a partir de los parámetros de un controlador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `params` - Parámetros del controlador.
+    ///
+    /// # Retorna
+    ///
+    /// El `PlaySnd` leído, o `None` si algún parámetro es incorrecto.
+    fn from_params(params: &HashMap<String, String>) -> Option<Self> {
+        let (common, sound) = parse_sound(params.get("value")?)?;
+        let number = |key: &str| match params.get(key) {
+            Some(value) => parse_number(value),
+            None => Some(0.0),
+        };
+        let (pan, abs_pan) = match params.get("abspan") {
+            Some(pan) => (parse_number(pan)?, true),
+            None => (number("pan")?, false),
+        };
+        Some(Self {
+            common,
+            sound,
+            volume_scale: match params.get("volumescale") {
+                Some(volume) => parse_number(volume)?,
+                None => 100.0,
+            },
+            channel: match params.get("channel") {
+                Some(channel) => parse_number(channel)? as i32,
+                None => -1,
+            },
+            low_priority: number("lowpriority")? != 0.0,
+            freq_mul: match params.get("freqmul") {
+                Some(freq_mul) => parse_number(freq_mul)?,
+                None => 1.0,
+            },
+            looping: number("loop")? != 0.0,
+            pan,
+            abs_pan,
+        })
+    }
+}
+
+impl Controller {
+    /// Crea un controlador a partir de su tipo y parámetros.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Tipo del controlador.
+    /// * `params` - Parámetros del controlador.
+    /// * `line` - Línea de la cabecera del controlador.
+    ///
+    /// # Retorna
+    ///
+    /// El controlador leído, o un `CnsError` si el tipo es desconocido o los parámetros son incorrectos.
+    fn from_params(
+        name: &str,
+        params: &HashMap<String, String>,
+        line: usize,
+    ) -> Result<Self, CnsError> {
+        let number = |key: &str| -> Result<Option<f64>, CnsError> {
+            match params.get(key) {
+                Some(value) => parse_number(value)
+                    .map(Some)
+                    .ok_or(CnsError::Malformed(line)),
+                None => Ok(None),
+            }
+        };
+        let expr = |key: &str| -> Result<Option<Expr>, CnsError> {
+            match params.get(key) {
+                Some(value) => expression::parse_expression(value)
+                    .map(Some)
+                    .map_err(|err| CnsError::InvalidExpression(err, line)),
+                None => Ok(None),
+            }
+        };
+        let value = || expr("value")?.ok_or(CnsError::Malformed(line));
+        let controller = match name.to_lowercase().as_str() {
+            "changestate" => Controller::ChangeState {
+                value: value()?,
+                ctrl: expr("ctrl")?,
+            },
+            "changeanim" => Controller::ChangeAnim { value: value()? },
+            "velset" => Controller::VelSet {
+                x: expr("x")?,
+                y: expr("y")?,
+            },
+            "veladd" => Controller::VelAdd {
+                x: expr("x")?,
+                y: expr("y")?,
+            },
+            "posadd" => Controller::PosAdd {
+                x: expr("x")?,
+                y: expr("y")?,
+            },
+            "posset" => Controller::PosSet {
+                x: expr("x")?,
+                y: expr("y")?,
+            },
+            "ctrlset" => Controller::CtrlSet { value: value()? },
+            "poweradd" => Controller::PowerAdd { value: value()? },
+            "statetypeset" => Controller::StateTypeSet {
+                state_type: params
+                    .get("statetype")
+                    .and_then(|s| parse_state(s))
+                    .ok_or(CnsError::Malformed(line))?,
+            },
+            "turn" => Controller::Turn,
+            "varset" | "varadd" => {
+                let var = number("v")?.ok_or(CnsError::Malformed(line))?;
+                if var < 0.0 || var as usize >= NUM_VARS {
+                    return Err(CnsError::Malformed(line));
+                }
+                let (var, value) = (var as usize, value()?);
+                if name.eq_ignore_ascii_case("varset") {
+                    Controller::VarSet { var, value }
+                } else {
+                    Controller::VarAdd { var, value }
+                }
+            }
+            "nothitby" => Controller::NotHitBy {
+                state_types: params
+                    .get("value")
+                    .and_then(|value| parse_state_types(value))
+                    .ok_or(CnsError::Malformed(line))?,
+                time: expr("time")?.unwrap_or(Expr::Int(1)),
+            },
+            "hitfallset" => Controller::HitFallSet {
+                value: number("value")?.unwrap_or(1.0) != 0.0,
+            },
+            "hitdef" => {
+                Controller::HitDef(HitDef::from_params(params).ok_or(CnsError::Malformed(line))?)
+            }
+            "playsnd" => {
+                Controller::PlaySnd(PlaySnd::from_params(params).ok_or(CnsError::Malformed(line))?)
+            }
+            "stopsnd" => Controller::StopSnd {
+                channel: number("channel")?.unwrap_or(-1.0) as i32,
+            },
+            "null" => Controller::Null,
+            _ => return Err(CnsError::UnknownController(name.to_string(), line)),
+        };
+        Ok(controller)
+    }
+}
+
+impl StateController {
+    /// Comprueba si se cumplen los disparadores del controlador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `ctx` - Contexto de evaluación.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si se cumplen todas las expresiones de `triggerall` y las de algún `triggerN`.
+    fn is_triggered(&self, ctx: &Context) -> bool {
+        self.trigger_all.iter().all(|expr| expr.is_true(ctx))
+            && self
+                .triggers
+                .iter()
+                .any(|group| group.iter().all(|expr| expr.is_true(ctx)))
+    }
+}
+
+impl StateDef {
+    /// Crea una definición de estado vacía.
+    ///
+    /// Retorna un nuevo `StateDef` con valores predeterminados.
+    fn new() -> Self {
+        Self {
+            state_type: None,
+            attack: false,
+            physics: None,
+            anim: None,
+            ctrl: None,
+            vel_set: None,
+            power_add: 0,
+            controllers: Vec::new(),
+        }
+    }
+
+    /// Aplica los valores iniciales del estado al personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `char` - Personaje que entra en el estado.
+    fn enter(&self, char: &mut dyn Character) {
+        char.set_state_time(0);
+        if let Some(state_type) = self.state_type {
+            char.set_state(state_type);
+        }
+        if self.attack {
+            char.set_attack(*char.get_state());
+        }
+        if let Some(anim) = self.anim {
+            char.set_anim(anim);
+        }
+        if let Some(ctrl) = self.ctrl {
+            char.set_ctrl(ctrl);
+        }
+        if let Some((x, y)) = self.vel_set {
+            char.set_vel_x(x);
+            char.set_vel_y(y);
+        }
+        if self.power_add != 0 {
+            char.add_power(self.power_add);
+        }
+    }
+
+    /// Ejecuta los controladores cuyos disparadores se cumplan.
+    ///
+    /// # Argumentos
+    ///
+    /// * `char` - Personaje que se encuentra en el estado.
+    /// * `p2` - Oponente del personaje, si se conoce.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si se ha cambiado de estado, `false` en caso contrario.
+    fn execute(&self, char: &mut dyn Character, p2: Option<&dyn Character>) -> bool {
+        for state_controller in &self.controllers {
+            if !state_controller.is_triggered(&Context::new(char, p2)) {
+                continue;
+            }
+            match &state_controller.controller {
+                Controller::ChangeState { value, ctrl } => {
+                    let Some(state_no) = eval_param(Some(value), char, p2) else {
+                        continue;
+                    };
+                    if let Some(ctrl) = eval_param(ctrl.as_ref(), char, p2) {
+                        char.set_ctrl(ctrl.is_true());
+                    }
+                    char.set_state_no(state_no.as_int());
+                    return true;
+                }
+                Controller::ChangeAnim { value } => {
+                    if let Some(anim) = eval_param(Some(value), char, p2) {
+                        char.set_anim(anim.as_int());
+                    }
+                }
+                Controller::VelSet { x, y } => {
+                    let x = eval_param(x.as_ref(), char, p2);
+                    let y = eval_param(y.as_ref(), char, p2);
+                    if let Some(x) = x {
+                        char.set_vel_x(x.as_float());
+                    }
+                    if let Some(y) = y {
+                        char.set_vel_y(y.as_float());
+                    }
+                }
+                Controller::VelAdd { x, y } => {
+                    let x = eval_param(x.as_ref(), char, p2);
+                    let y = eval_param(y.as_ref(), char, p2);
+                    char.add_vel_x(x.map_or(0.0, Value::as_float));
+                    char.add_vel_y(y.map_or(0.0, Value::as_float));
+                }
+                Controller::PosAdd { x, y } => {
+                    let x = eval_param(x.as_ref(), char, p2);
+                    let y = eval_param(y.as_ref(), char, p2);
+                    if let Some(x) = x {
+                        char.add_pos_x(x.as_float());
+                    }
+                    if let Some(y) = y {
+                        char.set_y(char.get_y() + y.as_float());
+                    }
+                }
+                Controller::PosSet { x, y } => {
+                    let x = eval_param(x.as_ref(), char, p2);
+                    let y = eval_param(y.as_ref(), char, p2);
+                    if let Some(x) = x {
+                        char.set_x(x.as_float());
+                    }
+                    if let Some(y) = y {
+                        char.set_y(GROUND_Y + y.as_float());
+                    }
+                }
+                Controller::CtrlSet { value } => {
+                    if let Some(ctrl) = eval_param(Some(value), char, p2) {
+                        char.set_ctrl(ctrl.is_true());
+                    }
+                }
+                Controller::PowerAdd { value } => {
+                    if let Some(power) = eval_param(Some(value), char, p2) {
+                        char.add_power(power.as_int());
+                    }
+                }
+                Controller::StateTypeSet { state_type } => char.set_state(*state_type),
+                Controller::Turn => char.set_current_flip(!char.is_flipped()),
+                Controller::VarSet { var, value } => {
+                    if let Some(value) = eval_param(Some(value), char, p2) {
+                        char.set_var(*var, value.as_int());
+                    }
+                }
+                Controller::VarAdd { var, value } => {
+                    if let Some(value) = eval_param(Some(value), char, p2) {
+                        char.set_var(*var, char.get_var(*var) + value.as_int());
+                    }
+                }
+                Controller::NotHitBy { state_types, time } => {
+                    if let Some(time) = eval_param(Some(time), char, p2) {
+                        char.set_not_hit_by(state_types.clone(), time.as_int());
+                    }
+                }
+                Controller::HitFallSet { value } => char.set_fall(*value),
+                Controller::HitDef(hit_def) => char.set_hit_def(Some(hit_def.clone())),
+                Controller::PlaySnd(play_snd) => {
+                    char.push_sound(SoundCommand::Play(play_snd.clone()))
+                }
+                Controller::StopSnd { channel } => char.push_sound(SoundCommand::Stop(*channel)),
+                Controller::Null => {}
+            }
+        }
+        if self.physics == Some(State::A) {
+            char.add_vel_y(char.get_def().movement.yaccel);
+        }
+        false
+    }
+}
+
+impl Default for StateTable {
+    /// Crea una tabla de estados vacía, igual que `StateTable::new`.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `StateTable` sin estados.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateTable {
+    /// Crea una tabla de estados vacía.
+    ///
+    /// Retorna una nueva instancia de `StateTable` sin estados.
+    pub fn new() -> Self {
+        Self {
+            states: HashMap::new(),
+            current: -1,
+        }
+    }
+
+    /// Obtiene las animaciones que usan los estados: la de cada `Statedef` y la de sus `ChangeAnim` fijos.
+    ///
+    /// # Retorna
+    ///
+    /// Los pares de número de estado y animación, ordenados.
+    pub fn animations(&self) -> BTreeSet<(i32, i32)> {
+        let mut animations = BTreeSet::new();
+        for (number, state_def) in &self.states {
+            animations.extend(state_def.anim.map(|anim| (*number, anim)));
+            for state_controller in &state_def.controllers {
+                if let Controller::ChangeAnim {
+                    value: Expr::Int(value),
+                } = state_controller.controller
+                {
+                    animations.insert((*number, value));
+                }
+            }
+        }
+        animations
+    }
+
+    /// Ejecuta un tick de la máquina de estados sobre el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `char` - Personaje a actualizar.
+    /// * `p2` - Oponente del personaje, usado por las redirecciones `p2,` y los disparadores `P2Dist`.
+    ///
+    /// Primero se ejecuta el estado especial `-1`, después el estado en el que se encuentre el personaje.
+    /// Al entrar en un estado se aplican los valores de su `Statedef`, también cuando se vuelve a establecer el mismo
+    /// estado, y si un controlador cambia de estado, el nuevo estado se ejecuta en el mismo tick.
+    pub fn run(&mut self, char: &mut dyn Character, p2: Option<&dyn Character>) {
+        if let Some(always) = self.states.get(&-1) {
+            always.execute(char, p2);
+        }
+        for _ in 0..MAX_STATE_CHANGES {
+            let state_no = char.get_state_no();
+            // Un golpe puede volver a poner al personaje en el estado en el que ya está
+            let entering = char.take_state_changed() || state_no != self.current;
+            if entering {
+                char.set_hit_def(None);
+            }
+            let state = match self.states.get(&state_no) {
+                Some(state) if state_no >= 0 => state,
+                _ => {
+                    self.current = -1;
+                    return;
+                }
+            };
+            if entering {
+                self.current = state_no;
+                state.enter(char);
+            }
+            if !state.execute(char, p2) {
+                char.set_state_time(char.get_state_time() + 1);
+                return;
+            }
+        }
+    }
+}
+
+/// Evalúa un parámetro de un controlador antes de aplicarlo al personaje.
+///
+/// # Argumentos
+///
+/// * `expr` - Expresión del parámetro, si se ha indicado.
+/// * `char` - Personaje que ejecuta el controlador.
+/// * `p2` - Oponente del personaje, si se conoce.
+///
+/// # Retorna
+///
+/// El valor del parámetro, o `None` si no se ha indicado o no está definido.
+fn eval_param(
+    expr: Option<&Expr>,
+    char: &dyn Character,
+    p2: Option<&dyn Character>,
+) -> Option<Value> {
+    expr?.eval(&Context::new(char, p2))
+}
+
+/// Interpreta un número de un parámetro.
+///
+/// # Argumentos
+///
+/// * `value` - Texto del número.
+///
+/// # Retorna
+///
+/// El número, o `None` si no es válido.
+pub fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok()
+}
+
+/// Interpreta un par de números separados por coma, cualquiera de los dos puede omitirse.
+///
+/// # Argumentos
+///
+/// * `value` - Texto del par.
+///
+/// # Retorna
+///
+/// El par de números, o `None` si alguno no es válido.
+pub fn parse_pair(value: &str) -> Option<(Option<f64>, Option<f64>)> {
+    let mut parts = value.split(',').map(|part| part.trim());
+    let mut next = || match parts.next() {
+        Some(part) if !part.is_empty() => parse_number(part).map(Some),
+        _ => Some(None),
+    };
+    Some((next()?, next()?))
+}
+
+/// Interpreta un tipo de estado (`S`, `C`, `A`, `L`).
+///
+/// # Argumentos
+///
+/// * `value` - Texto del tipo de estado.
+///
+/// # Retorna
+///
+/// El tipo de estado, o `None` si no es válido.
+fn parse_state(value: &str) -> Option<State> {
+    match value.trim().to_uppercase().as_str() {
+        "S" => Some(State::S),
+        "C" => Some(State::C),
+        "A" => Some(State::A),
+        "L" => Some(State::L),
+        _ => None,
+    }
+}
+
+/// Interpreta los tipos de estado de un atributo de golpe, como `SCA` en `SCA, NA`.
+///
+/// # Argumentos
+///
+/// * `value` - Texto del atributo, solo se tienen en cuenta las letras antes de la primera coma.
+///
+/// # Retorna
+///
+/// Los tipos de estado, o `None` si no hay ninguno o alguno no es válido.
+fn parse_state_types(value: &str) -> Option<Vec<State>> {
+    let letters = value.split(',').next()?.trim();
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .chars()
+        .map(|letter| parse_state(&letter.to_string()))
+        .collect()
+}
+
+/// Interpreta un sonido como grupo y número, con el prefijo `F` para los sonidos comunes y `S` para los del
+/// personaje.
+///
+/// # Argumentos
+///
+/// * `value` - Texto del sonido.
+///
+/// # Retorna
+///
+/// Si el sonido es común y su identificador, o `None` si no es válido.
+fn parse_sound(value: &str) -> Option<(bool, SoundId)> {
+    let value = value.trim();
+    let (common, value) = match value.chars().next()? {
+        'F' | 'f' => (true, &value[1..]),
+        'S' | 's' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let (group, number) = parse_pair(value)?;
+    let group = group?;
+    if group < 0.0 {
+        return None;
+    }
+    Some((common, SoundId::from((group as u32, number.unwrap_or(0.0).max(0.0) as u32))))
+}
+
+/// Aplica los parámetros leídos a una definición de estado.
+///
+/// # Argumentos
+///
+/// * `state_def` - Definición de estado a completar.
+/// * `params` - Parámetros leídos.
+/// * `line` - Línea de la cabecera del estado.
+///
+/// # Retorna
+///
+/// Un `CnsError` si algún parámetro es incorrecto.
+fn read_state_def(
+    state_def: &mut StateDef,
+    params: &HashMap<String, String>,
+    line: usize,
+) -> Result<(), CnsError> {
+    for (key, value) in params {
+        match key.as_str() {
+            "type" => state_def.state_type = parse_state(value),
+            "movetype" => state_def.attack = value.trim().eq_ignore_ascii_case("A"),
+            "physics" => state_def.physics = parse_state(value),
+            "anim" => {
+                state_def.anim = Some(parse_number(value).ok_or(CnsError::Malformed(line))? as i32)
+            }
+            "ctrl" => {
+                state_def.ctrl = Some(parse_number(value).ok_or(CnsError::Malformed(line))? != 0.0)
+            }
+            "velset" => match parse_pair(value) {
+                Some((x, y)) => state_def.vel_set = Some((x.unwrap_or(0.0), y.unwrap_or(0.0))),
+                None => return Err(CnsError::Malformed(line)),
+            },
+            "poweradd" => {
+                state_def.power_add = parse_number(value).ok_or(CnsError::Malformed(line))? as i32
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Crea un controlador de estado a partir de sus parámetros y disparadores.
+///
+/// # Argumentos
+///
+/// * `params` - Parámetros leídos.
+/// * `triggers` - Disparadores leídos, agrupados por número (`0` es `triggerall`).
+/// * `line` - Línea de la cabecera del controlador.
+///
+/// # Retorna
+///
+/// El controlador de estado, o un `CnsError` si falta el tipo o algún parámetro es incorrecto.
+fn read_state_controller(
+    params: &HashMap<String, String>,
+    mut triggers: BTreeMap<u32, Vec<Expr>>,
+    line: usize,
+) -> Result<StateController, CnsError> {
+    let name = params.get("type").ok_or(CnsError::Malformed(line))?;
+    let controller = Controller::from_params(name, params, line)?;
+    let trigger_all = triggers.remove(&0).unwrap_or_default();
+    Ok(StateController {
+        controller,
+        trigger_all,
+        triggers: triggers.into_values().collect(),
+    })
+}
+
+/// Sección de un archivo CNS que se está leyendo.
+enum Section {
+    /// Fuera de un estado
+    None,
+    /// Definición de estado, con su número y línea
+    StateDef(i32, usize),
+    /// Controlador de estado, con la línea de su cabecera
+    State(usize),
+}
+
+/// Cierra la sección que se estaba leyendo, añadiéndola a la tabla de estados.
+///
+/// # Argumentos
+///
+/// * `section` - Sección que se estaba leyendo.
+/// * `params` - Parámetros leídos en la sección, se vacían al terminar.
+/// * `triggers` - Disparadores leídos en la sección, se vacían al terminar.
+/// * `table` - Tabla de estados donde se añade la sección.
+/// * `current` - Número del último `Statedef` leído.
+///
+/// # Retorna
+///
+/// Un `CnsError` si la sección está malformada.
+fn close_section(
+    section: &Section,
+    params: &mut HashMap<String, String>,
+    triggers: &mut BTreeMap<u32, Vec<Expr>>,
+    table: &mut StateTable,
+    current: Option<i32>,
+) -> Result<(), CnsError> {
+    match section {
+        Section::None => {}
+        Section::StateDef(number, line) => {
+            let mut state_def = StateDef::new();
+            read_state_def(&mut state_def, params, *line)?;
+            table.states.insert(*number, state_def);
+        }
+        Section::State(line) => {
+            let state_controller = read_state_controller(params, std::mem::take(triggers), *line)?;
+            let number = current.ok_or(CnsError::Malformed(*line))?;
+            if let Some(state_def) = table.states.get_mut(&number) {
+                state_def.controllers.push(state_controller);
+            }
+        }
+    }
+    params.clear();
+    triggers.clear();
+    Ok(())
+}
+
+/// Lee las líneas de un archivo con formato CNS y devuelve la tabla de estados.
+///
+/// # Argumentos
+///
+/// * `lines` - Un vector de referencias a cadenas que representan las líneas del archivo CNS.
+///
+/// # Retorna
+///
+/// La tabla de estados, o un `CnsError` si el archivo está malformado.
+///
+/// # Ejemplo de formato
+///
+/// ```text
+/// [Statedef 200]
+/// type = S
+/// anim = 200
+///
+/// [State 200, End]
+/// type = ChangeState
+/// trigger1 = AnimTime = 0
+/// value = 0
+/// ```
+pub fn read_cns_file(lines: &[&str]) -> Result<StateTable, CnsError> {
+    let mut table = StateTable::new();
+    let mut section = Section::None;
+    let mut current = None;
+    let mut params: HashMap<String, String> = HashMap::new();
+    let mut triggers: BTreeMap<u32, Vec<Expr>> = BTreeMap::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            close_section(&section, &mut params, &mut triggers, &mut table, current)?;
+            let header = line
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .trim()
+                .to_lowercase();
+            if let Some(number) = header.strip_prefix("statedef") {
+                let number = number
+                    .trim()
+                    .parse::<i32>()
+                    .map_err(|_| CnsError::Malformed(i + 1))?;
+                current = Some(number);
+                section = Section::StateDef(number, i + 1);
+            } else if header.starts_with("state ") {
+                if current.is_none() {
+                    return Err(CnsError::Malformed(i + 1));
+                }
+                section = Section::State(i + 1);
+            } else {
+                section = Section::None;
+            }
+            continue;
+        }
+        if let Section::None = section {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(CnsError::Malformed(i + 1));
+        };
+        let key = key.trim().to_lowercase();
+        if let Some(number) = key.strip_prefix("trigger") {
+            let number = if number == "all" {
+                0
+            } else {
+                number
+                    .parse::<u32>()
+                    .map_err(|_| CnsError::Malformed(i + 1))?
+            };
+            let expr = expression::parse_expression(value)
+                .map_err(|err| CnsError::InvalidExpression(err, i + 1))?;
+            triggers.entry(number).or_default().push(expr);
+        } else {
+            params.insert(key, value.trim().to_string());
+        }
+    }
+    close_section(&section, &mut params, &mut triggers, &mut table, current)?;
+    Ok(table)
+}
+
+/// Crea la tabla de estados de un personaje leyendo su archivo CNS.
+///
+/// # Argumentos
+///
+/// * `cns` - Ruta del archivo CNS.
+///
+/// # Retorna
+///
+/// La tabla de estados, o un `CnsError` si no se encuentra o está malformado.
+pub fn parse_cns(cns: &str) -> Result<StateTable, CnsError> {
+    let content = match std::fs::read_to_string(cns) {
+        Ok(content) => content,
+        Err(_) => return Err(CnsError::NotFound(cns.to_string())),
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    read_cns_file(&lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        chars::data::CharData,
+        player::character::{def::manager::CharDef, snd::decoder::Snd},
+    };
+
+    const CNS: &str = "
+        [Statedef -1]
+
+        [State -1, Punch]
+        type = ChangeState
+        value = 200
+        triggerall = command = \"x\"
+        trigger1 = statetype = S && ctrl
+
+        [Statedef 200]
+        type = S
+        movetype = A
+        ctrl = 0
+        anim = 200
+        velset = 0,0
+        poweradd = 10
+
+        [State 200, HitDef]
+        type = HitDef
+        trigger1 = Time = 0
+        damage = 23
+        animtype = Light
+        ground.velocity = -2
+
+        [State 200, Step]
+        type = VelAdd
+        trigger1 = Time = 1
+        trigger2 = Time = 2
+        x = 1
+
+        [State 200, End]
+        type = ChangeState
+        trigger1 = AnimTime = 0
+        value = 0
+        ctrl = 1
+    ";
+
+    /// Crea una tabla de estados a partir de un texto CNS.
+    fn read(content: &str) -> Result<StateTable, CnsError> {
+        let lines: Vec<&str> = content.lines().collect();
+        read_cns_file(&lines)
+    }
+
+    /// Prueba si un archivo cns bien formado lo crea y si uno que ni existe falla
+    #[test]
+    fn test_parse_cns() {
+        assert!(parse_cns("src/chars/kfm/kfm.cns").is_ok());
+        assert!(parse_cns("INVALIDCNS.C.C.C").is_err());
+    }
+
+    /// Prueba de lectura de un archivo CNS
+    #[test]
+    fn test_read_cns_file() {
+        let table = read(CNS).unwrap();
+
+        assert_eq!(table.states.len(), 2);
+        let state = &table.states[&200];
+        assert_eq!(state.state_type, Some(State::S));
+        assert!(state.attack);
+        assert_eq!(state.anim, Some(200));
+        assert_eq!(state.ctrl, Some(false));
+        assert_eq!(state.vel_set, Some((0.0, 0.0)));
+        assert_eq!(state.power_add, 10);
+        assert_eq!(state.controllers.len(), 3);
+        assert_eq!(state.controllers[1].triggers.len(), 2);
+        assert_eq!(
+            state.controllers[2].controller,
+            Controller::ChangeState {
+                value: Expr::Int(0),
+                ctrl: Some(Expr::Int(1))
+            }
+        );
+        assert_eq!(table.states[&-1].controllers[0].trigger_all.len(), 1);
+        assert_eq!(table.states[&-1].controllers[0].triggers[0].len(), 1);
+        assert_eq!(table.animations(), BTreeSet::from([(200, 200)]));
+    }
+
+    /// Prueba que todos los estados a los que pasa KFM, él o su oponente, estén definidos en su CNS
+    #[test]
+    fn test_kfm_states() {
+        let table = parse_cns("src/chars/kfm/kfm.cns").unwrap();
+        let mut targets: Vec<i32> = (5000..5003).chain(5010..5013).chain(5020..5023).collect();
+        targets.extend([0, 1310, 1330, 1350, 5070]);
+        for state_def in table.states.values() {
+            for state_controller in &state_def.controllers {
+                match &state_controller.controller {
+                    Controller::ChangeState {
+                        value: Expr::Int(value),
+                        ..
+                    } => targets.push(*value),
+                    Controller::HitDef(hit_def) => {
+                        targets.extend(hit_def.p1_state_no);
+                        targets.extend(hit_def.p2_state_no);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let missing: Vec<i32> = targets
+            .into_iter()
+            .filter(|state_no| !table.states.contains_key(state_no))
+            .collect();
+        assert!(missing.is_empty(), "faltan los estados {:?}", missing);
+    }
+
+    /// Prueba que los sonidos de los estados de Kung Fu Man estén en sus archivos SND
+    #[test]
+    fn test_kfm_sounds() {
+        let table = parse_cns("src/chars/kfm/kfm.cns").unwrap();
+        let snd = Snd::load_snd(Path::new("src/chars/kfm/kfm.snd")).unwrap();
+        let common = Snd::load_snd(Path::new("assets/sounds/common.snd")).unwrap();
+        let mut sounds = Vec::new();
+        for state_def in table.states.values() {
+            for state_controller in &state_def.controllers {
+                match &state_controller.controller {
+                    Controller::PlaySnd(play_snd) => sounds.push((play_snd.common, play_snd.sound)),
+                    Controller::HitDef(hit_def) => {
+                        sounds.extend(hit_def.hit_sound);
+                        sounds.extend(hit_def.guard_sound);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        assert!(sounds.contains(&(false, SoundId::from((11, 0)))));
+        let missing: Vec<(bool, SoundId)> = sounds
+            .into_iter()
+            .filter(|&(is_common, id)| {
+                let snd = if is_common { &common } else { &snd };
+                snd.get_sound(id).is_none()
+            })
+            .collect();
+        assert!(missing.is_empty(), "faltan los sonidos {:?}", missing);
+    }
+
+    /// Prueba de lectura de archivos CNS malformados
+    #[test]
+    fn test_read_malformed_cns_file() {
+        assert!(matches!(
+            read("[State 200, End]\ntype = Null"),
+            Err(CnsError::Malformed(1))
+        ));
+        assert!(matches!(
+            read("[Statedef 200]\n[State 200, End]\ntrigger1 = Time = 0"),
+            Err(CnsError::Malformed(2))
+        ));
+        assert!(matches!(
+            read("[Statedef 200]\n[State 200, End]\ntype = ChangeState\ntrigger1 = 1"),
+            Err(CnsError::Malformed(2))
+        ));
+        assert!(matches!(
+            read("[Statedef 200]\n[State 200, End]\ntype = Explode"),
+            Err(CnsError::UnknownController(_, 2))
+        ));
+        assert!(matches!(
+            read("[Statedef 200]\n[State 200, End]\ntype = Null\ntrigger1 = Time = (1"),
+            Err(CnsError::InvalidExpression(_, 4))
+        ));
+    }
+
+    /// Prueba de ejecución de la máquina de estados
+    #[test]
+    fn test_run_state() {
+        let mut table = read(CNS).unwrap();
+        let mut char = CharData::from_def(CharDef::new());
+        char.add_power(-3000);
+        char.set_action("x".to_string());
+        char.set_anim_time(10);
+
+        table.run(&mut char, None);
+        assert_eq!(char.get_state_no(), 200);
+        assert_eq!(*char.get_anim(), 200);
+        assert!(char.get_new_anim());
+        assert!(!char.has_control());
+        assert_eq!(char.get_power(), 10);
+        assert_eq!(char.get_state_time(), 1);
+
+        char.set_anim_time(5);
+        table.run(&mut char, None);
+        table.run(&mut char, None);
+        assert_eq!(char.get_vel_x(), 2.0);
+        assert_eq!(char.get_state_time(), 3);
+
+        char.set_anim_time(0);
+        table.run(&mut char, None);
+        assert_eq!(char.get_state_no(), 0);
+        assert!(char.has_control());
+    }
+
+    /// Prueba de los controladores de giro, variables, invulnerabilidad y caída
+    #[test]
+    fn test_state_controllers() {
+        let mut table = read(concat!(
+            "[Statedef 0]\n",
+            "[State 0, Giro]\n",
+            "type = Turn\n",
+            "trigger1 = Time = 0\n",
+            "[State 0, Var]\n",
+            "type = VarSet\n",
+            "trigger1 = Time = 0\n",
+            "v = 2\n",
+            "value = 5\n",
+            "[State 0, Suma]\n",
+            "type = VarAdd\n",
+            "trigger1 = var(2) >= 5\n",
+            "v = 2\n",
+            "value = -1\n",
+            "[State 0, Parada]\n",
+            "type = NotHitBy\n",
+            "trigger1 = Time = 0\n",
+            "value = SC\n",
+            "time = 2\n",
+            "[State 0, Caída]\n",
+            "type = HitFallSet\n",
+            "trigger1 = Time = 0\n",
+            "value = 0\n",
+        ))
+        .unwrap();
+        let mut char = CharData::from_def(CharDef::new());
+        char.set_fall(true);
+
+        table.run(&mut char, None);
+        assert!(char.is_flipped());
+        assert_eq!(char.get_var(2), 4);
+        assert!(!char.get_fall());
+        assert!(!char.is_hit_by(&State::S));
+        assert!(char.is_hit_by(&State::A));
+
+        char.update_data();
+        char.update_data();
+        assert!(char.is_hit_by(&State::S));
+        assert!(matches!(
+            read("[Statedef 0]\n[State 0, Var]\ntype = VarSet\nv = 60\nvalue = 1"),
+            Err(CnsError::Malformed(2))
+        ));
+    }
+
+    /// Prueba de parámetros con expresiones y constantes, evaluados al ejecutar el controlador
+    #[test]
+    fn test_controller_expressions() {
+        let mut table = read(concat!(
+            "[Statedef 0]\n",
+            "[State 0, Andar]\n",
+            "type = VelSet\n",
+            "trigger1 = 1\n",
+            "x = const(velocity.walk.fwd.x) * 2\n",
+            "y = const(velocity.jump.neu.y)\n",
+            "[State 0, Var]\n",
+            "type = VarAdd\n",
+            "trigger1 = 1\n",
+            "v = 1\n",
+            "value = Time + const(movement.airjump.num)\n",
+            "[State 0, Salto]\n",
+            "type = ChangeState\n",
+            "trigger1 = Time = 2\n",
+            "value = ifelse(var(1) > 2, 40, 20)\n",
+            "ctrl = Time > 1\n",
+            "[Statedef 40]\n",
+            "[Statedef 20]\n",
+        ))
+        .unwrap();
+        let mut char = CharData::from_def(CharDef::new());
+
+        table.run(&mut char, None);
+        assert_eq!(char.get_vel_x(), 4.8);
+        assert_eq!(char.get_vel_y(), -8.4);
+        assert_eq!(char.get_var(1), 1);
+
+        table.run(&mut char, None);
+        table.run(&mut char, None);
+        assert_eq!(char.get_var(1), 6);
+        assert_eq!(char.get_state_no(), 40);
+        assert!(char.has_control());
+        assert!(matches!(
+            read("[Statedef 0]\n[State 0, Andar]\ntype = VelSet\nx = const(velocity.fly.x)"),
+            Err(CnsError::InvalidExpression(_, 2))
+        ));
+    }
+
+    /// Prueba de aplicación de un golpe
+    #[test]
+    fn test_hit_def_apply() {
+        let mut params = HashMap::new();
+        params.insert("damage".to_string(), "30, 5".to_string());
+        params.insert("animtype".to_string(), "Medium".to_string());
+        params.insert("ground.type".to_string(), "Low".to_string());
+        params.insert("ground.velocity".to_string(), "-3".to_string());
+        params.insert("guardflag".to_string(), "MA".to_string());
+        params.insert("p2stateno".to_string(), "5051".to_string());
+        let hit_def = HitDef::from_params(&params).unwrap();
+        let attacker = CharData::from_def(CharDef::new());
+
+        let mut target = CharData::from_def(CharDef::new());
+        hit_def.apply(&attacker, &mut target, false);
+        assert_eq!(target.get_life(), 970);
+        assert_eq!(target.get_state_no(), 5051);
+        assert_eq!(target.get_vel_x(), -3.0);
+        assert!(!target.get_fall());
+
+        params.remove("p2stateno");
+        let hit_def = HitDef::from_params(&params).unwrap();
+        let mut target = CharData::from_def(CharDef::new());
+        hit_def.apply(&attacker, &mut target, false);
+        assert_eq!(target.get_state_no(), 5011);
+
+        let mut target = CharData::from_def(CharDef::new());
+        target.set_state(State::C);
+        hit_def.apply(&attacker, &mut target, false);
+        assert_eq!(target.get_state_no(), 5021);
+
+        let mut target = CharData::from_def(CharDef::new());
+        target.set_direction(4);
+        assert!(hit_def.is_guarded(&target));
+        hit_def.apply(&attacker, &mut target, true);
+        assert_eq!(target.get_life(), 995);
+        assert_eq!(target.get_state_no(), 1310);
+        target.set_state(State::A);
+        assert!(!HitDef::from_params(&HashMap::new()).unwrap().is_guarded(&target));
+
+        let mut def = CharDef::new();
+        def.data.attack = 150;
+        let attacker = CharData::from_def(def);
+        let mut target = CharData::from_def(CharDef::new());
+        hit_def.apply(&attacker, &mut target, false);
+        assert_eq!(target.get_life(), 955);
+    }
+
+    /// Prueba que un segundo golpe vuelva a empezar el estado de golpe en el que ya está el objetivo
+    #[test]
+    fn test_hit_def_reenter() {
+        let mut table = read(concat!(
+            "[Statedef 0]\n",
+            "[Statedef 5000]\n",
+            "anim = 5000\n",
+            "ctrl = 0\n",
+            "[State 5000, End]\n",
+            "type = ChangeState\n",
+            "trigger1 = Time = 5\n",
+            "value = 0\n",
+        ))
+        .unwrap();
+        let hit_def = HitDef::from_params(&HashMap::new()).unwrap();
+        let attacker = CharData::from_def(CharDef::new());
+        let mut target = CharData::from_def(CharDef::new());
+
+        hit_def.apply(&attacker, &mut target, false);
+        for _ in 0..4 {
+            table.run(&mut target, None);
+        }
+        assert_eq!((target.get_state_no(), target.get_state_time()), (5000, 4));
+        target.set_new_anim(false);
+
+        hit_def.apply(&attacker, &mut target, false);
+        table.run(&mut target, None);
+        assert_eq!((target.get_state_no(), target.get_state_time()), (5000, 1));
+        assert!(target.get_new_anim());
+        for _ in 0..4 {
+            table.run(&mut target, None);
+        }
+        assert_eq!(target.get_state_no(), 5000);
+        table.run(&mut target, None);
+        assert_eq!(target.get_state_no(), 0);
+    }
+
+    /// Prueba de los controladores de sonido y del sonido de un golpe
+    #[test]
+    fn test_sound_controllers() {
+        let mut table = read(concat!(
+            "[Statedef -1]\n",
+            "[State -1, Voz]\n",
+            "type = PlaySnd\n",
+            "trigger1 = 1\n",
+            "value = F5, 2\n",
+            "channel = 0\n",
+            "lowpriority = 1\n",
+            "volumescale = 50\n",
+            "abspan = -20\n",
+            "[State -1, Silencio]\n",
+            "type = StopSnd\n",
+            "trigger1 = 1\n",
+        ))
+        .unwrap();
+        let mut char = CharData::from_def(CharDef::new());
+        table.run(&mut char, None);
+        assert_eq!(
+            char.take_sounds(),
+            vec![
+                SoundCommand::Play(PlaySnd {
+                    common: true,
+                    channel: 0,
+                    low_priority: true,
+                    volume_scale: 50.0,
+                    pan: -20.0,
+                    abs_pan: true,
+                    ..PlaySnd::new(SoundId::from((5, 2)))
+                }),
+                SoundCommand::Stop(-1),
+            ]
+        );
+        assert!(char.take_sounds().is_empty());
+
+        assert!(matches!(
+            read("[Statedef 200]\n[State 200, Snd]\ntype = PlaySnd\ntrigger1 = 1\nvalue = X5"),
+            Err(CnsError::Malformed(2))
+        ));
+
+        let mut params = HashMap::new();
+        params.insert("hitsound".to_string(), "S5, 1".to_string());
+        let hit_def = HitDef::from_params(&params).unwrap();
+        assert_eq!(
+            hit_def.sound(false),
+            Some(SoundCommand::Play(PlaySnd::new(SoundId::from((5, 1)))))
+        );
+        assert_eq!(hit_def.sound(true), None);
+    }
+}
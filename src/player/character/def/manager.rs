@@ -0,0 +1,723 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::error::def_error::DefError;
+
+use super::super::{
+    sff::decoder::MAX_PAL_NO,
+    span::{self, fields, float, key_value, section, split_lines, Report, Span},
+};
+
+/// Archivos que componen un personaje (`[Files]`), relativos a su carpeta.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Files {
+    /// Archivo de comandos
+    pub cmd: String,
+    /// Archivo de constantes
+    pub cns: String,
+    /// Archivo de estados, si no se indica se usa el de constantes
+    pub st: Option<String>,
+    /// Archivo de animaciones
+    pub anim: String,
+    /// Archivo de sprites
+    pub sprite: String,
+    /// Archivo de sonidos, opcional
+    pub sound: Option<String>,
+    /// Paletas ACT de cada color (`pal1` a `pal12`)
+    pub pal: BTreeMap<usize, String>,
+}
+
+/// Datos básicos del personaje (`[Data]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data {
+    /// Vida máxima
+    pub life: i32,
+    /// Poder máximo
+    pub power: i32,
+    /// Multiplicador de ataque en porcentaje
+    pub attack: i32,
+    /// Multiplicador de defensa en porcentaje
+    pub defence: i32,
+    /// Indica si los sonidos del personaje tienen eco tras un KO
+    pub ko_echo: bool,
+}
+
+/// Tamaño del personaje (`[Size]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Size {
+    /// Escala horizontal
+    pub xscale: f64,
+    /// Escala vertical
+    pub yscale: f64,
+    /// Ancho del cuerpo por detrás en el suelo
+    pub ground_back: i32,
+    /// Ancho del cuerpo por delante en el suelo
+    pub ground_front: i32,
+    /// Ancho del cuerpo por detrás en el aire
+    pub air_back: i32,
+    /// Ancho del cuerpo por delante en el aire
+    pub air_front: i32,
+    /// Altura del cuerpo
+    pub height: i32,
+}
+
+/// Velocidades del personaje (`[Velocity]`), en píxeles por tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Velocity {
+    /// Velocidad al andar hacia delante
+    pub walk_fwd: f64,
+    /// Velocidad al andar hacia atrás
+    pub walk_back: f64,
+    /// Velocidad al correr hacia delante
+    pub run_fwd: (f64, f64),
+    /// Velocidad del salto hacia atrás al correr (backdash)
+    pub run_back: (f64, f64),
+    /// Velocidad del salto en vertical
+    pub jump_neu: (f64, f64),
+    /// Velocidad en la X del salto hacia atrás
+    pub jump_back: f64,
+    /// Velocidad en la X del salto hacia delante
+    pub jump_fwd: f64,
+    /// Velocidad del salto hacia atrás corriendo
+    pub runjump_back: (f64, f64),
+    /// Velocidad del salto hacia delante corriendo
+    pub runjump_fwd: (f64, f64),
+}
+
+/// Movimiento del personaje (`[Movement]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Movement {
+    /// Número de saltos en el aire
+    pub airjump_num: i32,
+    /// Aceleración vertical (gravedad)
+    pub yaccel: f64,
+    /// Fricción de pie
+    pub stand_friction: f64,
+    /// Fricción agachado
+    pub crouch_friction: f64,
+    /// Velocidad por debajo de la cual se detiene de pie
+    pub stand_friction_threshold: f64,
+    /// Velocidad por debajo de la cual se detiene agachado
+    pub crouch_friction_threshold: f64,
+}
+
+/// Struct que representa la definición de un personaje leída de su archivo DEF y sus constantes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharDef {
+    /// Nombre interno del personaje
+    pub name: String,
+    /// Nombre que se muestra en pantalla
+    pub display_name: String,
+    /// Autor del personaje
+    pub author: String,
+    /// Archivos del personaje
+    pub files: Files,
+    /// Datos básicos
+    pub data: Data,
+    /// Tamaño
+    pub size: Size,
+    /// Velocidades
+    pub velocity: Velocity,
+    /// Movimiento
+    pub movement: Movement,
+    /// Frases de victoria
+    pub quotes: Vec<String>,
+}
+
+/// Entrada `clave = valor` de un archivo DEF o CNS.
+pub struct Entry {
+    /// Sección en minúsculas en la que se encuentra
+    pub section: String,
+    /// Línea de la cabecera de la sección, distingue secciones repetidas con el mismo nombre
+    pub section_line: usize,
+    /// Clave en minúsculas
+    pub key: String,
+    /// Valor sin comentarios
+    pub value: String,
+    /// Línea en la que se encuentra
+    pub line: usize,
+}
+
+impl Default for CharDef {
+    /// Crea una definición de personaje igual que `CharDef::new`.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo `CharDef` con los archivos y constantes de `kfm`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CharDef {
+    /// Crea una definición de personaje con los archivos y constantes de `kfm`.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo `CharDef`, cuyas constantes se mantienen si no aparecen en el archivo CNS.
+    pub fn new() -> Self {
+        Self {
+            name: "kfm".to_string(),
+            display_name: "Kung Fu Man".to_string(),
+            author: String::new(),
+            files: Files {
+                cmd: "kfm.cmd".to_string(),
+                cns: "kfm.cns".to_string(),
+                st: None,
+                anim: "kfm.air".to_string(),
+                sprite: "kfm.sff".to_string(),
+                sound: None,
+                pal: BTreeMap::new(),
+            },
+            data: Data {
+                life: 1000,
+                power: 3000,
+                attack: 100,
+                defence: 100,
+                ko_echo: false,
+            },
+            size: Size {
+                xscale: 1.0,
+                yscale: 1.0,
+                ground_back: 15,
+                ground_front: 16,
+                air_back: 12,
+                air_front: 12,
+                height: 60,
+            },
+            velocity: Velocity {
+                walk_fwd: 2.4,
+                walk_back: -2.2,
+                run_fwd: (4.6, 0.0),
+                run_back: (-4.5, -3.8),
+                jump_neu: (0.0, -8.4),
+                jump_back: -2.55,
+                jump_fwd: 2.5,
+                runjump_back: (-2.55, -4.1),
+                runjump_fwd: (4.0, -8.1),
+            },
+            movement: Movement {
+                airjump_num: 1,
+                yaccel: 0.44,
+                stand_friction: 0.85,
+                crouch_friction: 0.82,
+                stand_friction_threshold: 2.0,
+                crouch_friction_threshold: 0.05,
+            },
+            quotes: Vec::new(),
+        }
+    }
+
+    /// Obtiene el archivo de estados del personaje.
+    ///
+    /// # Retorna
+    ///
+    /// El archivo `st` si se ha indicado, o el de constantes en caso contrario.
+    pub fn get_states_file(&self) -> &str {
+        self.files.st.as_deref().unwrap_or(&self.files.cns)
+    }
+}
+
+/// Divide las líneas de un archivo en entradas `clave = valor`, ignorando comentarios y líneas sin `=`.
+///
+/// # Argumentos
+///
+/// * `lines` - Líneas del archivo.
+///
+/// # Retorna
+///
+/// Las entradas del archivo con su sección.
+pub fn read_entries(lines: &[&str]) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+    let mut section_line = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.starts_with('[') {
+            section = line
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .trim()
+                .to_lowercase();
+            section_line = i + 1;
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            entries.push(Entry {
+                section: section.clone(),
+                section_line,
+                key: key.trim().to_lowercase(),
+                value: value.trim().to_string(),
+                line: i + 1,
+            });
+        }
+    }
+    entries
+}
+
+/// Quita las comillas de un valor de texto.
+///
+/// # Argumentos
+///
+/// * `value` - Valor de texto.
+///
+/// # Retorna
+///
+/// El valor sin comillas ni espacios alrededor.
+pub fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').to_string()
+}
+
+/// Recorre las entradas `clave = valor` de las secciones que se leen de un archivo DEF o CNS.
+///
+/// Avisa de las secciones, las claves y las líneas que el juego pasa por alto.
+///
+/// # Argumentos
+///
+/// * `content` - El texto del archivo.
+/// * `file` - Nombre del archivo, usado en los errores.
+/// * `sections` - Secciones que se leen, en minúsculas.
+/// * `ignored` - Primera palabra de las secciones que se saltan sin aviso, como `statedef`.
+/// * `report` - El informe donde se añaden los problemas.
+/// * `entry` - Recibe la sección, la clave en minúsculas y el valor de cada entrada, y retorna false si no conoce la clave.
+fn read_sections<'a>(
+    content: &'a str,
+    file: &str,
+    sections: &[&str],
+    ignored: &[&str],
+    report: &mut Report<DefError>,
+    mut entry: impl FnMut(&str, &str, Span<'a>, &mut Report<DefError>) -> bool,
+) {
+    // `None` antes de la primera sección y en las secciones que se saltan
+    let mut current: Option<String> = None;
+    let mut started = false;
+    for line in split_lines(content) {
+        let line = span::content(line);
+        if line.fragment().is_empty() {
+            continue;
+        }
+        if let Some(name) = section(line) {
+            started = true;
+            let header = name.fragment().to_lowercase();
+            current = None;
+            if sections.contains(&header.as_str()) {
+                current = Some(header);
+            } else if !ignored.contains(&header.split_whitespace().next().unwrap_or_default()) {
+                report.warnings.push(DefError::malformed(
+                    file,
+                    &name,
+                    format!("Unknown section: {}", name.fragment()),
+                ));
+            }
+            continue;
+        }
+        let Some(current) = &current else {
+            if !started {
+                report.warnings.push(DefError::malformed(
+                    file,
+                    &line,
+                    "Line outside of a section".to_string(),
+                ));
+            }
+            continue;
+        };
+        let Some((key, value)) = key_value(line) else {
+            report.warnings.push(DefError::malformed(
+                file,
+                &line,
+                format!("Unknown line: {}", line.fragment()),
+            ));
+            continue;
+        };
+        if !entry(current, &key.fragment().to_lowercase(), value, report) {
+            report.warnings.push(DefError::malformed(
+                file,
+                &key,
+                format!("Unknown key: {}", key.fragment()),
+            ));
+        }
+    }
+}
+
+/// Revisa un archivo DEF, con las secciones `[Info]` y `[Files]`.
+///
+/// # Argumentos
+///
+/// * `content` - El texto del archivo DEF.
+/// * `file` - Nombre del archivo, usado en los errores.
+///
+/// # Retorna
+///
+/// La definición del personaje con las constantes por defecto, y los errores y avisos del archivo.
+/// Si falta algún archivo obligatorio se mantiene el de `kfm` y se añade un `DefError::MissingFile`.
+pub fn check_def(content: &str, file: &str) -> (CharDef, Report<DefError>) {
+    let mut report = Report::default();
+    let mut def = CharDef::new();
+    let mut display_name = None;
+    let mut files: [Option<String>; 4] = Default::default();
+    let sections = ["info", "files"];
+    read_sections(
+        content,
+        file,
+        &sections,
+        &[],
+        &mut report,
+        |section, key, value, _| {
+            let value = unquote(value.fragment());
+            match (section, key) {
+                ("info", "name") => def.name = value,
+                ("info", "displayname") => display_name = Some(value),
+                ("info", "author") => def.author = value,
+                ("files", "cmd") => files[0] = Some(value),
+                ("files", "cns") => files[1] = Some(value),
+                ("files", "anim") => files[2] = Some(value),
+                ("files", "sprite") => files[3] = Some(value),
+                ("files", "st") => def.files.st = Some(value),
+                ("files", "sound") => def.files.sound = Some(value),
+                ("files", key) => match key.strip_prefix("pal").map(str::parse::<usize>) {
+                    Some(Ok(slot)) if (1..=MAX_PAL_NO).contains(&slot) => {
+                        def.files.pal.insert(slot, value);
+                    }
+                    _ => return false,
+                },
+                _ => return false,
+            }
+            true
+        },
+    );
+    def.display_name = display_name.unwrap_or_else(|| def.name.clone());
+    let [cmd, cns, anim, sprite] = files;
+    let targets = [
+        ("cmd", cmd, &mut def.files.cmd),
+        ("cns", cns, &mut def.files.cns),
+        ("anim", anim, &mut def.files.anim),
+        ("sprite", sprite, &mut def.files.sprite),
+    ];
+    for (key, value, target) in targets {
+        match value {
+            Some(value) => *target = value,
+            None => report.errors.push(DefError::MissingFile(key.to_string())),
+        }
+    }
+    (def, report)
+}
+
+/// Lee un número de una constante, añadiendo un error si no es válido.
+///
+/// # Argumentos
+///
+/// * `value` - El texto del número.
+/// * `file` - Nombre del archivo, usado en los errores.
+/// * `report` - El informe donde se añaden los problemas.
+///
+/// # Retorna
+///
+/// El número, o `None` si no es válido.
+fn number(value: Span, file: &str, report: &mut Report<DefError>) -> Option<f64> {
+    let number = float(value);
+    if number.is_none() {
+        report.errors.push(DefError::malformed(
+            file,
+            &value,
+            format!("Invalid number: {}", value.fragment()),
+        ));
+    }
+    number
+}
+
+/// Lee un par de números separados por coma, cualquiera de los dos puede omitirse y vale 0.
+///
+/// # Argumentos
+///
+/// * `value` - El texto del par.
+/// * `file` - Nombre del archivo, usado en los errores.
+/// * `report` - El informe donde se añaden los problemas.
+///
+/// # Retorna
+///
+/// El par de números, o `None` si alguno no es válido.
+fn pair(value: Span, file: &str, report: &mut Report<DefError>) -> Option<(f64, f64)> {
+    let mut numbers = fields(value, ',').into_iter().take(2).map(|field| {
+        if field.fragment().is_empty() {
+            Some(0.0)
+        } else {
+            number(field, file, report)
+        }
+    });
+    let x = numbers.next().flatten();
+    let y = numbers.next().unwrap_or(Some(0.0));
+    Some((x?, y?))
+}
+
+/// Revisa las constantes de un archivo CNS (`[Data]`, `[Size]`, `[Velocity]`, `[Movement]` y `[Quotes]`).
+///
+/// Las constantes que no aparezcan o no sean válidas mantienen su valor.
+///
+/// # Argumentos
+///
+/// * `def` - Definición del personaje a completar.
+/// * `content` - El texto del archivo CNS.
+/// * `file` - Nombre del archivo, usado en los errores.
+///
+/// # Retorna
+///
+/// Los errores y avisos de las constantes, con su posición.
+pub fn check_constants(def: &mut CharDef, content: &str, file: &str) -> Report<DefError> {
+    /// Constante a la que se asigna el valor de una entrada.
+    enum Target<'a> {
+        /// Número entero
+        Int(&'a mut i32),
+        /// Número decimal
+        Float(&'a mut f64),
+        /// Número distinto de 0
+        Flag(&'a mut bool),
+        /// Primer número de un par
+        X(&'a mut f64),
+        /// Par de números
+        Pair(&'a mut (f64, f64)),
+    }
+
+    let mut report = Report::default();
+    let mut quotes = BTreeMap::new();
+    let sections = ["data", "size", "velocity", "movement", "quotes"];
+    let ignored = ["statedef", "state"];
+    read_sections(
+        content,
+        file,
+        &sections,
+        &ignored,
+        &mut report,
+        |section, key, value, report| {
+            let (data, size) = (&mut def.data, &mut def.size);
+            let (velocity, movement) = (&mut def.velocity, &mut def.movement);
+            let target = match (section, key) {
+                ("data", "life") => Target::Int(&mut data.life),
+                ("data", "power") => Target::Int(&mut data.power),
+                ("data", "attack") => Target::Int(&mut data.attack),
+                ("data", "defence") => Target::Int(&mut data.defence),
+                ("data", "ko.echo") => Target::Flag(&mut data.ko_echo),
+                ("size", "xscale") => Target::Float(&mut size.xscale),
+                ("size", "yscale") => Target::Float(&mut size.yscale),
+                ("size", "ground.back") => Target::Int(&mut size.ground_back),
+                ("size", "ground.front") => Target::Int(&mut size.ground_front),
+                ("size", "air.back") => Target::Int(&mut size.air_back),
+                ("size", "air.front") => Target::Int(&mut size.air_front),
+                ("size", "height") => Target::Int(&mut size.height),
+                ("velocity", "walk.fwd") => Target::X(&mut velocity.walk_fwd),
+                ("velocity", "walk.back") => Target::X(&mut velocity.walk_back),
+                ("velocity", "run.fwd") => Target::Pair(&mut velocity.run_fwd),
+                ("velocity", "run.back") => Target::Pair(&mut velocity.run_back),
+                ("velocity", "jump.neu") => Target::Pair(&mut velocity.jump_neu),
+                ("velocity", "jump.back") => Target::X(&mut velocity.jump_back),
+                ("velocity", "jump.fwd") => Target::X(&mut velocity.jump_fwd),
+                ("velocity", "runjump.back") => Target::Pair(&mut velocity.runjump_back),
+                ("velocity", "runjump.fwd") => Target::Pair(&mut velocity.runjump_fwd),
+                ("movement", "airjump.num") => Target::Int(&mut movement.airjump_num),
+                ("movement", "yaccel") => Target::Float(&mut movement.yaccel),
+                ("movement", "stand.friction") => Target::Float(&mut movement.stand_friction),
+                ("movement", "crouch.friction") => Target::Float(&mut movement.crouch_friction),
+                ("movement", "stand.friction.threshold") => {
+                    Target::Float(&mut movement.stand_friction_threshold)
+                }
+                ("movement", "crouch.friction.threshold") => {
+                    Target::Float(&mut movement.crouch_friction_threshold)
+                }
+                ("quotes", key) => match key.strip_prefix("victory").map(str::parse::<u32>) {
+                    Some(Ok(number)) => {
+                        quotes.insert(number, unquote(value.fragment()));
+                        return true;
+                    }
+                    _ => return false,
+                },
+                _ => return false,
+            };
+            match target {
+                Target::Int(target) => {
+                    if let Some(number) = number(value, file, report) {
+                        *target = number as i32;
+                    }
+                }
+                Target::Float(target) => {
+                    if let Some(number) = number(value, file, report) {
+                        *target = number;
+                    }
+                }
+                Target::Flag(target) => {
+                    if let Some(number) = number(value, file, report) {
+                        *target = number != 0.0;
+                    }
+                }
+                Target::X(target) => {
+                    if let Some((x, _)) = pair(value, file, report) {
+                        *target = x;
+                    }
+                }
+                Target::Pair(target) => {
+                    if let Some(pair) = pair(value, file, report) {
+                        *target = pair;
+                    }
+                }
+            }
+            true
+        },
+    );
+    if !quotes.is_empty() {
+        def.quotes = quotes.into_values().collect();
+    }
+    report
+}
+
+/// Lee un archivo y lo devuelve como texto.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo.
+///
+/// # Retorna
+///
+/// El contenido del archivo, o un `DefError` si no se encuentra.
+fn read_file(path: &Path) -> Result<String, DefError> {
+    std::fs::read_to_string(path).map_err(|_| DefError::NotFound(path.display().to_string()))
+}
+
+/// Crea la definición de un personaje leyendo su archivo DEF y las constantes del archivo CNS al que hace referencia.
+///
+/// # Argumentos
+///
+/// * `def` - Ruta del archivo DEF, el resto de archivos son relativos a su carpeta.
+///
+/// # Retorna
+///
+/// La definición del personaje, o un `DefError` si algún archivo no se encuentra o está malformado.
+pub fn parse_def(def: &str) -> Result<CharDef, DefError> {
+    let path = Path::new(def);
+    let content = read_file(path)?;
+    let (mut char_def, report) = check_def(&content, def);
+    report.into_result(())?;
+
+    let cns = char_def.files.cns.clone();
+    let content = read_file(&path.with_file_name(&cns))?;
+    check_constants(&mut char_def, &content, &cns).into_result(char_def)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba si el archivo DEF de kfm se lee con sus constantes y si uno que no existe falla
+    #[test]
+    fn test_parse_def() {
+        let def = parse_def("src/chars/kfm/kfm.def").unwrap();
+        assert_eq!(def.name, "kfm");
+        assert_eq!(def.display_name, "Kung Fu Man");
+        assert_eq!(def.files.sprite, "kfm.sff");
+        assert_eq!(def.get_states_file(), "kfm.cns");
+        assert_eq!(def.data.life, 1000);
+        assert_eq!(def.velocity.jump_neu, (0.0, -8.4));
+        assert_eq!(def.movement.crouch_friction, 0.82);
+        assert_eq!(def.quotes.len(), 7);
+        assert!(matches!(
+            parse_def("INVALIDDEF.D.E.F"),
+            Err(DefError::NotFound(_))
+        ));
+    }
+
+    /// Prueba de lectura de las secciones `[Info]` y `[Files]`
+    #[test]
+    fn test_check_def() {
+        let lines = [
+            "[Info]",
+            "name = \"ryu\" ; comentario",
+            "versiondate = 1",
+            "[Files]",
+            "cmd = ryu.cmd",
+            "cns = ryu.cns",
+            "st = ryu_states.cns",
+            "anim = ryu.air",
+            "sprite = ryu.sff",
+            "sound = ryu.snd",
+            "pal1 = ryu.act",
+            "pal12 = \"ryu12.act\"",
+            "pal13 = ryu13.act",
+        ];
+        let (def, report) = check_def(&lines.join("\n"), "ryu.def");
+        assert_eq!(def.name, "ryu");
+        assert_eq!(def.display_name, "ryu");
+        assert_eq!(def.files.cmd, "ryu.cmd");
+        assert_eq!(def.get_states_file(), "ryu_states.cns");
+        assert_eq!(def.files.sound, Some("ryu.snd".to_string()));
+        assert_eq!(
+            def.files.pal.into_iter().collect::<Vec<_>>(),
+            vec![(1, "ryu.act".to_string()), (12, "ryu12.act".to_string())]
+        );
+        assert!(report.errors.is_empty());
+        let warnings: Vec<String> = report.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "Def file ryu.def malformed on line 3, column 1: Unknown key: versiondate",
+                "Def file ryu.def malformed on line 13, column 1: Unknown key: pal13",
+            ]
+        );
+
+        let (_, report) = check_def(&lines[..6].join("\n"), "ryu.def");
+        assert!(matches!(
+            &report.errors[..],
+            [DefError::MissingFile(anim), DefError::MissingFile(sprite)]
+                if anim == "anim" && sprite == "sprite"
+        ));
+    }
+
+    /// Prueba de lectura de las constantes de un archivo CNS
+    #[test]
+    fn test_check_constants() {
+        let lines = [
+            "[Data]",
+            "life = 1200",
+            "defence = 90",
+            "ko.echo = 1",
+            "[Velocity]",
+            "walk.fwd = 3",
+            "run.back = -5, -4",
+            "[Movement]",
+            "yaccel = .5",
+            "[Quotes]",
+            "victory2 = \"Second\"",
+            "victory1 = \"First\"",
+            "[Statedef 200]",
+            "anim = 200",
+        ];
+        let mut def = CharDef::new();
+        let report = check_constants(&mut def, &lines.join("\n"), "test.cns");
+        assert!(report.is_empty(), "{:?}", report);
+        assert_eq!(def.data.life, 1200);
+        assert_eq!(def.data.defence, 90);
+        assert!(def.data.ko_echo);
+        assert_eq!(def.data.power, 3000);
+        assert_eq!(def.velocity.walk_fwd, 3.0);
+        assert_eq!(def.velocity.run_back, (-5.0, -4.0));
+        assert_eq!(def.movement.yaccel, 0.5);
+        assert_eq!(def.quotes, vec!["First", "Second"]);
+
+        let text = "[Data]\nlife = lots\nspeed = 2\n[Velocity]\nrun.fwd = 4, x\npower = 10";
+        let report = check_constants(&mut def, text, "test.cns");
+        let errors: Vec<String> = report.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "Def file test.cns malformed on line 2, column 8: Invalid number: lots",
+                "Def file test.cns malformed on line 5, column 14: Invalid number: x",
+            ]
+        );
+        assert_eq!(report.warnings.len(), 2);
+        assert!(matches!(
+            report.warnings[0],
+            DefError::Malformed {
+                line: 3,
+                column: 1,
+                ..
+            }
+        ));
+        assert_eq!(def.data.life, 1200);
+        assert_eq!(def.velocity.run_fwd, (4.6, 0.0));
+    }
+}
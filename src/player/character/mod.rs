@@ -1,4 +1,8 @@
 pub mod cmd;
 pub mod sff;
+pub mod snd;
 pub mod air;
+pub mod cns;
+pub mod def;
+pub mod span;
 pub mod character;
\ No newline at end of file
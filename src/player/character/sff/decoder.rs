@@ -1,17 +1,17 @@
-const MAX_PAL_NO: usize = 10;
+/// Cantidad máxima de paletas que se pueden elegir para un personaje (`pal1` a `pal12` en el DEF)
+pub const MAX_PAL_NO: usize = 12;
+
+/// Tamaño de un archivo de paleta ACT, 256 colores RGB
+const ACT_SIZE: usize = 768;
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
+    fmt,
     io::{self, Cursor, Read, Seek, SeekFrom},
-    path::PathBuf,
-    rc::Rc,
+    path::{Path, PathBuf},
 };
 
 use byteorder::{LittleEndian, ReadBytesExt};
-use flate2::read::ZlibDecoder;
-use gfx_device_gl::Resources;
-use image::RgbaImage;
-use piston_window::{G2dTextureContext, TextureSettings};
 
 use crate::error::sff_error::SffError;
 
@@ -28,6 +28,29 @@ pub struct SpriteId {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Version(u8, u8, u8, u8);
 
+/// Formato en el que están guardados los píxeles de un sprite dentro del SFF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteFormat {
+    /// Imagen PCX de los SFF versión 1
+    Pcx,
+    /// Píxeles sin comprimir
+    Raw,
+    /// Compresión RLE8
+    Rle8,
+    /// Compresión RLE5
+    Rle5,
+    /// Compresión LZ5
+    Lz5,
+    /// PNG con paleta de hasta 256 colores
+    Png8,
+    /// PNG de color verdadero sin transparencia
+    Png24,
+    /// PNG de color verdadero con transparencia
+    Png32,
+    /// Código de formato que no se conoce
+    Unknown(u8),
+}
+
 /// Estructura que representa la cabecera de un archivo SFF.
 #[derive(Debug, Clone, Copy)]
 struct SffHeader {
@@ -52,8 +75,9 @@ struct SffHeader {
 /// Representa un decodificador para archivos SFF, utilizado para manejar datos comprimidos
 /// como sprites, paletas y grupos.
 ///
-/// La estructura `Sff` contiene la cabecera del archivo SFF, una lista de sprites, una lista de paletas,
-/// el nombre del archivo y el contexto gráfico utilizado para renderizar los sprites.
+/// La estructura `Sff` contiene la cabecera del archivo SFF, una lista de sprites, una lista de paletas
+/// y el nombre del archivo. Los sprites se guardan como píxeles en memoria, sin crear texturas, así se
+/// puede decodificar sin ventana; las texturas las crea `SffTextures` al dibujar.
 pub struct Sff {
     /// Cabecera del SFF
     header: SffHeader,
@@ -65,8 +89,8 @@ pub struct Sff {
     filename: String,
     /// Nombre del personaje
     name: String,
-    /// Contexto de la ventana para dibujar el sprite
-    context: G2dTextureContext,
+    /// Grupo y número de los sprites en el orden del archivo
+    order: Vec<[i16; 2]>,
 }
 
 /// Estructura que representa un color con componentes RGBA.
@@ -91,20 +115,20 @@ pub struct PaletteList {
     palette_map: Vec<i32>,
     /// Tabla con una referencia del identificador en base a la cantidad de paletas existentes
     pal_table: HashMap<[i16; 2], i32>,
+    /// Grupo y número de cada paleta, en el orden del archivo
+    keys: Vec<[i16; 2]>,
 }
 
 /// Representa un sprite en un archivo SFF.
 ///
 /// Un sprite contiene la información necesaria para su representación gráfica,
-/// incluyendo la paleta de colores, textura, datos sin procesar y metadatos
+/// incluyendo la paleta de colores, los píxeles decodificados y metadatos
 /// relacionados con su grupo, número y dimensiones.
 #[derive(Debug, Clone)]
 pub struct Sprite {
     /// Paleta original en uso por el sprite
     pub pal: Vec<Color>,
-    /// Textura del Sprite
-    pub tex: Option<Rc<piston_window::Texture<Resources>>>,
-    /// Definición en cruda de la imagen
+    /// Píxeles de la imagen, índices de la paleta o RGBA si es de color verdadero
     pub raw: Vec<u8>,
     /// Grupo al que pertenece el sprite
     pub group: i16,
@@ -120,8 +144,10 @@ pub struct Sprite {
     pub rle: i32,
     /// Profundidad de colores del Sprite
     pub col_depth: u8,
-    /// Paleta temporal en uso por el Sprite
-    pub pal_temp: Vec<Color>,
+    /// Formato en el que está guardado en el archivo
+    pub format: SpriteFormat,
+    /// Grupo y número del sprite con el que comparte los datos, si está enlazado
+    pub link: Option<[i16; 2]>,
 }
 
 impl From<Version> for (u8, u8, u8, u8) {
@@ -139,6 +165,63 @@ impl From<Version> for (u8, u8, u8, u8) {
     }
 }
 
+impl fmt::Display for Version {
+    /// Formatea la versión con sus componentes separados por puntos.
+    ///
+    /// # Argumentos
+    ///
+    /// * `f` - El formateador.
+    ///
+    /// # Retorna
+    ///
+    /// Un `fmt::Result` indicando si el formateo fue exitoso.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.0, self.1, self.2, self.3)
+    }
+}
+
+impl SpriteFormat {
+    /// Obtiene el formato de un sprite versión 2 a partir de su código.
+    ///
+    /// # Argumentos
+    ///
+    /// * `code` - El código de formato de la cabecera del sprite.
+    ///
+    /// # Retorna
+    ///
+    /// El formato correspondiente, o `SpriteFormat::Unknown` si el código no existe.
+    fn from_code(code: u8) -> Self {
+        match code {
+            0 => SpriteFormat::Raw,
+            2 => SpriteFormat::Rle8,
+            3 => SpriteFormat::Rle5,
+            4 => SpriteFormat::Lz5,
+            10 => SpriteFormat::Png8,
+            11 => SpriteFormat::Png24,
+            12 => SpriteFormat::Png32,
+            code => SpriteFormat::Unknown(code),
+        }
+    }
+
+    /// Obtiene el código de formato de un sprite versión 2.
+    ///
+    /// # Retorna
+    ///
+    /// El código, o `None` si el formato no existe en la versión 2.
+    pub fn code(&self) -> Option<u8> {
+        match self {
+            SpriteFormat::Raw => Some(0),
+            SpriteFormat::Rle8 => Some(2),
+            SpriteFormat::Rle5 => Some(3),
+            SpriteFormat::Lz5 => Some(4),
+            SpriteFormat::Png8 => Some(10),
+            SpriteFormat::Png24 => Some(11),
+            SpriteFormat::Png32 => Some(12),
+            SpriteFormat::Pcx | SpriteFormat::Unknown(_) => None,
+        }
+    }
+}
+
 impl From<SpriteId> for (u16, u16) {
     /// Convierte un `SpriteId` a una tupla de dos elementos (u16, u16).
     ///
@@ -184,6 +267,27 @@ impl Color {
         }
     }
 
+    /// Crea un color a partir de sus componentes.
+    ///
+    /// # Argumentos
+    ///
+    /// * `red` - Color rojo.
+    /// * `green` - Color verde.
+    /// * `blue` - Color azul.
+    /// * `alpha` - Opacidad.
+    ///
+    /// # Retorna
+    ///
+    /// Un `Color` con los componentes indicados.
+    pub fn from_rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
     /// Obtiene una representación vectorial del color.
     ///
     /// # Retorna
@@ -194,6 +298,17 @@ impl Color {
     }
 }
 
+impl Default for Sprite {
+    /// Crea un sprite vacío, igual que `Sprite::new`.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo sprite con valores por defecto.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sprite {
     /// Crea un nuevo sprite vacío.
     ///
@@ -203,7 +318,6 @@ impl Sprite {
     pub fn new() -> Self {
         Self {
             pal: Vec::new(),
-            tex: None,
             raw: Vec::new(),
             group: 0,
             number: 0,
@@ -212,7 +326,8 @@ impl Sprite {
             pal_idx: -1,
             rle: 0,
             col_depth: 0,
-            pal_temp: Vec::new(),
+            format: SpriteFormat::Raw,
+            link: None,
         }
     }
 
@@ -223,7 +338,6 @@ impl Sprite {
     /// * `src` - El sprite del cual se copiarán los datos.
     fn share_copy(&mut self, src: Sprite) {
         self.pal = src.pal;
-        self.tex = src.tex;
         self.size = src.size;
         if self.pal_idx < 0 {
             self.pal_idx = src.pal_idx;
@@ -246,7 +360,7 @@ impl Sprite {
     /// El sprite modificado.
     fn read_header_v1(
         &mut self,
-        bytes: &mut Cursor<&Vec<u8>>,
+        bytes: &mut Cursor<&[u8]>,
         ofs: &mut u32,
         size: &mut u32,
         link: &mut u16,
@@ -259,6 +373,7 @@ impl Sprite {
         self.group = bytes.read_i16::<LittleEndian>()?;
         self.number = bytes.read_i16::<LittleEndian>()?;
         *link = bytes.read_u16::<LittleEndian>()?;
+        self.format = SpriteFormat::Pcx;
         Ok(self)
     }
 
@@ -268,8 +383,7 @@ impl Sprite {
     ///
     /// * `bytes` - El flujo de bytes desde el cual leer.
     /// * `offset` - Desplazamiento actual en el flujo de bytes.
-    /// * `datasize` - Tamaño de los datos del sprite.
-    /// * `next_subheader` - Desplazamiento del siguiente subencabezado.
+    /// * `datasize` - Tamaño de los datos del sprite, hasta el siguiente subencabezado.
     /// * `prev` - Sprite anterior.
     /// * `pl` - Lista de paletas.
     /// * `c00` - Indicador para el manejo de paletas.
@@ -279,31 +393,23 @@ impl Sprite {
     /// El sprite modificado.
     fn read_v1(
         &mut self,
-        mut bytes: &mut Cursor<&Vec<u8>>,
-        mut offset: &mut i64,
+        bytes: &mut Cursor<&[u8]>,
+        offset: &mut i64,
         mut datasize: u32,
-        next_subheader: u32,
         prev: &Option<Sprite>,
         pl: &mut PaletteList,
         c00: bool,
     ) -> Result<&mut Self, SffError> {
-        if next_subheader as i64 > *offset {
-            datasize = next_subheader - *offset as u32;
-        }
+        // El indicador de paleta repetida está en el byte 18 de la subcabecera, que ocupa 32 bytes
+        bytes.seek(SeekFrom::Start((*offset - 14) as u64))?;
         let ps = bytes.read_u8()?;
         let palette_same = ps != 0 && !prev.is_none();
 
-        self.read_pcx_header(&mut bytes, &mut offset)?;
+        self.read_pcx_header(bytes, offset)?;
 
         bytes.seek(SeekFrom::Start((*offset + 128) as u64))?;
 
-        let pal_size: u32;
-
-        if c00 || palette_same {
-            pal_size = 0;
-        } else {
-            pal_size = 768;
-        }
+        let pal_size: u32 = if c00 || palette_same { 0 } else { 768 };
         if datasize < 128 + pal_size {
             datasize = 128 + pal_size;
         }
@@ -316,25 +422,28 @@ impl Sprite {
         if palette_same {
             if let Some(_prev) = prev {
                 self.pal_idx = _prev.pal_idx;
+                self.pal = _prev.pal.clone();
             }
 
             if self.pal_idx < 0 {
                 self.pal_idx = pl.new_pal().0 as i32;
             }
         } else {
-            let result = pl.new_pal();
-            self.pal_idx = result.0 as i32;
+            let (idx, _) = pl.new_pal();
+            self.pal_idx = idx as i32;
             if c00 {
                 bytes.seek(SeekFrom::Start((*offset + datasize as i64 - 768) as u64))?;
             }
-            for _ in 0..result.1.len() {
-                let mut color = Color::new();
-
+            // La paleta del PCX son 256 colores RGB y el color 0 es el transparente
+            let mut pal = vec![Color::new(); 256];
+            for (i, color) in pal.iter_mut().enumerate() {
                 color.red = bytes.read_u8()?;
                 color.green = bytes.read_u8()?;
                 color.blue = bytes.read_u8()?;
-                color.alpha = bytes.read_u8()?;
+                color.alpha = if i == 0 { 0 } else { 255 };
             }
+            pl.set_source(idx, &pal);
+            self.pal = pal;
         }
         px = self.rle_pcx_decode(px);
         self.set_pxl(px);
@@ -347,7 +456,7 @@ impl Sprite {
     ///
     /// * `px` - Vector de bytes que representa los píxeles.
     fn set_pxl(&mut self, px: Vec<u8>) {
-        if px.len() == 0 {
+        if px.is_empty() {
             return;
         }
         if (px.len() as i64) != (self.size[0] as i64) * (self.size[1] as i64) {
@@ -366,10 +475,10 @@ impl Sprite {
     ///
     /// Vector de bytes decodificados.
     fn rle_pcx_decode(&mut self, rle: Vec<u8>) -> Vec<u8> {
-        if rle.len() == 0 || self.rle <= 0 {
+        if rle.is_empty() || self.rle <= 0 {
             return rle;
         }
-        let mut p = vec![0u8; (self.size[0] * self.size[1]) as usize];
+        let mut p = vec![0u8; self.size[0] as usize * self.size[1] as usize];
         let (mut i, mut j, mut k, w) = (0, 0, 0, self.size[0] as usize);
         while j < p.len() {
             let (mut n, mut d) = (1, rle[i]);
@@ -412,7 +521,7 @@ impl Sprite {
     /// Resultado vacío en caso de éxito o un error en caso contrario.
     fn read_pcx_header(
         &mut self,
-        bytes: &mut Cursor<&Vec<u8>>,
+        bytes: &mut Cursor<&[u8]>,
         offset: &mut i64,
     ) -> Result<(), io::Error> {
         bytes.seek(SeekFrom::Start(*offset as u64))?;
@@ -423,10 +532,7 @@ impl Sprite {
         let bpp = bytes.read_u8()?;
 
         if bpp != 8 {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "bpp is not equal to 8",
-            ));
+            return Err(io::Error::other("bpp is not equal to 8"));
         }
 
         let mut rect = [0u16; 4];
@@ -456,8 +562,7 @@ impl Sprite {
     /// * `bytes` - El flujo de bytes desde el cual leer.
     /// * `ofs` - Desplazamiento de los datos del sprite.
     /// * `size` - Tamaño de los datos del sprite.
-    /// * `lofs` - Desplazamiento del enlace.
-    /// * `tofs` - Desplazamiento de la textura.
+    /// * `header` - Cabecera del SFF, con los desplazamientos de los datos literales y traducidos.
     /// * `link` - Enlace del sprite.
     /// * `pl` - Lista de paletas.
     ///
@@ -466,11 +571,10 @@ impl Sprite {
     /// El sprite modificado.
     fn read_header_v2(
         &mut self,
-        bytes: &mut Cursor<&Vec<u8>>,
+        bytes: &mut Cursor<&[u8]>,
         ofs: &mut u32,
         size: &mut u32,
-        lofs: u32,
-        tofs: u32,
+        header: &SffHeader,
         link: &mut u16,
         pl: &PaletteList,
     ) -> Result<&mut Self, SffError> {
@@ -487,6 +591,7 @@ impl Sprite {
         let format = bytes.read_u8()?;
 
         self.rle = -(format as i32);
+        self.format = SpriteFormat::from_code(format);
 
         self.col_depth = bytes.read_u8()?;
         *ofs = bytes.read_u32::<LittleEndian>()?;
@@ -498,9 +603,9 @@ impl Sprite {
         let tmp = bytes.read_u16::<LittleEndian>()?;
 
         if tmp & 1 == 0 {
-            *ofs += lofs;
+            *ofs += header.lofs;
         } else {
-            *ofs += tofs;
+            *ofs += header.tofs;
         }
         Ok(self)
     }
@@ -512,17 +617,15 @@ impl Sprite {
     /// * `bytes` - El flujo de bytes desde el cual leer.
     /// * `offset` - Desplazamiento actual en el flujo de bytes.
     /// * `datasize` - Tamaño de los datos del sprite.
-    /// * `context` - Contexto de textura para la creación de texturas.
     ///
     /// # Retorna
     ///
     /// El sprite modificado.
     fn read_v2(
         &mut self,
-        bytes: &mut Cursor<&Vec<u8>>,
+        bytes: &mut Cursor<&[u8]>,
         offset: i64,
         mut datasize: u32,
-        context: &mut G2dTextureContext,
     ) -> Result<&mut Self, SffError> {
         let is_raw = false;
         let mut px: Vec<u8>;
@@ -536,11 +639,16 @@ impl Sprite {
                 *ele = bytes.read_u8()?;
             }
             match self.col_depth {
-                8 => {}
-                24 | 32 => {
-                    //is_raw = true;
-                    self.set_raw(px);
+                8 => self.set_pxl(px),
+                24 => {
+                    let rgba = px
+                        .chunks_exact(3)
+                        .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                        .collect();
+                    self.col_depth = 32;
+                    self.set_rgba(rgba)?;
                 }
+                32 => self.set_rgba(px)?,
                 _ => {
                     return Err(SffError::UnknownColorDepth(self.col_depth));
                 }
@@ -549,7 +657,7 @@ impl Sprite {
             bytes.seek(SeekFrom::Start((offset + 4) as u64))?;
             let format = -self.rle;
 
-            if 2 <= format && format <= 4 {
+            if (2..=4).contains(&format) {
                 if datasize < 4 {
                     datasize = 4;
                 }
@@ -576,59 +684,18 @@ impl Sprite {
                 }
             }
 
-            match format {
-                10 => {
-                    let iend_position;
-                    let mut idat_position = 0;
-                    let mut buffer = [0; 4];
-
-                    loop {
-                        match bytes.read_exact(&mut buffer) {
-                            Ok(_) => {
-                                if buffer == [73, 68, 65, 84] {
-                                    idat_position = bytes.seek(SeekFrom::Current(-3))?;
-                                } else {
-                                    bytes.seek(SeekFrom::Current(-3))?;
-                                }
-                                if buffer == [73, 69, 78, 68] {
-                                    iend_position = bytes.seek(SeekFrom::Current(-4))?;
-                                    break;
-                                }
-                            }
-                            Err(err) => return Err(SffError::InvalidData(err)),
-                        }
-                    }
-
-                    let mut cloned_bytes = vec![0u8; (iend_position - idat_position) as usize];
-                    bytes.seek(SeekFrom::Start(idat_position + 3 as u64))?;
-                    bytes.read_exact(&mut cloned_bytes)?;
-
-                    let cursor = Cursor::new(cloned_bytes);
-                    let mut decoder = ZlibDecoder::new(cursor);
-
-                    let mut new_vector = vec![0u8; self.size[0] as usize * self.size[1] as usize];
-                    for (i, pixel) in new_vector.iter_mut().enumerate() {
-                        if i % self.size[0] as usize == 0 {
-                            decoder.read_u16::<LittleEndian>()?;
-                        } else {
-                            *pixel = decoder.read_u8()?;
-                        }
-                    }
-
-                    self.set_pxl(new_vector);
+            if (10..=12).contains(&format) {
+                let mut data = vec![0u8; datasize.saturating_sub(4) as usize];
+                bytes.read_exact(&mut data)?;
+                let (px, rgba) = decode_png(&data, format == 10)?;
+                if rgba {
+                    self.col_depth = 32;
+                    self.set_rgba(px)?;
+                } else {
+                    self.col_depth = 8;
+                    self.set_pxl(px);
                 }
-                _ => {}
             }
-            //     11 | 12 => {
-            //         is_raw = true;
-            //     }
-            //     _ => {
-            //         return Err(SffError::InvalidSignature);
-            //     }
-            // }
-        }
-        if !self.raw.is_empty() {
-            self.set_texture(context);
         }
         Ok(self)
     }
@@ -646,7 +713,7 @@ impl Sprite {
         if rle.is_empty() {
             return rle;
         }
-        let mut p = vec![0u8; (self.size[0] * self.size[1]) as usize];
+        let mut p = vec![0u8; self.size[0] as usize * self.size[1] as usize];
         let mut i = 0;
         let mut j = 0;
         while j < p.len() {
@@ -681,40 +748,44 @@ impl Sprite {
         if rle.is_empty() {
             return rle;
         }
-        let mut p = vec![0u8; (self.size[0] * self.size[1]) as usize];
+        let mut p = vec![0u8; self.size[0] as usize * self.size[1] as usize];
         let mut i = 0;
         let mut j = 0;
         while j < p.len() {
-            let mut rl = rle[i] as usize;
+            // Cada paquete empieza con una repetición de un color de 8 bits y sigue con
+            // `dl` bytes que guardan repeticiones cortas de colores de 5 bits
+            let mut rl = rle[i] as i32;
             if i < rle.len() - 1 {
                 i += 1;
             }
-            let dl = (rle[i] & 0x7f) as usize;
+            let mut dl = (rle[i] & 0x7f) as i32;
             let mut c = 0;
             if rle[i] >> 7 != 0 {
                 if i < rle.len() - 1 {
                     i += 1;
                 }
-                c = rle[i] as usize;
+                c = rle[i];
             }
             if i < rle.len() - 1 {
                 i += 1;
             }
-            for _ in 0..dl {
-                for _ in 0..rl {
-                    if j < p.len() {
-                        p[j] = c as u8;
-                        j += 1;
-                    }
-                }
-                if i < rle.len() - 1 {
-                    i += 1;
+            loop {
+                if j < p.len() {
+                    p[j] = c;
+                    j += 1;
                 }
-                if i >= rle.len() {
-                    break;
+                rl -= 1;
+                if rl < 0 {
+                    dl -= 1;
+                    if dl < 0 {
+                        break;
+                    }
+                    c = rle[i] & 0x1f;
+                    rl = (rle[i] >> 5) as i32;
+                    if i < rle.len() - 1 {
+                        i += 1;
+                    }
                 }
-                c = rle[i] as usize & 0x1f;
-                rl = (rle[i] >> 5) as usize;
             }
         }
         p
@@ -733,7 +804,7 @@ impl Sprite {
         if rle.is_empty() {
             return rle;
         }
-        let mut p = vec![0u8; (self.size[0] * self.size[1]) as usize];
+        let mut p = vec![0u8; self.size[0] as usize * self.size[1] as usize];
         let (mut i, mut j) = (0, 0);
         let mut n;
         let (mut ct, mut cts, mut rb, mut rbc) = (rle[i], 0u32, 0u8, 0u32);
@@ -758,7 +829,7 @@ impl Sprite {
                 } else {
                     rb |= ((d & 0xc0) >> rbc) as u8;
                     rbc += 2;
-                    n = (d & 0x3f) as i32;
+                    n = d & 0x3f;
                     if rbc < 8 {
                         d = rle[i] as i32 + 1;
                         if i < rle.len() - 1 {
@@ -772,7 +843,7 @@ impl Sprite {
                 }
                 while n >= 0 {
                     if j < p.len() {
-                        if j > d as usize {
+                        if j >= d as usize {
                             p[j] = p[j - d as usize];
                         }
                         j += 1;
@@ -809,58 +880,143 @@ impl Sprite {
         p
     }
 
-    /// Establece los datos crudos del sprite.
+    /// Establece los píxeles de un sprite de color verdadero.
     ///
     /// # Argumentos
     ///
-    /// * `px` - Vector de datos crudos.
-    fn set_raw(&mut self, px: Vec<u8>) {
+    /// * `px` - Vector de bytes con los píxeles en RGBA.
+    ///
+    /// # Retorna
+    ///
+    /// Un error si la cantidad de píxeles no coincide con el tamaño del sprite.
+    fn set_rgba(&mut self, px: Vec<u8>) -> Result<(), SffError> {
+        if (px.len() as i64) != (self.size[0] as i64) * (self.size[1] as i64) * 4 {
+            return Err(SffError::SizeMismatch([self.group, self.number]));
+        }
         self.raw = px;
+        Ok(())
     }
 
-    /// Establece la paleta del sprite.
+    /// Indica si el sprite es de color verdadero, con los píxeles en RGBA en vez de índices de la paleta.
     ///
-    /// # Argumentos
+    /// # Retorna
     ///
-    /// * `palette` - Paleta de colores.
-    /// * `context` - Contexto de textura.
-    fn set_palette(&mut self, palette: &Vec<Color>, context: &mut G2dTextureContext) {
-        self.pal_temp = palette.clone();
-        self.set_texture(context);
+    /// `true` si el sprite no usa paleta.
+    pub fn is_true_color(&self) -> bool {
+        self.col_depth > 8
     }
 
-    /// Establece la textura del sprite.
+    /// Convierte los píxeles del sprite a RGBA.
     ///
     /// # Argumentos
     ///
-    /// * `context` - Contexto de textura.
-    fn set_texture(&mut self, context: &mut G2dTextureContext) {
-        let mut rgba: Vec<u8> = Vec::new();
-        let palette;
-
-        if !self.pal_temp.is_empty() {
-            palette = &self.pal_temp;
-        } else {
-            palette = &self.pal;
+    /// * `palette` - Paleta con la que pintar los índices, o `None` para usar la paleta original del sprite.
+    ///
+    /// # Retorna
+    ///
+    /// Los píxeles en RGBA, fila por fila. Los sprites de color verdadero ignoran la paleta.
+    pub fn to_rgba(&self, palette: Option<&Vec<Color>>) -> Vec<u8> {
+        if self.is_true_color() {
+            return self.raw.clone();
         }
-
+        let palette = palette.unwrap_or(&self.pal);
+        let mut rgba = Vec::with_capacity(self.raw.len() * 4);
         for &index in &self.raw {
-            if let Some(color) = palette.get(index as usize) {
-                rgba.extend_from_slice(&color.get_vec());
+            match palette.get(index as usize) {
+                Some(color) => rgba.extend_from_slice(&color.get_vec()),
+                None => rgba.extend_from_slice(&[0; 4]),
             }
         }
+        rgba
+    }
+}
 
-        let img = &RgbaImage::from_raw(self.size[0] as u32, self.size[1] as u32, rgba).unwrap();
-
-        let texture = piston_window::Texture::from_image(
-            context,
-            img,
-            &TextureSettings::new().filter(piston_window::Filter::Nearest),
-        )
-        .unwrap();
+/// Decodifica una paleta en formato ACT.
+///
+/// Los archivos ACT tienen 256 colores RGB en orden inverso: el primero es el índice 255 de la paleta.
+///
+/// # Argumentos
+///
+/// * `data` - Los bytes del archivo, algunos programas agregan 4 bytes al final que se ignoran.
+///
+/// # Retorna
+///
+/// La paleta con el índice 0 transparente, o `None` si el archivo es demasiado corto.
+pub fn decode_act(data: &[u8]) -> Option<Vec<Color>> {
+    let colors = data.get(..ACT_SIZE)?;
+    Some(
+        colors
+            .chunks_exact(3)
+            .rev()
+            .enumerate()
+            .map(|(i, rgb)| Color::from_rgba(rgb[0], rgb[1], rgb[2], if i == 0 { 0 } else { 255 }))
+            .collect(),
+    )
+}
 
-        self.tex = Some(Rc::new(texture));
+/// Decodifica los datos PNG de un sprite de los formatos 10, 11 y 12.
+///
+/// # Argumentos
+///
+/// * `data` - Datos del PNG, sin los 4 bytes con el tamaño descomprimido.
+/// * `indexed` - Indica si el sprite usa la paleta del SFF (formato 10).
+///
+/// # Retorna
+///
+/// Los píxeles y si están en RGBA, o índices de la paleta si el sprite es de formato 10 y el PNG tiene paleta.
+/// Un `SffError` si el PNG no es válido.
+fn decode_png(data: &[u8], indexed: bool) -> Result<(Vec<u8>, bool), SffError> {
+    let invalid = |err: png::DecodingError| SffError::InvalidPng(err.to_string());
+    let mut decoder = png::Decoder::new(data);
+    let header = decoder.read_header_info().map_err(invalid)?;
+    let keep_indices = indexed && header.color_type == png::ColorType::Indexed;
+    if keep_indices {
+        decoder.set_transformations(png::Transformations::IDENTITY);
+    } else {
+        decoder.set_transformations(
+            png::Transformations::EXPAND
+                | png::Transformations::ALPHA
+                | png::Transformations::STRIP_16,
+        );
+    }
+    let mut reader = decoder.read_info().map_err(invalid)?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf).map_err(invalid)?;
+    buf.truncate(frame.buffer_size());
+    let width = frame.width as usize;
+
+    if keep_indices {
+        // Los PNG con paleta pueden guardar varios índices por byte
+        let bits = frame.bit_depth as usize;
+        if bits == 8 {
+            return Ok((buf, false));
+        }
+        let mask = (1u8 << bits) - 1;
+        let mut px = Vec::with_capacity(width * frame.height as usize);
+        for line in buf.chunks(frame.line_size) {
+            for x in 0..width {
+                let bit = x * bits;
+                let shift = 8 - bits - bit % 8;
+                px.push((line[bit / 8] >> shift) & mask);
+            }
+        }
+        return Ok((px, false));
     }
+
+    let px = match frame.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+            .collect(),
+        color_type => {
+            return Err(SffError::InvalidPng(format!(
+                "tipo de color no soportado: {:?}",
+                color_type
+            )))
+        }
+    };
+    Ok((px, true))
 }
 
 impl PaletteList {
@@ -874,6 +1030,7 @@ impl PaletteList {
             palettes: Vec::new(),
             palette_map: Vec::new(),
             pal_table: HashMap::new(),
+            keys: Vec::new(),
         }
     }
 
@@ -883,9 +1040,9 @@ impl PaletteList {
     ///
     /// * `i` - El índice en el mapa de paletas.
     /// * `p` - Una referencia a un `Vec<Color>` que representa la paleta.
-    fn set_source(&mut self, i: usize, p: &Vec<Color>) {
+    fn set_source(&mut self, i: usize, p: &[Color]) {
         if i < self.palette_map.len() {
-            self.palette_map[i as usize] = i as i32;
+            self.palette_map[i] = i as i32;
         } else {
             while i > self.palette_map.len() {
                 self.palette_map.push(self.palette_map.len() as i32);
@@ -894,12 +1051,12 @@ impl PaletteList {
         }
 
         if i < self.palettes.len() {
-            self.palettes[i as usize] = p.clone();
+            self.palettes[i] = p.to_vec();
         } else {
             while i > self.palettes.len() {
                 self.palettes.push(vec![]);
             }
-            self.palettes.push(p.clone());
+            self.palettes.push(p.to_vec());
         }
     }
 
@@ -925,26 +1082,22 @@ impl PaletteList {
     ///
     /// Una referencia a un `Vec<Color>` que representa la paleta.
     fn get(&self, i: usize) -> &Vec<Color> {
-        return &self.palettes[self.palette_map[i] as usize];
+        &self.palettes[self.palette_map[i] as usize]
     }
 }
 
 impl Sff {
     /// Crea una nueva instancia de `Sff`.
     ///
-    /// Inicializa una nueva instancia de `Sff` con un contexto gráfico proporcionado. La lista de paletas se inicializa
+    /// Inicializa una nueva instancia de `Sff` vacía. La lista de paletas se inicializa
     /// con valores predeterminados.
     ///
-    /// # Argumentos
-    ///
-    /// * `context` - El contexto gráfico utilizado para renderizar los sprites.
-    ///
     /// # Retorna
     ///
-    /// Una nueva instancia de `Sff` con valor por defectos y contexto enviado.
-    pub fn new(context: G2dTextureContext) -> Self {
+    /// Una nueva instancia de `Sff` con valor por defectos.
+    pub fn new() -> Self {
         let mut pal_list = PaletteList::new();
-        for i in 1..MAX_PAL_NO as i16 {
+        for i in 1..=MAX_PAL_NO as i16 {
             let (index_map, _) = pal_list.new_pal();
             pal_list.pal_table.insert([1, i], index_map as i32);
         }
@@ -953,14 +1106,52 @@ impl Sff {
             sprites: HashMap::new(),
             pal_list,
             filename: String::new(),
-            context,
             name: String::new(),
+            order: Vec::new(),
         }
     }
 
+    /// Decodifica un archivo SFF que ya está en memoria.
+    ///
+    /// No necesita una ventana ni un contexto gráfico, los sprites quedan como píxeles con sus paletas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `data` - Los bytes del archivo SFF.
+    /// * `char` - Un indicador booleano que especifica si el archivo es de un personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Una instancia de `Sff` si se decodifica correctamente, o un `SffError` en caso de error.
+    pub fn decode(data: &[u8], char: bool) -> Result<Sff, SffError> {
+        Sff::decode_sprites(data, char, None)
+    }
+
+    /// Decodifica la cabecera, las paletas y los sprites de un archivo SFF en memoria.
+    ///
+    /// # Argumentos
+    ///
+    /// * `data` - Los bytes del archivo SFF.
+    /// * `char` - Un indicador booleano que especifica si el archivo es de un personaje.
+    /// * `limit` - Cantidad máxima de sprites a leer, o `None` para leerlos todos.
+    ///
+    /// # Retorna
+    ///
+    /// Una instancia de `Sff` si se decodifica correctamente, o un `SffError` en caso de error.
+    fn decode_sprites(data: &[u8], char: bool, limit: Option<usize>) -> Result<Sff, SffError> {
+        let mut sff = Sff::new();
+        sff.header.read(data)?;
+        if sff.header.ver0 != 1 {
+            sff.configure_pals_v2(data)?;
+        }
+        sff.configure_sprite(data, char, limit)?;
+        Ok(sff)
+    }
+
     /// Precarga un archivo SFF para un personaje específico.
     ///
-    /// Esta función carga y analiza el archivo SFF, configurando las paletas y los sprites. El archivo SFF debe estar ubicado
+    /// Esta función carga y analiza el archivo SFF, configurando las paletas y solo los primeros sprites,
+    /// que son los que se muestran en la selección de personajes. El archivo SFF debe estar ubicado
     /// en el directorio 'src/chars/<char_name>'.
     ///
     /// # Argumentos
@@ -968,48 +1159,26 @@ impl Sff {
     /// * `char_name` - El nombre del personaje asociado con el archivo SFF.
     /// * `filename` - El nombre del archivo SFF.
     /// * `char` - Un indicador booleano que especifica si el archivo es de un personaje.
-    /// * `context` - El contexto gráfico utilizado para renderizar los sprites.
     ///
     /// # Retorna
     ///
     /// Una instancia de `Sff` si se carga correctamente, o un `SffError` en caso de error.
-    pub fn preload_sff(
-        char_name: &str,
-        filename: String,
-        char: bool,
-        context: G2dTextureContext,
-    ) -> Result<Sff, SffError> {
-        let mut sff = Sff::new(context);
-        sff.filename = filename.clone();
-        sff.name = char_name.to_string();
-
-        let assets = std::env::current_dir()
+    pub fn preload_sff(char_name: &str, filename: String, char: bool) -> Result<Sff, SffError> {
+        let sff_path = std::env::current_dir()
             .unwrap()
             .join("src")
             .join("chars")
-            .join(char_name);
-        let sff_path = assets.join(filename);
+            .join(char_name)
+            .join(&filename);
 
         if !sff_path.exists() {
             return Err(SffError::NotFound(sff_path));
         }
 
-        match sff.header.read(sff_path.clone()) {
-            Ok(_) => println!("Header was read"),
-            Err(err) => return Err(err),
-        };
-
-        if sff.header.ver0 != 1 {
-            match sff.configure_pals_v2(sff_path.clone()) {
-                Ok(_) => println!("Configured and added all the palettes"),
-                Err(err) => return Err(err),
-            };
-        }
-
-        match sff.configure_sprite(sff_path.clone(), char, true) {
-            Ok(_) => println!("Readed and created all the sprites"),
-            Err(err) => return Err(err),
-        };
+        let data = std::fs::read(sff_path)?;
+        let mut sff = Sff::decode_sprites(&data, char, Some(2))?;
+        sff.filename = filename;
+        sff.name = char_name.to_string();
         Ok(sff)
     }
 
@@ -1023,48 +1192,41 @@ impl Sff {
     /// * `char_name` - El nombre del personaje asociado con el archivo SFF.
     /// * `filename` - El nombre del archivo SFF.
     /// * `char` - Un indicador booleano que especifica si el archivo es de un personaje.
-    /// * `context` - El contexto gráfico utilizado para renderizar los sprites.
     ///
     /// # Retorna
     ///
     /// Una instancia de `Sff` si se carga correctamente, o un `SffError` en caso de error.
-    pub fn load_sff(
-        char_name: &str,
-        filename: String,
-        char: bool,
-        context: G2dTextureContext,
-    ) -> Result<Sff, SffError> {
-        let mut sff = Sff::new(context);
-        sff.filename = filename.clone();
-        sff.name = char_name.to_string();
-
+    pub fn load_sff(char_name: &str, filename: String, char: bool) -> Result<Sff, SffError> {
         let assets = std::env::current_dir()
             .unwrap()
             .join("src")
             .join("chars")
             .join(char_name);
-        let sff_path = assets.join(filename);
+        let mut sff = Sff::load_file(assets.join(filename), char)?;
+        sff.name = char_name.to_string();
+        Ok(sff)
+    }
 
+    /// Carga un archivo SFF desde cualquier ruta, como los sprites de un escenario.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sff_path` - La ruta al archivo SFF.
+    /// * `char` - Un indicador booleano que especifica si el archivo es de un personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Una instancia de `Sff` si se carga correctamente, o un `SffError` en caso de error.
+    pub fn load_file(sff_path: PathBuf, char: bool) -> Result<Sff, SffError> {
         if !sff_path.exists() {
             return Err(SffError::NotFound(sff_path));
         }
 
-        match sff.header.read(sff_path.clone()) {
-            Ok(_) => println!("Header was read"),
-            Err(err) => return Err(err),
-        };
-
-        if sff.header.ver0 != 1 {
-            match sff.configure_pals_v2(sff_path.clone()) {
-                Ok(_) => println!("Configured and added all the palettes"),
-                Err(err) => return Err(err),
-            };
+        let data = std::fs::read(&sff_path)?;
+        let mut sff = Sff::decode(&data, char)?;
+        if let Some(filename) = sff_path.file_name() {
+            sff.filename = filename.to_string_lossy().to_string();
         }
-
-        match sff.configure_sprite(sff_path.clone(), char, false) {
-            Ok(_) => println!("Readed and created all the sprites"),
-            Err(err) => return Err(err),
-        };
         Ok(sff)
     }
 
@@ -1074,34 +1236,27 @@ impl Sff {
     ///
     /// # Argumentos
     ///
-    /// * `sff_path` - La ruta al archivo SFF.
+    /// * `data` - Los bytes del archivo SFF.
     /// * `char` - Un indicador booleano que especifica si el archivo es de un personaje.
-    /// * `preload` - Un indicador booleano que especifica si los sprites deben precargarse.
+    /// * `limit` - Cantidad máxima de sprites a leer, o `None` para leerlos todos.
     ///
     /// # Retorna
     ///
     /// Un `Result` vacío indicando éxito o un `SffError` en caso de error.
     fn configure_sprite(
         &mut self,
-        sff_path: PathBuf,
+        data: &[u8],
         char: bool,
-        preload: bool,
+        limit: Option<usize>,
     ) -> Result<(), SffError> {
         let mut sprite_list: Vec<Sprite> =
             vec![Sprite::new(); self.header.number_of_sprites as usize];
         let mut prev: Option<Sprite> = None;
         let mut shofs = self.header.first_sprite_header_offset as u64;
-        let number_sprites;
-        if preload {
-            number_sprites = 2;
-        } else {
-            number_sprites = sprite_list.len()
-        }
-
-        let data = std::fs::read(sff_path)?;
+        let number_sprites = limit.map_or(sprite_list.len(), |limit| limit.min(sprite_list.len()));
 
         for i in 0..number_sprites {
-            let mut bytes = Cursor::new(&data);
+            let mut bytes = Cursor::new(data);
             bytes.seek(SeekFrom::Start(shofs))?;
             let mut xofs: u32 = 0;
             let mut size: u32 = 0;
@@ -1120,8 +1275,7 @@ impl Sff {
                         &mut bytes,
                         &mut xofs,
                         &mut size,
-                        self.header.lofs,
-                        self.header.tofs,
+                        &self.header,
                         &mut index_of_previous,
                         &self.pal_list,
                     )?;
@@ -1133,29 +1287,33 @@ impl Sff {
             if size == 0 {
                 if index_of_previous < i as u16 {
                     let src = sprite_list[index_of_previous as usize].clone();
+                    sprite_list[i].link = Some([src.group, src.number]);
                     sprite_list[i].share_copy(src);
                 } else {
                     sprite_list[i].pal_idx = 0;
                 }
             } else {
-                let mut bytes = Cursor::new(&data);
+                let mut bytes = Cursor::new(data);
                 match self.header.ver0 {
                     1 => {
                         let c00 = char
                             && (prev.is_none()
                                 || sprite_list[i].group == 0 && sprite_list[i].number == 0);
+                        let mut offset = (shofs + 32) as i64;
+                        if xofs as i64 > offset {
+                            size = xofs - offset as u32;
+                        }
                         sprite_list[i].read_v1(
                             &mut bytes,
-                            &mut ((shofs + 32) as i64),
+                            &mut offset,
                             size,
-                            xofs,
                             &prev,
                             &mut self.pal_list,
                             c00,
                         )?;
                     }
                     2 => {
-                        sprite_list[i].read_v2(&mut bytes, xofs as i64, size, &mut self.context)?;
+                        sprite_list[i].read_v2(&mut bytes, xofs as i64, size)?;
                     }
                     _ => {
                         return Err(SffError::UnsupportedHeaderVersion(self.header.ver0));
@@ -1172,6 +1330,8 @@ impl Sff {
                 .get_mut(&[sprite_list[i].group, sprite_list[i].number])
                 .is_none()
             {
+                self.order
+                    .push([sprite_list[i].group, sprite_list[i].number]);
                 self.sprites.insert(
                     [sprite_list[i].group, sprite_list[i].number],
                     sprite_list[i].clone(),
@@ -1196,18 +1356,129 @@ impl Sff {
         self.name.clone()
     }
 
-    /// Establece una nueva paleta para los sprites.
+    /// Obtiene la versión del archivo.
+    ///
+    /// # Retorna
+    ///
+    /// La versión leída de la cabecera.
+    pub fn get_version(&self) -> Version {
+        Version(
+            self.header.ver0,
+            self.header.ver1,
+            self.header.ver2,
+            self.header.ver3,
+        )
+    }
+
+    /// Obtiene el grupo y número de los sprites en el orden en el que aparecen en el archivo.
+    ///
+    /// # Retorna
+    ///
+    /// Las claves de `sprites` ordenadas, sin los sprites repetidos.
+    pub fn get_order(&self) -> &Vec<[i16; 2]> {
+        &self.order
+    }
+
+    /// Obtiene la cantidad de paletas con colores del archivo.
+    ///
+    /// # Retorna
+    ///
+    /// La cantidad de paletas que no están vacías.
+    pub fn get_palette_count(&self) -> usize {
+        self.pal_list
+            .palettes
+            .iter()
+            .filter(|palette| !palette.is_empty())
+            .count()
+    }
+
+    /// Obtiene el grupo y número de una paleta.
+    ///
+    /// # Argumentos
+    ///
+    /// * `palette_index` - El índice de la paleta.
+    ///
+    /// # Retorna
+    ///
+    /// El grupo y número, o `None` si la paleta no está en la tabla de paletas de la versión 2.
+    pub fn get_palette_key(&self, palette_index: usize) -> Option<[i16; 2]> {
+        self.pal_list.keys.get(palette_index).copied()
+    }
+
+    /// Obtiene una de las paletas del archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `palette_index` - El índice de la paleta.
+    ///
+    /// # Retorna
+    ///
+    /// La paleta, o `None` si no existe.
+    pub fn get_palette(&self, palette_index: usize) -> Option<&Vec<Color>> {
+        let index = *self.pal_list.palette_map.get(palette_index)?;
+        self.pal_list.palettes.get(index as usize)
+    }
+
+    /// Obtiene la paleta de uno de los colores que se pueden elegir para el personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `slot` - El número del color, de 1 a `MAX_PAL_NO`, que es la paleta 1,`slot` del archivo.
+    ///
+    /// # Retorna
+    ///
+    /// La paleta, o `None` si ni el SFF ni un archivo ACT la definen.
+    pub fn get_slot_palette(&self, slot: usize) -> Option<&Vec<Color>> {
+        let index = *self.pal_list.pal_table.get(&[1, slot as i16])?;
+        self.get_palette(usize::try_from(index).ok()?)
+            .filter(|palette| !palette.is_empty())
+    }
+
+    /// Obtiene los colores que se pueden elegir para el personaje.
+    ///
+    /// # Retorna
+    ///
+    /// Los números de los colores con paleta, en orden.
+    pub fn get_palette_slots(&self) -> Vec<usize> {
+        (1..=MAX_PAL_NO)
+            .filter(|&slot| self.get_slot_palette(slot).is_some())
+            .collect()
+    }
+
+    /// Carga los archivos ACT de los colores del personaje (`pal1` a `pal12` en el DEF).
     ///
-    /// Esta función establece una nueva paleta para todos los sprites en la lista de sprites.
+    /// Como en MUGEN, los colores que ya tienen paleta en un SFF versión 2 mantienen la suya; los de
+    /// la versión 1 solo tienen las paletas de los archivos ACT.
     ///
     /// # Argumentos
     ///
-    /// * `palette_index` - El índice de la paleta a establecer.
-    pub fn set_palette(&mut self, palette_index: usize) {
-        let palette = self.pal_list.get(palette_index);
-        for (_, sprite) in self.sprites.iter_mut() {
-            sprite.set_palette(palette, &mut self.context);
+    /// * `folder` - Carpeta del personaje, los archivos son relativos a ella.
+    /// * `files` - Archivo ACT de cada color.
+    ///
+    /// # Retorna
+    ///
+    /// Un `SffError` si algún archivo no se encuentra o no es un ACT.
+    pub fn load_palettes(
+        &mut self,
+        folder: &Path,
+        files: &BTreeMap<usize, String>,
+    ) -> Result<(), SffError> {
+        for (&slot, file) in files {
+            if slot == 0 || slot > MAX_PAL_NO || self.get_slot_palette(slot).is_some() {
+                continue;
+            }
+            let path = folder.join(file);
+            if !path.exists() {
+                return Err(SffError::NotFound(path));
+            }
+            let palette = decode_act(&std::fs::read(&path)?).ok_or(SffError::InvalidAct(path))?;
+            let (index, _) = self.pal_list.new_pal();
+            self.pal_list.set_source(index, &palette);
+            self.pal_list
+                .pal_table
+                .insert([1, slot as i16], index as i32);
         }
+        Ok(())
     }
 
     /// Configura las paletas para la versión 2 del archivo SFF.
@@ -1216,15 +1487,14 @@ impl Sff {
     ///
     /// # Argumentos
     ///
-    /// * `sff_path` - La ruta al archivo Sff.
+    /// * `data` - Los bytes del archivo SFF.
     ///
     /// # Retorna
     ///
     /// Un `Result` vacío indicando éxito o un `SffError` en caso de error.
-    fn configure_pals_v2(&mut self, sff_path: PathBuf) -> Result<(), SffError> {
+    fn configure_pals_v2(&mut self, data: &[u8]) -> Result<(), SffError> {
         let mut unique_pals: HashMap<[i16; 2], u16> = HashMap::new();
-        let data = std::fs::read(sff_path)?;
-        let mut bytes = Cursor::new(&data);
+        let mut bytes = Cursor::new(data);
         bytes.seek(SeekFrom::Start(
             self.header.first_palette_header_offset as u64,
         ))?;
@@ -1272,12 +1542,12 @@ impl Sff {
             }
 
             unique_pals.insert([gn_[0], gn_[1]], idx as u16);
+            self.pal_list.keys.push([gn_[0], gn_[1]]);
             self.pal_list.set_source(i as usize, &pal);
             self.pal_list.pal_table.insert([gn_[0], gn_[1]], idx);
 
             if i <= MAX_PAL_NO as u32
-                && self.pal_list.pal_table[&[1, i as i16 + 1]]
-                    == self.pal_list.pal_table[&[gn_[0] as i16, gn_[1] as i16]]
+                && self.pal_list.pal_table.get(&[1, i as i16 + 1]) == Some(&idx)
                 && gn_[0] != 1
                 && gn_[1] != i as i16 + 1
             {
@@ -1295,7 +1565,18 @@ impl Sff {
     }
 }
 
-impl<'a> SffHeader {
+impl Default for Sff {
+    /// Crea un `Sff` vacío, igual que `Sff::new`.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `Sff` con valores por defecto.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SffHeader {
     /// Crea un nuevo `SffHeader` con valores por defecto.
     ///
     /// # Retorna
@@ -1320,7 +1601,7 @@ impl<'a> SffHeader {
     ///
     /// # Argumentos
     ///
-    /// * `sff_path` - Una ruta al archivo SFF.
+    /// * `data` - Los bytes del archivo SFF.
     ///
     /// # Retorna
     ///
@@ -1330,14 +1611,12 @@ impl<'a> SffHeader {
     ///
     /// Retorna `SffError::InvalidSignature` si la firma del archivo es inválida.
     /// Retorna `SffError::UnsupportedVersion` si la versión del archivo no es compatible.
-    fn read(&mut self, sff_path: PathBuf) -> Result<(), SffError> {
-        let data = std::fs::read(sff_path)?;
-
-        if &data[0..12] != b"ElecbyteSpr\0" {
+    fn read(&mut self, data: &[u8]) -> Result<(), SffError> {
+        if data.get(0..12) != Some(b"ElecbyteSpr\0".as_slice()) {
             return Err(SffError::InvalidSignature);
         }
 
-        let mut bytes = Cursor::new(&data);
+        let mut bytes = Cursor::new(data);
         bytes.set_position(12);
 
         self.ver3 = bytes.read_u8()?;
@@ -1379,3 +1658,212 @@ impl<'a> SffHeader {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Codifica una imagen PNG para las pruebas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `size` - Ancho y alto de la imagen.
+    /// * `color` - Tipo de color.
+    /// * `depth` - Bits por muestra.
+    /// * `data` - Filas de la imagen ya empaquetadas.
+    /// * `idat_size` - Tamaño máximo de cada bloque IDAT, para repartir los datos en varios.
+    ///
+    /// # Retorna
+    ///
+    /// Los bytes del PNG.
+    fn encode_png(
+        size: (u32, u32),
+        color: png::ColorType,
+        depth: png::BitDepth,
+        data: &[u8],
+        idat_size: usize,
+    ) -> Vec<u8> {
+        let mut out = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut out, size.0, size.1);
+            encoder.set_color(color);
+            encoder.set_depth(depth);
+            encoder.set_filter(png::FilterType::Paeth);
+            if color == png::ColorType::Indexed {
+                encoder.set_palette(vec![0u8; 3 * 16]);
+            }
+            let mut writer = encoder.write_header().unwrap();
+            let mut stream = writer.stream_writer_with_size(idat_size).unwrap();
+            std::io::Write::write_all(&mut stream, data).unwrap();
+            stream.finish().unwrap();
+        }
+        out
+    }
+
+    /// Prueba que un PNG con paleta de 8 bits en varios bloques IDAT mantiene los índices
+    #[test]
+    fn test_decode_png_indexed() {
+        let data: Vec<u8> = (0..64).map(|i| (i * 7 % 16) as u8).collect();
+        let png = encode_png((8, 8), png::ColorType::Indexed, png::BitDepth::Eight, &data, 8);
+        let (px, rgba) = decode_png(&png, true).unwrap();
+        assert!(!rgba);
+        assert_eq!(px, data);
+    }
+
+    /// Prueba que un PNG con paleta de 4 bits se desempaqueta a un índice por píxel
+    #[test]
+    fn test_decode_png_packed() {
+        let data = [0x12, 0x30, 0xab, 0xc0];
+        let png = encode_png((3, 2), png::ColorType::Indexed, png::BitDepth::Four, &data, 64);
+        let (px, rgba) = decode_png(&png, true).unwrap();
+        assert!(!rgba);
+        assert_eq!(px, vec![1, 2, 3, 10, 11, 12]);
+    }
+
+    /// Prueba que los PNG de color verdadero se convierten a RGBA
+    #[test]
+    fn test_decode_png_true_color() {
+        let data = [255, 0, 0, 0, 255, 0];
+        let png = encode_png((2, 1), png::ColorType::Rgb, png::BitDepth::Eight, &data, 64);
+        let (px, rgba) = decode_png(&png, false).unwrap();
+        assert!(rgba);
+        assert_eq!(px, vec![255, 0, 0, 255, 0, 255, 0, 255]);
+
+        let data = [10, 20, 30, 40];
+        let png = encode_png((1, 1), png::ColorType::Rgba, png::BitDepth::Eight, &data, 64);
+        assert_eq!(decode_png(&png, true).unwrap(), (data.to_vec(), true));
+
+        let data = [0x12, 0x34];
+        let png = encode_png((1, 1), png::ColorType::Grayscale, png::BitDepth::Sixteen, &data, 64);
+        assert_eq!(decode_png(&png, false).unwrap(), (vec![0x12, 0x12, 0x12, 255], true));
+
+        assert!(matches!(decode_png(&[1, 2, 3], false), Err(SffError::InvalidPng(_))));
+    }
+
+    /// Prueba que el SFF de un personaje se decodifica sin contexto gráfico
+    #[test]
+    fn test_decode_char_sff() {
+        let sff = Sff::load_file(PathBuf::from("src/chars/kfm/kfm.sff"), true).unwrap();
+        assert!(!sff.sprites.is_empty());
+        assert_eq!(sff.get_palette(0).map(|pal| pal.len()), Some(256));
+
+        let sprite = &sff.sprites[&[0, 0]];
+        let pixels = sprite.size[0] as usize * sprite.size[1] as usize;
+        assert!(pixels > 0);
+        assert!(!sprite.is_true_color());
+        assert_eq!(sprite.raw.len(), pixels);
+        assert_eq!(sprite.to_rgba(None).len(), pixels * 4);
+    }
+
+    /// Prueba que se decodifica un SFF desde bytes y que los datos que no son un SFF dan error
+    #[test]
+    fn test_decode_bytes() {
+        let data = std::fs::read("src/stages/training/training.sff").unwrap();
+        let sff = Sff::decode(&data, false).unwrap();
+        assert!(sff.sprites.values().any(|sprite| !sprite.raw.is_empty()));
+
+        assert!(matches!(
+            Sff::decode(b"no es un sff", false),
+            Err(SffError::InvalidSignature)
+        ));
+        assert!(matches!(
+            Sff::decode(&data[..40], false),
+            Err(SffError::InvalidData(_))
+        ));
+    }
+
+    /// Prueba que los índices se pintan con la paleta del sprite o con otra
+    #[test]
+    fn test_sprite_to_rgba() {
+        let color = |red, alpha| Color {
+            red,
+            green: 0,
+            blue: 0,
+            alpha,
+        };
+        let mut sprite = Sprite::new();
+        sprite.size = [3, 1];
+        sprite.col_depth = 8;
+        sprite.raw = vec![0, 1, 5];
+        sprite.pal = vec![color(0, 0), color(10, 255)];
+        assert_eq!(
+            sprite.to_rgba(None),
+            vec![0, 0, 0, 0, 10, 0, 0, 255, 0, 0, 0, 0]
+        );
+        let palette = vec![color(1, 255), color(2, 255)];
+        assert_eq!(
+            sprite.to_rgba(Some(&palette)),
+            vec![1, 0, 0, 255, 2, 0, 0, 255, 0, 0, 0, 0]
+        );
+
+        sprite.col_depth = 32;
+        sprite.raw = vec![1, 2, 3, 4];
+        assert_eq!(sprite.to_rgba(Some(&palette)), vec![1, 2, 3, 4]);
+
+        sprite.group = 9000;
+        assert!(matches!(
+            sprite.set_rgba(vec![0; 4]),
+            Err(SffError::SizeMismatch([9000, 0]))
+        ));
+        assert_eq!(sprite.raw, vec![1, 2, 3, 4]);
+        sprite.size = [1, 1];
+        assert!(sprite.set_rgba(vec![5, 6, 7, 8]).is_ok());
+        assert_eq!(sprite.raw, vec![5, 6, 7, 8]);
+    }
+
+    /// Prueba que una paleta ACT se lee en orden inverso con el índice 0 transparente
+    #[test]
+    fn test_decode_act() {
+        let mut data: Vec<u8> = (0..=255u8).rev().flat_map(|i| [i, 0, 255 - i]).collect();
+        data.extend_from_slice(&[0; 4]);
+        let palette = decode_act(&data).unwrap();
+        assert_eq!(palette.len(), 256);
+        assert_eq!(palette[0], Color::from_rgba(0, 0, 255, 0));
+        assert_eq!(palette[200], Color::from_rgba(200, 0, 55, 255));
+        assert_eq!(decode_act(&data[..767]), None);
+    }
+
+    /// Prueba que los archivos ACT agregan colores sin reemplazar las paletas del SFF
+    #[test]
+    fn test_load_palettes() {
+        let mut sff = Sff::load_file(PathBuf::from("src/chars/kfm/kfm.sff"), true).unwrap();
+        let slots = sff.get_palette_slots();
+        assert!(!slots.is_empty() && slots.len() < MAX_PAL_NO);
+        let original = sff.get_slot_palette(1).cloned();
+
+        let folder = std::env::temp_dir().join("sff_test_load_palettes");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("extra.act"), vec![7; ACT_SIZE]).unwrap();
+        std::fs::write(folder.join("short.act"), vec![7; 10]).unwrap();
+        let files: BTreeMap<usize, String> = [
+            (1, "missing.act".to_string()),
+            (MAX_PAL_NO, "extra.act".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        sff.load_palettes(&folder, &files).unwrap();
+        assert_eq!(sff.get_slot_palette(1).cloned(), original);
+        assert_eq!(
+            sff.get_slot_palette(MAX_PAL_NO)
+                .map(|palette| palette[1].clone()),
+            Some(Color::from_rgba(7, 7, 7, 255))
+        );
+        assert_eq!(sff.get_palette_slots().len(), slots.len() + 1);
+
+        let files = [(MAX_PAL_NO - 1, "short.act".to_string())]
+            .into_iter()
+            .collect();
+        assert!(matches!(
+            sff.load_palettes(&folder, &files),
+            Err(SffError::InvalidAct(_))
+        ));
+        let files = [(MAX_PAL_NO - 1, "missing.act".to_string())]
+            .into_iter()
+            .collect();
+        assert!(matches!(
+            sff.load_palettes(&folder, &files),
+            Err(SffError::NotFound(_))
+        ));
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+}
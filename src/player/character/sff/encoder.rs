@@ -0,0 +1,809 @@
+use std::collections::HashMap;
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::error::sff_error::SffError;
+
+use super::decoder::{Color, Sff, Sprite, SpriteFormat};
+
+/// Firma con la que empiezan los archivos SFF
+const SIGNATURE: &[u8; 12] = b"ElecbyteSpr\0";
+/// Tamaño de la cabecera del archivo en las dos versiones
+const HEADER_SIZE: u32 = 512;
+/// Tamaño de la subcabecera de un sprite versión 1
+const SUBHEADER_SIZE: u32 = 32;
+/// Tamaño de la cabecera de una paleta versión 2
+const PALETTE_HEADER_SIZE: u32 = 16;
+/// Tamaño de la cabecera de un sprite versión 2
+const SPRITE_HEADER_SIZE: u32 = 28;
+/// Tamaño de la cabecera de una imagen PCX
+const PCX_HEADER_SIZE: usize = 128;
+
+/// Codificador de archivos SFF a partir de sprites y paletas en memoria.
+///
+/// Los sprites con los mismos píxeles y la misma paleta se guardan enlazados al primero, y las paletas
+/// repetidas se guardan enlazadas a la primera con los mismos colores.
+pub struct SffWriter {
+    /// Paletas con su grupo y número
+    palettes: Vec<([i16; 2], Vec<Color>)>,
+    /// Sprites en el orden en el que se guardan, su `pal_idx` es un índice de `palettes`
+    sprites: Vec<Sprite>,
+}
+
+impl SffWriter {
+    /// Crea un codificador sin sprites ni paletas.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `SffWriter`.
+    pub fn new() -> Self {
+        Self {
+            palettes: Vec::new(),
+            sprites: Vec::new(),
+        }
+    }
+
+    /// Crea un codificador con los sprites y paletas de un SFF decodificado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sff` - El SFF decodificado.
+    ///
+    /// # Retorna
+    ///
+    /// Un `SffWriter` con los sprites en el orden del archivo original.
+    pub fn from_sff(sff: &Sff) -> Self {
+        let mut writer = Self::new();
+        let mut indexes = HashMap::new();
+        let mut index = 0;
+        while let Some(palette) = sff.get_palette(index) {
+            if !palette.is_empty() {
+                let key = sff
+                    .get_palette_key(index)
+                    .unwrap_or([1, writer.palettes.len() as i16 + 1]);
+                indexes.insert(index as i32, writer.add_palette(key, palette.clone()));
+            }
+            index += 1;
+        }
+        for key in sff.get_order() {
+            let mut sprite = sff.sprites[key].clone();
+            sprite.pal_idx = match indexes.get(&sprite.pal_idx) {
+                Some(&index) => index as i32,
+                None => {
+                    let key = [1, writer.palettes.len() as i16 + 1];
+                    writer.add_palette(key, sprite.pal.clone()) as i32
+                }
+            };
+            writer.add_sprite(sprite);
+        }
+        writer
+    }
+
+    /// Agrega una paleta.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - Grupo y número de la paleta.
+    /// * `colors` - Colores de la paleta.
+    ///
+    /// # Retorna
+    ///
+    /// El índice de la paleta, que es el que deben usar los sprites en `pal_idx`.
+    pub fn add_palette(&mut self, key: [i16; 2], colors: Vec<Color>) -> usize {
+        self.palettes.push((key, colors));
+        self.palettes.len() - 1
+    }
+
+    /// Agrega un sprite al final del archivo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sprite` - El sprite, con su formato y el índice de su paleta en este codificador.
+    pub fn add_sprite(&mut self, sprite: Sprite) {
+        self.sprites.push(sprite);
+    }
+
+    /// Cambia el formato con el que se guardan todos los sprites.
+    ///
+    /// # Argumentos
+    ///
+    /// * `format` - El nuevo formato.
+    pub fn set_format(&mut self, format: SpriteFormat) {
+        for sprite in &mut self.sprites {
+            sprite.format = format;
+        }
+    }
+
+    /// Codifica un SFF versión 1, con los sprites en PCX.
+    ///
+    /// Cada sprite guarda su paleta, salvo que sea la misma que la del sprite anterior.
+    ///
+    /// # Retorna
+    ///
+    /// Los bytes del archivo, o un `SffError` si hay sprites de color verdadero.
+    pub fn encode_v1(&self) -> Result<Vec<u8>, SffError> {
+        let mut groups: Vec<i16> = self.sprites.iter().map(|sprite| sprite.group).collect();
+        groups.sort();
+        groups.dedup();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(SIGNATURE);
+        out.extend_from_slice(&[0, 1, 0, 1]);
+        out.write_u32::<LittleEndian>(groups.len() as u32)?;
+        out.write_u32::<LittleEndian>(self.sprites.len() as u32)?;
+        out.write_u32::<LittleEndian>(HEADER_SIZE)?;
+        out.write_u32::<LittleEndian>(SUBHEADER_SIZE)?;
+        out.resize(HEADER_SIZE as usize, 0);
+
+        let mut shared = HashMap::new();
+        let mut prev_palette: Option<&[Color]> = None;
+        for (i, sprite) in self.sprites.iter().enumerate() {
+            if sprite.is_true_color() {
+                return Err(SffError::UnencodableSprite(
+                    [sprite.group, sprite.number],
+                    SpriteFormat::Pcx,
+                ));
+            }
+            let palette = self.get_palette(sprite);
+            let mut same_palette = false;
+            let mut data = Vec::new();
+            let link = match self.find_shared(&mut shared, i) {
+                Some(link) => link,
+                None => {
+                    same_palette = prev_palette == Some(palette);
+                    data = encode_pcx(sprite, if same_palette { None } else { Some(palette) })?;
+                    prev_palette = Some(palette);
+                    0
+                }
+            };
+
+            let next = out.len() as u32 + SUBHEADER_SIZE + data.len() as u32;
+            out.write_u32::<LittleEndian>(next)?;
+            out.write_u32::<LittleEndian>(data.len() as u32)?;
+            out.write_i16::<LittleEndian>(sprite.offset[0])?;
+            out.write_i16::<LittleEndian>(sprite.offset[1])?;
+            out.write_i16::<LittleEndian>(sprite.group)?;
+            out.write_i16::<LittleEndian>(sprite.number)?;
+            out.write_u16::<LittleEndian>(link as u16)?;
+            out.write_u8(same_palette as u8)?;
+            out.extend_from_slice(&[0; 13]);
+            out.extend_from_slice(&data);
+        }
+        Ok(out)
+    }
+
+    /// Codifica un SFF versión 2.01, con cada sprite en su formato.
+    ///
+    /// Los sprites PCX se guardan en RLE8.
+    ///
+    /// # Retorna
+    ///
+    /// Los bytes del archivo, o un `SffError` si algún sprite no se puede guardar en su formato.
+    pub fn encode_v2(&self) -> Result<Vec<u8>, SffError> {
+        let first_palette = HEADER_SIZE;
+        let first_sprite = first_palette + PALETTE_HEADER_SIZE * self.palettes.len() as u32;
+        let lofs = first_sprite + SPRITE_HEADER_SIZE * self.sprites.len() as u32;
+        let mut headers = Vec::new();
+        let mut ldata = Vec::new();
+
+        for (i, (key, colors)) in self.palettes.iter().enumerate() {
+            headers.write_i16::<LittleEndian>(key[0])?;
+            headers.write_i16::<LittleEndian>(key[1])?;
+            headers.write_u16::<LittleEndian>(colors.len() as u16)?;
+            match self.palettes[..i]
+                .iter()
+                .position(|(_, other)| other == colors)
+            {
+                Some(link) => {
+                    headers.write_u16::<LittleEndian>(link as u16)?;
+                    headers.write_u32::<LittleEndian>(0)?;
+                    headers.write_u32::<LittleEndian>(0)?;
+                }
+                None => {
+                    headers.write_u16::<LittleEndian>(0)?;
+                    headers.write_u32::<LittleEndian>(ldata.len() as u32)?;
+                    headers.write_u32::<LittleEndian>(colors.len() as u32 * 4)?;
+                    for color in colors {
+                        ldata.extend_from_slice(&color.get_vec());
+                    }
+                }
+            }
+        }
+
+        let mut shared = HashMap::new();
+        for (i, sprite) in self.sprites.iter().enumerate() {
+            let format = self.get_v2_format(sprite)?;
+            let col_depth = match format {
+                SpriteFormat::Png24 => 24,
+                SpriteFormat::Png32 => 32,
+                SpriteFormat::Raw if sprite.is_true_color() => 32,
+                _ => 8,
+            };
+            let (link, ofs, size) = match self.find_shared(&mut shared, i) {
+                Some(link) => (link, 0, 0),
+                None => {
+                    let data = encode_v2_data(sprite, format, self.get_palette(sprite))?;
+                    let ofs = ldata.len();
+                    ldata.extend_from_slice(&data);
+                    (0, ofs, data.len())
+                }
+            };
+
+            headers.write_i16::<LittleEndian>(sprite.group)?;
+            headers.write_i16::<LittleEndian>(sprite.number)?;
+            headers.write_u16::<LittleEndian>(sprite.size[0])?;
+            headers.write_u16::<LittleEndian>(sprite.size[1])?;
+            headers.write_i16::<LittleEndian>(sprite.offset[0])?;
+            headers.write_i16::<LittleEndian>(sprite.offset[1])?;
+            headers.write_u16::<LittleEndian>(link as u16)?;
+            headers.write_u8(format.code().unwrap_or(0))?;
+            headers.write_u8(col_depth)?;
+            headers.write_u32::<LittleEndian>(ofs as u32)?;
+            headers.write_u32::<LittleEndian>(size as u32)?;
+            headers.write_u16::<LittleEndian>(sprite.pal_idx.max(0) as u16)?;
+            headers.write_u16::<LittleEndian>(0)?;
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(SIGNATURE);
+        out.extend_from_slice(&[0, 1, 0, 2]);
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&[0, 0, 0, 2]);
+        out.extend_from_slice(&[0; 8]);
+        out.write_u32::<LittleEndian>(first_sprite)?;
+        out.write_u32::<LittleEndian>(self.sprites.len() as u32)?;
+        out.write_u32::<LittleEndian>(first_palette)?;
+        out.write_u32::<LittleEndian>(self.palettes.len() as u32)?;
+        out.write_u32::<LittleEndian>(lofs)?;
+        out.write_u32::<LittleEndian>(ldata.len() as u32)?;
+        out.write_u32::<LittleEndian>(lofs + ldata.len() as u32)?;
+        out.write_u32::<LittleEndian>(0)?;
+        out.resize(HEADER_SIZE as usize, 0);
+        out.extend_from_slice(&headers);
+        out.extend_from_slice(&ldata);
+        Ok(out)
+    }
+
+    /// Obtiene la paleta con la que se guarda un sprite.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sprite` - El sprite.
+    ///
+    /// # Retorna
+    ///
+    /// La paleta de su `pal_idx`, o la que tiene el propio sprite si el índice no existe.
+    fn get_palette<'a>(&'a self, sprite: &'a Sprite) -> &'a [Color] {
+        usize::try_from(sprite.pal_idx)
+            .ok()
+            .and_then(|index| self.palettes.get(index))
+            .map_or(&sprite.pal, |(_, colors)| colors)
+    }
+
+    /// Busca un sprite anterior con los mismos píxeles y paleta para enlazarlo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `shared` - Sprites ya guardados con datos, identificados por sus píxeles, tamaño y paleta.
+    /// * `i` - Índice del sprite a guardar.
+    ///
+    /// # Retorna
+    ///
+    /// El índice del sprite al que se enlaza, o `None` si hay que guardar sus datos. Los sprites sin
+    /// píxeles se enlazan a sí mismos, que el decodificador lee como un sprite vacío.
+    fn find_shared<'a>(
+        &'a self,
+        shared: &mut HashMap<(&'a [u8], [u16; 2], i32), usize>,
+        i: usize,
+    ) -> Option<usize> {
+        let sprite = &self.sprites[i];
+        if sprite.raw.is_empty() {
+            return Some(i);
+        }
+        match shared.get(&(&sprite.raw[..], sprite.size, sprite.pal_idx)) {
+            Some(&link) => Some(link),
+            None => {
+                shared.insert((&sprite.raw[..], sprite.size, sprite.pal_idx), i);
+                None
+            }
+        }
+    }
+
+    /// Obtiene el formato con el que se guarda un sprite en la versión 2.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sprite` - El sprite.
+    ///
+    /// # Retorna
+    ///
+    /// El formato, o un `SffError` si el sprite no se puede guardar en él.
+    fn get_v2_format(&self, sprite: &Sprite) -> Result<SpriteFormat, SffError> {
+        let format = match sprite.format {
+            SpriteFormat::Pcx => SpriteFormat::Rle8,
+            format => format,
+        };
+        let indexed = !sprite.is_true_color();
+        let valid = match format {
+            SpriteFormat::Raw | SpriteFormat::Png24 | SpriteFormat::Png32 => true,
+            SpriteFormat::Rle8 | SpriteFormat::Rle5 | SpriteFormat::Png8 => indexed,
+            // Los colores que no se copian de píxeles anteriores tienen 5 bits
+            SpriteFormat::Lz5 => indexed && sprite.raw.iter().all(|&index| index < 32),
+            SpriteFormat::Pcx | SpriteFormat::Unknown(_) => false,
+        };
+        if !valid {
+            return Err(SffError::UnencodableSprite(
+                [sprite.group, sprite.number],
+                format,
+            ));
+        }
+        Ok(format)
+    }
+}
+
+impl Default for SffWriter {
+    /// Crea un codificador vacío, igual que `SffWriter::new`.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `SffWriter`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Codifica los datos de un sprite versión 2.
+///
+/// # Argumentos
+///
+/// * `sprite` - El sprite.
+/// * `format` - El formato en el que se guarda.
+/// * `palette` - La paleta del sprite, para los PNG.
+///
+/// # Retorna
+///
+/// Los datos del sprite. Salvo en el formato sin comprimir, empiezan con el tamaño de los píxeles descomprimidos.
+fn encode_v2_data(
+    sprite: &Sprite,
+    format: SpriteFormat,
+    palette: &[Color],
+) -> Result<Vec<u8>, SffError> {
+    let (data, size) = match format {
+        SpriteFormat::Rle8 => (rle8_encode(&sprite.raw), sprite.raw.len()),
+        SpriteFormat::Rle5 => (rle5_encode(&sprite.raw), sprite.raw.len()),
+        SpriteFormat::Lz5 => (lz5_encode(&sprite.raw), sprite.raw.len()),
+        SpriteFormat::Png8 => (
+            encode_png(sprite.size, png::ColorType::Indexed, palette, &sprite.raw)?,
+            sprite.raw.len(),
+        ),
+        SpriteFormat::Png24 => {
+            let rgb: Vec<u8> = sprite
+                .to_rgba(Some(&palette.to_vec()))
+                .chunks_exact(4)
+                .flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+                .collect();
+            (
+                encode_png(sprite.size, png::ColorType::Rgb, palette, &rgb)?,
+                rgb.len(),
+            )
+        }
+        SpriteFormat::Png32 => {
+            let rgba = sprite.to_rgba(Some(&palette.to_vec()));
+            (
+                encode_png(sprite.size, png::ColorType::Rgba, palette, &rgba)?,
+                rgba.len(),
+            )
+        }
+        _ => return Ok(sprite.raw.clone()),
+    };
+    let mut out = Vec::with_capacity(data.len() + 4);
+    out.write_u32::<LittleEndian>(size as u32)?;
+    out.extend_from_slice(&data);
+    Ok(out)
+}
+
+/// Codifica un sprite como una imagen PCX de 8 bits comprimida con RLE.
+///
+/// # Argumentos
+///
+/// * `sprite` - El sprite.
+/// * `palette` - La paleta que se guarda al final de la imagen, o `None` si usa la del sprite anterior.
+///
+/// # Retorna
+///
+/// Los bytes de la imagen.
+fn encode_pcx(sprite: &Sprite, palette: Option<&[Color]>) -> Result<Vec<u8>, SffError> {
+    let width = sprite.size[0] as usize;
+    // Las líneas de un PCX tienen un número par de bytes
+    let bpl = width + width % 2;
+
+    let mut out = Vec::with_capacity(PCX_HEADER_SIZE + sprite.raw.len());
+    out.extend_from_slice(&[0x0a, 5, 1, 8]);
+    for value in [0, 0, sprite.size[0] - 1, sprite.size[1] - 1] {
+        out.write_u16::<LittleEndian>(value)?;
+    }
+    out.write_u16::<LittleEndian>(sprite.size[0])?;
+    out.write_u16::<LittleEndian>(sprite.size[1])?;
+    out.resize(65, 0);
+    out.write_u8(1)?;
+    out.write_u16::<LittleEndian>(bpl as u16)?;
+    out.write_u16::<LittleEndian>(1)?;
+    out.resize(PCX_HEADER_SIZE, 0);
+
+    for row in sprite.raw.chunks(width) {
+        let mut line = row.to_vec();
+        line.resize(bpl, 0);
+        let mut i = 0;
+        while i < line.len() {
+            let color = line[i];
+            let mut n = 1;
+            while i + n < line.len() && line[i + n] == color && n < 63 {
+                n += 1;
+            }
+            if n > 1 || color >= 0xc0 {
+                out.push(0xc0 | n as u8);
+            }
+            out.push(color);
+            i += n;
+        }
+    }
+
+    if let Some(palette) = palette {
+        out.push(0x0c);
+        for i in 0..256 {
+            let rgba = palette.get(i).map_or(vec![0; 4], |color| color.get_vec());
+            out.extend_from_slice(&rgba[..3]);
+        }
+    }
+    Ok(out)
+}
+
+/// Cuenta cuántas veces se repite el píxel de una posición.
+///
+/// # Argumentos
+///
+/// * `px` - Los píxeles.
+/// * `start` - La posición del primer píxel.
+/// * `max` - Máximo de repeticiones a contar.
+///
+/// # Retorna
+///
+/// La cantidad de píxeles iguales seguidos, al menos 1.
+fn run_length(px: &[u8], start: usize, max: usize) -> usize {
+    let mut n = 1;
+    while start + n < px.len() && px[start + n] == px[start] && n < max {
+        n += 1;
+    }
+    n
+}
+
+/// Comprime píxeles en RLE8.
+///
+/// # Argumentos
+///
+/// * `px` - Los índices de la paleta.
+///
+/// # Retorna
+///
+/// Los datos comprimidos.
+fn rle8_encode(px: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < px.len() {
+        let color = px[i];
+        let n = run_length(px, i, 63);
+        // Los colores que parecen una repetición se guardan como una repetición de 1
+        if n > 1 || color & 0xc0 == 0x40 {
+            out.push(0x40 | n as u8);
+        }
+        out.push(color);
+        i += n;
+    }
+    out
+}
+
+/// Comprime píxeles en RLE5.
+///
+/// # Argumentos
+///
+/// * `px` - Los índices de la paleta.
+///
+/// # Retorna
+///
+/// Los datos comprimidos.
+fn rle5_encode(px: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut j = 0;
+    while j < px.len() {
+        let color = px[j];
+        let n = run_length(px, j, 256);
+        j += n;
+
+        // Las repeticiones cortas de colores de 5 bits que siguen van empaquetadas en un byte
+        let mut data = Vec::new();
+        while j < px.len() && data.len() < 127 && px[j] < 32 {
+            let n = run_length(px, j, 9);
+            if n > 8 {
+                break;
+            }
+            data.push(((n - 1) << 5) as u8 | px[j]);
+            j += n;
+        }
+
+        out.push((n - 1) as u8);
+        if color == 0 {
+            out.push(data.len() as u8);
+        } else {
+            out.push(data.len() as u8 | 0x80);
+            out.push(color);
+        }
+        out.extend_from_slice(&data);
+    }
+    out
+}
+
+/// Comprime píxeles en LZ5.
+///
+/// Se usan repeticiones de un color y copias largas de píxeles anteriores; las copias cortas, que
+/// reparten la distancia entre varios paquetes, no se generan.
+///
+/// # Argumentos
+///
+/// * `px` - Los índices de la paleta, todos menores que 32.
+///
+/// # Retorna
+///
+/// Los datos comprimidos.
+fn lz5_encode(px: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut control = 0;
+    let mut bit = 8;
+    let mut chains: HashMap<[u8; 3], Vec<usize>> = HashMap::new();
+    let mut j = 0;
+    while j < px.len() {
+        // Cada byte de control indica si los 8 paquetes siguientes son copias o repeticiones
+        if bit == 8 {
+            control = out.len();
+            out.push(0);
+            bit = 0;
+        }
+
+        let (mut best_len, mut best_dist) = (0, 0);
+        if j + 3 <= px.len() {
+            if let Some(starts) = chains.get(&[px[j], px[j + 1], px[j + 2]]) {
+                for &start in starts.iter().rev().take(32) {
+                    let dist = j - start;
+                    if dist > 1024 {
+                        break;
+                    }
+                    let mut len = 0;
+                    while j + len < px.len() && len < 258 && px[start + len] == px[j + len] {
+                        len += 1;
+                    }
+                    if len > best_len {
+                        best_len = len;
+                        best_dist = dist;
+                    }
+                }
+            }
+        }
+
+        let run = run_length(px, j, 263);
+        let step = if best_len >= 3 && best_len > run {
+            out[control] |= 1 << bit;
+            let dist = best_dist - 1;
+            out.push(((dist >> 8) << 6) as u8);
+            out.push(dist as u8);
+            out.push((best_len - 3) as u8);
+            best_len
+        } else {
+            if run >= 8 {
+                out.push(px[j]);
+                out.push((run - 8) as u8);
+            } else {
+                out.push((run << 5) as u8 | px[j]);
+            }
+            run
+        };
+
+        for k in j..(j + step).min(px.len().saturating_sub(2)) {
+            chains
+                .entry([px[k], px[k + 1], px[k + 2]])
+                .or_default()
+                .push(k);
+        }
+        j += step;
+        bit += 1;
+    }
+    out
+}
+
+/// Codifica una imagen PNG de 8 bits por muestra.
+///
+/// # Argumentos
+///
+/// * `size` - Ancho y alto de la imagen.
+/// * `color` - Tipo de color de los píxeles.
+/// * `palette` - La paleta, que se guarda si la imagen es de tipo `Indexed`.
+/// * `data` - Los píxeles.
+///
+/// # Retorna
+///
+/// Los bytes del PNG, o un `SffError` si no se puede codificar.
+fn encode_png(
+    size: [u16; 2],
+    color: png::ColorType,
+    palette: &[Color],
+    data: &[u8],
+) -> Result<Vec<u8>, SffError> {
+    let invalid = |err: png::EncodingError| SffError::InvalidPng(err.to_string());
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, size[0] as u32, size[1] as u32);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        if color == png::ColorType::Indexed {
+            let mut plte: Vec<u8> = palette
+                .iter()
+                .take(256)
+                .flat_map(|color| color.get_vec()[..3].to_vec())
+                .collect();
+            if plte.is_empty() {
+                plte = vec![0; 3];
+            }
+            encoder.set_palette(plte);
+        }
+        let mut writer = encoder.write_header().map_err(invalid)?;
+        writer.write_image_data(data).map_err(invalid)?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Carga el SFF de prueba.
+    ///
+    /// # Retorna
+    ///
+    /// El SFF de KFM decodificado.
+    fn kfm() -> Sff {
+        Sff::load_file(PathBuf::from("src/chars/kfm/kfm.sff"), true).unwrap()
+    }
+
+    /// Comprueba que dos SFF tienen los mismos sprites con los mismos píxeles.
+    ///
+    /// # Argumentos
+    ///
+    /// * `original` - El SFF original.
+    /// * `decoded` - El SFF codificado y vuelto a decodificar.
+    fn assert_same_pixels(original: &Sff, decoded: &Sff) {
+        assert_eq!(original.get_order(), decoded.get_order());
+        for key in original.get_order() {
+            let (a, b) = (&original.sprites[key], &decoded.sprites[key]);
+            assert_eq!(a.size, b.size, "sprite {:?}", key);
+            assert_eq!(a.offset, b.offset, "sprite {:?}", key);
+            assert_eq!(a.to_rgba(None), b.to_rgba(None), "sprite {:?}", key);
+        }
+    }
+
+    /// Prueba que KFM se vuelve a codificar en la versión 2 con sus formatos, enlaces y paletas
+    #[test]
+    fn test_round_trip_v2() {
+        let sff = kfm();
+        let data = SffWriter::from_sff(&sff).encode_v2().unwrap();
+        let decoded = Sff::decode(&data, true).unwrap();
+        assert_same_pixels(&sff, &decoded);
+        assert_eq!(decoded.get_palette_count(), sff.get_palette_count());
+        for index in 0..sff.get_palette_count() {
+            assert_eq!(decoded.get_palette(index), sff.get_palette(index));
+            assert_eq!(decoded.get_palette_key(index), sff.get_palette_key(index));
+        }
+        for key in sff.get_order() {
+            assert_eq!(decoded.sprites[key].format, sff.sprites[key].format);
+            assert_eq!(decoded.sprites[key].link, sff.sprites[key].link);
+        }
+    }
+
+    /// Prueba que KFM se vuelve a codificar con los mismos píxeles en cada formato de la versión 2
+    #[test]
+    fn test_round_trip_formats() {
+        let sff = kfm();
+        for format in [
+            SpriteFormat::Raw,
+            SpriteFormat::Rle8,
+            SpriteFormat::Rle5,
+            SpriteFormat::Png8,
+            SpriteFormat::Png32,
+        ] {
+            let mut writer = SffWriter::from_sff(&sff);
+            writer.set_format(format);
+            let decoded = Sff::decode(&writer.encode_v2().unwrap(), true).unwrap();
+            assert_same_pixels(&sff, &decoded);
+        }
+    }
+
+    /// Prueba que KFM se vuelve a codificar en la versión 1 con los mismos índices y colores
+    #[test]
+    fn test_round_trip_v1() {
+        let sff = kfm();
+        let data = SffWriter::from_sff(&sff).encode_v1().unwrap();
+        let decoded = Sff::decode(&data, true).unwrap();
+        assert_eq!(decoded.get_version().to_string(), "1.0.1.0");
+        assert_eq!(sff.get_order(), decoded.get_order());
+        let rgb = |colors: &Vec<Color>| -> Vec<u8> {
+            colors
+                .iter()
+                .flat_map(|color| color.get_vec()[..3].to_vec())
+                .collect()
+        };
+        for key in sff.get_order() {
+            let (a, b) = (&sff.sprites[key], &decoded.sprites[key]);
+            assert_eq!(a.size, b.size, "sprite {:?}", key);
+            assert_eq!(a.offset, b.offset, "sprite {:?}", key);
+            assert_eq!(a.raw, b.raw, "sprite {:?}", key);
+            assert_eq!(rgb(&a.pal), rgb(&b.pal), "sprite {:?}", key);
+            assert_eq!(b.link.is_some(), a.link.is_some(), "sprite {:?}", key);
+        }
+    }
+
+    /// Prueba que LZ5 codifica repeticiones largas y copias que se solapan
+    #[test]
+    fn test_lz5_runs_and_copies() {
+        let mut sprite = Sprite::new();
+        sprite.size = [40, 10];
+        sprite.col_depth = 8;
+        sprite.format = SpriteFormat::Lz5;
+        sprite.raw = (0..400)
+            .map(|i: usize| match i {
+                0..=99 => 0,
+                100..=199 => (i % 3) as u8 + 1,
+                _ => (i * 7 % 31) as u8,
+            })
+            .collect();
+        let mut writer = SffWriter::new();
+        writer.add_palette([1, 1], vec![Color::from_rgba(0, 0, 0, 0); 32]);
+        writer.add_sprite(sprite.clone());
+        let decoded = Sff::decode(&writer.encode_v2().unwrap(), false).unwrap();
+        assert_eq!(decoded.sprites[&[0, 0]].raw, sprite.raw);
+
+        sprite.raw[0] = 40;
+        let mut writer = SffWriter::new();
+        writer.add_sprite(sprite);
+        assert!(matches!(
+            writer.encode_v2(),
+            Err(SffError::UnencodableSprite([0, 0], SpriteFormat::Lz5))
+        ));
+    }
+
+    /// Prueba que las paletas repetidas y los sprites iguales se guardan enlazados
+    #[test]
+    fn test_shared_palettes_and_sprites() {
+        let palette: Vec<Color> = (0..=255).map(|i| Color::from_rgba(i, i, i, 255)).collect();
+        let mut writer = SffWriter::new();
+        writer.add_palette([1, 1], palette.clone());
+        writer.add_palette([1, 2], palette.clone());
+        for number in 0..2 {
+            let mut sprite = Sprite::new();
+            sprite.number = number;
+            sprite.size = [2, 2];
+            sprite.col_depth = 8;
+            sprite.format = SpriteFormat::Rle8;
+            sprite.raw = vec![0x40, 0x41, 200, 200];
+            sprite.pal_idx = 0;
+            writer.add_sprite(sprite);
+        }
+        let data = writer.encode_v2().unwrap();
+        // La segunda paleta no tiene datos y enlaza a la primera
+        let header = (HEADER_SIZE + PALETTE_HEADER_SIZE) as usize;
+        assert_eq!(&data[header + 6..header + 16], &[0; 10]);
+
+        let decoded = Sff::decode(&data, false).unwrap();
+        assert_eq!(decoded.get_palette(1), Some(&palette));
+        assert_eq!(decoded.sprites[&[0, 1]].link, Some([0, 0]));
+        assert_eq!(decoded.sprites[&[0, 1]].raw, vec![0x40, 0x41, 200, 200]);
+    }
+}
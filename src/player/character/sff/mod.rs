@@ -1 +1,3 @@
-pub mod decoder;
\ No newline at end of file
+pub mod decoder;
+pub mod encoder;
+pub mod texture;
@@ -0,0 +1,259 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    rc::{Rc, Weak},
+};
+
+use gfx_device_gl::Resources;
+use graphics::{draw_state::Blend, DrawState};
+use image::RgbaImage;
+use piston_window::{Filter, G2dTextureContext, Texture, TextureSettings};
+
+use crate::error::sff_error::SffError;
+
+use super::decoder::{Color, Sff, Sprite};
+
+/// Texturas de los sprites pintados con una misma paleta, identificadas por su grupo y número.
+type TextureSet = HashMap<[i16; 2], Rc<Texture<Resources>>>;
+
+thread_local! {
+    /// Texturas de los SFF de personajes que siguen en uso, para que dos jugadores con el mismo personaje
+    /// compartan el SFF decodificado y las texturas de cada paleta.
+    static LOADED: RefCell<HashMap<PathBuf, Weak<RefCell<TextureCache>>>> = RefCell::new(HashMap::new());
+}
+
+/// SFF decodificado junto con las texturas de las paletas que ya se usaron.
+///
+/// Los sprites se guardan como índices y cada paleta se sube una sola vez: volver a una paleta que ya
+/// se usó, por ejemplo en un destello al recibir un golpe, no sube ninguna textura.
+struct TextureCache {
+    /// Sprites y paletas decodificados
+    sff: Rc<Sff>,
+    /// Texturas de cada color, `None` es la paleta original de cada sprite
+    sets: HashMap<Option<usize>, Rc<TextureSet>>,
+    /// Contexto de la ventana para crear las texturas
+    context: G2dTextureContext,
+}
+
+impl TextureCache {
+    /// Obtiene las texturas de los sprites pintados con un color, subiéndolas si es la primera vez.
+    ///
+    /// # Argumentos
+    ///
+    /// * `palette` - El número del color, o `None` para usar la paleta de cada sprite.
+    ///
+    /// # Retorna
+    ///
+    /// Las texturas, o `None` si el color no tiene paleta.
+    fn get_set(&mut self, palette: Option<usize>) -> Option<Rc<TextureSet>> {
+        if let Some(set) = self.sets.get(&palette) {
+            return Some(Rc::clone(set));
+        }
+        let colors = match palette {
+            Some(slot) => Some(self.sff.get_slot_palette(slot)?.clone()),
+            None => None,
+        };
+        let mut set = TextureSet::new();
+        for (key, sprite) in &self.sff.sprites {
+            if let Some(texture) = create_texture(&mut self.context, sprite, colors.as_ref()) {
+                set.insert(*key, Rc::new(texture));
+            }
+        }
+        let set = Rc::new(set);
+        self.sets.insert(palette, Rc::clone(&set));
+        Some(set)
+    }
+}
+
+/// Sprites de un SFF decodificado junto con sus texturas, listos para dibujar.
+///
+/// El SFF se decodifica sin contexto gráfico; esta estructura es el paso en el que el renderizador
+/// sube los píxeles a la tarjeta gráfica. Cada `SffTextures` elige su paleta, pero el SFF y las
+/// texturas se comparten con los demás que se crearon a partir del mismo archivo.
+pub struct SffTextures {
+    /// Sprites y paletas decodificados
+    sff: Rc<Sff>,
+    /// Texturas de todas las paletas, compartidas
+    cache: Rc<RefCell<TextureCache>>,
+    /// Texturas de la paleta actual
+    textures: Rc<TextureSet>,
+    /// Color actual, `None` si cada sprite usa su paleta original
+    palette: Option<usize>,
+}
+
+impl SffTextures {
+    /// Sube los sprites de un SFF con sus paletas originales.
+    ///
+    /// # Argumentos
+    ///
+    /// * `sff` - El SFF decodificado.
+    /// * `context` - El contexto gráfico utilizado para crear las texturas.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `SffTextures` con una textura por cada sprite con píxeles.
+    pub fn new(sff: Sff, context: G2dTextureContext) -> Self {
+        Self::from_cache(Rc::new(RefCell::new(TextureCache {
+            sff: Rc::new(sff),
+            sets: HashMap::new(),
+            context,
+        })))
+    }
+
+    /// Carga el SFF de un personaje con sus archivos ACT, o comparte el que ya está cargado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `char_name` - El nombre del personaje, el SFF está en `src/chars/<nombre>`.
+    /// * `filename` - El nombre del archivo SFF.
+    /// * `palettes` - Archivos ACT de los colores del personaje.
+    /// * `context` - El contexto gráfico utilizado para crear las texturas si el SFF no estaba cargado.
+    ///
+    /// # Retorna
+    ///
+    /// Un `SffTextures` con la paleta original de cada sprite, o un `SffError` si no se puede cargar.
+    pub fn load_char(
+        char_name: &str,
+        filename: String,
+        palettes: &BTreeMap<usize, String>,
+        context: G2dTextureContext,
+    ) -> Result<Self, SffError> {
+        let folder = std::env::current_dir()
+            .unwrap()
+            .join("src")
+            .join("chars")
+            .join(char_name);
+        let path = folder.join(&filename);
+        let loaded = LOADED.with(|loaded| loaded.borrow().get(&path).and_then(Weak::upgrade));
+        if let Some(cache) = loaded {
+            return Ok(Self::from_cache(cache));
+        }
+
+        let mut sff = Sff::load_sff(char_name, filename, true)?;
+        sff.load_palettes(&folder, palettes)?;
+        let textures = Self::new(sff, context);
+        LOADED.with(|loaded| {
+            let mut loaded = loaded.borrow_mut();
+            loaded.retain(|_, cache| cache.strong_count() > 0);
+            loaded.insert(path, Rc::downgrade(&textures.cache));
+        });
+        Ok(textures)
+    }
+
+    /// Crea un `SffTextures` que comparte las texturas ya subidas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `cache` - El SFF con sus texturas.
+    ///
+    /// # Retorna
+    ///
+    /// Un `SffTextures` con la paleta original de cada sprite.
+    fn from_cache(cache: Rc<RefCell<TextureCache>>) -> Self {
+        let sff = Rc::clone(&cache.borrow().sff);
+        let textures = cache.borrow_mut().get_set(None).unwrap_or_default();
+        Self {
+            sff,
+            cache,
+            textures,
+            palette: None,
+        }
+    }
+
+    /// Obtiene el SFF decodificado.
+    ///
+    /// # Retorna
+    ///
+    /// Una referencia al SFF con los sprites y las paletas.
+    pub fn get_sff(&self) -> &Sff {
+        &self.sff
+    }
+
+    /// Obtiene la textura de un sprite con la paleta actual.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - Grupo y número del sprite.
+    ///
+    /// # Retorna
+    ///
+    /// La textura, o `None` si el sprite no existe o no tiene píxeles.
+    pub fn get_texture(&self, key: &[i16; 2]) -> Option<&Rc<Texture<Resources>>> {
+        self.textures.get(key)
+    }
+
+    /// Obtiene el color con el que se pintan los sprites.
+    ///
+    /// # Retorna
+    ///
+    /// El número del color, o `None` si cada sprite usa su paleta original.
+    pub fn get_palette(&self) -> Option<usize> {
+        self.palette
+    }
+
+    /// Pinta los sprites con otro de los colores del personaje.
+    ///
+    /// Las texturas de cada color se suben la primera vez que se usa, después cambiar de color solo
+    /// cambia qué texturas se dibujan.
+    ///
+    /// # Argumentos
+    ///
+    /// * `slot` - El número del color, si no tiene paleta se mantienen las texturas actuales.
+    pub fn set_palette(&mut self, slot: usize) {
+        if self.palette == Some(slot) {
+            return;
+        }
+        if let Some(textures) = self.cache.borrow_mut().get_set(Some(slot)) {
+            self.textures = textures;
+            self.palette = Some(slot);
+        }
+    }
+}
+
+/// Crea la textura de un sprite.
+///
+/// # Argumentos
+///
+/// * `context` - El contexto gráfico.
+/// * `sprite` - El sprite decodificado.
+/// * `palette` - Paleta con la que pintar el sprite, o `None` para usar la suya.
+///
+/// # Retorna
+///
+/// La textura, o `None` si el sprite no tiene píxeles.
+fn create_texture(
+    context: &mut G2dTextureContext,
+    sprite: &Sprite,
+    palette: Option<&Vec<Color>>,
+) -> Option<Texture<Resources>> {
+    if sprite.raw.is_empty() {
+        return None;
+    }
+    let img = RgbaImage::from_raw(
+        sprite.size[0] as u32,
+        sprite.size[1] as u32,
+        sprite.to_rgba(palette),
+    )?;
+    Texture::from_image(
+        context,
+        &img,
+        &TextureSettings::new().filter(Filter::Nearest),
+    )
+    .ok()
+}
+
+/// Obtiene el modo de mezcla y el color con los que se dibuja un sprite con transparencia por resta.
+///
+/// Los modos de mezcla disponibles no restan el sprite del fondo, así que se aproxima oscureciendo a
+/// la mitad el fondo donde el sprite es opaco. La usan tanto los personajes como los escenarios.
+///
+/// # Retorna
+///
+/// El estado de dibujo y el color que multiplica al sprite.
+pub fn subtract_blend() -> (DrawState, [f32; 4]) {
+    (
+        DrawState::default().blend(Blend::Alpha),
+        [0.0, 0.0, 0.0, 0.5],
+    )
+}
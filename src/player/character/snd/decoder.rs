@@ -0,0 +1,377 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io::{self, Cursor},
+    path::Path,
+    rc::Rc,
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::error::snd_error::SndError;
+
+/// Tamaño de la cabecera de un archivo SND
+const HEADER_SIZE: usize = 512;
+/// Tamaño de la cabecera de cada subarchivo
+const SUBFILE_HEADER_SIZE: usize = 16;
+/// Formato de audio PCM en la cabecera WAV
+const WAVE_FORMAT_PCM: u16 = 1;
+
+/// Estructura que representa un identificador de sonido.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SoundId {
+    /// Grupo del sonido
+    pub group: u32,
+    /// Número del sonido respecto al grupo
+    pub number: u32,
+}
+
+/// Representa un sonido decodificado de un archivo WAV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sound {
+    /// Número de canales (1 mono, 2 estéreo)
+    pub channels: u16,
+    /// Muestras por segundo
+    pub sample_rate: u32,
+    /// Muestras de 16 bits con signo, intercaladas por canal
+    pub samples: Vec<i16>,
+}
+
+/// Representa un decodificador para archivos SND, que contienen los sonidos de un personaje
+/// accesibles por su grupo y número.
+#[derive(Debug, Clone, Default)]
+pub struct Snd {
+    /// Sonidos del SND accesibles por su grupo y número, compartidos con el mezclador
+    sounds: HashMap<SoundId, Rc<Sound>>,
+    /// Sonidos que no se han podido decodificar y se han saltado
+    skipped: Vec<SoundId>,
+}
+
+impl From<SoundId> for (u32, u32) {
+    /// Convierte un `SoundId` a una tupla de dos elementos (u32, u32).
+    ///
+    /// # Argumentos
+    ///
+    /// * `sound_id` - El identificador de sonido a convertir.
+    ///
+    /// # Retorna
+    ///
+    /// Una tupla de dos elementos que representa el identificador de sonido.
+    fn from(SoundId { group, number }: SoundId) -> Self {
+        (group, number)
+    }
+}
+
+impl From<(u32, u32)> for SoundId {
+    /// Convierte una tupla de dos elementos (u32, u32) a un `SoundId`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `tuple` - La tupla a convertir.
+    ///
+    /// # Retorna
+    ///
+    /// Un `SoundId` que representa la tupla.
+    fn from((group, number): (u32, u32)) -> Self {
+        SoundId { group, number }
+    }
+}
+
+impl Sound {
+    /// Decodifica un archivo WAV PCM de 8 o 16 bits, mono o estéreo.
+    ///
+    /// # Argumentos
+    ///
+    /// * `data` - Bytes del archivo WAV.
+    ///
+    /// # Retorna
+    ///
+    /// El sonido con sus muestras convertidas a 16 bits con signo, o un `SndError` si el formato no está soportado.
+    pub fn from_wav(data: &[u8]) -> Result<Self, SndError> {
+        if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+            return Err(SndError::InvalidWav);
+        }
+
+        let mut format = None;
+        let mut pcm = None;
+        let mut pos = 12;
+        while pos + 8 <= data.len() {
+            let id = &data[pos..pos + 4];
+            let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]);
+            let start = pos + 8;
+            // Algunos archivos indican un tamaño mayor al real en el último bloque
+            let end = start.saturating_add(size as usize).min(data.len());
+            match id {
+                b"fmt " => {
+                    let mut bytes = Cursor::new(&data[start..end]);
+                    let audio_format = bytes.read_u16::<LittleEndian>()?;
+                    let channels = bytes.read_u16::<LittleEndian>()?;
+                    let sample_rate = bytes.read_u32::<LittleEndian>()?;
+                    bytes.read_u32::<LittleEndian>()?;
+                    bytes.read_u16::<LittleEndian>()?;
+                    let bits_per_sample = bytes.read_u16::<LittleEndian>()?;
+                    format = Some((audio_format, channels, sample_rate, bits_per_sample));
+                }
+                b"data" => pcm = Some(&data[start..end]),
+                _ => {}
+            }
+            // Los bloques se alinean a 2 bytes
+            pos = end + (size as usize & 1);
+        }
+
+        let (Some((audio_format, channels, sample_rate, bits_per_sample)), Some(pcm)) = (format, pcm)
+        else {
+            return Err(SndError::InvalidWav);
+        };
+        if audio_format != WAVE_FORMAT_PCM {
+            return Err(SndError::UnsupportedFormat(audio_format));
+        }
+        if channels != 1 && channels != 2 {
+            return Err(SndError::UnsupportedChannels(channels));
+        }
+
+        let samples = match bits_per_sample {
+            8 => pcm.iter().map(|&sample| (sample as i16 - 128) << 8).collect(),
+            16 => pcm
+                .chunks_exact(2)
+                .map(|sample| i16::from_le_bytes([sample[0], sample[1]]))
+                .collect(),
+            _ => return Err(SndError::UnsupportedBitDepth(bits_per_sample)),
+        };
+
+        Ok(Self {
+            channels,
+            sample_rate,
+            samples,
+        })
+    }
+
+    /// Obtiene el número de fotogramas de audio (una muestra por canal).
+    ///
+    /// # Retorna
+    ///
+    /// El número de fotogramas del sonido.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+}
+
+impl Snd {
+    /// Lee los sonidos de un archivo SND en memoria.
+    ///
+    /// Si hay varios sonidos con el mismo grupo y número se mantiene el primero. Los sonidos que no se pueden
+    /// decodificar (formatos no soportados) se saltan y se sigue con el resto.
+    ///
+    /// # Argumentos
+    ///
+    /// * `data` - Bytes del archivo SND.
+    ///
+    /// # Retorna
+    ///
+    /// Una instancia de `Snd` con los sonidos decodificados, o un `SndError` en caso de error.
+    pub fn read(data: &[u8]) -> Result<Self, SndError> {
+        if data.len() < HEADER_SIZE || &data[0..12] != b"ElecbyteSnd\0" {
+            return Err(SndError::InvalidSignature);
+        }
+
+        let mut bytes = Cursor::new(data);
+        bytes.set_position(16);
+        let number_of_sounds = bytes.read_u32::<LittleEndian>()?;
+        let mut offset = bytes.read_u32::<LittleEndian>()? as usize;
+
+        let mut sounds = HashMap::new();
+        let mut skipped = Vec::new();
+        for _ in 0..number_of_sounds {
+            if offset == 0 {
+                break;
+            }
+            bytes.set_position(offset as u64);
+            let next_offset = bytes.read_u32::<LittleEndian>()? as usize;
+            let length = bytes.read_u32::<LittleEndian>()? as usize;
+            let group = bytes.read_u32::<LittleEndian>()?;
+            let number = bytes.read_u32::<LittleEndian>()?;
+
+            let start = offset + SUBFILE_HEADER_SIZE;
+            let wav = data
+                .get(start..start.saturating_add(length))
+                .ok_or(io::Error::from(io::ErrorKind::UnexpectedEof))?;
+            let id = SoundId { group, number };
+            if let Entry::Vacant(entry) = sounds.entry(id) {
+                match Sound::from_wav(wav) {
+                    Ok(sound) => {
+                        entry.insert(Rc::new(sound));
+                    }
+                    Err(_) => skipped.push(id),
+                }
+            }
+
+            if next_offset <= offset {
+                break;
+            }
+            offset = next_offset;
+        }
+
+        Ok(Self { sounds, skipped })
+    }
+
+    /// Carga un archivo SND.
+    ///
+    /// # Argumentos
+    ///
+    /// * `path` - Ruta del archivo SND.
+    ///
+    /// # Retorna
+    ///
+    /// Una instancia de `Snd` si se carga correctamente, o un `SndError` en caso de error.
+    pub fn load_snd(path: &Path) -> Result<Self, SndError> {
+        if !path.exists() {
+            return Err(SndError::NotFound(path.to_path_buf()));
+        }
+        let data = std::fs::read(path)?;
+        Self::read(&data)
+    }
+
+    /// Obtiene un sonido por su grupo y número.
+    ///
+    /// # Argumentos
+    ///
+    /// * `id` - Identificador del sonido.
+    ///
+    /// # Retorna
+    ///
+    /// El sonido, o `None` si no existe.
+    pub fn get_sound(&self, id: SoundId) -> Option<&Rc<Sound>> {
+        self.sounds.get(&id)
+    }
+
+    /// Obtiene los sonidos que no se han podido decodificar al leer el SND.
+    ///
+    /// # Retorna
+    ///
+    /// Los identificadores de los sonidos saltados, en el orden del archivo.
+    pub fn get_skipped(&self) -> &[SoundId] {
+        &self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Crea un archivo WAV con los datos PCM dados.
+    fn wav(format: u16, channels: u16, bits: u16, pcm: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"RIFF");
+        data.extend_from_slice(&(36 + pcm.len() as u32).to_le_bytes());
+        data.extend_from_slice(b"WAVE");
+        data.extend_from_slice(b"fmt ");
+        data.extend_from_slice(&16u32.to_le_bytes());
+        data.extend_from_slice(&format.to_le_bytes());
+        data.extend_from_slice(&channels.to_le_bytes());
+        data.extend_from_slice(&11025u32.to_le_bytes());
+        data.extend_from_slice(&(11025 * channels as u32 * bits as u32 / 8).to_le_bytes());
+        data.extend_from_slice(&(channels * bits / 8).to_le_bytes());
+        data.extend_from_slice(&bits.to_le_bytes());
+        data.extend_from_slice(b"data");
+        data.extend_from_slice(&(pcm.len() as u32).to_le_bytes());
+        data.extend_from_slice(pcm);
+        data
+    }
+
+    /// Crea un archivo SND con los sonidos dados.
+    fn snd(sounds: &[((u32, u32), Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0; HEADER_SIZE];
+        data[0..12].copy_from_slice(b"ElecbyteSnd\0");
+        data[12..16].copy_from_slice(&[0, 0, 1, 0]);
+        data[16..20].copy_from_slice(&(sounds.len() as u32).to_le_bytes());
+        data[20..24].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        for (i, ((group, number), wav)) in sounds.iter().enumerate() {
+            let next = if i + 1 == sounds.len() {
+                0
+            } else {
+                (data.len() + SUBFILE_HEADER_SIZE + wav.len()) as u32
+            };
+            data.extend_from_slice(&next.to_le_bytes());
+            data.extend_from_slice(&(wav.len() as u32).to_le_bytes());
+            data.extend_from_slice(&group.to_le_bytes());
+            data.extend_from_slice(&number.to_le_bytes());
+            data.extend_from_slice(wav);
+        }
+        data
+    }
+
+    /// Prueba de decodificación de archivos WAV de 8 y 16 bits
+    #[test]
+    fn test_sound_from_wav() {
+        let sound = Sound::from_wav(&wav(1, 1, 8, &[128, 255, 0])).unwrap();
+        assert_eq!(sound.channels, 1);
+        assert_eq!(sound.sample_rate, 11025);
+        assert_eq!(sound.samples, vec![0, 127 << 8, -128 << 8]);
+        assert_eq!(sound.frames(), 3);
+
+        let pcm: Vec<u8> = [1000i16, -1000, i16::MAX, i16::MIN]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        let sound = Sound::from_wav(&wav(1, 2, 16, &pcm)).unwrap();
+        assert_eq!(sound.channels, 2);
+        assert_eq!(sound.samples, vec![1000, -1000, i16::MAX, i16::MIN]);
+        assert_eq!(sound.frames(), 2);
+    }
+
+    /// Prueba de archivos WAV no soportados
+    #[test]
+    fn test_unsupported_wav() {
+        assert!(matches!(Sound::from_wav(b"RIFF"), Err(SndError::InvalidWav)));
+        assert!(matches!(
+            Sound::from_wav(&wav(2, 1, 16, &[0, 0])),
+            Err(SndError::UnsupportedFormat(2))
+        ));
+        assert!(matches!(
+            Sound::from_wav(&wav(1, 1, 24, &[0, 0, 0])),
+            Err(SndError::UnsupportedBitDepth(24))
+        ));
+        assert!(matches!(
+            Sound::from_wav(&wav(1, 6, 16, &[0; 12])),
+            Err(SndError::UnsupportedChannels(6))
+        ));
+    }
+
+    /// Prueba de lectura de un archivo SND y búsqueda por grupo y número
+    #[test]
+    fn test_read_snd() {
+        let data = snd(&[
+            ((0, 0), wav(1, 1, 8, &[128, 129])),
+            ((5, 1), wav(1, 1, 8, &[130])),
+            ((0, 0), wav(1, 1, 8, &[0])),
+        ]);
+        let bank = Snd::read(&data).unwrap();
+        assert_eq!(bank.sounds.len(), 2);
+        assert_eq!(bank.get_sound((0, 0).into()).unwrap().samples, vec![0, 256]);
+        assert_eq!(bank.get_sound((5, 1).into()).unwrap().samples, vec![512]);
+        assert!(bank.get_sound((5, 2).into()).is_none());
+
+        assert!(bank.get_skipped().is_empty());
+
+        assert!(Snd::read(&snd(&[])).unwrap().sounds.is_empty());
+        assert!(matches!(Snd::read(&[0; 600]), Err(SndError::InvalidSignature)));
+        assert!(matches!(
+            Snd::load_snd(Path::new("INVALIDSND.S.N.D")),
+            Err(SndError::NotFound(_))
+        ));
+    }
+
+    /// Prueba que un sonido no soportado se salta sin impedir leer el resto
+    #[test]
+    fn test_read_snd_skips_bad_sound() {
+        let data = snd(&[
+            ((0, 0), wav(1, 1, 8, &[128])),
+            ((1, 0), wav(1, 1, 24, &[0, 0, 0])),
+            ((2, 0), wav(1, 1, 8, &[129])),
+        ]);
+        let bank = Snd::read(&data).unwrap();
+        assert_eq!(bank.sounds.len(), 2);
+        assert!(bank.get_sound((1, 0).into()).is_none());
+        assert_eq!(bank.get_sound((2, 0).into()).unwrap().samples, vec![256]);
+        assert_eq!(bank.get_skipped(), &[SoundId::from((1, 0))]);
+    }
+}
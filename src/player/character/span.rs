@@ -0,0 +1,249 @@
+use nom::{
+    bytes::complete::{take_till, take_till1},
+    character::complete::{char, digit1, line_ending, not_line_ending, one_of, space0},
+    combinator::{all_consuming, opt, recognize},
+    number::complete::recognize_float,
+    sequence::{delimited, pair},
+    IResult, Slice,
+};
+use nom_locate::LocatedSpan;
+
+/// Texto de un archivo junto con su línea y columna.
+pub type Span<'a> = LocatedSpan<&'a str>;
+
+/// Resultado de los analizadores de nom sobre un `Span`.
+type Res<'a, T> = IResult<Span<'a>, T>;
+
+/// Problemas encontrados al leer un archivo de texto de un personaje.
+#[derive(Debug)]
+pub struct Report<E> {
+    /// Problemas por los que el juego no puede usar el archivo
+    pub errors: Vec<E>,
+    /// Problemas que el juego pasa por alto
+    pub warnings: Vec<E>,
+}
+
+impl<E> Default for Report<E> {
+    /// Crea un informe sin problemas.
+    ///
+    /// # Retorna
+    ///
+    /// Un `Report` vacío.
+    fn default() -> Self {
+        Self {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl<E> Report<E> {
+    /// Comprueba si no se encontró ningún problema.
+    ///
+    /// # Retorna
+    ///
+    /// True si no hay errores ni avisos.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+
+    /// Convierte el informe en el resultado de la lectura, descartando los avisos.
+    ///
+    /// # Argumentos
+    ///
+    /// * `value` - Lo que se leyó del archivo.
+    ///
+    /// # Retorna
+    ///
+    /// El valor leído, o el primer error encontrado.
+    pub fn into_result<T>(self, value: T) -> Result<T, E> {
+        match self.errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Separa un texto en líneas sin el salto de línea ni la marca de UTF-8 del principio.
+///
+/// # Argumentos
+///
+/// * `text` - El texto completo del archivo.
+///
+/// # Retorna
+///
+/// Las líneas con su posición en el archivo.
+pub fn split_lines(text: &str) -> Vec<Span<'_>> {
+    let mut lines = Vec::new();
+    let mut input = Span::new(text.strip_prefix('\u{feff}').unwrap_or(text));
+    loop {
+        let result: Res<Span> = not_line_ending(input);
+        let Ok((rest, line)) = result else {
+            lines.push(input);
+            break;
+        };
+        lines.push(line);
+        let result: Res<Span> = line_ending(rest);
+        match result {
+            Ok((rest, _)) => input = rest,
+            Err(_) => break,
+        }
+    }
+    lines
+}
+
+/// Quita los espacios al principio y al final de un texto.
+///
+/// # Argumentos
+///
+/// * `span` - El texto.
+///
+/// # Retorna
+///
+/// El texto sin espacios, con la posición del primer carácter que no es un espacio.
+pub fn trim(span: Span) -> Span {
+    let result: Res<Span> = space0(span);
+    let span = result.map_or(span, |(span, _)| span);
+    span.slice(..span.fragment().trim_end().len())
+}
+
+/// Obtiene el contenido de una línea sin el comentario ni los espacios.
+///
+/// # Argumentos
+///
+/// * `line` - La línea.
+///
+/// # Retorna
+///
+/// El contenido, vacío si la línea solo tiene un comentario.
+pub fn content(line: Span) -> Span {
+    let result: Res<Span> = take_till(|c| c == ';')(line);
+    trim(result.map_or(line, |(_, text)| text))
+}
+
+/// Lee el nombre de una sección, como `Begin Action 0` en `[Begin Action 0]`.
+///
+/// # Argumentos
+///
+/// * `line` - El contenido de la línea.
+///
+/// # Retorna
+///
+/// El nombre de la sección, o `None` si la línea no es una sección.
+pub fn section(line: Span) -> Option<Span> {
+    let result: Res<Span> = delimited(char('['), take_till(|c| c == ']'), char(']'))(line);
+    result.ok().map(|(_, name)| trim(name))
+}
+
+/// Separa una línea `clave = valor`.
+///
+/// # Argumentos
+///
+/// * `line` - El contenido de la línea.
+///
+/// # Retorna
+///
+/// La clave y el valor sin espacios, o `None` si la línea no tiene `=`.
+pub fn key_value(line: Span) -> Option<(Span, Span)> {
+    let result: Res<(Span, char)> = pair(take_till1(|c| c == '='), char('='))(line);
+    let (value, (key, _)) = result.ok()?;
+    Some((trim(key), trim(value)))
+}
+
+/// Separa los valores de una lista.
+///
+/// # Argumentos
+///
+/// * `span` - La lista.
+/// * `separator` - El carácter que separa los valores, como `,` o `+`.
+///
+/// # Retorna
+///
+/// Cada valor sin espacios, vacío si no hay nada entre dos separadores.
+pub fn fields(span: Span, separator: char) -> Vec<Span> {
+    let mut fields = Vec::new();
+    let mut rest = span;
+    loop {
+        let result: Res<Span> = take_till(|c| c == separator)(rest);
+        let Ok((after, field)) = result else {
+            break;
+        };
+        fields.push(trim(field));
+        let result: Res<char> = char(separator)(after);
+        match result {
+            Ok((after, _)) => rest = after,
+            Err(_) => break,
+        }
+    }
+    fields
+}
+
+/// Lee un número entero que ocupa todo el texto.
+///
+/// # Argumentos
+///
+/// * `span` - El texto.
+///
+/// # Retorna
+///
+/// El número, o `None` si el texto no es un entero.
+pub fn integer(span: Span) -> Option<i32> {
+    let result: Res<Span> = all_consuming(recognize(pair(opt(one_of("+-")), digit1)))(span);
+    result.ok()?.1.fragment().parse().ok()
+}
+
+/// Lee un número decimal que ocupa todo el texto.
+///
+/// # Argumentos
+///
+/// * `span` - El texto.
+///
+/// # Retorna
+///
+/// El número, o `None` si el texto no es un número.
+pub fn float(span: Span) -> Option<f64> {
+    let result: Res<Span> = all_consuming(recognize_float)(span);
+    result.ok()?.1.fragment().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba que las líneas, las listas y los números conservan su posición
+    #[test]
+    fn test_spans() {
+        let lines = split_lines("\u{feff}a\r\n  b = 1, x ; nota\n");
+        assert_eq!(lines[0].get_utf8_column(), 1);
+        assert_eq!(lines.len(), 3);
+        let line = content(lines[1]);
+        assert_eq!(*line.fragment(), "b = 1, x");
+        assert_eq!((line.location_line(), line.get_utf8_column()), (2, 3));
+
+        let (key, value) = key_value(line).unwrap();
+        assert_eq!(*key.fragment(), "b");
+        let values = fields(value, ',');
+        assert_eq!(values[1].get_utf8_column(), 10);
+        assert_eq!(integer(values[0]), Some(1));
+        assert_eq!(integer(values[1]), None);
+        assert_eq!(float(Span::new("-1.5")), Some(-1.5));
+        assert_eq!(
+            section(Span::new("[ Begin Action 5 ]")).unwrap().fragment(),
+            &"Begin Action 5"
+        );
+        assert_eq!(section(Span::new("Begin")), None);
+    }
+
+    /// Prueba que el informe devuelva el primer error y descarte los avisos
+    #[test]
+    fn test_report() {
+        let mut report: Report<&str> = Report::default();
+        assert!(report.is_empty());
+        report.warnings.push("aviso");
+        assert!(!report.is_empty());
+        report.errors.push("primero");
+        report.errors.push("segundo");
+        assert_eq!(report.into_result(1), Err("primero"));
+        assert_eq!(Report::<&str>::default().into_result(1), Ok(1));
+    }
+}
@@ -0,0 +1,383 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use piston::Key;
+
+use crate::{error::config_error::ConfigError, CK};
+
+/// Archivo con las teclas de los jugadores.
+pub const KEYS_CFG: &str = "config/keys.cfg";
+
+/// Acciones que se pueden asignar a una tecla, con su nombre en el archivo de configuración.
+///
+/// Los nombres son los de MUGEN: `Left` y `Right` son atrás y adelante con el personaje mirando a
+/// la derecha, y los botones `x`, `y`, `z` son los puños y `a`, `b`, `c` las patadas.
+pub const BINDINGS: [(&str, CK); 11] = [
+    ("Jump", CK::U),
+    ("Crouch", CK::D),
+    ("Left", CK::B),
+    ("Right", CK::F),
+    ("A", CK::LK),
+    ("B", CK::MK),
+    ("C", CK::HK),
+    ("X", CK::LP),
+    ("Y", CK::MP),
+    ("Z", CK::HP),
+    ("Start", CK::Start),
+];
+
+/// Estructura que representa el mapeo de teclas para el jugador.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyMap {
+    /// Tecla de cada acción, con `F` como derecha y `B` como izquierda
+    keys: BTreeMap<CK, Key>,
+}
+
+impl KeyMap {
+    /// Crea el mapeo de teclas por defecto de un jugador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `first_player` - Indica si el mapeo es del primer jugador.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `KeyMap`.
+    pub fn new(first_player: bool) -> Self {
+        let keys = if first_player {
+            [
+                Key::Up,
+                Key::Down,
+                Key::Left,
+                Key::Right,
+                Key::Z,
+                Key::X,
+                Key::C,
+                Key::A,
+                Key::S,
+                Key::D,
+                Key::RShift,
+            ]
+        } else {
+            [
+                Key::I,
+                Key::K,
+                Key::J,
+                Key::L,
+                Key::F,
+                Key::G,
+                Key::H,
+                Key::R,
+                Key::T,
+                Key::Y,
+                Key::RCtrl,
+            ]
+        };
+        Self {
+            keys: BINDINGS
+                .iter()
+                .zip(keys)
+                .map(|(&(_, action), key)| (action, key))
+                .collect(),
+        }
+    }
+
+    /// Obtiene la tecla asignada a una acción.
+    ///
+    /// # Argumentos
+    ///
+    /// * `action` - La acción, una de las de `BINDINGS`.
+    ///
+    /// # Retorna
+    ///
+    /// La tecla asignada, o `Key::Unknown` si la acción no se puede asignar.
+    pub fn get_key(&self, action: CK) -> Key {
+        self.keys.get(&action).copied().unwrap_or(Key::Unknown)
+    }
+
+    /// Asigna una tecla a una acción.
+    ///
+    /// # Argumentos
+    ///
+    /// * `action` - La acción, una de las de `BINDINGS`.
+    /// * `key` - La nueva tecla.
+    pub fn set_key(&mut self, action: CK, key: Key) {
+        if let Some(current) = self.keys.get_mut(&action) {
+            *current = key;
+        }
+    }
+
+    /// Traduce una tecla del teclado a su acción.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla del teclado.
+    ///
+    /// # Retorna
+    ///
+    /// La acción, con la derecha como adelante, o `None` si la tecla no está asignada.
+    pub fn translate(&self, key: &Key) -> Option<CK> {
+        self.keys
+            .iter()
+            .find(|(_, bound)| *bound == key)
+            .map(|(action, _)| *action)
+    }
+}
+
+/// Obtiene el nombre con el que se guarda y se muestra una tecla.
+///
+/// # Argumentos
+///
+/// * `key` - La tecla.
+///
+/// # Retorna
+///
+/// El nombre de la tecla.
+pub fn key_name(key: Key) -> String {
+    format!("{:?}", key)
+}
+
+/// Interpreta el nombre o el código de una tecla.
+///
+/// # Argumentos
+///
+/// * `value` - El nombre de la tecla, sin distinguir mayúsculas, o su código numérico.
+///
+/// # Retorna
+///
+/// La tecla, o `None` si no existe.
+fn parse_key(value: &str) -> Option<Key> {
+    if let Ok(code) = value.parse::<u32>() {
+        return Some(Key::from(code)).filter(|key| *key != Key::Unknown);
+    }
+    // Los códigos de las teclas son los de SDL: ASCII y las teclas especiales desde 0x40000039
+    (0..0x80)
+        .chain(0x4000_0039..=0x4000_011A)
+        .map(Key::from)
+        .filter(|key| *key != Key::Unknown)
+        .find(|key| key_name(*key).eq_ignore_ascii_case(value))
+}
+
+/// Busca las asignaciones que comparten tecla con otra, de cualquiera de los dos jugadores.
+///
+/// # Argumentos
+///
+/// * `key_maps` - Los mapeos de los dos jugadores.
+///
+/// # Retorna
+///
+/// El jugador y la acción de cada asignación en conflicto.
+pub fn find_conflicts(key_maps: &[KeyMap; 2]) -> Vec<(usize, CK)> {
+    let bound: Vec<(usize, CK, Key)> = key_maps
+        .iter()
+        .enumerate()
+        .flat_map(|(player, key_map)| {
+            key_map
+                .keys
+                .iter()
+                .map(move |(action, key)| (player, *action, *key))
+        })
+        .collect();
+    bound
+        .iter()
+        .filter(|(player, action, key)| {
+            bound.iter().any(|(other, other_action, other_key)| {
+                other_key == key && (other, other_action) != (player, action)
+            })
+        })
+        .map(|(player, action, _)| (*player, *action))
+        .collect()
+}
+
+/// Carga los mapeos de teclas de los dos jugadores.
+///
+/// El archivo tiene una sección `[P1 Keys]` y otra `[P2 Keys]` con una línea
+/// `<acción> = <tecla>` por asignación; `;` inicia un comentario. Las acciones que no aparecen
+/// conservan su tecla por defecto.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo de configuración.
+///
+/// # Retorna
+///
+/// Los mapeos del jugador 1 y 2, o un error si el archivo no existe, está malformado o asigna
+/// una tecla más de una vez.
+pub fn load_key_maps(path: &str) -> Result<[KeyMap; 2], ConfigError> {
+    if !Path::new(path).exists() {
+        return Err(ConfigError::NotFound(path.to_string()));
+    }
+    let content = fs::read_to_string(path)?;
+    let mut key_maps = [KeyMap::new(true), KeyMap::new(false)];
+    let mut player = None;
+    for (i, line) in content.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            let header = line
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim()
+                .to_lowercase();
+            player = match header.as_str() {
+                "p1 keys" => Some(0),
+                "p2 keys" => Some(1),
+                _ => None,
+            };
+            continue;
+        }
+        let Some(player) = player else {
+            continue;
+        };
+        let (name, value) = line.split_once('=').ok_or(ConfigError::Malformed(i + 1))?;
+        let action = BINDINGS
+            .iter()
+            .find(|(binding, _)| binding.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, action)| *action)
+            .ok_or(ConfigError::Malformed(i + 1))?;
+        let key = parse_key(value.trim()).ok_or(ConfigError::Malformed(i + 1))?;
+        key_maps[player].set_key(action, key);
+    }
+    if let Some(&(player, action)) = find_conflicts(&key_maps).first() {
+        return Err(ConfigError::KeyConflict(key_name(
+            key_maps[player].get_key(action),
+        )));
+    }
+    Ok(key_maps)
+}
+
+/// Guarda los mapeos de teclas de los dos jugadores, creando la carpeta si no existe.
+///
+/// # Argumentos
+///
+/// * `path` - Ruta del archivo de configuración.
+/// * `key_maps` - Los mapeos del jugador 1 y 2.
+///
+/// # Retorna
+///
+/// Un error si los mapeos tienen conflictos o no se pudo escribir el archivo.
+pub fn save_key_maps(path: &str, key_maps: &[KeyMap; 2]) -> Result<(), ConfigError> {
+    if let Some(&(player, action)) = find_conflicts(key_maps).first() {
+        return Err(ConfigError::KeyConflict(key_name(
+            key_maps[player].get_key(action),
+        )));
+    }
+    let mut content = String::from("; Teclas de cada jugador, por nombre o por código\n");
+    for (player, key_map) in key_maps.iter().enumerate() {
+        content.push_str(&format!("\n[P{} Keys]\n", player + 1));
+        for (name, action) in BINDINGS {
+            content.push_str(&format!(
+                "{} = {}\n",
+                name,
+                key_name(key_map.get_key(action))
+            ));
+        }
+    }
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba de los mapeos por defecto
+    #[test]
+    fn test_default_key_maps() {
+        let key_maps = [KeyMap::new(true), KeyMap::new(false)];
+        assert_eq!(key_maps[0].translate(&Key::A), Some(CK::LP));
+        assert_eq!(key_maps[0].translate(&Key::Right), Some(CK::F));
+        assert_eq!(key_maps[1].translate(&Key::F), Some(CK::LK));
+        assert_eq!(key_maps[1].translate(&Key::A), None);
+        assert_eq!(key_maps[1].get_key(CK::Start), Key::RCtrl);
+        assert_eq!(key_maps[1].get_key(CK::DF), Key::Unknown);
+        assert!(find_conflicts(&key_maps).is_empty());
+    }
+
+    /// Prueba de la lectura de nombres y códigos de teclas
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("RShift"), Some(Key::RShift));
+        assert_eq!(parse_key("rshift"), Some(Key::RShift));
+        assert_eq!(parse_key("D1"), Some(Key::D1));
+        assert_eq!(parse_key("F12"), Some(Key::F12));
+        assert_eq!(parse_key("97"), Some(Key::A));
+        assert_eq!(parse_key("Unknown"), None);
+        assert_eq!(parse_key("NoKey"), None);
+        for (_, action) in BINDINGS {
+            let key = KeyMap::new(true).get_key(action);
+            assert_eq!(parse_key(&key_name(key)), Some(key));
+        }
+    }
+
+    /// Prueba de conflictos dentro de un jugador y entre los dos
+    #[test]
+    fn test_find_conflicts() {
+        let mut key_maps = [KeyMap::new(true), KeyMap::new(false)];
+        key_maps[1].set_key(CK::HP, Key::A);
+        assert_eq!(find_conflicts(&key_maps), [(0, CK::LP), (1, CK::HP)]);
+
+        let mut key_maps = [KeyMap::new(true), KeyMap::new(false)];
+        key_maps[0].set_key(CK::MK, Key::Z);
+        assert_eq!(find_conflicts(&key_maps), [(0, CK::LK), (0, CK::MK)]);
+    }
+
+    /// Prueba de guardar y volver a cargar los mapeos
+    #[test]
+    fn test_save_and_load_key_maps() {
+        let path = std::env::temp_dir()
+            .join("rust_fg_test_keys")
+            .join("keys.cfg");
+        let path = path.to_str().unwrap();
+        let mut key_maps = [KeyMap::new(true), KeyMap::new(false)];
+        key_maps[0].set_key(CK::LP, Key::Q);
+        key_maps[1].set_key(CK::U, Key::NumPad8);
+        save_key_maps(path, &key_maps).unwrap();
+        assert_eq!(load_key_maps(path).unwrap(), key_maps);
+
+        key_maps[1].set_key(CK::D, Key::Q);
+        assert!(matches!(
+            save_key_maps(path, &key_maps),
+            Err(ConfigError::KeyConflict(key)) if key == "Q"
+        ));
+        std::fs::remove_dir_all(Path::new(path).parent().unwrap()).unwrap();
+        assert!(matches!(load_key_maps(path), Err(ConfigError::NotFound(_))));
+    }
+
+    /// Prueba de la lectura de un archivo con teclas parciales, comentarios y errores
+    #[test]
+    fn test_load_key_maps() {
+        let path = std::env::temp_dir().join("rust_fg_test_load_keys.cfg");
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "[Options]\nDifficulty = 4\n\n[P2 Keys] ; 8 del teclado numérico por código\nJump = 1073741920\nx = q\n",
+        )
+        .unwrap();
+        let key_maps = load_key_maps(path).unwrap();
+        assert_eq!(key_maps[0], KeyMap::new(true));
+        assert_eq!(key_maps[1].get_key(CK::U), Key::NumPad8);
+        assert_eq!(key_maps[1].get_key(CK::LP), Key::Q);
+        assert_eq!(key_maps[1].get_key(CK::MP), Key::T);
+
+        std::fs::write(path, "[P1 Keys]\nJump = Up\nPunch = A\n").unwrap();
+        assert!(matches!(
+            load_key_maps(path),
+            Err(ConfigError::Malformed(3))
+        ));
+        std::fs::write(path, "[P1 Keys]\nJump\n").unwrap();
+        assert!(matches!(
+            load_key_maps(path),
+            Err(ConfigError::Malformed(2))
+        ));
+        std::fs::write(path, "[P1 Keys]\nJump = Left\n").unwrap();
+        assert!(matches!(
+            load_key_maps(path),
+            Err(ConfigError::KeyConflict(key)) if key == "Left"
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,7 +1,12 @@
 use crate::{player::character::cmd, CK};
 
-use cmd::manager::CommandNode;
-use std::mem;
+use cmd::manager::CommandList;
+use std::collections::{HashMap, HashSet};
+
+/// Frames que se guardan en el historial de entrada.
+const HISTORY_SIZE: usize = 120;
+/// Frames máximos entre los dos toques de una carrera.
+const DASH_TIME: usize = 15;
 
 /// Representa diferentes comandos de ataque y sus valores asociados.
 ///
@@ -50,6 +55,20 @@ enum Direction {
     DB = 15,
 }
 
+/// Política para resolver direcciones opuestas presionadas a la vez (SOCD).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Socd {
+    /// Adelante y atrás, o arriba y abajo, se anulan y quedan en neutro.
+    #[default]
+    Neutral,
+    /// Gana la última dirección presionada de cada eje.
+    LastInputWins,
+    /// Arriba gana a abajo y entre adelante y atrás gana la última presionada.
+    UpPriority,
+    /// Como un Hitbox: arriba gana a abajo y adelante y atrás quedan en neutro.
+    Hitbox,
+}
+
 /// Almacena el estado de entrada de un jugador.
 ///
 /// Esta estructura contiene el estado de entrada actual de un jugador, indicando si
@@ -85,48 +104,77 @@ pub struct PlayerInput {
     pub db: bool,
     /// Estado de la tecla de Inicio o Provocar
     pub start: bool,
+    /// Política para las direcciones opuestas
+    socd: Socd,
+    /// Última dirección horizontal presionada, `F` o `B`
+    last_horizontal: CK,
+    /// Última dirección vertical presionada, `U` o `D`
+    last_vertical: CK,
 }
 
-/// Representa una tecla de entrada junto con su tiempo de almacenamiento en búfer.
+/// Teclas presionadas durante un frame.
 ///
-/// Esta estructura contiene una tecla de entrada junto con el tiempo que ha estado almacenada en búfer.
-/// Se utiliza para rastrear la duración durante la cual se ha mantenido presionada una tecla particular.
-#[derive(Debug, Clone, Copy)]
-pub struct InputKey {
-    /// Tecla de comando asociada con la entrada
-    cmd_key: CK,
-    /// Tiempo por el cual la entrada ha estado almacenada en búfer
-    buff_time: u128,
+/// Contiene como mucho una dirección, ya resuelta a partir de las teclas direccionales, y los
+/// botones presionados.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputFrame {
+    /// Lista de teclas presionadas
+    keys: Vec<CK>,
+    /// Frames seguidos que lleva presionada cada tecla, incluido este
+    charges: HashMap<CK, u32>,
+    /// Frames seguidos que lleva presionada cada dirección o una de sus diagonales, incluido este
+    direction_charges: HashMap<CK, u32>,
+    /// Teclas que se presionaron desde el frame anterior
+    pressed: Vec<CK>,
+    /// Teclas que se soltaron desde el frame anterior
+    released: Vec<CK>,
 }
 
-/// Representa una secuencia de comandos de entrada.
+/// Gestiona la entrada del jugador y el reconocimiento de comandos.
 ///
-/// Esta estructura representa una secuencia de comandos de entrada que se han almacenado en búfer,
-/// formando una entrada de comando. Contiene una lista de teclas de entrada, una ventana de entrada
-/// que indica la duración máxima durante la cual la entrada es válida, y una bandera para
-/// rastrear si la entrada ha sido procesada.
-#[derive(Clone, Debug)]
-pub struct CommandInput {
-    /// Lista de teclas de entrada en la secuencia
-    keys: Vec<InputKey>,
-    /// Duración máxima durante la cual la entrada es válida
-    input_window: u16,
-    // Bandera que indica si la entrada ha sido procesada
-    walked: bool,
-    /// Indica si se ha encontrado la entrada
-    found: bool,
-}
-
-/// Gestiona la entrada del jugador y el almacenamiento de comandos.
-///
-/// Esta estructura maneja la entrada del jugador y administra el almacenamiento de comandos de entrada.
-/// Mantiene el estado de entrada actual del jugador y almacena un búfer de
-/// comandos de entrada para su procesamiento.
+/// Esta estructura guarda el estado de entrada actual del jugador, un historial con las teclas de
+/// cada frame y los comandos que están activos.
 pub struct InputManager {
     /// Estado de entrada del jugador
     pub player_input: PlayerInput,
-    /// Búfer para almacenar los comandos de entrada
-    input_buffer: Vec<CommandInput>,
+    /// Teclas de los últimos frames, del más antiguo al actual
+    history: Vec<InputFrame>,
+    /// Comandos activos y los frames que les quedan
+    active_commands: Vec<(String, u16)>,
+    /// Botones presionados desde el último frame
+    pressed: Vec<CK>,
+    /// Botones soltados desde el último frame
+    released: Vec<CK>,
+}
+
+impl Socd {
+    /// Pasa a la siguiente política, volviendo a la primera después de la última.
+    ///
+    /// # Retorna
+    ///
+    /// La política siguiente.
+    pub fn next(self) -> Self {
+        match self {
+            Socd::Neutral => Socd::LastInputWins,
+            Socd::LastInputWins => Socd::UpPriority,
+            Socd::UpPriority => Socd::Hitbox,
+            Socd::Hitbox => Socd::Neutral,
+        }
+    }
+
+    /// Obtiene el nombre de la política para mostrarlo en pantalla.
+    ///
+    /// # Retorna
+    ///
+    /// El nombre de la política.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Socd::Neutral => "Neutral",
+            Socd::LastInputWins => "Last input",
+            Socd::UpPriority => "Up priority",
+            Socd::Hitbox => "Hitbox",
+        }
+    }
 }
 
 impl PlayerInput {
@@ -152,20 +200,19 @@ impl PlayerInput {
             df: false,
             db: false,
             start: false,
+            socd: Socd::default(),
+            last_horizontal: CK::F,
+            last_vertical: CK::U,
         }
     }
 
-    /// Limpia todas las acciones del jugador.
+    /// Establece la política para las direcciones opuestas.
+    ///
+    /// # Argumentos
     ///
-    /// Restablece todos los estados de teclas de acción a `false`.
-    fn clear_action(&mut self) {
-        self.lp = false;
-        self.mp = false;
-        self.hp = false;
-        self.lk = false;
-        self.mk = false;
-        self.hk = false;
-        self.start = false;
+    /// * `socd` - La política a usar.
+    pub fn set_socd(&mut self, socd: Socd) {
+        self.socd = socd;
     }
 
     /// Establece el estado de una tecla especificada.
@@ -230,60 +277,74 @@ impl PlayerInput {
             }
             _ => return false, // Other cases are not considered
         }
+        if state && !result {
+            match symbol {
+                "f" => self.last_horizontal = CK::F,
+                "b" => self.last_horizontal = CK::B,
+                "u" => self.last_vertical = CK::U,
+                "d" => self.last_vertical = CK::D,
+                _ => (),
+            }
+        }
         result
     }
 
-    /// Recupera el estado de una tecla de comando especificada.
-    ///
-    /// Devuelve el estado actual de la tecla de comando especificada.
-    ///
-    /// # Argumentos
-    ///
-    /// * `cmd_key` - Una referencia a la tecla de comando cuyo estado se va a recuperar.
+    /// Cambia adelante por atrás, cuando el personaje se da la vuelta.
+    fn flip(&mut self) {
+        std::mem::swap(&mut self.f, &mut self.b);
+        self.last_horizontal = match self.last_horizontal {
+            CK::F => CK::B,
+            _ => CK::F,
+        };
+    }
+
+    /// Resuelve las direcciones opuestas según la política SOCD.
     ///
     /// # Retorna
     ///
-    /// El estado actual de la tecla de comando especificada.
-    fn get_state(&self, cmd_key: &CK) -> bool {
-        match cmd_key {
-            CK::DB => self.db,
-            CK::D => self.d,
-            CK::DF => self.df,
-            CK::B => self.b,
-            CK::F => self.f,
-            CK::UB => self.ub,
-            CK::U => self.u,
-            CK::UF => self.uf,
-            CK::LP => self.lp,
-            CK::MP => self.mp,
-            CK::HP => self.hp,
-            CK::LK => self.lk,
-            CK::MK => self.mk,
-            CK::HK => self.hk,
-            CK::Start => self.start,
+    /// El estado de adelante, atrás, arriba y abajo una vez resueltos, en ese orden.
+    fn clean_directions(&self) -> (bool, bool, bool, bool) {
+        let (mut f, mut b, mut u, mut d) = (self.f, self.b, self.u, self.d);
+        if f && b {
+            (f, b) = match self.socd {
+                Socd::Neutral | Socd::Hitbox => (false, false),
+                Socd::LastInputWins | Socd::UpPriority => {
+                    (self.last_horizontal == CK::F, self.last_horizontal == CK::B)
+                }
+            };
         }
+        if u && d {
+            (u, d) = match self.socd {
+                Socd::Neutral => (false, false),
+                Socd::LastInputWins => (self.last_vertical == CK::U, self.last_vertical == CK::D),
+                Socd::UpPriority | Socd::Hitbox => (true, false),
+            };
+        }
+        (f, b, u, d)
     }
 
     /// Convierte la entrada del jugador a una representación de bits.
     ///
     /// Convierte el estado actual de la entrada del jugador en una representación de 16 bits
     /// donde cada bit corresponde al estado de una tecla específica o una entrada direccional.
+    /// Las direcciones opuestas se resuelven antes según la política SOCD.
     ///
     /// # Retorna
     ///
     /// El estado actual de la entrada del jugador en 16 bits.
     fn to_bits(&self) -> u16 {
         let mut result = 0;
-        if self.f {
+        let (f, b, u, d) = self.clean_directions();
+        if f {
             result += 5
         };
-        if self.u {
+        if u {
             result += 2
         };
-        if self.b {
+        if b {
             result += 6
         };
-        if self.d {
+        if d {
             result += 9
         };
         if self.ub {
@@ -321,81 +382,269 @@ impl PlayerInput {
         };
         result
     }
+
+    /// Obtiene las teclas presionadas, con la dirección ya resuelta.
+    ///
+    /// # Retorna
+    ///
+    /// Los botones presionados seguidos de la dirección, si hay alguna.
+    fn get_keys(&self) -> Vec<CK> {
+        let player_input = self.to_bits();
+        let mut keys = Vec::new();
+
+        let actions = [
+            (Action::LP, CK::LP),
+            (Action::MP, CK::MP),
+            (Action::HP, CK::HP),
+            (Action::LK, CK::LK),
+            (Action::MK, CK::MK),
+            (Action::HK, CK::HK),
+            (Action::Start, CK::Start),
+        ];
+
+        for (action, command_key) in &actions {
+            if player_input & (*action as u16) != 0 {
+                keys.push(*command_key);
+            }
+        }
+
+        let last_bits = player_input & 0b1111;
+
+        let directions = [
+            (Direction::U as u16, CK::U),
+            (Direction::F as u16, CK::F),
+            (Direction::D as u16, CK::D),
+            (Direction::B as u16, CK::B),
+            (Direction::UF as u16, CK::UF),
+            (Direction::UB as u16, CK::UB),
+            (Direction::DF as u16, CK::DF),
+            (Direction::DB as u16, CK::DB),
+        ];
+
+        for (bits, command_key) in &directions {
+            if last_bits == *bits {
+                keys.push(*command_key);
+                break;
+            }
+        }
+        keys
+    }
 }
 
-impl InputKey {
-    /// Crea una nueva instancia de `InputKey` con la tecla de comando especificada.
+impl InputFrame {
+    /// Crea un frame con las teclas dadas.
     ///
-    /// Devuelve una nueva instancia de `InputKey` inicializada con la tecla de comando proporcionada
-    /// y un tiempo de búfer de cero.
+    /// La carga de cada tecla continúa la del frame anterior si la tecla sigue presionada, y las
+    /// teclas presionadas y soltadas son las que cambian respecto al frame anterior.
     ///
     /// # Argumentos
     ///
-    /// * `key` - La tecla de comando asociada con la entrada.
+    /// * `keys` - Las teclas presionadas durante el frame.
+    /// * `previous` - El frame anterior, si lo hay.
     ///
     /// # Retorna
-    /// Una nueva instancia de `InputKey` por defecto usando la tecla designada.
-    fn new(key: CK) -> Self {
-        Self {
-            cmd_key: key,
-            buff_time: 0,
+    ///
+    /// Una nueva instancia de `InputFrame`.
+    pub fn new(keys: Vec<CK>, previous: Option<&InputFrame>) -> Self {
+        let mut frame = Self {
+            keys,
+            ..Default::default()
+        };
+        for key in &frame.keys {
+            let charge = previous.map_or(0, |previous| previous.get_charge(key, false));
+            frame.charges.insert(*key, charge + 1);
         }
+        for key in [CK::F, CK::B, CK::U, CK::D] {
+            if frame.holds(&key, true) {
+                let charge = previous.map_or(0, |previous| previous.get_charge(&key, true));
+                frame.direction_charges.insert(key, charge + 1);
+            }
+        }
+        let previous_keys = previous.map_or(&[][..], |previous| previous.keys.as_slice());
+        frame.pressed = frame
+            .keys
+            .iter()
+            .filter(|key| !previous_keys.contains(key))
+            .copied()
+            .collect();
+        frame.released = previous_keys
+            .iter()
+            .filter(|key| !frame.keys.contains(key))
+            .copied()
+            .collect();
+        frame
     }
 
-    /// Actualiza el tiempo de búfer de la tecla de entrada.
+    /// Añade al frame las teclas presionadas y soltadas entre este frame y el anterior.
+    ///
+    /// Así no se pierden las pulsaciones más cortas que un frame ni las que se sueltan y se
+    /// vuelven a presionar, que empiezan a cargar de nuevo.
+    ///
+    /// # Argumentos
     ///
-    /// Incrementa el tiempo de búfer de la tecla de entrada en una unidad.
-    fn update(&mut self) {
-        self.buff_time += 1;
+    /// * `pressed` - Teclas presionadas desde el frame anterior.
+    /// * `released` - Teclas soltadas desde el frame anterior.
+    fn add_events(&mut self, pressed: &[CK], released: &[CK]) {
+        for key in pressed {
+            if !self.pressed.contains(key) {
+                self.pressed.push(*key);
+            }
+        }
+        for key in released {
+            if !self.released.contains(key) {
+                self.released.push(*key);
+            }
+            if self.keys.contains(key) {
+                self.charges.insert(*key, 1);
+            }
+        }
     }
 
-    /// Recupera una referencia a la tecla de comando asociada con la entrada.
+    /// Indica si una tecla está presionada en el frame.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla a comprobar.
     ///
     /// # Retorna
     ///
-    /// Una referencia a la tecla de comando asociada con la entrada.
-    pub fn get_cmd_key_ref(&self) -> &CK {
-        &self.cmd_key
+    /// `true` si la tecla está presionada, de lo contrario `false`.
+    pub fn contains(&self, key: &CK) -> bool {
+        self.keys.contains(key)
     }
-}
 
-impl CommandInput {
-    /// Crea una nueva instancia de `CommandInput`.
+    /// Indica si una tecla está presionada en el frame, opcionalmente contando las diagonales.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla a comprobar.
+    /// * `diagonals` - Si las diagonales cuentan para las direcciones, como hace `$` en los comandos.
     ///
     /// # Retorna
-    /// Una nueva instancia de `CommandInput` inicalizada por defecto
-    pub fn new() -> Self {
-        Self {
-            keys: Vec::new(),
-            input_window: 0,
-            walked: false,
-            found: false,
+    ///
+    /// `true` si la tecla, o una de sus diagonales, está presionada, de lo contrario `false`.
+    pub fn holds(&self, key: &CK, diagonals: bool) -> bool {
+        let with_diagonals: &[CK] = match key {
+            CK::F => &[CK::UF, CK::DF],
+            CK::B => &[CK::UB, CK::DB],
+            CK::U => &[CK::UF, CK::UB],
+            CK::D => &[CK::DF, CK::DB],
+            _ => &[],
+        };
+        self.contains(key) || (diagonals && with_diagonals.iter().any(|key| self.contains(key)))
+    }
+
+    /// Obtiene los frames seguidos que lleva presionada una tecla.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla a comprobar.
+    /// * `diagonals` - Si las diagonales cuentan para las direcciones.
+    ///
+    /// # Retorna
+    ///
+    /// Los frames que lleva presionada hasta este, incluido, o 0 si no está presionada.
+    pub fn get_charge(&self, key: &CK, diagonals: bool) -> u32 {
+        let charges = match key {
+            CK::F | CK::B | CK::U | CK::D if diagonals => &self.direction_charges,
+            _ => &self.charges,
+        };
+        charges.get(key).copied().unwrap_or(0)
+    }
+
+    /// Indica si una tecla se presionó en este frame.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla a comprobar.
+    /// * `diagonals` - Si las diagonales cuentan para las direcciones, así pasar de una diagonal a
+    ///   la dirección no es presionarla de nuevo.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si la tecla se presionó desde el frame anterior, de lo contrario `false`.
+    pub fn was_pressed(&self, key: &CK, diagonals: bool) -> bool {
+        match key {
+            CK::F | CK::B | CK::U | CK::D if diagonals => self.get_charge(key, true) == 1,
+            _ => self.pressed.contains(key),
         }
     }
 
-    /// Recupera una referencia a la duración de la ventana de entrada.
+    /// Indica si se presionó alguna tecla en este frame.
     ///
     /// # Retorna
-    /// Una referencia a la duración de la ventana de entrada, que indica la duración máxima
-    /// durante la cual la entrada es válida.
-    pub fn get_input_window_ref(&self) -> &u16 {
-        &self.input_window
+    ///
+    /// `true` si hay teclas presionadas desde el frame anterior, de lo contrario `false`.
+    pub fn has_presses(&self) -> bool {
+        !self.pressed.is_empty()
     }
 
-    /// Recupera una referencia a la lista de teclas de entrada.
+    /// Indica si se soltó en este frame alguna tecla distinta de las indicadas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `keys` - Las teclas que se pueden soltar.
+    /// * `diagonals` - Si soltar una diagonal cuenta como soltar sus direcciones.
     ///
     /// # Retorna
-    /// Una referencia a la lista de `keys` de entrada almacenadas en la instancia de `CommandInput`.
-    pub fn get_keys_ref(&self) -> &Vec<InputKey> {
-        &self.keys
+    ///
+    /// `true` si se soltó alguna otra tecla desde el frame anterior, de lo contrario `false`.
+    pub fn has_other_releases(&self, keys: &HashSet<CK>, diagonals: bool) -> bool {
+        self.released.iter().any(|released| {
+            let released = InputFrame::new(vec![*released], None);
+            !keys.iter().any(|key| released.holds(key, diagonals))
+        })
     }
 
-    /// Establece el estado de `found`.
+    /// Indica si una tecla se soltó en este frame.
     ///
     /// # Argumentos
-    /// * `bool` - El nuevo estado de `found`.
-    pub fn set_found(&mut self, bool: bool) {
-        self.found = bool;
+    ///
+    /// * `key` - La tecla a comprobar.
+    /// * `diagonals` - Si las diagonales cuentan para las direcciones.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si la tecla se soltó desde el frame anterior, de lo contrario `false`.
+    pub fn was_released(&self, key: &CK, diagonals: bool) -> bool {
+        let released = InputFrame::new(self.released.clone(), None);
+        !self.holds(key, diagonals) && released.holds(key, diagonals)
+    }
+
+    /// Recupera una referencia a la lista de teclas presionadas.
+    ///
+    /// # Retorna
+    ///
+    /// Una referencia a las `keys` del frame.
+    pub fn get_keys_ref(&self) -> &Vec<CK> {
+        &self.keys
+    }
+
+    /// Intercambia adelante y atrás en las teclas y las cargas del frame.
+    fn flip(&mut self) {
+        let flip = |key: CK| match key {
+            CK::B => CK::F,
+            CK::F => CK::B,
+            CK::UB => CK::UF,
+            CK::UF => CK::UB,
+            CK::DB => CK::DF,
+            CK::DF => CK::DB,
+            other => other,
+        };
+        for key in self
+            .keys
+            .iter_mut()
+            .chain(self.pressed.iter_mut())
+            .chain(self.released.iter_mut())
+        {
+            *key = flip(*key);
+        }
+        for charges in [&mut self.charges, &mut self.direction_charges] {
+            *charges = charges
+                .drain()
+                .map(|(key, charge)| (flip(key), charge))
+                .collect();
+        }
     }
 }
 
@@ -407,82 +656,136 @@ impl InputManager {
     pub fn new() -> Self {
         Self {
             player_input: PlayerInput::new(),
-            input_buffer: Vec::new(),
+            history: Vec::new(),
+            active_commands: Vec::new(),
+            pressed: Vec::new(),
+            released: Vec::new(),
         }
     }
 
-    /// Actualiza la duración de retención de las teclas de entrada en el búfer de entrada.
+    /// Establece el estado de una tecla y guarda el evento si es un botón.
     ///
-    /// Itera a través de cada comando de entrada en el búfer de entrada e incrementa
-    /// la duración de retención de las teclas de entrada que actualmente están presionadas.
-    pub fn update_hold_key(&mut self) {
-        for inputs in &mut self.input_buffer {
-            for key in &mut inputs.keys {
-                if self.player_input.get_state(&key.cmd_key) {
-                    key.update();
-                }
-            }
+    /// Las diagonales no tienen tecla propia, se establecen con sus dos direcciones.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla para establecer el estado.
+    /// * `state` - El valor booleano que indica si la tecla está presionada (`true`) o liberada (`false`).
+    ///
+    /// # Retorna
+    ///
+    /// El estado anterior de la tecla antes de que se estableciera.
+    pub fn set_state(&mut self, key: CK, state: bool) -> bool {
+        let symbol = match key {
+            CK::LP => "lp",
+            CK::MP => "mp",
+            CK::HP => "hp",
+            CK::LK => "lk",
+            CK::MK => "mk",
+            CK::HK => "hk",
+            CK::Start => "start",
+            CK::F => "f",
+            CK::B => "b",
+            CK::U => "u",
+            CK::D => "d",
+            CK::DB | CK::DF | CK::UB | CK::UF => return false,
+        };
+        let previous = self.player_input.set_state(symbol, state);
+        let is_button = !matches!(key, CK::F | CK::B | CK::U | CK::D);
+        if is_button && previous != state {
+            let events = if state {
+                &mut self.pressed
+            } else {
+                &mut self.released
+            };
+            events.push(key);
         }
+        previous
     }
 
-    /// Voltea la dirección de entrada del jugador.
+    /// Guarda en el historial las teclas presionadas en el frame actual.
     ///
-    /// Invierte la dirección de la entrada del jugador intercambiando los comandos de izquierda y derecha.
+    /// Cada tecla y dirección lleva la cuenta de los frames seguidos que ha estado presionada,
+    /// así las cargas pueden durar más que el historial. Los botones presionados y soltados desde
+    /// el frame anterior se guardan como eventos del frame. Se descartan los frames más antiguos
+    /// que `HISTORY_SIZE`.
+    pub fn update(&mut self) {
+        let mut frame = InputFrame::new(self.player_input.get_keys(), self.history.last());
+        frame.add_events(&self.pressed, &self.released);
+        self.pressed.clear();
+        self.released.clear();
+        if self.history.len() >= HISTORY_SIZE {
+            self.history.remove(0);
+        }
+        self.history.push(frame);
+    }
 
+    /// Voltea la dirección de entrada del jugador.
+    ///
+    /// Invierte la dirección de la entrada del jugador intercambiando los comandos de izquierda
+    /// y derecha, también en el historial.
     pub fn flip(&mut self) {
-        for inputs in &mut self.input_buffer {
-            for key in &mut inputs.keys {
-                if key.cmd_key == CK::B {
-                    key.cmd_key = CK::F;
-                } else if key.cmd_key == CK::F {
-                    key.cmd_key = CK::B;
-                }
-            }
+        for frame in &mut self.history {
+            frame.flip();
         }
-        let f = self.player_input.f;
-        let b = self.player_input.b;
-        self.player_input.b = f;
-        self.player_input.f = b;
+        self.player_input.flip();
     }
 
-    /// Procesa el búfer de entrada en busca de secuencias de comandos válidas.
+    /// Busca en el historial los comandos que se completan en el frame actual.
     ///
-    /// Recorre el búfer de entrada en busca de secuencias de comandos válidas
-    /// basadas en el árbol de nodos de comando proporcionado. Si se encuentra una
-    /// secuencia válida, se ejecuta la acción correspondiente.
+    /// Se evalúan todos los comandos en cada frame, por lo que puede haber varios activos a la vez.
+    /// Cada comando encontrado sigue activo durante su `buffer.time`.
     ///
     /// # Argumentos
     ///
-    /// * `tree` - Una referencia al árbol de nodos de comando utilizado para buscar secuencias de comandos válidas.
+    /// * `commands` - Los comandos del personaje.
     ///
     /// # Retorna
     ///
-    /// El nombre de la acción ejecutada si se encuentra una secuencia válida, de lo contrario, una cadena vacía.
-    pub fn walk_input_buffer(&mut self, tree: &CommandNode) -> String {
-        let input_buffer = &mut self.input_buffer;
-        for pos in 0..input_buffer.len() {
-            for input in &mut *input_buffer {
-                input.set_found(false);
-            }
-            if let Some(input) = input_buffer.get_mut(pos) {
-                if !input.walked {
-                    input.walked = true;
-                    if let Some(name) = tree.search(input_buffer, pos) {
-                        return name.clone();
-                    } else {
-                        if let Some(name) = tree.search(input_buffer, pos) {
-                            return name.clone();
-                        }
-                    }
-                }
+    /// El nombre del comando completado en este frame con más elementos, si hay alguno.
+    pub fn update_commands(&mut self, commands: &CommandList) -> Option<String> {
+        for (_, time) in &mut self.active_commands {
+            *time -= 1;
+        }
+        self.active_commands.retain(|(_, time)| *time > 0);
+
+        let found = commands.search(&self.history);
+        for command in &found {
+            let buffer_time = command.get_buffer_time().max(1);
+            match self
+                .active_commands
+                .iter_mut()
+                .find(|(name, _)| name == command.get_name())
+            {
+                Some((_, time)) => *time = (*time).max(buffer_time),
+                None => self
+                    .active_commands
+                    .push((command.get_name().to_string(), buffer_time)),
             }
         }
-        return "".to_string();
+        found
+            .iter()
+            .rev()
+            .max_by_key(|command| command.len())
+            .map(|command| command.get_name().to_string())
+    }
+
+    /// Obtiene los nombres de los comandos activos.
+    ///
+    /// # Retorna
+    ///
+    /// Los nombres de los comandos activos en este frame.
+    pub fn get_active_commands(&self) -> Vec<String> {
+        self.active_commands
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
     }
 
     /// Obtiene la dirección activa según la entrada del jugador.
     ///
-    /// Determina la dirección activa basada en la entrada del jugador y el historial de entrada.
+    /// Determina la dirección activa basada en la entrada del jugador, con las direcciones opuestas
+    /// ya resueltas según la política SOCD, y el historial de entrada.
     ///
     /// # Retorna
     ///
@@ -496,144 +799,82 @@ impl InputManager {
     /// * 7 - Arriba-Atrás (UB)
     /// * 8 - Arriba (U)
     /// * 9 - Arriba-Adelante (UF)
+    /// * 66 - Doble toque adelante, en el frame del segundo toque
+    /// * 44 - Doble toque atrás, en el frame del segundo toque
     pub fn get_active_direction(&self) -> u8 {
-        let player = &self.player_input;
-        if self.input_buffer.len() > 1
-            && (player.f || player.b || player.u || player.uf || player.ub)
-        {
-            if let (Some(last_b_one), Some(last)) = (
-                self.input_buffer.get(self.input_buffer.len() - 2),
-                self.input_buffer.last(),
-            ) {
-                let last_b_one_values = &last_b_one.get_keys_ref()[0];
-                let last_values = &last.get_keys_ref()[0];
-
-                if last_b_one_values.cmd_key == last_values.cmd_key {
-                    match last_values.cmd_key {
-                        CK::F => {
-                            if last.input_window < 15 {
-                                if last_values.buff_time > 1 {
-                                    return 6;
-                                } else {
-                                    return 66;
-                                }
-                            }
-                        }
-                        CK::B => {
-                            if last.input_window < 15 {
-                                if last_values.buff_time > 1 {
-                                    return 4;
-                                } else {
-                                    return 44;
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
+        for (key, dash) in [(CK::F, 66), (CK::B, 44)] {
+            if self.is_double_tap(&key) {
+                return dash;
             }
         }
-        if player.db || (player.d && player.b) {
-            return 1;
-        }
-        if player.df || (player.d && player.f) {
-            return 3;
-        }
-        if player.ub || (player.u && player.b) {
-            return 7;
-        }
-        if player.uf || (player.u && player.f) {
-            return 9;
-        }
-        if player.b && !player.f {
-            return 4;
-        }
-        if player.f && !player.b {
-            return 6;
-        }
-        if player.u && !player.d {
-            return 8;
-        }
-        if player.d && !player.u {
-            return 2;
-        }
-        5
+        self.player_input
+            .get_keys()
+            .iter()
+            .find_map(|key| match key {
+                CK::DB => Some(1),
+                CK::D => Some(2),
+                CK::DF => Some(3),
+                CK::B => Some(4),
+                CK::F => Some(6),
+                CK::UB => Some(7),
+                CK::U => Some(8),
+                CK::UF => Some(9),
+                _ => None,
+            })
+            .unwrap_or(5)
     }
 
-    /// Limpia el estado de entrada del jugador y el búfer de entrada.
+    /// Indica si una dirección se ha presionado dos veces seguidas, la segunda en este frame.
     ///
-    /// Restablece todas las teclas de acción del jugador y vacía el búfer de entrada.
-    pub fn clear(&mut self) {
-        self.player_input.clear_action();
-        self.input_buffer.clear();
-    }
-
-    /// Maneja la entrada del jugador y actualiza el búfer de entrada en consecuencia.
+    /// # Argumentos
     ///
-    /// Procesa la entrada del jugador, la convierte en comandos de entrada, y actualiza
-    /// el búfer de entrada con la nueva entrada. Opcionalmente, reemplaza la última entrada en
-    /// el búfer si el búfer excede su tamaño máximo.
+    /// * `key` - La dirección a comprobar.
     ///
-    /// # Argumentos
+    /// # Retorna
     ///
-    /// * `ticks` - Una referencia mutable al contador de ticks que representa el tiempo actual del juego.
-    /// * `replace` - Un booleano que indica si reemplazar la última entrada en el búfer si es necesario.
-    pub fn handle_key_input(&mut self, ticks: &mut u16, replace: bool) {
-        let input_buffer = &mut self.input_buffer;
-        let player_input = self.player_input.to_bits();
-        let mut input = CommandInput::new();
-
-        if input_buffer.len() > 32 {
-            input_buffer.remove(0);
+    /// `true` si la dirección se presionó en este frame y otra vez hace menos de `DASH_TIME`
+    /// frames sin ninguna otra dirección entre medias, de lo contrario `false`.
+    fn is_double_tap(&self, key: &CK) -> bool {
+        let is_direction = |key: &CK| {
+            !matches!(
+                key,
+                CK::LP | CK::MP | CK::HP | CK::LK | CK::MK | CK::HK | CK::Start
+            )
+        };
+        let pressed = |frame: usize| {
+            self.history[frame].contains(key)
+                && (frame == 0 || !self.history[frame - 1].contains(key))
+        };
+        let Some(now) = self.history.len().checked_sub(1) else {
+            return false;
+        };
+        if !pressed(now) {
+            return false;
         }
-
-        let actions = [
-            (Action::LP, CK::LP),
-            (Action::MP, CK::MP),
-            (Action::HP, CK::HP),
-            (Action::LK, CK::LK),
-            (Action::MK, CK::MK),
-            (Action::HK, CK::HK),
-            (Action::Start, CK::Start),
-        ];
-
-        for (action, command_key) in &actions {
-            if player_input & (*action as u16) != 0 {
-                input.keys.push(InputKey::new(*command_key));
+        for frame in (now.saturating_sub(DASH_TIME)..now).rev() {
+            if pressed(frame) {
+                return true;
             }
-        }
-
-        let last_bits = player_input & 0b1111;
-
-        let directions = [
-            (Direction::U as u16, CK::U),
-            (Direction::F as u16, CK::F),
-            (Direction::D as u16, CK::D),
-            (Direction::B as u16, CK::B),
-            (Direction::UF as u16, CK::UF),
-            (Direction::UB as u16, CK::UB),
-            (Direction::DF as u16, CK::DF),
-            (Direction::DB as u16, CK::DB),
-        ];
-
-        for (bits, command_key) in &directions {
-            if last_bits == *bits {
-                input.keys.push(InputKey::new(*command_key));
-                break;
+            if self.history[frame]
+                .get_keys_ref()
+                .iter()
+                .any(|other| other != key && is_direction(other))
+            {
+                return false;
             }
         }
+        false
+    }
 
-        if !input.keys.is_empty() {
-            if replace {
-                if let Some(last) = input_buffer.last_mut() {
-                    input.input_window = last.input_window;
-                    *last = input;
-                }
-            } else {
-                input.input_window = mem::replace(ticks, 0);
-                input_buffer.push(input);
-            }
-        }
+    /// Limpia el estado de entrada del jugador, el historial y los comandos activos.
+    ///
+    /// Restablece todas las teclas del jugador y vacía el historial.
+    pub fn clear(&mut self) {
+        self.player_input = PlayerInput::new();
+        self.history.clear();
+        self.active_commands.clear();
+        self.pressed.clear();
+        self.released.clear();
     }
 }
 
@@ -666,23 +907,275 @@ mod tests {
         assert_eq!(bits, (Action::LP as u16) | (Action::HP as u16) | 5 | 2);
     }
 
+    /// Prueba de las políticas SOCD con todas las combinaciones de direcciones
+    #[test]
+    fn test_player_input_socd() {
+        // Resultado de cada eje con las dos direcciones presionadas, según cuál se presionó
+        // primero: adelante o arriba en la primera posición, atrás o abajo en la segunda
+        let cases = [
+            (Socd::Neutral, [None, None], [None, None]),
+            (
+                Socd::LastInputWins,
+                [Some(CK::B), Some(CK::F)],
+                [Some(CK::D), Some(CK::U)],
+            ),
+            (
+                Socd::UpPriority,
+                [Some(CK::B), Some(CK::F)],
+                [Some(CK::U), Some(CK::U)],
+            ),
+            (Socd::Hitbox, [None, None], [Some(CK::U), Some(CK::U)]),
+        ];
+        for (socd, horizontal, vertical) in cases {
+            for pressed in 0..16 {
+                for reverse in [false, true] {
+                    let mut symbols: Vec<&str> = ["f", "b", "u", "d"]
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(i, _)| pressed & (1 << i) != 0)
+                        .map(|(_, symbol)| symbol)
+                        .collect();
+                    if reverse {
+                        symbols.reverse();
+                    }
+                    let mut input = PlayerInput::new();
+                    input.set_socd(socd);
+                    for symbol in &symbols {
+                        input.set_state(symbol, true);
+                    }
 
-    /// Prueba de actualización de teclas en el buffer
+                    let first = usize::from(reverse);
+                    let held = |symbol: &str| symbols.contains(&symbol);
+                    let h = match (held("f"), held("b")) {
+                        (true, true) => horizontal[first],
+                        (true, false) => Some(CK::F),
+                        (false, true) => Some(CK::B),
+                        (false, false) => None,
+                    };
+                    let v = match (held("u"), held("d")) {
+                        (true, true) => vertical[first],
+                        (true, false) => Some(CK::U),
+                        (false, true) => Some(CK::D),
+                        (false, false) => None,
+                    };
+                    let expected = match (v, h) {
+                        (Some(CK::U), Some(CK::F)) => Some(CK::UF),
+                        (Some(CK::U), Some(CK::B)) => Some(CK::UB),
+                        (Some(CK::D), Some(CK::F)) => Some(CK::DF),
+                        (Some(CK::D), Some(CK::B)) => Some(CK::DB),
+                        (Some(key), None) | (None, Some(key)) => Some(key),
+                        _ => None,
+                    };
+                    assert_eq!(
+                        input.get_keys().last().copied(),
+                        expected,
+                        "{:?} {:?}",
+                        socd,
+                        symbols
+                    );
+                }
+            }
+        }
+
+        // Al darse la vuelta la última dirección presionada también cambia de lado
+        let mut manager = InputManager::new();
+        manager.player_input.set_socd(Socd::LastInputWins);
+        manager.set_state(CK::F, true);
+        manager.set_state(CK::B, true);
+        assert_eq!(manager.get_active_direction(), 4);
+        manager.flip();
+        assert_eq!(manager.get_active_direction(), 6);
+        manager.player_input.set_socd(Socd::Neutral);
+        assert_eq!(manager.get_active_direction(), 5);
+    }
+
+    /// Prueba de que cada frame se guarda en el historial
     #[test]
-    fn test_input_manager_update_hold_key() {
+    fn test_input_manager_update() {
         let mut manager = InputManager::new();
-        let mut input_key = InputKey::new(CK::LP);
-        input_key.update();
-        let command_input = CommandInput {
-            keys: vec![input_key],
-            input_window: 0,
-            walked: false,
-            found: false,
-        };
-        manager.input_buffer.push(command_input);
         manager.player_input.lp = true;
-        manager.update_hold_key();
-        assert_eq!(manager.input_buffer[0].keys[0].buff_time, 2);
+        manager.player_input.d = true;
+        manager.player_input.f = true;
+        manager.update();
+        manager.player_input.lp = false;
+        manager.update();
+        assert_eq!(manager.history.len(), 2);
+        assert_eq!(manager.history[0].get_keys_ref(), &vec![CK::LP, CK::DF]);
+        assert_eq!(manager.history[1].get_keys_ref(), &vec![CK::DF]);
+
+        for _ in 0..HISTORY_SIZE {
+            manager.update();
+        }
+        assert_eq!(manager.history.len(), HISTORY_SIZE);
+    }
+
+    /// Prueba de los comandos activos y su tiempo de búfer
+    #[test]
+    fn test_input_manager_update_commands() {
+        let commands = cmd::manager::parse_command_list(
+            "[Command]\nname = \"QCF_x\"\ncommand = D, DF, F, x\nbuffer.time = 3\n\
+             [Command]\nname = \"x\"\ncommand = x\ntime = 1\n",
+        )
+        .unwrap();
+
+        let mut manager = InputManager::new();
+        let mut found = Vec::new();
+        for (d, f, lp) in [
+            (true, false, false),
+            (true, true, false),
+            (false, true, false),
+            (false, true, true),
+        ] {
+            manager.player_input.d = d;
+            manager.player_input.f = f;
+            manager.player_input.lp = lp;
+            manager.update();
+            found.push(manager.update_commands(&commands));
+        }
+        assert_eq!(found, [None, None, None, Some("QCF_x".to_string())]);
+        assert_eq!(manager.get_active_commands(), ["QCF_x", "x"]);
+
+        manager.update();
+        assert_eq!(manager.update_commands(&commands), None);
+        assert_eq!(manager.get_active_commands(), ["QCF_x"]);
+        manager.update();
+        manager.update_commands(&commands);
+        manager.update();
+        manager.update_commands(&commands);
+        assert!(manager.get_active_commands().is_empty());
+    }
+
+    /// Prueba del doble toque para correr
+    #[test]
+    fn test_input_manager_double_tap() {
+        let mut manager = InputManager::new();
+        let mut directions = Vec::new();
+        for f in [true, true, false, true, true] {
+            manager.player_input.f = f;
+            manager.update();
+            directions.push(manager.get_active_direction());
+        }
+        assert_eq!(directions, [6, 6, 5, 66, 6]);
+
+        let mut manager = InputManager::new();
+        for (f, d) in [(true, false), (false, true), (true, false)] {
+            manager.player_input.f = f;
+            manager.player_input.d = d;
+            manager.update();
+        }
+        assert_eq!(manager.get_active_direction(), 6);
+    }
+
+    /// Prueba de las cargas de teclas y direcciones, más largas que el historial
+    #[test]
+    fn test_input_manager_charges() {
+        let mut manager = InputManager::new();
+        manager.player_input.b = true;
+        manager.player_input.mk = true;
+        for _ in 0..HISTORY_SIZE {
+            manager.update();
+        }
+        manager.player_input.d = true;
+        for _ in 0..30 {
+            manager.update();
+        }
+        let frame = manager.history.last().unwrap();
+        assert_eq!(frame.get_charge(&CK::B, true), HISTORY_SIZE as u32 + 30);
+        assert_eq!(frame.get_charge(&CK::B, false), 0);
+        assert_eq!(frame.get_charge(&CK::DB, false), 30);
+        assert_eq!(frame.get_charge(&CK::D, true), 30);
+        assert_eq!(frame.get_charge(&CK::MK, false), HISTORY_SIZE as u32 + 30);
+
+        manager.flip();
+        let frame = manager.history.last().unwrap();
+        assert_eq!(frame.get_charge(&CK::F, true), HISTORY_SIZE as u32 + 30);
+        assert_eq!(frame.get_charge(&CK::DF, false), 30);
+
+        let commands = cmd::manager::parse_command_list(
+            "[Command]\nname = \"charge_b\"\ncommand = ~140$F, B, b\n",
+        )
+        .unwrap();
+
+        manager.player_input.d = false;
+        manager.player_input.mk = false;
+        manager.player_input.f = false;
+        manager.player_input.b = true;
+        manager.update();
+        assert_eq!(manager.update_commands(&commands), None);
+        manager.player_input.mk = true;
+        manager.update();
+        assert_eq!(
+            manager.update_commands(&commands),
+            Some("charge_b".to_string())
+        );
+    }
+
+    /// Test de pulsaciones y liberaciones de botones dentro de un mismo frame
+    #[test]
+    fn test_input_manager_button_events() {
+        let commands = cmd::manager::parse_command_list(
+            "[Command]\nname = \"x\"\ncommand = x\n\
+             [Command]\nname = \"release_x\"\ncommand = ~x\n",
+        )
+        .unwrap();
+
+        let mut manager = InputManager::new();
+        manager.update();
+        assert!(!manager.set_state(CK::LP, true));
+        assert!(manager.set_state(CK::LP, false));
+        manager.update();
+        let frame = manager.history.last().unwrap();
+        assert!(!frame.contains(&CK::LP));
+        assert!(frame.was_pressed(&CK::LP, false));
+        assert!(frame.was_released(&CK::LP, false));
+        manager.update_commands(&commands);
+        assert_eq!(manager.get_active_commands(), ["x", "release_x"]);
+
+        // Soltar y volver a presionar en el mismo frame reinicia la carga
+        manager.set_state(CK::MP, true);
+        for _ in 0..10 {
+            manager.update();
+        }
+        manager.set_state(CK::MP, false);
+        manager.set_state(CK::MP, true);
+        manager.update();
+        let frame = manager.history.last().unwrap();
+        assert!(frame.contains(&CK::MP));
+        assert_eq!(frame.get_charge(&CK::MP, false), 1);
+        assert!(frame.was_pressed(&CK::MP, false));
+
+        // Las direcciones no generan eventos, se comparan con el frame anterior
+        assert!(!manager.set_state(CK::F, true));
+        manager.update();
+        assert!(manager.history.last().unwrap().was_pressed(&CK::F, false));
+        manager.update();
+        assert!(!manager.history.last().unwrap().was_pressed(&CK::F, false));
+    }
+
+    /// Test de comandos con flanco negativo, soltando el botón al final del movimiento
+    #[test]
+    fn test_input_manager_negative_edge() {
+        let commands = cmd::manager::parse_command_list(
+            "[Command]\nname = \"QCF_x\"\ncommand = D, DF, F, ~x\n",
+        )
+        .unwrap();
+
+        let mut manager = InputManager::new();
+        manager.set_state(CK::LP, true);
+        manager.update();
+        assert_eq!(manager.update_commands(&commands), None);
+        for (d, f) in [(true, false), (true, true), (false, true)] {
+            manager.set_state(CK::D, d);
+            manager.set_state(CK::F, f);
+            manager.update();
+            assert_eq!(manager.update_commands(&commands), None);
+        }
+        manager.set_state(CK::LP, false);
+        manager.update();
+        assert_eq!(
+            manager.update_commands(&commands),
+            Some("QCF_x".to_string())
+        );
     }
 
     /// Test de giro de buffer
@@ -701,9 +1194,9 @@ mod tests {
     fn test_input_manager_clear() {
         let mut manager = InputManager::new();
         manager.player_input.lp = true;
-        manager.input_buffer.push(CommandInput::new());
+        manager.update();
         manager.clear();
         assert_eq!(manager.player_input.lp, false);
-        assert!(manager.input_buffer.is_empty());
+        assert!(manager.history.is_empty());
     }
-}
\ No newline at end of file
+}
@@ -1 +1,3 @@
-pub mod manager;
\ No newline at end of file
+pub mod key_map;
+pub mod manager;
+pub mod source;
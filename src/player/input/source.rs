@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use piston::Key;
+
+use crate::CK;
+
+use super::key_map::KeyMap;
+
+/// Fuente de entrada que controla a un jugador.
+///
+/// Cada frame entrega los cambios de estado de las teclas de comando desde el frame anterior,
+/// en el orden en que ocurrieron. Las direcciones son relativas al personaje: `F` es hacia donde
+/// mira y `B` hacia su espalda, y las diagonales se dan con sus dos direcciones.
+pub trait InputSource {
+    /// Atiende un evento de teclado. Las fuentes que no usan el teclado lo ignoran.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla del evento.
+    /// * `pressed` - `true` si la tecla se ha presionado y `false` si se ha soltado.
+    fn on_key(&mut self, key: &Key, pressed: bool) {
+        let _ = (key, pressed);
+    }
+
+    /// Recoge los cambios de estado de las teclas desde el último frame.
+    ///
+    /// # Argumentos
+    ///
+    /// * `flipped` - Indica si el personaje mira hacia la izquierda.
+    ///
+    /// # Retorna
+    ///
+    /// Las teclas que han cambiado y su nuevo estado, en orden.
+    fn poll(&mut self, flipped: bool) -> Vec<(CK, bool)>;
+}
+
+/// Fuente de entrada que lee el teclado con un mapeo de teclas.
+pub struct KeyboardSource {
+    /// Mapeo de teclas del jugador
+    key_map: KeyMap,
+    /// Teclas presionadas y soltadas desde el último frame, con la derecha como adelante
+    events: Vec<(CK, bool)>,
+}
+
+/// Fuente de entrada virtual, guiada por un programa.
+///
+/// Sirve para la IA, las repeticiones o un rival en red: cada frame entrega el siguiente grupo
+/// de cambios que se le haya añadido, y cuando se acaban las teclas se quedan como están.
+#[derive(Default)]
+pub struct VirtualSource {
+    /// Cambios de estado de cada frame pendiente, del próximo al último
+    frames: VecDeque<Vec<(CK, bool)>>,
+}
+
+impl KeyboardSource {
+    /// Crea una fuente de teclado.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key_map` - El mapeo de teclas a usar.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `KeyboardSource`.
+    pub fn new(key_map: KeyMap) -> Self {
+        Self {
+            key_map,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl InputSource for KeyboardSource {
+    fn on_key(&mut self, key: &Key, pressed: bool) {
+        if let Some(key) = self.key_map.translate(key) {
+            self.events.push((key, pressed));
+        }
+    }
+
+    fn poll(&mut self, flipped: bool) -> Vec<(CK, bool)> {
+        self.events
+            .drain(..)
+            .map(|(key, pressed)| match key {
+                CK::F if flipped => (CK::B, pressed),
+                CK::B if flipped => (CK::F, pressed),
+                key => (key, pressed),
+            })
+            .collect()
+    }
+}
+
+impl VirtualSource {
+    /// Crea una fuente virtual sin ningún frame pendiente.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `VirtualSource`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Añade al final los cambios de estado de un frame.
+    ///
+    /// # Argumentos
+    ///
+    /// * `events` - Las teclas que cambian en ese frame y su nuevo estado, en orden.
+    pub fn push_frame(&mut self, events: Vec<(CK, bool)>) {
+        self.frames.push_back(events);
+    }
+}
+
+impl InputSource for VirtualSource {
+    fn poll(&mut self, flipped: bool) -> Vec<(CK, bool)> {
+        let _ = flipped;
+        self.frames.pop_front().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{character::cmd, input::manager::InputManager};
+
+    /// Prueba de la fuente de teclado, que ignora las teclas sin asignar y gira las direcciones
+    #[test]
+    fn test_keyboard_source() {
+        let mut source = KeyboardSource::new(KeyMap::new(true));
+        source.on_key(&Key::Right, true);
+        source.on_key(&Key::A, true);
+        source.on_key(&Key::Q, true);
+        source.on_key(&Key::A, false);
+        assert_eq!(
+            source.poll(false),
+            [(CK::F, true), (CK::LP, true), (CK::LP, false)]
+        );
+        assert_eq!(source.poll(false), []);
+
+        source.on_key(&Key::Right, false);
+        source.on_key(&Key::Left, true);
+        assert_eq!(source.poll(true), [(CK::B, false), (CK::F, true)]);
+
+        let mut source = KeyboardSource::new(KeyMap::new(false));
+        source.on_key(&Key::A, true);
+        source.on_key(&Key::R, true);
+        assert_eq!(source.poll(false), [(CK::LP, true)]);
+    }
+
+    /// Prueba de una fuente virtual que hace un comando frame a frame
+    #[test]
+    fn test_virtual_source() {
+        let commands = cmd::manager::parse_command_list(
+            "[Command]\nname = \"QCF_x\"\ncommand = D, DF, F, x\n",
+        )
+        .unwrap();
+
+        let mut source = VirtualSource::new();
+        source.push_frame(vec![(CK::D, true)]);
+        source.push_frame(vec![(CK::F, true)]);
+        source.push_frame(vec![(CK::D, false)]);
+        source.push_frame(vec![(CK::LP, true), (CK::LP, false)]);
+
+        let mut manager = InputManager::new();
+        let mut found = Vec::new();
+        for _ in 0..5 {
+            for (key, state) in source.poll(false) {
+                manager.set_state(key, state);
+            }
+            manager.update();
+            found.push(manager.update_commands(&commands));
+        }
+        assert_eq!(found, [None, None, None, Some("QCF_x".to_string()), None]);
+        assert!(source.poll(false).is_empty());
+    }
+}
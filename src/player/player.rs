@@ -1,13 +1,17 @@
-use std::sync::MutexGuard;
-
-use gfx_device_gl::Resources;
+use graphics::math::Matrix2d;
 use piston::Key;
-use piston_window::{G2dTextureContext, Texture};
-use sprite::Sprite;
+use piston_window::{G2d, G2dTextureContext};
 
-use super::{character::{air::manager::Clsn, character::Character}, input::manager::InputManager};
+use crate::audio::mixer::Mixer;
 
-const PAUSE_DURATION: i32 = 3;
+use super::{
+    character::{air::manager::Clsn, character::Character, snd::decoder::Snd},
+    input::{
+        manager::{InputManager, Socd},
+        key_map::KeyMap,
+        source::{InputSource, KeyboardSource},
+    },
+};
 
 /// Estructura que representa el jugador en el juego.
 pub struct Player {
@@ -15,90 +19,10 @@ pub struct Player {
     first_player: bool,
     /// Gestiona los inputs del jugador
     input_manager: InputManager,
-    /// Mapeo de teclas personalizado del jugador
-    key_map: KeyMap,
+    /// Fuente de la que vienen las teclas del jugador
+    source: Box<dyn InputSource>,
     /// Personaje elegido por el jugador
     character: Option<Character>,
-    /// Estado donde se puede modificar ciertos inputs (para casos de teclas dobles)
-    replace_action: bool,
-    /// Franja de tiempo donde permite modificar ciertos input (para casos de teclas dobles)
-    replace_timer: i32,
-}
-
-/// Estructura que represental el mapeo de teclas para el jugador.
-pub struct KeyMap {
-    /// Puño débil
-    lp: Key,
-    /// Puño medio
-    mp: Key,
-    /// Puño fuerte
-    hp: Key,
-    /// Patada baja
-    lk: Key,
-    /// Patada media
-    mk: Key,
-    /// Patada fuerte
-    hk: Key,
-    /// Adelante
-    f: Key,
-    /// Arriba
-    u: Key,
-    /// Atrás
-    b: Key,
-    /// Abajo
-    d: Key,
-    /// Inicio/Taunt
-    start: Key,
-}
-
-impl KeyMap {
-    fn new(first_player: bool) -> Self {
-        if first_player {
-            return Self {
-                lp: Key::A,
-                mp: Key::S,
-                hp: Key::D,
-                lk: Key::Z,
-                mk: Key::X,
-                hk: Key::C,
-                f: Key::Right,
-                u: Key::Up,
-                b: Key::Left,
-                d: Key::Down,
-                start: Key::RShift,
-            };
-        }
-        Self {
-            lp: Key::R,
-            mp: Key::T,
-            hp: Key::Y,
-            lk: Key::F,
-            mk: Key::G,
-            hk: Key::H,
-            f: Key::L,
-            u: Key::I,
-            b: Key::J,
-            d: Key::K,
-            start: Key::RCtrl,
-        }
-    }
-
-    fn translate(&self, key: &Key, flip: bool) -> &str {
-        match *key {
-            k if k == self.lp => "lp",
-            k if k == self.mp => "mp",
-            k if k == self.hp => "hp",
-            k if k == self.lk => "lk",
-            k if k == self.mk => "mk",
-            k if k == self.hk => "hk",
-            k if k == self.f => if flip {"b"} else {"f"},
-            k if k == self.u => "u",
-            k if k == self.b => if flip {"f"} else {"b"},
-            k if k == self.d => "d",
-            k if k == self.start => "start",
-            _ => "",
-        }
-    }
 }
 
 impl Player {
@@ -107,18 +31,17 @@ impl Player {
     /// # Argumentos
     ///
     /// * `first_player` - Un booleano que indica si el jugador es el primer jugador o no.
+    /// * `key_map` - El mapeo de teclas con el que el jugador controla al personaje.
     ///
     /// # Retorna
     ///
     /// Una nueva instancia de `Player`.
-    pub fn new(first_player: bool) -> Self {
+    pub fn new(first_player: bool, key_map: KeyMap) -> Self {
         Self {
             first_player,
             input_manager: InputManager::new(),
-            key_map: KeyMap::new(first_player),
+            source: Box::new(KeyboardSource::new(key_map)),
             character: None,
-            replace_action: false,
-            replace_timer: 0,
         }
     }
 
@@ -140,6 +63,24 @@ impl Player {
         self.character.as_ref().unwrap().get_life()
     }
 
+    /// Obtiene las frases de victoria del personaje del jugador.
+    ///
+    /// # Retorna
+    ///
+    /// Las frases de victoria del personaje.
+    pub fn get_quotes(&self) -> &[String] {
+        self.character.as_ref().unwrap().get_quotes()
+    }
+
+    /// Multiplica la vida máxima del personaje del jugador y lo deja con la vida llena.
+    ///
+    /// # Argumentos
+    ///
+    /// * `multiplier` - El multiplicador de la vida definida por el personaje.
+    pub fn set_life_multiplier(&mut self, multiplier: f64) {
+        self.character.as_mut().unwrap().set_life_multiplier(multiplier);
+    }
+
     /// Establece si el jugador ganó.
     ///
     /// # Argumentos
@@ -217,22 +158,30 @@ impl Player {
         }
     }
 
-    /// Establece el estado de una tecla del jugador.
+    /// Pasa un evento de teclado a la fuente de entrada del jugador.
     ///
     /// # Argumentos
     ///
-    /// * `key` - Una referencia a la tecla que se está estableciendo.
+    /// * `key` - Una referencia a la tecla del evento.
     /// * `value` - El valor booleano que indica si la tecla está presionada (`true`) o liberada (`false`).
+    pub fn on_key(&mut self, key: &Key, value: bool) {
+        self.source.on_key(key, value);
+    }
+
+    /// Cambia la fuente de entrada que controla al jugador.
+    ///
+    /// Se sueltan las teclas que mantuviera la fuente anterior.
+    ///
+    /// # Argumentos
+    ///
+    /// * `source` - La nueva fuente de entrada.
     ///
     /// # Retorna
     ///
-    /// El estado anterior de la tecla antes de que se estableciera.
-    pub fn set_player_input(&mut self, key: &Key, value: bool) -> bool {
-        let symbol = self.key_map.translate(key, self.character.as_ref().unwrap().is_flipped());
-        if symbol == "" {
-            return value;
-        }
-        self.input_manager.player_input.set_state(symbol, value)
+    /// La fuente de entrada anterior.
+    pub fn set_input_source(&mut self, source: Box<dyn InputSource>) -> Box<dyn InputSource> {
+        self.input_manager.clear();
+        std::mem::replace(&mut self.source, source)
     }
 
     /// Elige un personaje para el jugador.
@@ -249,89 +198,116 @@ impl Player {
         self.character = Some(character);
     }
 
+    /// Coloca al personaje del jugador en su posición inicial.
+    ///
+    /// # Argumentos
+    ///
+    /// * `x` - Posición en la X en coordenadas del escenario.
+    /// * `y` - Altura respecto al suelo, negativa hacia arriba.
+    pub fn set_start_position(&mut self, x: f64, y: f64) {
+        self.character.as_mut().unwrap().set_start_position(x, y);
+    }
+
+    /// Obtiene la posición del personaje del jugador.
+    ///
+    /// # Retorna
+    ///
+    /// La posición en coordenadas del escenario.
+    pub fn get_position(&self) -> (f64, f64) {
+        self.character.as_ref().unwrap().get_position()
+    }
+
+    /// Establece los límites en la X en los que se puede mover el jugador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `left` - Límite izquierdo en coordenadas del escenario.
+    /// * `right` - Límite derecho en coordenadas del escenario.
+    pub fn set_bounds(&mut self, left: f64, right: f64) {
+        self.character.as_mut().unwrap().set_bounds(left, right);
+    }
+
     /// Establece la paleta del personaje.
     ///
     /// # Argumentos
     ///
-    /// * `palette_index` - El índice de la paleta.
+    /// * `palette_index` - El número del color elegido, de 1 a 12.
     pub fn set_palette(&mut self, palette_index: usize) {
         self.character.as_mut().unwrap().set_palette(palette_index);
     }
 
+    /// Establece la política para las direcciones opuestas del jugador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `socd` - La política SOCD elegida.
+    pub fn set_socd(&mut self, socd: Socd) {
+        self.input_manager.player_input.set_socd(socd);
+    }
+
     /// Actualiza el estado del jugador.
-    pub fn update(&mut self) {
-        self.input_manager.update_hold_key();
+    ///
+    /// # Argumentos
+    ///
+    /// * `opponent` - Jugador rival, si lo hay.
+    pub fn update(&mut self, opponent: Option<&Player>) {
         if self.character.as_mut().unwrap().is_flipping() {
             self.input_manager.flip();
         }
+        let flipped = self.character.as_ref().unwrap().is_flipped();
+        for (key, state) in self.source.poll(flipped) {
+            self.input_manager.set_state(key, state);
+        }
+        self.input_manager.update();
 
-        if self.replace_timer < PAUSE_DURATION {
-            self.replace_timer += 1;
-        } else if self.replace_action {
-            let action = self
-                .input_manager
-                .walk_input_buffer(self.character.as_ref().unwrap().get_cmd());
-            self.character.as_mut().unwrap().set_action(action);
-            self.input_manager.clear();
-            self.replace_action = false;
+        let action = self
+            .input_manager
+            .update_commands(self.character.as_ref().unwrap().get_cmd());
+        let commands = self.input_manager.get_active_commands();
+        let character = self.character.as_mut().unwrap();
+        let expired = !commands.iter().any(|command| command == character.get_action());
+        match action {
+            Some(action) => character.set_action(action),
+            None if expired => character.set_action(String::new()),
+            None => {}
         }
+        character.set_commands(commands);
 
         self.character
             .as_mut()
             .unwrap()
             .set_direction(self.input_manager.get_active_direction());
-        self.character.as_mut().unwrap().update();
+        let opponent = opponent.and_then(|opponent| opponent.character.as_ref());
+        self.character.as_mut().unwrap().update(opponent);
     }
 
-     /// Maneja la entrada de teclado del jugador.
+    /// Envía al mezclador los sonidos pendientes del personaje.
     ///
     /// # Argumentos
     ///
-    /// * `ticks` - Una referencia mutable al contador de ticks que representa el tiempo actual del juego.
-    /// * `replace` - Un booleano que indica si reemplazar la última entrada en el búfer si es necesario.
-    pub fn handle_key_input(&mut self, ticks: &mut u16, replace: bool) {
-        self.input_manager
-            .handle_key_input(ticks, if replace { self.replace_action } else { false });
+    /// * `mixer` - Mezclador de audio de la pelea.
+    /// * `owner` - Índice del jugador, cada uno tiene sus propios canales.
+    /// * `common` - Sonidos comunes.
+    pub fn play_sounds(&mut self, mixer: &mut Mixer, owner: usize, common: &Snd) {
+        self.character
+            .as_mut()
+            .unwrap()
+            .play_sounds(mixer, owner, common);
     }
 
-    /// Indica si el jugador está reemplazando una acción.
-    ///
-    /// # Retorna
-    ///
-    /// `true` si el jugador está reemplazando una acción, de lo contrario, `false`.
-    pub fn is_replacing_action(&self) -> bool {
-        self.replace_action
+    /// Suelta todas las teclas del jugador y olvida su historial de entrada y sus comandos.
+    pub fn clear_input(&mut self) {
+        self.input_manager.clear();
     }
 
-    /// Indica si el jugador está en movimiento.
+    /// Dibuja el personaje del jugador.
     ///
     /// # Argumentos
     ///
-    /// * `key` - La tecla que se está verificando.
-    ///
-    /// # Retorna
-    ///
-    /// `true` si el jugador está en movimiento con la tecla especificada, de lo contrario, `false`.
-    pub fn is_moving(&self, key: Key) -> bool {
-        if key == self.key_map.f || key == self.key_map.b || key == self.key_map.u || key == self.key_map.d {
-            return true;
-        }
-        false
-    }
-
-    /// Establece que el jugador está reemplazando una acción.
-    pub fn set_replacing(&mut self) {
-        self.replace_action = true;
-        self.replace_timer = 0;
-    }
-
-    /// Obtiene una referencia mutable al sprite del jugador.
-    ///
-    /// # Retorna
-    ///
-    /// Una referencia mutable al sprite del jugador.
-    pub fn get_mut_sprite(&mut self) -> MutexGuard<Sprite<Texture<Resources>>> {
-        self.character.as_mut().unwrap().get_sprite()
+    /// * `transform` - Transformación del escenario a la pantalla.
+    /// * `g` - El contexto de gráficos.
+    pub fn draw(&self, transform: Matrix2d, g: &mut G2d) {
+        self.character.as_ref().unwrap().draw(transform, g);
     }
 
     /// Obtiene una referencia a los colisionadores del jugador.
@@ -5,8 +5,15 @@ use piston_window::{Flip, Glyphs, PistonWindow, Texture, TextureSettings};
 
 use crate::{
     chars,
-    error::{pop_up::show_error_popup, preload_error::PreloadError},
-    player::character::sff::decoder::Sff,
+    config::game_config::GameConfig,
+    error::{
+        config_error::ConfigError, def_error::DefError, pop_up::show_error_popup,
+        preload_error::PreloadError,
+    },
+    player::{
+        character::sff::{decoder::Sff, texture::SffTextures},
+        input::key_map::{load_key_maps, KeyMap, KEYS_CFG},
+    },
 };
 
 /// Struct que contiene los recursos precargados para el juego, como fondos, fuentes y personajes.
@@ -16,7 +23,11 @@ pub struct Preloads {
     /// Lista de fuentes precargadas
     fonts: Vec<Glyphs>,
     /// Lista de archivos SFF de personajes precargados
-    roster: Vec<Sff>,
+    roster: Vec<SffTextures>,
+    /// Mapeos de teclas de los jugadores 1 y 2
+    key_maps: [KeyMap; 2],
+    /// Configuración del juego
+    game_config: GameConfig,
 }
 
 impl Preloads {
@@ -25,11 +36,12 @@ impl Preloads {
     /// # Argumentos
     ///
     /// * `window` - Referencia mutable a la ventana de Piston donde se cargan los recursos.
+    /// * `game_config` - La configuración del juego, cargada antes de crear la ventana.
     ///
     /// # Retorna
     ///
     /// Una nueva instancia de `Preloads` con los recursos cargados, o un error si no se pudieron cargar.    
-    pub fn new(window: &mut PistonWindow) -> Result<Self, PreloadError> {
+    pub fn new(window: &mut PistonWindow, game_config: GameConfig) -> Result<Self, PreloadError> {
         let mut fonts = Vec::new();
         let mut backgrounds = Vec::new();
 
@@ -68,17 +80,6 @@ impl Preloads {
             Err(_) => return Err(PreloadError::BackgroundNotFound(background.to_string())),
         };
 
-        let background = "assets\\images\\background_3.png";
-        match Texture::from_path(
-            &mut window.create_texture_context(),
-            background,
-            Flip::None,
-            &TextureSettings::new(),
-        ) {
-            Ok(texture) => backgrounds.push(texture),
-            Err(_) => return Err(PreloadError::BackgroundNotFound(background.to_string())),
-        };
-
         let mut roster = Vec::new();
 
         for entry in fs::read_dir("src\\chars\\").unwrap() {
@@ -87,15 +88,25 @@ impl Preloads {
             if path.is_dir() {
                 if let Some(name) = path.file_name() {
                     if let Some(char_name) = name.to_str() {
-                        let char = chars::get_char(char_name).unwrap();
-                        let context = window.create_texture_context();
-                        match Sff::preload_sff(
-                            char_name,
-                            char.get_sff_name().to_string() + ".sff",
-                            true,
-                            context,
-                        ) {
-                            Ok(sff) => roster.push(sff),
+                        let char = match chars::get_char(char_name) {
+                            Ok(char) => char,
+                            // Las carpetas sin archivo DEF no son personajes
+                            Err(DefError::NotFound(_)) => continue,
+                            Err(err) => {
+                                show_error_popup(&err);
+                                std::process::exit(1);
+                            }
+                        };
+                        let def = char.get_def();
+                        let sff = Sff::preload_sff(char_name, def.files.sprite.clone(), true)
+                            .and_then(|mut sff| {
+                                sff.load_palettes(&path, &def.files.pal)?;
+                                Ok(sff)
+                            });
+                        match sff {
+                            Ok(sff) => {
+                                roster.push(SffTextures::new(sff, window.create_texture_context()))
+                            }
                             Err(err) => {
                                 show_error_popup(&err);
                                 std::process::exit(1);
@@ -106,10 +117,22 @@ impl Preloads {
             }
         }
 
+        // Sin archivo de teclas se usan las teclas por defecto
+        let key_maps = match load_key_maps(KEYS_CFG) {
+            Ok(key_maps) => key_maps,
+            Err(ConfigError::NotFound(_)) => [KeyMap::new(true), KeyMap::new(false)],
+            Err(err) => {
+                show_error_popup(&err);
+                [KeyMap::new(true), KeyMap::new(false)]
+            }
+        };
+
         Ok(Self {
             backgrounds,
             fonts,
             roster,
+            key_maps,
+            game_config,
         })
     }
 
@@ -127,7 +150,7 @@ impl Preloads {
     /// # Retorna
     ///
     /// Una referencia a los personajes precargados.
-    pub fn get_ref_roster(&self) -> &Vec<Sff> {
+    pub fn get_ref_roster(&self) -> &Vec<SffTextures> {
         &self.roster
     }
 
@@ -139,4 +162,40 @@ impl Preloads {
     pub fn get_mut_ref_fonts(&mut self) -> &mut Vec<Glyphs> {
         &mut self.fonts
     }
+
+    /// Obtiene una referencia a los mapeos de teclas de los jugadores.
+    ///
+    /// # Retorna
+    ///
+    /// Una referencia a los mapeos del jugador 1 y 2.
+    pub fn get_ref_key_maps(&self) -> &[KeyMap; 2] {
+        &self.key_maps
+    }
+
+    /// Establece los mapeos de teclas de los jugadores.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key_maps` - Los mapeos del jugador 1 y 2.
+    pub fn set_key_maps(&mut self, key_maps: [KeyMap; 2]) {
+        self.key_maps = key_maps;
+    }
+
+    /// Obtiene una referencia a la configuración del juego.
+    ///
+    /// # Retorna
+    ///
+    /// Una referencia a la configuración del juego.
+    pub fn get_ref_game_config(&self) -> &GameConfig {
+        &self.game_config
+    }
+
+    /// Establece la configuración del juego.
+    ///
+    /// # Argumentos
+    ///
+    /// * `game_config` - La nueva configuración del juego.
+    pub fn set_game_config(&mut self, game_config: GameConfig) {
+        self.game_config = game_config;
+    }
 }
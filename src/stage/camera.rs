@@ -0,0 +1,217 @@
+use graphics::{math::Matrix2d, Transformed};
+use piston_window::Context;
+
+use super::manager::{CameraDef, StageDef};
+
+/// Cámara que sigue a los jugadores por el escenario.
+///
+/// Los jugadores se mueven en coordenadas del escenario: la X es 0 en el centro y la Y es 0 en el suelo,
+/// negativa hacia arriba. La cámara traduce esas coordenadas a las de la pantalla, que están en el
+/// `localcoord` del escenario y se escalan al tamaño de la ventana.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    /// Posición en la X del centro de la pantalla
+    x: f64,
+    /// Posición en la Y de la pantalla, negativa hacia arriba
+    y: f64,
+    /// Límites y comportamiento de la cámara
+    def: CameraDef,
+    /// Tamaño de la pantalla en coordenadas del escenario
+    local_coord: (f64, f64),
+    /// Altura del suelo en la pantalla
+    zoffset: f64,
+    /// Límites izquierdo y derecho de los jugadores en el escenario
+    player_bounds: (f64, f64),
+}
+
+impl Camera {
+    /// Crea la cámara de un escenario en su posición inicial.
+    ///
+    /// # Argumentos
+    ///
+    /// * `stage` - Definición del escenario.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `Camera`.
+    pub fn new(stage: &StageDef) -> Self {
+        Self {
+            x: stage.camera.start_x,
+            y: stage.camera.start_y,
+            def: stage.camera.clone(),
+            local_coord: stage.stage_info.local_coord,
+            zoffset: stage.stage_info.zoffset,
+            player_bounds: (stage.player_info.left_bound, stage.player_info.right_bound),
+        }
+    }
+
+    /// Obtiene la posición de la cámara.
+    ///
+    /// # Retorna
+    ///
+    /// La posición en coordenadas del escenario.
+    pub fn get_pos(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+
+    /// Mueve la cámara para seguir a los jugadores.
+    ///
+    /// En horizontal la cámara solo se mueve cuando un jugador se acerca a menos de `tension` de un borde,
+    /// sin dejar nunca a ninguno fuera de la pantalla. En vertical sigue una fracción de la altura del
+    /// jugador más alto.
+    ///
+    /// # Argumentos
+    ///
+    /// * `players` - Posiciones de los jugadores en coordenadas del escenario.
+    pub fn update(&mut self, players: &[(f64, f64)]) {
+        if players.is_empty() {
+            return;
+        }
+        let half_width = self.local_coord.0 / 2.0;
+        let min_x = players.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let max_x = players
+            .iter()
+            .map(|p| p.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let tension = self.def.tension.min(half_width);
+
+        let left_push = min_x < self.x - half_width + tension;
+        let right_push = max_x > self.x + half_width - tension;
+        let mut x = match (left_push, right_push) {
+            (true, true) => (min_x + max_x) / 2.0,
+            (true, false) => min_x + half_width - tension,
+            (false, true) => max_x - half_width + tension,
+            (false, false) => self.x,
+        };
+        // Los dos jugadores tienen que seguir dentro de la pantalla
+        let (low, high) = (max_x - half_width, min_x + half_width);
+        x = if low <= high {
+            x.clamp(low, high)
+        } else {
+            (min_x + max_x) / 2.0
+        };
+        self.x = x.clamp(
+            self.def.bound_left,
+            self.def.bound_right.max(self.def.bound_left),
+        );
+
+        let top = players.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let y = (top + self.def.floor_tension).min(0.0) * self.def.vertical_follow;
+        self.y = y.clamp(
+            self.def.bound_high,
+            self.def.bound_low.max(self.def.bound_high),
+        );
+    }
+
+    /// Obtiene los límites en la X en los que se pueden mover los jugadores.
+    ///
+    /// # Retorna
+    ///
+    /// Los bordes izquierdo y derecho de la pantalla, recortados por los límites del escenario.
+    pub fn get_player_bounds(&self) -> (f64, f64) {
+        let half_width = self.local_coord.0 / 2.0;
+        (
+            (self.x - half_width).max(self.player_bounds.0),
+            (self.x + half_width).min(self.player_bounds.1),
+        )
+    }
+
+    /// Obtiene la mitad del ancho de la pantalla en coordenadas del escenario.
+    ///
+    /// # Retorna
+    ///
+    /// La distancia desde el centro de la pantalla a cada borde.
+    pub fn get_half_width(&self) -> f64 {
+        self.local_coord.0 / 2.0
+    }
+
+    /// Obtiene la transformación de la pantalla, que escala el `localcoord` del escenario a la ventana.
+    ///
+    /// # Argumentos
+    ///
+    /// * `c` - El contexto de dibujo.
+    ///
+    /// # Retorna
+    ///
+    /// La transformación para dibujar en coordenadas de la pantalla.
+    pub fn screen_transform(&self, c: &Context) -> Matrix2d {
+        let scale = c.get_view_size()[0] / self.local_coord.0;
+        c.transform.scale(scale, scale)
+    }
+
+    /// Obtiene la transformación del escenario, para dibujar a los jugadores.
+    ///
+    /// # Argumentos
+    ///
+    /// * `c` - El contexto de dibujo.
+    ///
+    /// # Retorna
+    ///
+    /// La transformación para dibujar en coordenadas del escenario.
+    pub fn stage_transform(&self, c: &Context) -> Matrix2d {
+        self.screen_transform(c)
+            .trans(self.local_coord.0 / 2.0 - self.x, self.zoffset - self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Crea una cámara de una pantalla de 320 de ancho con los límites indicados.
+    ///
+    /// # Argumentos
+    ///
+    /// * `bound` - Límite izquierdo y derecho de la cámara.
+    ///
+    /// # Retorna
+    ///
+    /// La cámara en el centro del escenario.
+    fn camera(bound: f64) -> Camera {
+        let mut def = StageDef::new();
+        def.camera.bound_left = -bound;
+        def.camera.bound_right = bound;
+        def.camera.bound_high = -100.0;
+        def.camera.tension = 50.0;
+        def.camera.vertical_follow = 0.5;
+        Camera::new(&def)
+    }
+
+    /// Prueba que la cámara no se mueve mientras los jugadores estén lejos de los bordes
+    #[test]
+    fn test_camera_tension() {
+        let mut camera = camera(200.0);
+        camera.update(&[(-100.0, 0.0), (100.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (0.0, 0.0));
+
+        camera.update(&[(-100.0, 0.0), (130.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (20.0, 0.0));
+        assert_eq!(camera.get_player_bounds(), (-140.0, 180.0));
+
+        camera.update(&[(-160.0, 0.0), (100.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (-50.0, 0.0));
+    }
+
+    /// Prueba que la cámara se queda entre los dos jugadores y dentro de sus límites
+    #[test]
+    fn test_camera_bounds() {
+        let mut camera = camera(100.0);
+        camera.update(&[(-150.0, 0.0), (150.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (0.0, 0.0));
+
+        camera.update(&[(400.0, 0.0), (450.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (100.0, 0.0));
+        assert_eq!(camera.get_player_bounds(), (-60.0, 260.0));
+    }
+
+    /// Prueba que la cámara sigue la altura del jugador más alto sin pasar de su límite superior
+    #[test]
+    fn test_camera_vertical_follow() {
+        let mut camera = camera(0.0);
+        camera.update(&[(0.0, -80.0), (0.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (0.0, -40.0));
+
+        camera.update(&[(0.0, -400.0), (0.0, 0.0)]);
+        assert_eq!(camera.get_pos(), (0.0, -100.0));
+    }
+}
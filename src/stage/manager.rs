@@ -0,0 +1,592 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    error::stage_error::StageError,
+    player::character::{
+        air::manager::read_animation_table,
+        cns::manager::{parse_number, parse_pair},
+        def::manager::{read_entries, unquote},
+    },
+};
+
+/// Información del escenario (`[Info]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Info {
+    /// Nombre interno del escenario
+    pub name: String,
+    /// Nombre que se muestra en pantalla
+    pub display_name: String,
+    /// Autor del escenario
+    pub author: String,
+}
+
+/// Límites y comportamiento de la cámara (`[Camera]`), en coordenadas del escenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraDef {
+    /// Posición inicial de la cámara en la X
+    pub start_x: f64,
+    /// Posición inicial de la cámara en la Y
+    pub start_y: f64,
+    /// Límite izquierdo de la cámara
+    pub bound_left: f64,
+    /// Límite derecho de la cámara
+    pub bound_right: f64,
+    /// Límite superior de la cámara, negativo hacia arriba
+    pub bound_high: f64,
+    /// Límite inferior de la cámara
+    pub bound_low: f64,
+    /// Distancia al borde de la pantalla a partir de la cual la cámara sigue a los jugadores
+    pub tension: f64,
+    /// Fracción de la altura de los jugadores que sigue la cámara en vertical
+    pub vertical_follow: f64,
+    /// Altura mínima de los jugadores para que la cámara empiece a subir
+    pub floor_tension: f64,
+}
+
+/// Posiciones iniciales y límites de los jugadores (`[PlayerInfo]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerInfo {
+    /// Posición inicial del jugador 1
+    pub p1_start: (f64, f64),
+    /// Orientación inicial del jugador 1, 1 a la derecha y -1 a la izquierda
+    pub p1_facing: i32,
+    /// Posición inicial del jugador 2
+    pub p2_start: (f64, f64),
+    /// Orientación inicial del jugador 2
+    pub p2_facing: i32,
+    /// Límite izquierdo de los jugadores
+    pub left_bound: f64,
+    /// Límite derecho de los jugadores
+    pub right_bound: f64,
+}
+
+/// Datos generales del escenario (`[StageInfo]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageInfo {
+    /// Altura del suelo en la pantalla
+    pub zoffset: f64,
+    /// Indica si los jugadores se giran automáticamente para mirarse
+    pub auto_turn: bool,
+    /// Indica si el fondo se reinicia entre rondas
+    pub reset_bg: bool,
+    /// Resolución en la que están definidas las coordenadas del escenario
+    pub local_coord: (f64, f64),
+    /// Escala horizontal de los jugadores
+    pub xscale: f64,
+    /// Escala vertical de los jugadores
+    pub yscale: f64,
+}
+
+/// Tipo de un elemento del fondo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BgType {
+    /// Sprite estático
+    Normal,
+    /// Sprite deformado para simular profundidad en el suelo
+    Parallax,
+    /// Sprite animado con una acción
+    Anim,
+}
+
+/// Transparencia de un elemento del fondo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trans {
+    /// Sin transparencia
+    None,
+    /// Suma de colores
+    Add,
+    /// Suma de colores con el destino a la mitad
+    Add1,
+    /// Resta de colores
+    Sub,
+    /// Suma con los factores de origen y destino, de 0 a 256
+    AddAlpha(u16, u16),
+}
+
+/// Elemento del fondo (`[BG ...]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BgElement {
+    /// Nombre del elemento, sacado de la cabecera de su sección
+    pub name: String,
+    /// Tipo del elemento
+    pub bg_type: BgType,
+    /// Sprite del elemento (grupo, número)
+    pub sprite: [i16; 2],
+    /// Acción que usa si es animado
+    pub action: Option<i32>,
+    /// Identificador del elemento
+    pub id: i32,
+    /// Capa, 0 detrás de los jugadores y 1 delante
+    pub layer: i32,
+    /// Posición inicial respecto al centro de la pantalla
+    pub start: (f64, f64),
+    /// Cuánto se desplaza respecto a la cámara
+    pub delta: (f64, f64),
+    /// Transparencia
+    pub trans: Trans,
+    /// Repeticiones en la X e Y, 0 no se repite, 1 se repite sin fin y más de 1 ese número de veces
+    pub tile: (i32, i32),
+    /// Espacio entre repeticiones
+    pub tile_spacing: (f64, f64),
+    /// Velocidad con la que se desplaza solo, en píxeles por tick
+    pub velocity: (f64, f64),
+    /// Escala horizontal de los bordes superior e inferior si es de tipo parallax
+    pub xscale: (f64, f64),
+    /// Ancho de los bordes superior e inferior si es de tipo parallax, tiene prioridad sobre `xscale`
+    pub width: Option<(f64, f64)>,
+}
+
+/// Fotograma de la acción de un elemento animado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BgFrame {
+    /// Sprite del fotograma (grupo, número)
+    pub sprite: [i16; 2],
+    /// Desplazamiento del fotograma
+    pub offset: (f64, f64),
+    /// Ticks que dura el fotograma, -1 para siempre
+    pub time: i32,
+    /// Volteo horizontal y vertical
+    pub flip: (bool, bool),
+}
+
+/// Acción de un elemento animado (`[Begin Action N]`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BgAction {
+    /// Fotogramas en orden
+    pub frames: Vec<BgFrame>,
+    /// Fotograma al que se vuelve al terminar
+    pub loopstart: usize,
+}
+
+/// Struct que representa la definición de un escenario leída de su archivo DEF.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageDef {
+    /// Información del escenario
+    pub info: Info,
+    /// Cámara
+    pub camera: CameraDef,
+    /// Jugadores
+    pub player_info: PlayerInfo,
+    /// Datos generales
+    pub stage_info: StageInfo,
+    /// Archivo de sprites, relativo a la carpeta del DEF
+    pub sprite: String,
+    /// Elementos del fondo en el orden en que se dibujan
+    pub elements: Vec<BgElement>,
+    /// Acciones de los elementos animados
+    pub actions: HashMap<i32, BgAction>,
+}
+
+impl BgElement {
+    /// Crea un elemento normal con los valores por defecto.
+    ///
+    /// # Argumentos
+    ///
+    /// * `name` - Nombre del elemento.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo `BgElement`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            bg_type: BgType::Normal,
+            sprite: [0, 0],
+            action: None,
+            id: 0,
+            layer: 0,
+            start: (0.0, 0.0),
+            delta: (1.0, 1.0),
+            trans: Trans::None,
+            tile: (0, 0),
+            tile_spacing: (0.0, 0.0),
+            velocity: (0.0, 0.0),
+            xscale: (1.0, 1.0),
+            width: None,
+        }
+    }
+}
+
+impl StageDef {
+    /// Crea una definición de escenario vacía con los valores por defecto de MUGEN.
+    ///
+    /// # Retorna
+    ///
+    /// Un nuevo `StageDef` sin elementos.
+    pub fn new() -> Self {
+        Self {
+            info: Info {
+                name: String::new(),
+                display_name: String::new(),
+                author: String::new(),
+            },
+            camera: CameraDef {
+                start_x: 0.0,
+                start_y: 0.0,
+                bound_left: 0.0,
+                bound_right: 0.0,
+                bound_high: 0.0,
+                bound_low: 0.0,
+                tension: 50.0,
+                vertical_follow: 0.2,
+                floor_tension: 0.0,
+            },
+            player_info: PlayerInfo {
+                p1_start: (-70.0, 0.0),
+                p1_facing: 1,
+                p2_start: (70.0, 0.0),
+                p2_facing: -1,
+                left_bound: -1000.0,
+                right_bound: 1000.0,
+            },
+            stage_info: StageInfo {
+                zoffset: 190.0,
+                auto_turn: true,
+                reset_bg: true,
+                local_coord: (320.0, 240.0),
+                xscale: 1.0,
+                yscale: 1.0,
+            },
+            sprite: String::new(),
+            elements: Vec::new(),
+            actions: HashMap::new(),
+        }
+    }
+}
+
+/// Interpreta un par de números, manteniendo el valor anterior de los que se omitan.
+///
+/// # Argumentos
+///
+/// * `value` - Texto del par.
+/// * `default` - Valor que se mantiene para los números omitidos.
+///
+/// # Retorna
+///
+/// El par de números, o `None` si alguno no es válido.
+fn parse_pair_or(value: &str, default: (f64, f64)) -> Option<(f64, f64)> {
+    let (x, y) = parse_pair(value)?;
+    Some((x.unwrap_or(default.0), y.unwrap_or(default.1)))
+}
+
+/// Interpreta el tipo de transparencia de un elemento.
+///
+/// # Argumentos
+///
+/// * `value` - Texto de la transparencia.
+///
+/// # Retorna
+///
+/// La transparencia sin factores, o `None` si no es válida.
+fn parse_trans(value: &str) -> Option<Trans> {
+    match value.trim().to_lowercase().as_str() {
+        "none" => Some(Trans::None),
+        "add" => Some(Trans::Add),
+        "add1" => Some(Trans::Add1),
+        "sub" => Some(Trans::Sub),
+        "addalpha" => Some(Trans::AddAlpha(256, 0)),
+        _ => None,
+    }
+}
+
+/// Lee las líneas del archivo DEF de un escenario.
+///
+/// Cada sección `[BG ...]` crea un elemento nuevo aunque se repita el nombre, y las acciones `[Begin Action N]`
+/// se leen igual que en un archivo AIR.
+///
+/// # Argumentos
+///
+/// * `lines` - Líneas del archivo DEF.
+/// * `file` - Nombre del archivo, usado en los errores.
+///
+/// # Retorna
+///
+/// La definición del escenario, o un `StageError` si algún valor está malformado o falta el archivo de sprites.
+pub fn read_stage_file(lines: &[&str], file: &str) -> Result<StageDef, StageError> {
+    let mut def = StageDef::new();
+    let mut display_name = None;
+    let mut sprite = None;
+    let mut alphas = Vec::new();
+    let mut current_line = 0;
+
+    for entry in read_entries(lines) {
+        let malformed = || StageError::Malformed(file.to_string(), entry.line);
+        let number = || parse_number(&entry.value).ok_or_else(malformed);
+        let flag = || Ok::<bool, StageError>(number()? != 0.0);
+        let section = entry.section.as_str();
+
+        if section == "bg" || section.starts_with("bg ") {
+            if entry.section_line != current_line {
+                current_line = entry.section_line;
+                def.elements.push(BgElement::new(section[2..].trim()));
+                alphas.push((256.0, 0.0));
+            }
+            let element = def.elements.last_mut().unwrap();
+            let pair = |default| parse_pair_or(&entry.value, default).ok_or_else(malformed);
+            match entry.key.as_str() {
+                "type" => {
+                    element.bg_type = match unquote(&entry.value).to_lowercase().as_str() {
+                        "normal" => BgType::Normal,
+                        "parallax" => BgType::Parallax,
+                        "anim" => BgType::Anim,
+                        _ => return Err(malformed()),
+                    }
+                }
+                "spriteno" => {
+                    let (group, number) = pair((0.0, 0.0))?;
+                    element.sprite = [group as i16, number as i16];
+                }
+                "actionno" => element.action = Some(number()? as i32),
+                "id" => element.id = number()? as i32,
+                "layerno" => element.layer = number()? as i32,
+                "start" => element.start = pair(element.start)?,
+                "delta" => element.delta = pair(element.delta)?,
+                "trans" => element.trans = parse_trans(&entry.value).ok_or_else(malformed)?,
+                "alpha" => *alphas.last_mut().unwrap() = pair((256.0, 0.0))?,
+                "tile" => {
+                    let (x, y) = pair((0.0, 0.0))?;
+                    element.tile = (x as i32, y as i32);
+                }
+                "tilespacing" => {
+                    let (x, y) = parse_pair(&entry.value).ok_or_else(malformed)?;
+                    let x = x.unwrap_or(0.0);
+                    element.tile_spacing = (x, y.unwrap_or(x));
+                }
+                "velocity" => element.velocity = pair(element.velocity)?,
+                "xscale" => element.xscale = pair(element.xscale)?,
+                "width" => element.width = Some(pair((0.0, 0.0))?),
+                _ => {}
+            }
+            continue;
+        }
+
+        let pair = |default| parse_pair_or(&entry.value, default).ok_or_else(malformed);
+        let value = unquote(&entry.value);
+        let (camera, players, stage) = (&mut def.camera, &mut def.player_info, &mut def.stage_info);
+        match (section, entry.key.as_str()) {
+            ("info", "name") => def.info.name = value,
+            ("info", "displayname") => display_name = Some(value),
+            ("info", "author") => def.info.author = value,
+            ("camera", "startx") => camera.start_x = number()?,
+            ("camera", "starty") => camera.start_y = number()?,
+            ("camera", "boundleft") => camera.bound_left = number()?,
+            ("camera", "boundright") => camera.bound_right = number()?,
+            ("camera", "boundhigh") => camera.bound_high = number()?,
+            ("camera", "boundlow") => camera.bound_low = number()?,
+            ("camera", "tension") => camera.tension = number()?,
+            ("camera", "verticalfollow") => camera.vertical_follow = number()?,
+            ("camera", "floortension") => camera.floor_tension = number()?,
+            ("playerinfo", "p1startx") => players.p1_start.0 = number()?,
+            ("playerinfo", "p1starty") => players.p1_start.1 = number()?,
+            ("playerinfo", "p1facing") => players.p1_facing = number()?.signum() as i32,
+            ("playerinfo", "p2startx") => players.p2_start.0 = number()?,
+            ("playerinfo", "p2starty") => players.p2_start.1 = number()?,
+            ("playerinfo", "p2facing") => players.p2_facing = number()?.signum() as i32,
+            ("playerinfo", "leftbound") => players.left_bound = number()?,
+            ("playerinfo", "rightbound") => players.right_bound = number()?,
+            ("stageinfo", "zoffset") => stage.zoffset = number()?,
+            ("stageinfo", "autoturn") => stage.auto_turn = flag()?,
+            ("stageinfo", "resetbg") => stage.reset_bg = flag()?,
+            ("stageinfo", "localcoord") => stage.local_coord = pair(stage.local_coord)?,
+            ("stageinfo", "xscale") => stage.xscale = number()?,
+            ("stageinfo", "yscale") => stage.yscale = number()?,
+            ("bgdef", "spr") => sprite = Some(value),
+            _ => {}
+        }
+    }
+    // Los factores de `alpha` solo se usan con `trans = addalpha`, que puede aparecer antes o después
+    for (element, (src, dst)) in def.elements.iter_mut().zip(alphas) {
+        if let Trans::AddAlpha(..) = element.trans {
+            element.trans =
+                Trans::AddAlpha(src.clamp(0.0, 256.0) as u16, dst.clamp(0.0, 256.0) as u16);
+        }
+    }
+
+    def.info.display_name = display_name.unwrap_or_else(|| def.info.name.clone());
+    def.sprite = sprite.ok_or(StageError::MissingFile("spr".to_string()))?;
+    def.actions = read_actions(lines)?;
+    Ok(def)
+}
+
+/// Lee las acciones `[Begin Action N]` de los elementos animados.
+///
+/// # Argumentos
+///
+/// * `lines` - Líneas del archivo DEF.
+///
+/// # Retorna
+///
+/// Las acciones identificadas por su número, o un `StageError` si alguna está malformada.
+fn read_actions(lines: &[&str]) -> Result<HashMap<i32, BgAction>, StageError> {
+    let table = read_animation_table(&lines.join("\n"))?;
+    let mut actions = HashMap::new();
+    for (&number, animation) in table.get_animations() {
+        let frames: Vec<BgFrame> = animation
+            .get_frames()
+            .iter()
+            .map(|frame| {
+                let (x, y) = frame.get_offset();
+                BgFrame {
+                    sprite: frame.get_sprite(),
+                    offset: (x as f64, y as f64),
+                    time: frame.get_time(),
+                    flip: frame.get_flip(),
+                }
+            })
+            .collect();
+        if frames.is_empty() {
+            continue;
+        }
+        let loopstart = (animation.get_loopstart().max(0) as usize).min(frames.len() - 1);
+        actions.insert(number, BgAction { frames, loopstart });
+    }
+    Ok(actions)
+}
+
+/// Crea la definición de un escenario leyendo su archivo DEF.
+///
+/// # Argumentos
+///
+/// * `def` - Ruta del archivo DEF, el archivo de sprites es relativo a su carpeta.
+///
+/// # Retorna
+///
+/// La definición del escenario, o un `StageError` si el archivo no se encuentra o está malformado.
+pub fn parse_stage(def: &str) -> Result<StageDef, StageError> {
+    let path = Path::new(def);
+    let content =
+        std::fs::read_to_string(path).map_err(|_| StageError::NotFound(def.to_string()))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let file = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| def.to_string());
+    read_stage_file(&lines, &file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prueba si el escenario de entrenamiento se lee y si uno que no existe falla
+    #[test]
+    fn test_parse_stage() {
+        let def = parse_stage("src/stages/training/training.def").unwrap();
+        assert_eq!(def.info.name, "training");
+        assert_eq!(def.sprite, "training.sff");
+        assert_eq!(def.stage_info.local_coord, (512.0, 512.0));
+        assert_eq!(def.elements.len(), 1);
+        assert_eq!(def.elements[0].sprite, [0, 0]);
+        assert!(parse_stage("src/stages/missing/missing.def").is_err());
+    }
+
+    /// Prueba la lectura de secciones generales y elementos repetidos
+    #[test]
+    fn test_read_stage_file() {
+        let lines = vec![
+            "[Info]",
+            "name = \"Test\"",
+            "[Camera]",
+            "boundleft = -150 ; comentario",
+            "boundright = 150",
+            "tension = 60",
+            "[PlayerInfo]",
+            "p1startx = -80",
+            "p2facing = -1",
+            "[StageInfo]",
+            "zoffset = 200",
+            "localcoord = 320, 240",
+            "[BGdef]",
+            "spr = \"test.sff\"",
+            "[BG Sky]",
+            "spriteno = 0, 1",
+            "start = 0, -20",
+            "delta = .5",
+            "tile = 1, 0",
+            "tilespacing = 4",
+            "velocity = -1, 0",
+            "[BG Sky]",
+            "spriteno = 0, 2",
+            "layerno = 1",
+            "trans = addalpha",
+            "alpha = 128, 128",
+        ];
+        let def = read_stage_file(&lines, "test.def").unwrap();
+        assert_eq!(def.info.display_name, "Test");
+        assert_eq!(def.camera.bound_left, -150.0);
+        assert_eq!(def.camera.tension, 60.0);
+        assert_eq!(def.player_info.p1_start, (-80.0, 0.0));
+        assert_eq!(def.player_info.p2_facing, -1);
+        assert_eq!(def.stage_info.zoffset, 200.0);
+        assert_eq!(def.sprite, "test.sff");
+
+        assert_eq!(def.elements.len(), 2);
+        let sky = &def.elements[0];
+        assert_eq!(sky.name, "sky");
+        assert_eq!(sky.sprite, [0, 1]);
+        assert_eq!(sky.start, (0.0, -20.0));
+        assert_eq!(sky.delta, (0.5, 1.0));
+        assert_eq!(sky.tile, (1, 0));
+        assert_eq!(sky.tile_spacing, (4.0, 4.0));
+        assert_eq!(sky.velocity, (-1.0, 0.0));
+        assert_eq!(sky.layer, 0);
+        let front = &def.elements[1];
+        assert_eq!(front.sprite, [0, 2]);
+        assert_eq!(front.layer, 1);
+        assert_eq!(front.trans, Trans::AddAlpha(128, 128));
+    }
+
+    /// Prueba la lectura de elementos parallax y animados con sus acciones
+    #[test]
+    fn test_read_stage_anim() {
+        let lines = vec![
+            "[BGdef]",
+            "spr = stage.sff",
+            "[BG Floor]",
+            "type = parallax",
+            "spriteno = 10, 0",
+            "xscale = 1, 1.5",
+            "[BG Flag]",
+            "type = anim",
+            "actionno = 100",
+            "trans = add",
+            "[Begin Action 100]",
+            "20,0, 0,0, 5",
+            "Loopstart",
+            "20,1, 0,-2, 5, H",
+        ];
+        let def = read_stage_file(&lines, "test.def").unwrap();
+        assert_eq!(def.elements[0].bg_type, BgType::Parallax);
+        assert_eq!(def.elements[0].xscale, (1.0, 1.5));
+        assert_eq!(def.elements[1].bg_type, BgType::Anim);
+        assert_eq!(def.elements[1].action, Some(100));
+        assert_eq!(def.elements[1].trans, Trans::Add);
+
+        let action = &def.actions[&100];
+        assert_eq!(action.frames.len(), 2);
+        assert_eq!(action.loopstart, 1);
+        assert_eq!(action.frames[1].sprite, [20, 1]);
+        assert_eq!(action.frames[1].time, 5);
+        assert!(action.frames[1].flip.0);
+    }
+
+    /// Prueba que los valores malformados y la falta de sprites se detectan
+    #[test]
+    fn test_read_stage_malformed() {
+        let lines = vec!["[BGdef]", "spr = a.sff", "[BG A]", "type = sprite"];
+        assert!(matches!(
+            read_stage_file(&lines, "test.def"),
+            Err(StageError::Malformed(_, 4))
+        ));
+        let lines = vec!["[StageInfo]", "zoffset = abc", "[BGdef]", "spr = a.sff"];
+        assert!(matches!(
+            read_stage_file(&lines, "test.def"),
+            Err(StageError::Malformed(_, 2))
+        ));
+        let lines = vec!["[Info]", "name = a"];
+        assert!(matches!(
+            read_stage_file(&lines, "test.def"),
+            Err(StageError::MissingFile(_))
+        ));
+    }
+}
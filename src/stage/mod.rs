@@ -0,0 +1,3 @@
+pub mod camera;
+pub mod manager;
+pub mod stage;
@@ -0,0 +1,340 @@
+use gfx_device_gl::Resources;
+use graphics::{draw_state::Blend, math::Matrix2d, DrawState, Image, Transformed};
+use piston_window::{Context, G2d, G2dTextureContext, Texture};
+
+use crate::{
+    error::stage_error::StageError,
+    player::character::sff::{
+        decoder::{Sff, Sprite},
+        texture::{subtract_blend, SffTextures},
+    },
+};
+
+use super::{
+    camera::Camera,
+    manager::{parse_stage, BgElement, BgType, StageDef, Trans},
+};
+
+/// Estado de un elemento del fondo que cambia durante la pelea.
+#[derive(Debug, Clone, Default)]
+struct ElementState {
+    /// Desplazamiento acumulado por la velocidad del elemento
+    scroll: (f64, f64),
+    /// Fotograma actual de la acción si es animado
+    frame: usize,
+    /// Ticks que le quedan al fotograma actual
+    time: i32,
+}
+
+/// Escenario de la pelea, con sus sprites y el estado de sus elementos.
+///
+/// Las coordenadas del escenario están en su `localcoord` y se escalan al tamaño de la ventana al dibujar.
+pub struct Stage {
+    /// Definición del escenario
+    def: StageDef,
+    /// Sprites del escenario con sus texturas
+    sff: SffTextures,
+    /// Estado de cada elemento, en el mismo orden que en la definición
+    states: Vec<ElementState>,
+}
+
+impl Stage {
+    /// Carga un escenario de la carpeta `src/stages`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `stage_name` - Nombre del escenario, su archivo DEF es `src/stages/<nombre>/<nombre>.def`.
+    /// * `context` - El contexto gráfico utilizado para crear las texturas de los sprites.
+    ///
+    /// # Retorna
+    ///
+    /// El escenario, o un `StageError` si no se encuentra o está malformado.
+    pub fn load_stage(stage_name: &str, context: G2dTextureContext) -> Result<Stage, StageError> {
+        let folder = std::env::current_dir()
+            .unwrap()
+            .join("src")
+            .join("stages")
+            .join(stage_name);
+        let def_path = folder.join(format!("{}.def", stage_name));
+        let def = parse_stage(&def_path.to_string_lossy())?;
+        let sff = SffTextures::new(Sff::load_file(folder.join(&def.sprite), false)?, context);
+        let mut stage = Stage {
+            states: vec![ElementState::default(); def.elements.len()],
+            def,
+            sff,
+        };
+        stage.reset();
+        Ok(stage)
+    }
+
+    /// Obtiene la definición del escenario.
+    ///
+    /// # Retorna
+    ///
+    /// La definición leída del archivo DEF.
+    pub fn get_def(&self) -> &StageDef {
+        &self.def
+    }
+
+    /// Reinicia el desplazamiento y las animaciones de los elementos.
+    pub fn reset(&mut self) {
+        for (element, state) in self.def.elements.iter().zip(self.states.iter_mut()) {
+            *state = ElementState::default();
+            if let Some(frame) = element
+                .action
+                .and_then(|action| self.def.actions.get(&action))
+                .and_then(|action| action.frames.first())
+            {
+                state.time = frame.time;
+            }
+        }
+    }
+
+    /// Avanza un tick el desplazamiento y las animaciones de los elementos.
+    pub fn update(&mut self) {
+        for (element, state) in self.def.elements.iter().zip(self.states.iter_mut()) {
+            state.scroll.0 += element.velocity.0;
+            state.scroll.1 += element.velocity.1;
+
+            let action = match element
+                .action
+                .and_then(|action| self.def.actions.get(&action))
+            {
+                Some(action) if element.bg_type == BgType::Anim => action,
+                _ => continue,
+            };
+            if state.time == -1 {
+                continue;
+            }
+            state.time -= 1;
+            if state.time <= 0 {
+                state.frame += 1;
+                if state.frame >= action.frames.len() {
+                    state.frame = action.loopstart;
+                }
+                state.time = action.frames[state.frame].time;
+            }
+        }
+    }
+
+    /// Dibuja los elementos de una capa del escenario.
+    ///
+    /// # Argumentos
+    ///
+    /// * `layer` - Capa a dibujar, 0 detrás de los jugadores y 1 delante.
+    /// * `camera` - Cámara de la pelea.
+    /// * `c` - El contexto de dibujo.
+    /// * `g` - El contexto de gráficos.
+    pub fn draw(&self, layer: i32, camera: &Camera, c: Context, g: &mut G2d) {
+        let local = self.def.stage_info.local_coord;
+        let transform = camera.screen_transform(&c);
+        let camera = camera.get_pos();
+
+        for (element, state) in self.def.elements.iter().zip(self.states.iter()) {
+            if element.layer != layer {
+                continue;
+            }
+            let (sprite, offset, flip) = match element.bg_type {
+                BgType::Anim => {
+                    let frame = match element
+                        .action
+                        .and_then(|action| self.def.actions.get(&action))
+                        .and_then(|action| action.frames.get(state.frame))
+                    {
+                        Some(frame) => frame,
+                        None => continue,
+                    };
+                    (frame.sprite, frame.offset, frame.flip)
+                }
+                _ => (element.sprite, (0.0, 0.0), (false, false)),
+            };
+            let (sprite, texture) = match (
+                self.sff.get_sff().sprites.get(&sprite),
+                self.sff.get_texture(&sprite),
+            ) {
+                (Some(sprite), Some(texture)) => (sprite, texture),
+                _ => continue,
+            };
+
+            let x = element.start.0 + state.scroll.0 + offset.0 - camera.0 * element.delta.0
+                + local.0 / 2.0;
+            let y = element.start.1 + state.scroll.1 + offset.1 - camera.1 * element.delta.1;
+            draw_element(
+                element,
+                sprite,
+                texture,
+                (x, y),
+                camera.0,
+                flip,
+                local,
+                transform,
+                g,
+            );
+        }
+    }
+}
+
+/// Dibuja un elemento del fondo con sus repeticiones.
+///
+/// # Argumentos
+///
+/// * `element` - Elemento a dibujar.
+/// * `sprite` - Sprite del elemento.
+/// * `texture` - Textura del sprite.
+/// * `pos` - Posición del eje del sprite en coordenadas de la pantalla.
+/// * `camera_x` - Posición de la cámara en la X, usada para deformar los elementos parallax.
+/// * `flip` - Volteo horizontal y vertical.
+/// * `local` - Tamaño de la pantalla en coordenadas del escenario.
+/// * `transform` - Transformación de la pantalla.
+/// * `g` - El contexto de gráficos.
+#[allow(clippy::too_many_arguments)]
+fn draw_element(
+    element: &BgElement,
+    sprite: &Sprite,
+    texture: &Texture<Resources>,
+    pos: (f64, f64),
+    camera_x: f64,
+    flip: (bool, bool),
+    local: (f64, f64),
+    transform: Matrix2d,
+    g: &mut G2d,
+) {
+    let width = sprite.size[0] as f64;
+    let height = sprite.size[1] as f64;
+    let left = pos.0 - sprite.offset[0] as f64;
+    let top = pos.1 - sprite.offset[1] as f64;
+
+    // El parallax se aproxima inclinando el sprite: el borde inferior se desplaza distinto que el superior
+    let shear = if element.bg_type == BgType::Parallax && height > 0.0 {
+        let xscale = match element.width {
+            Some((top, bottom)) if width > 0.0 => (top / width, bottom / width),
+            _ => element.xscale,
+        };
+        -camera_x * element.delta.0 * (xscale.1 - xscale.0) / height
+    } else {
+        0.0
+    };
+
+    let step_x = width + element.tile_spacing.0;
+    let step_y = height + element.tile_spacing.1;
+    let xs = tile_positions(left, step_x, element.tile.0, local.0);
+    let ys = tile_positions(top, step_y, element.tile.1, local.1);
+
+    let (draw_state, color) = blend(element.trans);
+    let image = Image::new_color(color);
+    for &y in &ys {
+        for &x in &xs {
+            let mut t = transform.trans(x, y).shear(shear, 0.0);
+            if flip.0 {
+                t = t.trans(width, 0.0).flip_h();
+            }
+            if flip.1 {
+                t = t.trans(0.0, height).flip_v();
+            }
+            image.draw(texture, &draw_state, t, g);
+        }
+    }
+}
+
+/// Calcula las posiciones de las repeticiones de un elemento en un eje.
+///
+/// # Argumentos
+///
+/// * `start` - Posición del elemento sin repetir.
+/// * `step` - Distancia entre repeticiones.
+/// * `tile` - 0 no se repite, 1 se repite hasta cubrir la pantalla y más de 1 ese número de veces.
+/// * `screen` - Tamaño de la pantalla en ese eje.
+///
+/// # Retorna
+///
+/// Las posiciones en las que se dibuja el elemento.
+fn tile_positions(start: f64, step: f64, tile: i32, screen: f64) -> Vec<f64> {
+    if step <= 0.0 || tile == 0 {
+        return vec![start];
+    }
+    if tile > 1 {
+        return (0..tile).map(|i| start + step * i as f64).collect();
+    }
+    let first = start - ((start / step).ceil() * step);
+    let mut positions = Vec::new();
+    let mut pos = first;
+    while pos < screen {
+        positions.push(pos);
+        pos += step;
+    }
+    positions
+}
+
+/// Obtiene el modo de mezcla y el color con los que se dibuja una transparencia.
+///
+/// Las sumas usan la mezcla `Lighter`, que multiplica el sprite por su alpha, igual que `frame_blend` en los
+/// personajes.
+///
+/// # Argumentos
+///
+/// * `trans` - Transparencia del elemento.
+///
+/// # Retorna
+///
+/// El estado de dibujo y el color que multiplica al sprite.
+fn blend(trans: Trans) -> (DrawState, [f32; 4]) {
+    let draw_state = DrawState::default();
+    match trans {
+        Trans::None => (draw_state.blend(Blend::Alpha), [1.0; 4]),
+        Trans::Add => (draw_state.blend(Blend::Lighter), [1.0; 4]),
+        Trans::Add1 => (draw_state.blend(Blend::Lighter), [1.0, 1.0, 1.0, 0.5]),
+        Trans::Sub => subtract_blend(),
+        Trans::AddAlpha(src, _) => (
+            draw_state.blend(Blend::Alpha),
+            [1.0, 1.0, 1.0, src as f32 / 256.0],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Calcula el color que queda en pantalla al dibujar un píxel opaco del sprite sobre el fondo, con los factores
+    /// de mezcla que usa `gfx_graphics` para cada modo.
+    fn draw_pixel(trans: Trans, sprite: f32, background: f32) -> f32 {
+        let (state, color) = blend(trans);
+        let (src, alpha) = (sprite * color[0], color[3]);
+        match state.blend {
+            Some(Blend::Alpha) => src * alpha + background * (1.0 - alpha),
+            Some(Blend::Add) => src + background,
+            Some(Blend::Lighter) => src * alpha + background,
+            other => panic!("modo de mezcla inesperado {:?}", other),
+        }
+    }
+
+    /// Prueba lo que se dibuja con cada transparencia: la suma a la mitad respeta el alpha y la resta se dibuja igual
+    /// que en los personajes
+    #[test]
+    fn test_blend() {
+        assert_eq!(draw_pixel(Trans::None, 0.6, 0.2), 0.6);
+        assert_eq!(draw_pixel(Trans::Add, 0.6, 0.2), 0.8);
+        assert_eq!(draw_pixel(Trans::Add1, 0.6, 0.2), 0.5);
+        assert_eq!(draw_pixel(Trans::AddAlpha(128, 128), 0.6, 0.2), 0.4);
+        assert_eq!(draw_pixel(Trans::Sub, 0.6, 0.2), 0.1);
+        assert_eq!(blend(Trans::Sub), subtract_blend());
+    }
+
+    /// Prueba las posiciones de las repeticiones sin repetir, con un número fijo y hasta cubrir la pantalla
+    #[test]
+    fn test_tile_positions() {
+        assert_eq!(tile_positions(10.0, 100.0, 0, 300.0), vec![10.0]);
+        assert_eq!(
+            tile_positions(10.0, 100.0, 3, 300.0),
+            vec![10.0, 110.0, 210.0]
+        );
+        assert_eq!(
+            tile_positions(150.0, 100.0, 1, 300.0),
+            vec![-50.0, 50.0, 150.0, 250.0]
+        );
+        assert_eq!(
+            tile_positions(-30.0, 100.0, 1, 200.0),
+            vec![-30.0, 70.0, 170.0]
+        );
+    }
+}
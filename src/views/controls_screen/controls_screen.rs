@@ -0,0 +1,192 @@
+use std::sync::{mpsc::Sender, Arc, Mutex};
+
+use graphics::{clear, image};
+use piston::Key;
+
+use crate::{
+    error::pop_up::show_error_popup,
+    player::input::key_map::{find_conflicts, save_key_maps, KeyMap, BINDINGS, KEYS_CFG},
+    preloader::preloader::Preloads,
+    views::{
+        screen::Screen,
+        screen_manager::{Event, ScreenType},
+    },
+};
+
+use super::gui;
+
+/// Fila con la opción de volver a las teclas por defecto.
+const DEFAULTS_ROW: usize = BINDINGS.len();
+/// Fila con la opción de guardar.
+const SAVE_ROW: usize = BINDINGS.len() + 1;
+
+/// Representa la pantalla de asignación de teclas.
+///
+/// Se maneja con las flechas, `Return` y `Escape`, que no dependen de las teclas asignadas.
+pub struct ControlsScreen {
+    /// Elementos precargados
+    preloads: Arc<Mutex<Preloads>>,
+    /// Emisor de eventos
+    event_sender: Sender<Event>,
+    /// Mapeos en edición de los jugadores 1 y 2
+    key_maps: [KeyMap; 2],
+    /// Jugador cuya tecla está elegida
+    player: usize,
+    /// Fila elegida: una acción, las teclas por defecto o guardar
+    row: usize,
+    /// Indica si se espera la tecla para la acción elegida
+    capturing: bool,
+    /// Indica si se intentó guardar con teclas en conflicto
+    conflict_warning: bool,
+}
+
+impl ControlsScreen {
+    /// Asigna una tecla a la acción elegida y pasa a la siguiente, hasta llegar a la última.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla presionada.
+    fn capture(&mut self, key: Key) {
+        let (_, action) = BINDINGS[self.row];
+        self.key_maps[self.player].set_key(action, key);
+        self.conflict_warning = false;
+        if self.row + 1 < BINDINGS.len() {
+            self.row += 1;
+        } else {
+            self.capturing = false;
+        }
+    }
+
+    /// Guarda los mapeos y vuelve a la pantalla principal, si no hay teclas en conflicto.
+    fn save(&mut self) {
+        if !find_conflicts(&self.key_maps).is_empty() {
+            self.conflict_warning = true;
+            return;
+        }
+        if let Err(err) = save_key_maps(KEYS_CFG, &self.key_maps) {
+            show_error_popup(&err);
+            return;
+        }
+        self.preloads
+            .lock()
+            .unwrap()
+            .set_key_maps(self.key_maps.clone());
+        self.event_sender
+            .send(Event::ChangeScreen(ScreenType::Main))
+            .unwrap();
+    }
+}
+
+impl Screen for ControlsScreen {
+    /// Crea una nueva instancia de `ControlsScreen`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `event_sender` - El canal de eventos para comunicarse con el administrador de pantallas.
+    /// * `preloads` - Los recursos precargados para el juego.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `ControlsScreen`.
+    fn new(event_sender: Sender<Event>, preloads: Arc<Mutex<Preloads>>) -> Self
+    where
+        Self: Sized,
+    {
+        let key_maps = preloads.lock().unwrap().get_ref_key_maps().clone();
+        Self {
+            preloads,
+            event_sender,
+            key_maps,
+            player: 0,
+            row: 0,
+            capturing: false,
+            conflict_warning: false,
+        }
+    }
+
+    /// Actualiza el estado de la pantalla de asignación de teclas.
+    fn update(&mut self) {}
+
+    /// Maneja el evento de presionar una tecla en la pantalla de asignación de teclas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla que se ha presionado.
+    fn on_press(&mut self, key: Key) {
+        if self.capturing {
+            if key == Key::Escape {
+                self.capturing = false;
+            } else {
+                self.capture(key);
+            }
+            return;
+        }
+        match key {
+            Key::Up if self.row > 0 => self.row -= 1,
+            Key::Down if self.row < SAVE_ROW => self.row += 1,
+            Key::Left => self.player = 0,
+            Key::Right => self.player = 1,
+            Key::Return => match self.row {
+                DEFAULTS_ROW => {
+                    self.key_maps = [KeyMap::new(true), KeyMap::new(false)];
+                    self.conflict_warning = false;
+                }
+                SAVE_ROW => self.save(),
+                _ => self.capturing = true,
+            },
+            Key::Escape => {
+                self.event_sender
+                    .send(Event::ChangeScreen(ScreenType::Main))
+                    .unwrap();
+            }
+            _ => (),
+        }
+    }
+
+    /// Maneja el evento de soltar una tecla en la pantalla de asignación de teclas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla que se ha soltado.
+    fn on_release(&mut self, key: Key) {
+        let _ = key;
+    }
+
+    /// Dibuja el contenido de la pantalla de asignación de teclas.
+    ///
+    /// # Argumentos
+    ///
+    /// * `c` - El contexto de dibujo.
+    /// * `g` - El contexto de gráficos.
+    /// * `device` - El dispositivo de dibujo.
+    fn draw(
+        &mut self,
+        c: graphics::Context,
+        g: &mut piston_window::prelude::G2d,
+        device: &mut gfx_device_gl::Device,
+    ) {
+        clear([1.0; 4], g);
+        let mut preloads = self.preloads.lock().unwrap();
+        image(
+            preloads.get_mut_ref_background().first().unwrap(),
+            c.transform,
+            g,
+        );
+        let glyphs = preloads.get_mut_ref_fonts().get_mut(0).unwrap();
+        let conflicts = find_conflicts(&self.key_maps);
+        gui::draw_bindings(
+            c,
+            g,
+            glyphs,
+            &self.key_maps,
+            &conflicts,
+            (self.player, self.row),
+            self.capturing,
+        );
+        gui::draw_options(c, g, glyphs, self.row.checked_sub(DEFAULTS_ROW));
+        if self.conflict_warning {
+            gui::draw_warning(c, g, glyphs);
+        }
+        glyphs.factory.encoder.flush(device);
+    }
+}
@@ -0,0 +1,148 @@
+use graphics::{rectangle, CharacterCache, Context, Transformed};
+use piston_window::{G2d, Glyphs};
+
+use crate::{
+    config::game_config::WINDOW_SIZE,
+    player::input::key_map::{key_name, KeyMap, BINDINGS},
+    CK,
+};
+
+const LABELS: [&str; 11] = [
+    "JUMP", "CROUCH", "LEFT", "RIGHT", "LK", "MK", "HK", "LP", "MP", "HP", "TAUNT",
+];
+const OPTIONS: [&str; 2] = ["Defaults", "Save"];
+const PLAYERS: [&str; 2] = ["P1", "P2"];
+const TEXT_SIZE: u32 = 20;
+const PADDING: f64 = 17.0;
+const ROWS_MARGIN_TOP: f64 = 80.0;
+const ROW_HEIGHT: f64 = 27.0;
+const COLUMNS_X: [f64; 3] = [PADDING * 2.0, 200.0, 350.0];
+const TEXT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.4, 0.2, 1.0];
+const CONFLICT_COLOR: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+
+/// Dibuja un texto con el tamaño de la pantalla de controles.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+/// - `text`: El texto a dibujar.
+/// - `position`: La posición del texto.
+/// - `color`: El color del texto.
+fn draw_text(
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    text: &str,
+    position: [f64; 2],
+    color: [f32; 4],
+) {
+    let transform = c.transform.trans(position[0], position[1]).zoom(0.5);
+    graphics::text::Text::new_color(color, TEXT_SIZE * 2)
+        .draw(text, glyphs, &c.draw_state, transform, g)
+        .unwrap();
+}
+
+/// Dibuja la tabla con la tecla de cada acción para los dos jugadores.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+/// - `key_maps`: Los mapeos de teclas de los jugadores.
+/// - `conflicts`: El jugador y la acción de las teclas en conflicto.
+/// - `selected`: El jugador y la fila elegidos.
+/// - `capturing`: Indica si se espera una tecla para la acción elegida.
+pub fn draw_bindings(
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    key_maps: &[KeyMap; 2],
+    conflicts: &[(usize, CK)],
+    selected: (usize, usize),
+    capturing: bool,
+) {
+    rectangle(
+        [1.0, 1.0, 1.0, 0.7],
+        [
+            PADDING,
+            PADDING,
+            WINDOW_SIZE - PADDING * 2.0,
+            WINDOW_SIZE - PADDING * 2.0,
+        ],
+        c.transform,
+        g,
+    );
+    for (player, label) in PLAYERS.iter().enumerate() {
+        draw_text(
+            c,
+            g,
+            glyphs,
+            label,
+            [COLUMNS_X[player + 1], ROWS_MARGIN_TOP - ROW_HEIGHT],
+            TEXT_COLOR,
+        );
+    }
+    for (row, (&(_, action), label)) in BINDINGS.iter().zip(LABELS).enumerate() {
+        let y = ROWS_MARGIN_TOP + row as f64 * ROW_HEIGHT;
+        draw_text(c, g, glyphs, label, [COLUMNS_X[0], y], TEXT_COLOR);
+        for (player, key_map) in key_maps.iter().enumerate() {
+            let is_selected = selected == (player, row);
+            let text = if is_selected && capturing {
+                "...".to_string()
+            } else {
+                key_name(key_map.get_key(action)).to_uppercase()
+            };
+            let color = if is_selected {
+                SELECTED_COLOR
+            } else if conflicts.contains(&(player, action)) {
+                CONFLICT_COLOR
+            } else {
+                TEXT_COLOR
+            };
+            draw_text(c, g, glyphs, &text, [COLUMNS_X[player + 1], y], color);
+        }
+    }
+}
+
+/// Dibuja las opciones de volver a las teclas por defecto y de guardar.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+/// - `index`: El índice de la opción seleccionada, si hay alguna.
+pub fn draw_options(c: Context, g: &mut G2d, glyphs: &mut Glyphs, index: Option<usize>) {
+    let y = ROWS_MARGIN_TOP + (BINDINGS.len() as f64 + 0.5) * ROW_HEIGHT;
+    for (i, option) in OPTIONS.iter().enumerate() {
+        let color = if Some(i) == index {
+            SELECTED_COLOR
+        } else {
+            TEXT_COLOR
+        };
+        draw_text(c, g, glyphs, option, [COLUMNS_X[i + 1], y], color);
+    }
+}
+
+/// Dibuja el aviso de que no se puede guardar con teclas en conflicto.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+pub fn draw_warning(c: Context, g: &mut G2d, glyphs: &mut Glyphs) {
+    let text = "Some keys are assigned more than once";
+    let text_width = glyphs.width(TEXT_SIZE, text).unwrap();
+    draw_text(
+        c,
+        g,
+        glyphs,
+        text,
+        [
+            WINDOW_SIZE / 2.0 - text_width / 2.0,
+            WINDOW_SIZE - PADDING * 2.0,
+        ],
+        CONFLICT_COLOR,
+    );
+}
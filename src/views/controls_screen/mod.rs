@@ -0,0 +1,2 @@
+pub mod controls_screen;
+pub mod gui;
\ No newline at end of file
@@ -1,22 +1,49 @@
 use std::{
+    path::Path,
     sync::{mpsc::Sender, Arc, Mutex},
-    time::{Duration, Instant},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 use gfx_device_gl::Device;
-use graphics::{clear, image};
+use graphics::clear;
 use piston::Key;
 use piston_window::{Context, G2d, PistonWindow};
 
 use crate::{
-    player::player::Player, preloader::preloader::Preloads, views::{
+    audio::{backend, mixer::Mixer},
+    config::game_config::GameConfig,
+    error::{pop_up::show_error_popup, snd_error::SndError},
+    player::{
+        character::snd::decoder::Snd,
+        input::{
+            manager::Socd,
+            source::{InputSource, VirtualSource},
+        },
+        player::Player,
+    },
+    preloader::preloader::Preloads,
+    stage::{camera::Camera, stage::Stage},
+    views::{
         screen::Screen,
         screen_manager::{Event, ScreenType},
-    }
+    },
+    CK,
 };
 
 use super::gui;
 
+/// Archivo con los sonidos comunes de la pelea
+const COMMON_SND: &str = "assets/sounds/common.snd";
+
+/// Escenario en el que se pelea
+const STAGE: &str = "training";
+
+/// Frames del juego que dura un segundo del temporizador
+const TICKS_PER_SECOND: u32 = 60;
+
+/// Segundos que se muestra el resultado de una ronda antes de pasar a la siguiente
+const ROUND_END_TIME: u32 = 3;
+
 /// Enumera los estados posibles de la pantalla de combate.
 #[derive(PartialEq)]
 enum State {
@@ -32,22 +59,24 @@ enum State {
 pub struct FightScreen {
     /// Jugadores
     players: [Player; 2],
-    /// Tiempo transcurrido en la pelea
-    ticks: u16,
     /// Estado de debug
     debug: bool,
     /// Última instancia de tiempo (tramiento en segundos)
     last_print_time: Instant,
-    /// Última instande de actualización (tramiento en segundos)
-    last_update: Instant,
-    /// Tiempo total transcurrido (tramiento en segundos)
-    total_time: Duration,
+    /// Frames del juego transcurridos en el estado actual
+    ticks: u32,
     /// Frames que han habido entre segundo y segundo
     total_frames: i32,
     /// Tiempo que lleva el temporizador
     timer: u32,
     /// Estado con el fin de ronda, 1 gana jugador 1, 2 gana jugador 2, 3 empate 
     end_round: u8,
+    /// Rondas ganadas por los jugadores 1 y 2
+    wins: [u32; 2],
+    /// Frase de victoria del ganador de la pelea
+    quote: Option<String>,
+    /// Configuración del juego con las reglas de la pelea
+    config: GameConfig,
     /// Emisor de eventos
     event_sender: Sender<Event>,
     /// Promedio de Frames entre segundos
@@ -56,6 +85,16 @@ pub struct FightScreen {
     state: State,
     /// Elementos precargados
     preloads: Arc<Mutex<Preloads>>,
+    /// Mezclador de los sonidos de la pelea
+    mixer: Mixer,
+    /// Sonidos comunes a todos los personajes
+    common_snd: Snd,
+    /// Escenario de la pelea
+    stage: Stage,
+    /// Cámara que sigue a los jugadores
+    camera: Camera,
+    /// Fuente de entrada del jugador 2 mientras lo controla el muñeco de entrenamiento
+    stored_source: Option<Box<dyn InputSource>>,
 }
 
 impl FightScreen {
@@ -67,6 +106,8 @@ impl FightScreen {
     /// * `event_sender` - El canal de eventos para comunicarse con el administrador de pantallas.
     /// * `characters` - Los nombres de los personajes que participarán en la pelea.
     /// * `palettes` - Las paletas de colores de los personajes.
+    /// * `socd` - Las políticas SOCD de los jugadores.
+    /// * `wins` - Las rondas ganadas por los jugadores antes de esta ronda.
     /// * `preloads` - Los recursos precargados para el juego.
     ///
     /// # Retorna
@@ -77,38 +118,121 @@ impl FightScreen {
         event_sender: Sender<Event>,
         characters: &[String; 2],
         palettes: [usize; 2],
+        socd: [Socd; 2],
+        wins: [u32; 2],
         preloads: Arc<Mutex<Preloads>>
     ) -> Self {
+        let [key_map_p1, key_map_p2] = preloads.lock().unwrap().get_ref_key_maps().clone();
+        let config = preloads.lock().unwrap().get_ref_game_config().clone();
+        let life_multiplier = config.life as f64 / 100.0;
+
         let context_p1 = window.create_texture_context();
-        let mut player_one = Player::new(true);
+        let mut player_one = Player::new(true, key_map_p1);
         player_one.choose_char(&characters[0], context_p1);
-        player_one.set_palette(palettes[0] - 1);
+        player_one.set_palette(palettes[0]);
+        player_one.set_socd(socd[0]);
+        player_one.set_life_multiplier(life_multiplier);
 
         let context_p2 = window.create_texture_context();
-        let mut player_two = Player::new(false);
+        let mut player_two = Player::new(false, key_map_p2);
         player_two.choose_char(&characters[1], context_p2);
-        player_two.set_palette(palettes[1] - 1);
+        player_two.set_palette(palettes[1]);
+        player_two.set_socd(socd[1]);
+        player_two.set_life_multiplier(life_multiplier);
 
-        let players = [player_one, player_two];
+        let mut players = [player_one, player_two];
 
         let last_print_time = Instant::now();
-        let last_update = Instant::now();
-        let total_time: Duration = Duration::new(0, 0);
+
+        // Los sonidos comunes son opcionales, sin ellos los sonidos con prefijo F no suenan
+        let common_snd = match Snd::load_snd(Path::new(COMMON_SND)) {
+            Ok(snd) => snd,
+            Err(SndError::NotFound(_)) => Snd::default(),
+            Err(err) => {
+                show_error_popup(&err);
+                Snd::default()
+            }
+        };
+
+        let stage = match Stage::load_stage(STAGE, window.create_texture_context()) {
+            Ok(stage) => stage,
+            Err(err) => {
+                show_error_popup(&err);
+                std::process::exit(1);
+            }
+        };
+
+        let player_info = &stage.get_def().player_info;
+        players[0].set_start_position(player_info.p1_start.0, player_info.p1_start.1);
+        players[1].set_start_position(player_info.p2_start.0, player_info.p2_start.1);
+        let camera = Camera::new(stage.get_def());
+
+        let mut mixer = Mixer::new(backend::default_backend());
+        mixer.set_listener(camera.get_pos().0, camera.get_half_width());
+        mixer.set_ticks_per_second(config.game_speed);
 
         Self {
             players,
-            ticks: 0,
             debug: false,
             last_print_time,
-            last_update,
-            total_time,
+            ticks: 0,
             total_frames: -1,
-            timer: 3,
+            timer: config.countdown,
             end_round: 0,
+            wins,
+            quote: None,
+            config,
             event_sender,
             average_fps: 0.0,
             state: State::StartFight,
             preloads,
+            mixer,
+            common_snd,
+            stage,
+            camera,
+            stored_source: None,
+        }
+    }
+
+    /// Indica si algún jugador ya ganó las rondas necesarias para ganar la pelea.
+    ///
+    /// # Retorna
+    ///
+    /// `true` si la pelea terminó.
+    fn is_match_over(&self) -> bool {
+        self.wins.iter().any(|&wins| wins >= self.config.rounds_to_win)
+    }
+
+    /// Elige al azar una de las frases de victoria de un jugador.
+    ///
+    /// # Argumentos
+    ///
+    /// * `winner` - Índice del jugador ganador.
+    ///
+    /// # Retorna
+    ///
+    /// La frase elegida, o `None` si el personaje no tiene frases.
+    fn pick_quote(&self, winner: usize) -> Option<String> {
+        let quotes = self.players[winner].get_quotes();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.subsec_nanos());
+        quotes.get(nanos as usize % quotes.len().max(1)).cloned()
+    }
+
+    /// Alterna el control del jugador 2 entre su fuente de entrada y un muñeco de entrenamiento
+    /// que se queda agachado en guardia.
+    fn toggle_dummy(&mut self) {
+        let player = &mut self.players[1];
+        match self.stored_source.take() {
+            Some(source) => {
+                player.set_input_source(source);
+            }
+            None => {
+                let mut dummy = VirtualSource::new();
+                dummy.push_frame(vec![(CK::D, true), (CK::B, true)]);
+                self.stored_source = Some(player.set_input_source(Box::new(dummy)));
+            }
         }
     }
 }
@@ -135,9 +259,7 @@ impl Screen for FightScreen {
     
      /// Actualiza el estado de la pantalla de combate.
     fn update(&mut self) {
-        let delta_time = self.last_update.elapsed();
-        self.last_update = Instant::now();
-        self.total_time += delta_time;
+        self.ticks += 1;
 
         if self.debug {
             self.total_frames += 1;
@@ -149,28 +271,29 @@ impl Screen for FightScreen {
             }
         }
         
+        self.stage.update();
+
+        let (left, right) = self.camera.get_player_bounds();
         for player in &mut self.players {
-            player.update();
+            player.set_bounds(left, right);
         }
+        let [p1, p2] = &mut self.players;
+        p1.update(Some(p2));
+        p2.update(Some(p1));
         
         if self.state == State::StartFight {
-            if self.total_time.as_secs() > 3 - self.timer as u64 {
-                if self.timer > 0 {
-                    self.timer -= 1;
-                } else {
-                    self.timer = 104;
-                    self.state = State::Fighting;
-                }
+            let elapsed = self.ticks / TICKS_PER_SECOND;
+            if elapsed > self.config.countdown {
+                self.ticks = 0;
+                self.timer = self.config.round_time;
+                self.state = State::Fighting;
+            } else {
+                self.timer = self.config.countdown - elapsed;
             }
         }
         
         if self.state == State::Fighting {
-            if self.total_time.as_secs() > 104 - self.timer as u64 {
-                if self.timer > 0 {
-                    self.timer -= 1;
-                }
-            }
-            self.ticks += 1u16;
+            self.timer = self.config.round_time.saturating_sub(self.ticks / TICKS_PER_SECOND);
 
 
             let (prev, curr) = self.players.split_at_mut(1);
@@ -216,7 +339,41 @@ impl Screen for FightScreen {
                     p2.set_lose(true);
                 }
             }
+
+            if self.state == State::EndFight {
+                // Ya no se atienden las teclas, así que se sueltan las que estén mantenidas
+                p1.clear_input();
+                p2.clear_input();
+                // En un empate ningún jugador suma la ronda
+                match self.end_round {
+                    1 => self.wins[0] += 1,
+                    2 => self.wins[1] += 1,
+                    _ => {}
+                }
+                // Al terminar la pelea el ganador dice una de sus frases de victoria
+                if self.is_match_over() && matches!(self.end_round, 1 | 2) {
+                    self.quote = self.pick_quote(self.end_round as usize - 1);
+                }
+                self.ticks = 0;
+            }
+        } else if self.state == State::EndFight
+            && !self.is_match_over()
+            && self.ticks == ROUND_END_TIME * TICKS_PER_SECOND
+        {
+            self.event_sender.send(Event::SetWins(self.wins)).unwrap();
+            self.event_sender
+                .send(Event::ChangeScreen(ScreenType::Fight))
+                .unwrap();
+        }
+
+        let positions = self.players.each_ref().map(|player| player.get_position());
+        self.camera.update(&positions);
+
+        self.mixer.set_listener(self.camera.get_pos().0, self.camera.get_half_width());
+        for (owner, player) in self.players.iter_mut().enumerate() {
+            player.play_sounds(&mut self.mixer, owner, &self.common_snd);
         }
+        self.mixer.update();
     }
 
 
@@ -234,6 +391,9 @@ impl Screen for FightScreen {
                     self.last_print_time = Instant::now();
                 }
             }
+            k if k == Key::F2 && self.debug && self.state == State::Fighting => {
+                self.toggle_dummy();
+            }
             k if k == Key::Escape && self.state == State::EndFight => {
                 if self.end_round > 0 {
                     self.event_sender
@@ -243,12 +403,7 @@ impl Screen for FightScreen {
             }
             _ if self.state == State::Fighting => {
                 for player in &mut self.players {
-                    if !player.set_player_input(&key, true) {
-                        player.handle_key_input(&mut self.ticks, true);
-                        if !player.is_moving(key) && !player.is_replacing_action() {
-                            player.set_replacing();
-                        }
-                    }
+                    player.on_key(&key, true);
                 }
             }
             _ => {}
@@ -264,9 +419,7 @@ impl Screen for FightScreen {
         match key {
             _ if self.state == State::Fighting => {
                 for player in &mut self.players {
-                    if player.set_player_input(&key, false) && !player.is_replacing_action() {
-                        player.handle_key_input(&mut self.ticks, false);
-                    }
+                    player.on_key(&key, false);
                 }
             }
             _ => {}
@@ -283,9 +436,10 @@ impl Screen for FightScreen {
     fn draw(&mut self, c: Context, g: &mut G2d, device: &mut Device) {
         clear([1.0; 4], g);
         let mut preloads = self.preloads.lock().unwrap();
-        image(preloads.get_mut_ref_background().get(1).unwrap(), c.transform, g);
+        self.stage.draw(0, &self.camera, c, g);
         if self.state == State::StartFight {
-            gui::draw_countdown(c, g, device, preloads.get_mut_ref_fonts().get_mut(1).unwrap(), self.timer);
+            self.stage.draw(1, &self.camera, c, g);
+            gui::draw_countdown(c, g, device, preloads.get_mut_ref_fonts().get_mut(1).unwrap(), self.timer, self.config.countdown);
         }
 
         if self.state == State::Fighting || self.state == State::EndFight {
@@ -295,12 +449,15 @@ impl Screen for FightScreen {
             } else {
                 priority = 0
             }
+            let transform = self.camera.stage_transform(&c);
             let mut i = 0;
             while i < 2 {
-                self.players[(i + priority) % 2]
-                    .get_mut_sprite()
-                    .draw(c.transform, g);
-                let player = &self.players[(i + priority) % 2];
+                self.players[(i + priority) % 2].draw(transform, g);
+                i += 1;
+            }
+            self.stage.draw(1, &self.camera, c, g);
+
+            for player in &self.players {
                 if self.debug {
                     for clsn in player.get_clsns() {
                         let rect = clsn.get_rectangle();
@@ -309,7 +466,7 @@ impl Screen for FightScreen {
                         } else {
                             [1.0, 0.0, 0.0, 0.5]
                         };
-                        graphics::rectangle(color, rect, c.transform, g);
+                        graphics::rectangle(color, rect, transform, g);
                     }
                 }
                 gui::draw_health_bar(
@@ -324,15 +481,22 @@ impl Screen for FightScreen {
                     player.get_power_as_percentage(),
                     player.is_first_player(),
                 );
-                i += 1;
             }
             if self.debug {
                 gui::draw_fps(c, g, device, preloads.get_mut_ref_fonts().get_mut(1).unwrap(), self.average_fps);
             }
-            gui::draw_timer(c, g, device, preloads.get_mut_ref_fonts().get_mut(1).unwrap(), self.timer);
+            gui::draw_timer(c, g, device, preloads.get_mut_ref_fonts().get_mut(1).unwrap(), self.timer, self.config.round_time);
 
             if self.state == State::EndFight {
-                gui::end_round(c, g, device, preloads.get_mut_ref_fonts().get_mut(1).unwrap(), self.end_round);
+                gui::end_round(
+                    c,
+                    g,
+                    device,
+                    preloads.get_mut_ref_fonts().get_mut(1).unwrap(),
+                    self.end_round,
+                    self.wins,
+                    self.quote.as_deref(),
+                );
             }
         }
     }
@@ -4,6 +4,8 @@ use gfx_device_gl::Device;
 use graphics::{ellipse, polygon, rectangle, text, CharacterCache, Context, Transformed};
 use piston_window::{G2d, Glyphs};
 
+use crate::config::game_config::WINDOW_SIZE;
+
 const HEALTH_BAR_HEIGHT: f64 = 20.0;
 const HEALTH_BAR_MAX_WIDTH: f64 = 200.0;
 const PADDING: f64 = 10.0;
@@ -12,6 +14,7 @@ const TIMER_CENTER_X: f64 = HEALTH_BAR_MAX_WIDTH + TIMER_RADIUS + 25.0;
 const TIMER_CENTER_Y: f64 = TIMER_RADIUS;
 const TEXT_SIZE: u32 = 32;
 const FPS_TEXT_SIZE: u32 = 15;
+const QUOTE_TEXT_SIZE: u32 = 14;
 
 /// Dibuja la barra de poder para un jugador.
 ///
@@ -25,7 +28,7 @@ pub fn draw_power_bar(c: Context, g: &mut G2d, power: f64, is_player_one: bool)
     let mut x_pos = PADDING;
 
     if !is_player_one {
-        x_pos = 512.0 - HEALTH_BAR_MAX_WIDTH / 2.0 - PADDING;
+        x_pos = WINDOW_SIZE - HEALTH_BAR_MAX_WIDTH / 2.0 - PADDING;
     }
 
     let y_pos = PADDING * 4.0;
@@ -69,7 +72,7 @@ pub fn draw_health_bar(c: Context, g: &mut G2d, life: f64, is_player_one: bool)
     let mut x_pos = PADDING;
 
     if !is_player_one {
-        x_pos = 512.0 - HEALTH_BAR_MAX_WIDTH - PADDING;
+        x_pos = WINDOW_SIZE - HEALTH_BAR_MAX_WIDTH - PADDING;
     }
 
     let y_pos = PADDING + PADDING;
@@ -109,8 +112,9 @@ pub fn draw_health_bar(c: Context, g: &mut G2d, life: f64, is_player_one: bool)
 /// - `device`: El dispositivo gráfico.
 /// - `glyphs`: Las fuentes para el texto.
 /// - `timer`: El valor del temporizador.
-pub fn draw_countdown(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyphs, timer: u32) {
-    let progress = timer as f64 / 3.0;
+/// - `countdown`: La duración de la cuenta regresiva en segundos.
+pub fn draw_countdown(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyphs, timer: u32, countdown: u32) {
+    let progress = timer as f64 / countdown.max(1) as f64;
     let end_angle = 2.0 * PI * progress;
 
     let center = [TIMER_CENTER_X, 255.0];
@@ -171,8 +175,9 @@ pub fn draw_countdown(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut
 /// - `device`: El dispositivo gráfico.
 /// - `glyphs`: Las fuentes para el texto.
 /// - `timer`: El valor del temporizador.
-pub fn draw_timer(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyphs, timer: u32) {
-    let progress = timer as f64 / 100.0;
+/// - `round_time`: La duración de la ronda en segundos.
+pub fn draw_timer(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyphs, timer: u32, round_time: u32) {
+    let progress = timer as f64 / round_time.max(1) as f64;
     let end_angle = 2.0 * PI * progress;
 
     let center = [TIMER_CENTER_X, TIMER_CENTER_Y];
@@ -259,7 +264,17 @@ pub fn draw_fps(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyph
 /// - `device`: El dispositivo gráfico.
 /// - `glyphs`: Las fuentes para el texto.
 /// - `winner`: El número del jugador ganador.
-pub fn end_round(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyphs, winner: u8) {
+/// - `wins`: Las rondas ganadas por cada jugador.
+/// - `quote`: La frase de victoria del ganador, si terminó la pelea.
+pub fn end_round(
+    c: Context,
+    g: &mut G2d,
+    device: &mut Device,
+    glyphs: &mut Glyphs,
+    winner: u8,
+    wins: [u32; 2],
+    quote: Option<&str>,
+) {
     let mut text = "Who won?";
     match winner {
         1 => {
@@ -276,7 +291,7 @@ pub fn end_round(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyp
     let text_width = glyphs.width(TEXT_SIZE, text).unwrap();
     let text_height = TEXT_SIZE as f64;
 
-    let transform = c.transform.trans(500.0 / 2.0  - text_width / 2.0, 500.0 / 2.0 + text_height / 4.0).zoom(0.5);
+    let transform = c.transform.trans(WINDOW_SIZE / 2.0 - text_width / 2.0, WINDOW_SIZE / 2.0 + text_height / 4.0).zoom(0.5);
 
     text::Text::new_color([0.0, 0.0, 0.0, 1.0], TEXT_SIZE * 2)
         .draw(
@@ -288,5 +303,56 @@ pub fn end_round(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyp
         )
         .unwrap();
 
+    let score = format!("{} - {}", wins[0], wins[1]);
+    let score_width = glyphs.width(TEXT_SIZE, &score).unwrap();
+    let transform = c.transform.trans(WINDOW_SIZE / 2.0 - score_width / 2.0, WINDOW_SIZE / 2.0 + text_height * 1.25).zoom(0.5);
+
+    text::Text::new_color([0.0, 0.0, 0.0, 1.0], TEXT_SIZE * 2)
+        .draw(
+            &score,
+            glyphs,
+            &c.draw_state,
+            transform,
+            g,
+        )
+        .unwrap();
+
+    if let Some(quote) = quote {
+        let mut y = WINDOW_SIZE / 2.0 + text_height * 2.5;
+        for line in wrap_text(glyphs, QUOTE_TEXT_SIZE, quote, WINDOW_SIZE - PADDING * 4.0) {
+            let line_width = glyphs.width(QUOTE_TEXT_SIZE, &line).unwrap();
+            let transform = c
+                .transform
+                .trans(WINDOW_SIZE / 2.0 - line_width / 2.0, y)
+                .zoom(0.5);
+            text::Text::new_color([0.0, 0.0, 0.0, 1.0], QUOTE_TEXT_SIZE * 2)
+                .draw(&line, glyphs, &c.draw_state, transform, g)
+                .unwrap();
+            y += QUOTE_TEXT_SIZE as f64 * 1.25;
+        }
+    }
+
     glyphs.factory.encoder.flush(device);
+}
+
+/// Parte un texto en líneas que no superen un ancho, sin cortar palabras.
+///
+/// # Parámetros
+/// - `glyphs`: Las fuentes para el texto.
+/// - `size`: El tamaño del texto.
+/// - `text`: El texto a partir.
+/// - `max_width`: El ancho máximo de cada línea.
+fn wrap_text(glyphs: &mut Glyphs, size: u32, text: &str, max_width: f64) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        if let Some(line) = lines.last_mut() {
+            let joined = format!("{} {}", line, word);
+            if glyphs.width(size, &joined).unwrap() <= max_width {
+                *line = joined;
+                continue;
+            }
+        }
+        lines.push(word.to_string());
+    }
+    lines
 }
\ No newline at end of file
@@ -2,31 +2,40 @@ use gfx_device_gl::Device;
 use graphics::{rectangle, text, CharacterCache, Context, Transformed};
 use piston_window::{G2d, Glyphs};
 
+use crate::{
+    config::game_config::WINDOW_SIZE,
+    player::input::key_map::{key_name, KeyMap},
+    CK,
+};
+
 const TITLE_GAME: &str = "RUST FG";
-const OPTIONS: [&str; 3] = ["Versus", "Info", "Exit"];
+const OPTIONS: [&str; 5] = ["Versus", "Info", "Controls", "Options", "Exit"];
 const INFO_OPTIONS: [&str; 2] = ["GUI Controls", "Fight Controls"];
-const FIGHT_CONTROLS: [[&str; 11]; 3] = [
-    [
-        "FORWARD", "BACK", "JUMP", "CROUCH", "LP", "MP", "HP", "LK", "MK", "HK", "TAUNT",
-    ],
-    [
-        "RIGHT", "LEFT", "UP", "DOWN", "A", "S", "D", "Z", "X", "C", "RSHIFT",
-    ],
-    ["L", "J", "I", "K", "R", "T", "Y", "F", "G", "H", "RCTRL"],
+const FIGHT_CONTROLS: [(&str, CK); 11] = [
+    ("FORWARD", CK::F),
+    ("BACK", CK::B),
+    ("JUMP", CK::U),
+    ("CROUCH", CK::D),
+    ("LP", CK::LP),
+    ("MP", CK::MP),
+    ("HP", CK::HP),
+    ("LK", CK::LK),
+    ("MK", CK::MK),
+    ("HK", CK::HK),
+    ("TAUNT", CK::Start),
 ];
-const GUI_CONTROLS: [[&str; 3]; 7] = [
-    ["ACCEPT", "RETURN/Z", "F"],
-    ["DEBUG", "F1", ""],
-    ["CANCEL/EXIT", "ESCAPE", ""],
-    ["UP", "UP", "J"],
-    ["DOWN", "DOWN", "K"],
-    ["LEFT", "LEFT", "J"],
-    ["RIGHT", "RIGHT", "L"],
+const GUI_CONTROLS: [(&str, &str, Option<CK>); 7] = [
+    ("ACCEPT", "RETURN/", Some(CK::LK)),
+    ("DEBUG", "F1", None),
+    ("CANCEL/EXIT", "ESCAPE", None),
+    ("UP", "UP/", Some(CK::U)),
+    ("DOWN", "DOWN/", Some(CK::D)),
+    ("LEFT", "LEFT/", Some(CK::B)),
+    ("RIGHT", "RIGHT/", Some(CK::F)),
 ];
 const TITLE_SIZE: u32 = 50;
 const TEXT_SIZE: u32 = 25;
 const INFO_TEXT_SIZE: u32 = 20;
-const WINDOW_SIZE: [f64; 2] = [512.0; 2];
 const PADDING: f64 = 17.0;
 const OPTION_MARGIN_TOP: f64 = 200.0;
 
@@ -37,16 +46,24 @@ const OPTION_MARGIN_TOP: f64 = 200.0;
 /// - `g`: La referencia mutable al gráfico 2D.
 /// - `device`: El dispositivo gráfico.
 /// - `glyphs`: Las fuentes para el texto.
+/// - `key_maps`: Los mapeos de teclas de los jugadores.
 /// - `index`: El índice de la opción seleccionada.
-pub fn draw_info(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyphs, index: usize) {
+pub fn draw_info(
+    c: Context,
+    g: &mut G2d,
+    device: &mut Device,
+    glyphs: &mut Glyphs,
+    key_maps: &[KeyMap; 2],
+    index: usize,
+) {
     let mut cummulative_width = 0.0;
     rectangle(
         [1.0, 1.0, 1.0, 0.7],
         [
             PADDING,
             PADDING,
-            WINDOW_SIZE[0] - PADDING * 2.0,
-            WINDOW_SIZE[1] - PADDING * 2.0,
+            WINDOW_SIZE - PADDING * 2.0,
+            WINDOW_SIZE - PADDING * 2.0,
         ],
         c.transform,
         g,
@@ -72,8 +89,14 @@ pub fn draw_info(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyp
             .unwrap();
     }
 
+    let key = |player: usize, action: CK| key_name(key_maps[player].get_key(action)).to_uppercase();
     if index == 1 {
-        for (i, column) in FIGHT_CONTROLS.iter().enumerate() {
+        let fight_controls = [
+            FIGHT_CONTROLS.map(|(label, _)| label.to_string()),
+            FIGHT_CONTROLS.map(|(_, action)| key(0, action)),
+            FIGHT_CONTROLS.map(|(_, action)| key(1, action)),
+        ];
+        for (i, column) in fight_controls.iter().enumerate() {
             for (j, control) in column.iter().enumerate() {
                 let text_height = INFO_TEXT_SIZE as f64;
 
@@ -91,7 +114,15 @@ pub fn draw_info(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Glyp
             }
         }
     } else if index == 0 {
-        for (i, column) in GUI_CONTROLS.iter().enumerate() {
+        let gui_controls = GUI_CONTROLS.map(|(label, fixed, action)| match action {
+            Some(action) => [
+                label.to_string(),
+                format!("{}{}", fixed, key(0, action)),
+                key(1, action),
+            ],
+            None => [label.to_string(), fixed.to_string(), String::new()],
+        });
+        for (i, column) in gui_controls.iter().enumerate() {
             cummulative_width = 0.0;
             for (j, control) in column.iter().enumerate() {
                 let text_width = glyphs.width(INFO_TEXT_SIZE, control).unwrap();
@@ -149,7 +180,7 @@ pub fn draw_options(
         let transform = c
             .transform
             .trans(
-                WINDOW_SIZE[0] / 2.0 - text_width / 2.0,
+                WINDOW_SIZE / 2.0 - text_width / 2.0,
                 OPTION_MARGIN_TOP + i as f64 * (text_height + PADDING),
             )
             .zoom(0.5);
@@ -176,8 +207,8 @@ pub fn draw_title(c: Context, g: &mut G2d, device: &mut Device, glyphs: &mut Gly
     let transform = c
         .transform
         .trans(
-            WINDOW_SIZE[0] / 2.0 - text_width / 2.0,
-            WINDOW_SIZE[1] / 4.0 + text_height / 4.0,
+            WINDOW_SIZE / 2.0 - text_width / 2.0,
+            WINDOW_SIZE / 4.0 + text_height / 4.0,
         )
         .zoom(0.5);
 
@@ -12,6 +12,7 @@ use crate::{
         screen::Screen,
         screen_manager::{Event, ScreenType},
     },
+    CK,
 };
 
 use super::gui;
@@ -63,18 +64,49 @@ impl Screen for MainScreen {
     ///
     /// * `key` - La tecla que se ha presionado.
     fn on_press(&mut self, key: piston_window::prelude::Key) {
-        match key {
-            Key::Left | Key::J => {
+        let action = self
+            .preloads
+            .lock()
+            .unwrap()
+            .get_ref_key_maps()
+            .iter()
+            .find_map(|key_map| key_map.translate(&key));
+        match (key, action) {
+            (Key::Escape, _) => {
+                if self.info_popup {
+                    self.info_popup = false;
+                    return;
+                }
+                process::exit(0);
+            }
+            (Key::Return, _) | (_, Some(CK::LK)) => match self.selected_index {
+                0 => self
+                    .event_sender
+                    .send(Event::ChangeScreen(ScreenType::Roster))
+                    .unwrap(),
+                1 => self.info_popup = true,
+                2 => self
+                    .event_sender
+                    .send(Event::ChangeScreen(ScreenType::Controls))
+                    .unwrap(),
+                3 => self
+                    .event_sender
+                    .send(Event::ChangeScreen(ScreenType::Options))
+                    .unwrap(),
+                4 => process::exit(0),
+                _ => (),
+            },
+            (Key::Left, _) | (_, Some(CK::B)) => {
                 if self.info_popup && self.info_index > 0 {
                     self.info_index -= 1;
                 }
             }
-            Key::Right | Key::L => {
+            (Key::Right, _) | (_, Some(CK::F)) => {
                 if self.info_popup && self.info_index < 1 {
                     self.info_index += 1;
                 }
             }
-            Key::Up | Key::I => {
+            (Key::Up, _) | (_, Some(CK::U)) => {
                 if self.info_popup {
                     return;
                 }
@@ -82,30 +114,14 @@ impl Screen for MainScreen {
                     self.selected_index -= 1;
                 }
             }
-            Key::Down | Key::K => {
+            (Key::Down, _) | (_, Some(CK::D)) => {
                 if self.info_popup {
                     return;
                 }
-                if self.selected_index < 2 {
+                if self.selected_index < 4 {
                     self.selected_index += 1;
                 }
             }
-            Key::Return | Key::Z | Key::F => match self.selected_index {
-                0 => self
-                    .event_sender
-                    .send(Event::ChangeScreen(ScreenType::Roster))
-                    .unwrap(),
-                1 => self.info_popup = true,
-                2 => process::exit(0),
-                _ => (),
-            },
-            Key::Escape => {
-                if self.info_popup {
-                    self.info_popup = false;
-                    return;
-                }
-                process::exit(0);
-            }
             _ => (),
         }
     }
@@ -134,6 +150,7 @@ impl Screen for MainScreen {
     ) {
         clear([1.0; 4], g);
         let mut preload = self.preloads.lock().unwrap();
+        let key_maps = preload.get_ref_key_maps().clone();
         image(
             preload.get_mut_ref_background().get(0).unwrap(),
             c.transform,
@@ -143,7 +160,7 @@ impl Screen for MainScreen {
         gui::draw_title(c, g, device, glyphs);
         gui::draw_options(c, g, device, glyphs, self.selected_index);
         if self.info_popup {
-            gui::draw_info(c, g, device, glyphs, self.info_index);
+            gui::draw_info(c, g, device, glyphs, &key_maps, self.info_index);
         }
     }
 }
@@ -4,8 +4,12 @@ pub mod main_screen;
 pub mod roster_screen;
 pub mod screen_manager;
 pub mod loading_screen;
+pub mod controls_screen;
+pub mod options_screen;
 
+pub use self::controls_screen::controls_screen::ControlsScreen;
 pub use self::fight_screen::fight_screen::FightScreen;
 pub use self::loading_screen::loading_screen::LoadingScreen;
 pub use self::main_screen::main_screen::MainScreen;
+pub use self::options_screen::options_screen::OptionsScreen;
 pub use self::roster_screen::roster_screen::RosterScreen;
\ No newline at end of file
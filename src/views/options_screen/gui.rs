@@ -0,0 +1,135 @@
+use graphics::{rectangle, CharacterCache, Context, Transformed};
+use piston_window::{G2d, Glyphs};
+
+use crate::config::game_config::WINDOW_SIZE;
+
+/// Nombres de las opciones; las marcadas con `*` se aplican al reiniciar el juego.
+pub const LABELS: [&str; 10] = [
+    "ROUNDS TO WIN",
+    "ROUND TIME",
+    "COUNTDOWN",
+    "LIFE",
+    "TEAM 1VS2 LIFE",
+    "TEAM LOSE ON KO",
+    "GAME SPEED *",
+    "WINDOW SCALE *",
+    "VSYNC *",
+    "MSAA *",
+];
+const OPTIONS: [&str; 2] = ["Defaults", "Save"];
+const TEXT_SIZE: u32 = 20;
+const PADDING: f64 = 17.0;
+const ROWS_MARGIN_TOP: f64 = 60.0;
+const ROW_HEIGHT: f64 = 30.0;
+const COLUMNS_X: [f64; 3] = [PADDING * 2.0, 200.0, 330.0];
+const TEXT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.4, 0.2, 1.0];
+
+/// Dibuja un texto con el tamaño de la pantalla de opciones.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+/// - `text`: El texto a dibujar.
+/// - `position`: La posición del texto.
+/// - `color`: El color del texto.
+fn draw_text(
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    text: &str,
+    position: [f64; 2],
+    color: [f32; 4],
+) {
+    let transform = c.transform.trans(position[0], position[1]).zoom(0.5);
+    graphics::text::Text::new_color(color, TEXT_SIZE * 2)
+        .draw(text, glyphs, &c.draw_state, transform, g)
+        .unwrap();
+}
+
+/// Dibuja la tabla con el valor de cada opción.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+/// - `values`: El valor de cada opción, en el orden de `LABELS`.
+/// - `selected`: La fila elegida.
+pub fn draw_values(
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    values: &[String; LABELS.len()],
+    selected: usize,
+) {
+    rectangle(
+        [1.0, 1.0, 1.0, 0.7],
+        [
+            PADDING,
+            PADDING,
+            WINDOW_SIZE - PADDING * 2.0,
+            WINDOW_SIZE - PADDING * 2.0,
+        ],
+        c.transform,
+        g,
+    );
+    for (row, (label, value)) in LABELS.iter().zip(values).enumerate() {
+        let y = ROWS_MARGIN_TOP + row as f64 * ROW_HEIGHT;
+        let color = if row == selected {
+            SELECTED_COLOR
+        } else {
+            TEXT_COLOR
+        };
+        draw_text(c, g, glyphs, label, [COLUMNS_X[0], y], TEXT_COLOR);
+        draw_text(
+            c,
+            g,
+            glyphs,
+            &format!("< {} >", value),
+            [COLUMNS_X[2], y],
+            color,
+        );
+    }
+}
+
+/// Dibuja las opciones de volver a la configuración por defecto y de guardar.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+/// - `index`: El índice de la opción seleccionada, si hay alguna.
+pub fn draw_options(c: Context, g: &mut G2d, glyphs: &mut Glyphs, index: Option<usize>) {
+    let y = ROWS_MARGIN_TOP + (LABELS.len() as f64 + 0.5) * ROW_HEIGHT;
+    for (i, option) in OPTIONS.iter().enumerate() {
+        let color = if Some(i) == index {
+            SELECTED_COLOR
+        } else {
+            TEXT_COLOR
+        };
+        draw_text(c, g, glyphs, option, [COLUMNS_X[i + 1], y], color);
+    }
+}
+
+/// Dibuja la nota de las opciones que se aplican al reiniciar el juego.
+///
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `glyphs`: Las fuentes para el texto.
+pub fn draw_restart_note(c: Context, g: &mut G2d, glyphs: &mut Glyphs) {
+    let text = "* Applies after restarting the game";
+    let text_width = glyphs.width(TEXT_SIZE, text).unwrap();
+    draw_text(
+        c,
+        g,
+        glyphs,
+        text,
+        [
+            WINDOW_SIZE / 2.0 - text_width / 2.0,
+            WINDOW_SIZE - PADDING * 2.0,
+        ],
+        TEXT_COLOR,
+    );
+}
@@ -0,0 +1,2 @@
+pub mod gui;
+pub mod options_screen;
@@ -0,0 +1,221 @@
+use std::{
+    ops::RangeInclusive,
+    sync::{mpsc::Sender, Arc, Mutex},
+};
+
+use graphics::{clear, image};
+use piston::Key;
+
+use crate::{
+    config::game_config::{
+        save_game_config, GameConfig, COUNTDOWN, GAME_CFG, GAME_SPEED, LIFE, ROUNDS_TO_WIN,
+        ROUND_TIME, SAMPLES, WINDOW_SCALE,
+    },
+    error::pop_up::show_error_popup,
+    preloader::preloader::Preloads,
+    views::{
+        screen::Screen,
+        screen_manager::{Event, ScreenType},
+    },
+};
+
+use super::gui::{self, LABELS};
+
+/// Fila con la opción de volver a la configuración por defecto.
+const DEFAULTS_ROW: usize = LABELS.len();
+/// Fila con la opción de guardar.
+const SAVE_ROW: usize = LABELS.len() + 1;
+
+/// Representa la pantalla de opciones del juego.
+///
+/// Se maneja con las flechas, `Return` y `Escape`, que no dependen de las teclas asignadas.
+pub struct OptionsScreen {
+    /// Elementos precargados
+    preloads: Arc<Mutex<Preloads>>,
+    /// Emisor de eventos
+    event_sender: Sender<Event>,
+    /// Configuración en edición
+    config: GameConfig,
+    /// Fila elegida: una opción, la configuración por defecto o guardar
+    row: usize,
+}
+
+/// Avanza o retrocede un valor dentro de un rango, sin salirse de él.
+///
+/// # Argumentos
+///
+/// * `value` - El valor actual.
+/// * `step` - Cuánto cambia el valor.
+/// * `range` - Los valores aceptados.
+/// * `forward` - Indica si el valor aumenta.
+///
+/// # Retorna
+///
+/// El nuevo valor.
+fn step_value(value: u32, step: u32, range: RangeInclusive<u32>, forward: bool) -> u32 {
+    if forward {
+        (value + step).min(*range.end())
+    } else {
+        value.saturating_sub(step).max(*range.start())
+    }
+}
+
+impl OptionsScreen {
+    /// Cambia el valor de la opción elegida.
+    ///
+    /// # Argumentos
+    ///
+    /// * `forward` - Indica si el valor aumenta.
+    fn change_value(&mut self, forward: bool) {
+        let config = &mut self.config;
+        match self.row {
+            0 => config.rounds_to_win = step_value(config.rounds_to_win, 1, ROUNDS_TO_WIN, forward),
+            1 => config.round_time = step_value(config.round_time, 1, ROUND_TIME, forward),
+            2 => config.countdown = step_value(config.countdown, 1, COUNTDOWN, forward),
+            3 => config.life = step_value(config.life, 10, LIFE, forward),
+            4 => config.team_1vs2_life = step_value(config.team_1vs2_life, 10, LIFE, forward),
+            5 => config.team_lose_on_ko = !config.team_lose_on_ko,
+            6 => config.game_speed = step_value(config.game_speed, 5, GAME_SPEED, forward),
+            7 => config.window_scale = step_value(config.window_scale, 1, WINDOW_SCALE, forward),
+            8 => config.vsync = !config.vsync,
+            9 => {
+                let index = SAMPLES
+                    .iter()
+                    .position(|&samples| samples == config.samples)
+                    .unwrap_or_default();
+                let index = step_value(index as u32, 1, 0..=SAMPLES.len() as u32 - 1, forward);
+                config.samples = SAMPLES[index as usize];
+            }
+            _ => (),
+        }
+    }
+
+    /// Obtiene el texto con el valor de cada opción.
+    ///
+    /// # Retorna
+    ///
+    /// Los valores en el orden de las filas.
+    fn values(&self) -> [String; LABELS.len()] {
+        let on_off = |value: bool| if value { "ON" } else { "OFF" }.to_string();
+        let config = &self.config;
+        [
+            config.rounds_to_win.to_string(),
+            format!("{}s", config.round_time),
+            format!("{}s", config.countdown),
+            format!("{}%", config.life),
+            format!("{}%", config.team_1vs2_life),
+            on_off(config.team_lose_on_ko),
+            format!("{} FPS", config.game_speed),
+            format!("x{}", config.window_scale),
+            on_off(config.vsync),
+            match config.samples {
+                0 => "OFF".to_string(),
+                samples => format!("x{}", samples),
+            },
+        ]
+    }
+
+    /// Guarda la configuración y vuelve a la pantalla principal.
+    fn save(&mut self) {
+        if let Err(err) = save_game_config(GAME_CFG, &self.config) {
+            show_error_popup(&err);
+            return;
+        }
+        self.preloads
+            .lock()
+            .unwrap()
+            .set_game_config(self.config.clone());
+        self.event_sender
+            .send(Event::ChangeScreen(ScreenType::Main))
+            .unwrap();
+    }
+}
+
+impl Screen for OptionsScreen {
+    /// Crea una nueva instancia de `OptionsScreen`.
+    ///
+    /// # Argumentos
+    ///
+    /// * `event_sender` - El canal de eventos para comunicarse con el administrador de pantallas.
+    /// * `preloads` - Los recursos precargados para el juego.
+    ///
+    /// # Retorna
+    ///
+    /// Una nueva instancia de `OptionsScreen`.
+    fn new(event_sender: Sender<Event>, preloads: Arc<Mutex<Preloads>>) -> Self
+    where
+        Self: Sized,
+    {
+        let config = preloads.lock().unwrap().get_ref_game_config().clone();
+        Self {
+            preloads,
+            event_sender,
+            config,
+            row: 0,
+        }
+    }
+
+    /// Actualiza el estado de la pantalla de opciones.
+    fn update(&mut self) {}
+
+    /// Maneja el evento de presionar una tecla en la pantalla de opciones.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla que se ha presionado.
+    fn on_press(&mut self, key: Key) {
+        match key {
+            Key::Up if self.row > 0 => self.row -= 1,
+            Key::Down if self.row < SAVE_ROW => self.row += 1,
+            Key::Left => self.change_value(false),
+            Key::Right => self.change_value(true),
+            Key::Return => match self.row {
+                DEFAULTS_ROW => self.config = GameConfig::default(),
+                SAVE_ROW => self.save(),
+                _ => (),
+            },
+            Key::Escape => {
+                self.event_sender
+                    .send(Event::ChangeScreen(ScreenType::Main))
+                    .unwrap();
+            }
+            _ => (),
+        }
+    }
+
+    /// Maneja el evento de soltar una tecla en la pantalla de opciones.
+    ///
+    /// # Argumentos
+    ///
+    /// * `key` - La tecla que se ha soltado.
+    fn on_release(&mut self, key: Key) {
+        let _ = key;
+    }
+
+    /// Dibuja el contenido de la pantalla de opciones.
+    ///
+    /// # Argumentos
+    ///
+    /// * `c` - El contexto de dibujo.
+    /// * `g` - El contexto de gráficos.
+    /// * `device` - El dispositivo de dibujo.
+    fn draw(
+        &mut self,
+        c: graphics::Context,
+        g: &mut piston_window::prelude::G2d,
+        device: &mut gfx_device_gl::Device,
+    ) {
+        clear([1.0; 4], g);
+        let mut preloads = self.preloads.lock().unwrap();
+        image(
+            preloads.get_mut_ref_background().first().unwrap(),
+            c.transform,
+            g,
+        );
+        let glyphs = preloads.get_mut_ref_fonts().get_mut(0).unwrap();
+        gui::draw_values(c, g, glyphs, &self.values(), self.row);
+        gui::draw_options(c, g, glyphs, self.row.checked_sub(DEFAULTS_ROW));
+        gui::draw_restart_note(c, g, glyphs);
+        glyphs.factory.encoder.flush(device);
+    }
+}
@@ -4,11 +4,14 @@ use piston_window::G2d;
 use sprite::Sprite;
 use std::{rc::Rc, sync::MutexGuard};
 
-use crate::{player::character::sff::decoder::Sff, preloader::preloader::Preloads};
+use crate::{
+    config::game_config::WINDOW_SIZE,
+    player::{character::sff::texture::SffTextures, input::manager::Socd},
+    preloader::preloader::Preloads,
+};
 
 const KEY_CHAR: [i16; 2] = [9000, 0];
 const KEY_PREVIEW: [i16; 2] = [9000, 1];
-const WINDOW_SIZE: [f64; 2] = [512.0; 2];
 const CHARACTER_SIZE: [f64; 2] = [25.0; 2];
 const PREVIEW_SIZE: [f64; 2] = [120.0, 140.0];
 const PADDING: [f64; 2] = [100.0; 2];
@@ -44,12 +47,12 @@ pub fn draw_color_pick(
     if first_player {
         x = PREVIEW_SIZE[0] + 10.0;
     } else {
-        x = WINDOW_SIZE[0] - PREVIEW_SIZE[0] - text_pick - 10.0;
+        x = WINDOW_SIZE - PREVIEW_SIZE[0] - text_pick - 10.0;
     }
 
     let transform = c
         .transform
-        .trans(x, WINDOW_SIZE[1] - PREVIEW_SIZE[1] / 4.0).zoom(0.5);
+        .trans(x, WINDOW_SIZE - PREVIEW_SIZE[1] / 4.0).zoom(0.5);
     
     text::Text::new_color([1.0, 1.0, 1.0, 1.0], 15 * 2)
         .draw("Pick a color", glyphs, &c.draw_state, transform, g)
@@ -61,12 +64,12 @@ pub fn draw_color_pick(
     if first_player {
         x = PREVIEW_SIZE[0] + 10.0;
     } else {
-        x = WINDOW_SIZE[0] - PREVIEW_SIZE[0] - text_width - 10.0;
+        x = WINDOW_SIZE - PREVIEW_SIZE[0] - text_width - 10.0;
     }
 
     let transform = c
         .transform
-        .trans(x, WINDOW_SIZE[1] - PREVIEW_SIZE[1] / 20.0).zoom(0.5);
+        .trans(x, WINDOW_SIZE - PREVIEW_SIZE[1] / 20.0).zoom(0.5);
     
     let color;
     if picked {
@@ -82,6 +85,43 @@ pub fn draw_color_pick(
     glyphs.factory.encoder.flush(device);
 }
 
+/// Dibuja la política SOCD elegida por el jugador.
+/// 
+/// # Parámetros
+/// - `c`: El contexto de gráficos.
+/// - `g`: La referencia mutable al gráfico 2D.
+/// - `device`: El dispositivo gráfico.
+/// - `preloads`: Referencia mutable a las cargas previas.
+/// - `socd`: La política elegida.
+/// - `first_player`: Indica si es el primer jugador.
+pub fn draw_socd(
+    c: Context,
+    g: &mut G2d,
+    device: &mut Device,
+    preloads: &mut MutexGuard<Preloads>,
+    socd: Socd,
+    first_player: bool,
+) {
+    let glyphs = preloads.get_mut_ref_fonts().get_mut(0).unwrap();
+    let text = format!("SOCD {}", socd.get_name());
+    let text_width = glyphs.width(15, &text).unwrap();
+    let x = if first_player {
+        PREVIEW_SIZE[0] + 10.0
+    } else {
+        WINDOW_SIZE - PREVIEW_SIZE[0] - text_width - 10.0
+    };
+
+    let transform = c
+        .transform
+        .trans(x, WINDOW_SIZE - PREVIEW_SIZE[1] / 2.5).zoom(0.5);
+
+    text::Text::new_color([1.0, 1.0, 1.0, 1.0], 15 * 2)
+        .draw(&text, glyphs, &c.draw_state, transform, g)
+        .unwrap();
+
+    glyphs.factory.encoder.flush(device);
+}
+
 /// Dibuja la vista previa del personaje.
 /// 
 /// # Parámetros
@@ -105,47 +145,45 @@ pub fn draw_preview(
     }
 
     let mut x;
-    if let Some(spr_data) = roster[index].sprites.get(&KEY_PREVIEW) {
-        if let Some(texture) = &spr_data.tex {
-            let mut sprite = Sprite::from_texture(Rc::clone(texture));
-            if first_player {
-                sprite.set_anchor(0.0, 1.0);
-                x = 0.0;
-            } else {
-                sprite.set_anchor(1.0, 1.0);
-                x = WINDOW_SIZE[0];
-            }
-            sprite.draw(c.transform.trans(x, WINDOW_SIZE[1]), g);
+    if let Some(texture) = roster[index].get_texture(&KEY_PREVIEW) {
+        let mut sprite = Sprite::from_texture(Rc::clone(texture));
+        if first_player {
+            sprite.set_anchor(0.0, 1.0);
+            x = 0.0;
+        } else {
+            sprite.set_anchor(1.0, 1.0);
+            x = WINDOW_SIZE;
         }
+        sprite.draw(c.transform.trans(x, WINDOW_SIZE), g);
     }
 
     if first_player {
         x = 0.0
     } else {
-        x = WINDOW_SIZE[0] - PREVIEW_SIZE[0];
+        x = WINDOW_SIZE - PREVIEW_SIZE[0];
     }
 
     let background_rect = rectangle::rectangle_by_corners(
         x,
-        WINDOW_SIZE[1] - PREVIEW_SIZE[1] / 3.5,
+        WINDOW_SIZE - PREVIEW_SIZE[1] / 3.5,
         x + PREVIEW_SIZE[0],
-        WINDOW_SIZE[1] + 5.0,
+        WINDOW_SIZE + 5.0,
     );
 
     if first_player {
         x = 0.0 + PREVIEW_SIZE[0] / 7.0;
     } else {
-        x = WINDOW_SIZE[0] - PREVIEW_SIZE[0] * 6.0 / 7.0;
+        x = WINDOW_SIZE - PREVIEW_SIZE[0] * 6.0 / 7.0;
     }
 
     rectangle([1.0, 1.0, 1.0, 0.5], background_rect, c.transform, g);
 
-    let text = roster[index].get_name();
+    let text = roster[index].get_sff().get_name();
     
     let glyphs = preloads.get_mut_ref_fonts().get_mut(0).unwrap();
     let transform = c
         .transform
-        .trans(x, WINDOW_SIZE[1] - PREVIEW_SIZE[1] / 20.0).zoom(0.5);
+        .trans(x, WINDOW_SIZE - PREVIEW_SIZE[1] / 20.0).zoom(0.5);
 
     text::Text::new_color([0.0, 0.0, 0.0, 1.0], TEXT_SIZE * 2)
         .draw(&text, glyphs, &c.draw_state, transform, g)
@@ -162,15 +200,15 @@ pub fn draw_preview(
 /// - `index`: El índice del personaje.
 /// - `first_player`: Indica si es el primer jugador.
 pub fn draw_selector(c: Context, g: &mut G2d, index: usize, first_player: bool) {
-    let characters_per_row = ((WINDOW_SIZE[0] - 2.0 * PADDING[0])
+    let characters_per_row = ((WINDOW_SIZE - 2.0 * PADDING[0])
         / (CHARACTER_SIZE[0] + INTERNAL_SEPARATION[0]))
         .floor() as usize;
 
-    let start_x = (WINDOW_SIZE[0]
+    let start_x = (WINDOW_SIZE
         - (characters_per_row as f64 * (CHARACTER_SIZE[0] + INTERNAL_SEPARATION[0])
             - INTERNAL_SEPARATION[0]))
         / 2.0;
-    let start_y = (WINDOW_SIZE[1]
+    let start_y = (WINDOW_SIZE
         - (characters_per_row as f64 * (CHARACTER_SIZE[1] + INTERNAL_SEPARATION[1])
             - INTERNAL_SEPARATION[1]))
         / 2.0;
@@ -241,41 +279,37 @@ pub fn draw_selector(c: Context, g: &mut G2d, index: usize, first_player: bool)
 /// - `c`: El contexto de gráficos.
 /// - `g`: La referencia mutable al gráfico 2D.
 /// - `roster`: La lista de personajes.
-pub fn draw_characters(c: Context, g: &mut G2d, roster: &Vec<Sff>) {
+pub fn draw_characters(c: Context, g: &mut G2d, roster: &Vec<SffTextures>) {
     if roster.is_empty() {
         return;
     }
 
-    let characters_per_row = ((WINDOW_SIZE[0] - 2.0 * PADDING[0])
+    let characters_per_row = ((WINDOW_SIZE - 2.0 * PADDING[0])
         / (CHARACTER_SIZE[0] + INTERNAL_SEPARATION[0]))
         .floor() as usize;
-    let characters_per_col = ((WINDOW_SIZE[1] - 2.0 * PADDING[1])
+    let characters_per_col = ((WINDOW_SIZE - 2.0 * PADDING[1])
         / (CHARACTER_SIZE[1] + INTERNAL_SEPARATION[1]))
         .floor() as usize;
 
-    let start_x = (WINDOW_SIZE[0]
+    let start_x = (WINDOW_SIZE
         - (characters_per_row as f64 * (CHARACTER_SIZE[0] + INTERNAL_SEPARATION[0])
             - INTERNAL_SEPARATION[0]))
         / 2.0;
-    let start_y = (WINDOW_SIZE[1]
+    let start_y = (WINDOW_SIZE
         - (characters_per_col as f64 * (CHARACTER_SIZE[1] + INTERNAL_SEPARATION[1])
             - INTERNAL_SEPARATION[1]))
         / 2.0;
 
     for (i, sff) in roster.iter().enumerate() {
-        if let Some(spr_data) = sff.sprites.get(&KEY_CHAR) {
-            if let Some(texture) = &spr_data.tex {
-                let x = start_x
-                    + (i % characters_per_row) as f64
-                        * (CHARACTER_SIZE[0] + INTERNAL_SEPARATION[0]);
-                let y = start_y
-                    + (i / characters_per_row) as f64
-                        * (CHARACTER_SIZE[1] + INTERNAL_SEPARATION[1]);
+        if let Some(texture) = sff.get_texture(&KEY_CHAR) {
+            let x = start_x
+                + (i % characters_per_row) as f64 * (CHARACTER_SIZE[0] + INTERNAL_SEPARATION[0]);
+            let y = start_y
+                + (i / characters_per_row) as f64 * (CHARACTER_SIZE[1] + INTERNAL_SEPARATION[1]);
 
-                let mut sprite = Sprite::from_texture(Rc::clone(texture));
-                sprite.set_anchor(0.0, 0.0);
-                sprite.draw(c.transform.trans(x, y), g);
-            }
+            let mut sprite = Sprite::from_texture(Rc::clone(texture));
+            sprite.set_anchor(0.0, 0.0);
+            sprite.draw(c.transform.trans(x, y), g);
         }
     }
 }
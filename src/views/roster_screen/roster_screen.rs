@@ -4,11 +4,13 @@ use graphics::{clear, image};
 use piston::Key;
 
 use crate::{
+    player::input::manager::Socd,
     preloader::preloader::Preloads,
     views::{
         screen::Screen,
         screen_manager::{Event, ScreenType},
     },
+    CK,
 };
 
 use super::gui;
@@ -33,6 +35,10 @@ pub struct RosterScreen {
     p2_index_color: usize,
     /// Color elegido por el jugador 2
     p2_color: usize,
+    /// Política SOCD elegida por el jugador 1
+    p1_socd: Socd,
+    /// Política SOCD elegida por el jugador 2
+    p2_socd: Socd,
     /// Ticks que han transcurrido en la escena
     ticks: usize,
     /// Emisor de eventos
@@ -41,6 +47,59 @@ pub struct RosterScreen {
     preloads: Arc<Mutex<Preloads>>,
 }
 
+impl RosterScreen {
+    /// Obtiene los colores que se pueden elegir para un personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `character` - El índice del personaje en el roster.
+    ///
+    /// # Retorna
+    ///
+    /// Los números de los colores con paleta, tanto del SFF como de los archivos ACT.
+    fn get_slots(&self, character: usize) -> Vec<usize> {
+        let preloads = self.preloads.lock().unwrap();
+        preloads
+            .get_ref_roster()
+            .get(character)
+            .map(|sff| sff.get_sff().get_palette_slots())
+            .unwrap_or_default()
+    }
+
+    /// Obtiene el primer color que se puede elegir para un personaje.
+    ///
+    /// # Argumentos
+    ///
+    /// * `character` - El índice del personaje en el roster.
+    ///
+    /// # Retorna
+    ///
+    /// El primer color con paleta, o 1 si el personaje no define ninguno.
+    fn first_slot(&self, character: usize) -> usize {
+        self.get_slots(character).first().copied().unwrap_or(1)
+    }
+}
+
+/// Pasa al color anterior o siguiente que tenga paleta.
+///
+/// # Argumentos
+///
+/// * `slots` - Los colores con paleta, en orden.
+/// * `current` - El color actual.
+/// * `forward` - `true` para pasar al siguiente y `false` al anterior.
+///
+/// # Retorna
+///
+/// El nuevo color, o el actual si no hay más en esa dirección.
+fn step_slot(slots: &[usize], current: usize, forward: bool) -> usize {
+    if forward {
+        slots.iter().copied().find(|&slot| slot > current)
+    } else {
+        slots.iter().rev().copied().find(|&slot| slot < current)
+    }
+    .unwrap_or(current)
+}
+
 impl Screen for RosterScreen {
     /// Crea una nueva instancia de `RosterScreen`.
     ///
@@ -67,6 +126,8 @@ impl Screen for RosterScreen {
             p2_selected: false,
             p2_index_color: 1,
             p2_color: 0,
+            p1_socd: Socd::default(),
+            p2_socd: Socd::default(),
             event_sender,
         }
     }
@@ -77,8 +138,8 @@ impl Screen for RosterScreen {
         if self.p1_color > 0 && self.p2_color > 0 && self.ticks > TICK_RESET {
             let preloads = self.preloads.lock().unwrap();
             let characters = [
-                preloads.get_ref_roster()[self.p1_selected_index].get_name(),
-                preloads.get_ref_roster()[self.p2_selected_index].get_name(),
+                preloads.get_ref_roster()[self.p1_selected_index].get_sff().get_name(),
+                preloads.get_ref_roster()[self.p2_selected_index].get_sff().get_name(),
             ];
             self.event_sender
                 .send(Event::SetCharacters(characters))
@@ -86,6 +147,9 @@ impl Screen for RosterScreen {
             self.event_sender
                 .send(Event::SetPalettes([self.p1_color, self.p2_color]))
                 .unwrap();
+            self.event_sender
+                .send(Event::SetSocd([self.p1_socd, self.p2_socd]))
+                .unwrap();
             self.event_sender
                 .send(Event::ChangeScreen(ScreenType::Fight))
                 .unwrap();
@@ -98,49 +162,56 @@ impl Screen for RosterScreen {
     ///
     /// * `key` - La tecla que se ha presionado.
     fn on_press(&mut self, key: piston_window::prelude::Key) {
-        match key {
-            Key::Up | Key::Down | Key::Left | Key::Right | Key::Z => {
-                if key == Key::Z {
-                    if !self.p1_selected {
-                        self.p1_selected = true;
-                    } else if self.p1_color == 0 {
-                        self.p1_color = self.p1_index_color;
-                        self.ticks = 0;
-                    }
-                }
-                if self.p1_selected && self.p1_color == 0 {
-                    if self.p1_index_color > 1 && key == Key::Left {
-                        self.p1_index_color -= 1;
-                    }
-                    if self.p1_index_color < 6 && key == Key::Right {
-                        self.p1_index_color += 1;
-                    }
+        if key == Key::Escape {
+            self.event_sender
+                .send(Event::ChangeScreen(ScreenType::Main))
+                .unwrap();
+            return;
+        }
+        let [p1_action, p2_action] = self
+            .preloads
+            .lock()
+            .unwrap()
+            .get_ref_key_maps()
+            .each_ref()
+            .map(|key_map| key_map.translate(&key));
+
+        if let Some(action) = p1_action {
+            if action == CK::LK {
+                if !self.p1_selected {
+                    self.p1_selected = true;
+                    self.p1_index_color = self.first_slot(self.p1_selected_index);
+                } else if self.p1_color == 0 {
+                    self.p1_color = self.p1_index_color;
+                    self.ticks = 0;
                 }
             }
-            Key::J | Key::I | Key::K | Key::L | Key::F => {
-                if key == Key::F {
-                    if !self.p2_selected {
-                        self.p2_selected = true;
-                    } else if self.p2_color == 0 {
-                        self.p2_color = self.p2_index_color;
-                        self.ticks = 0;
-                    }
-                }
-                if self.p2_selected && self.p2_color == 0 {
-                    if self.p2_index_color > 1 && key == Key::J {
-                        self.p2_index_color -= 1;
-                    }
-                    if self.p2_index_color < 6 && key == Key::L {
-                        self.p2_index_color += 1;
-                    }
+            if self.p1_selected && self.p1_color == 0 && (action == CK::B || action == CK::F) {
+                let slots = self.get_slots(self.p1_selected_index);
+                self.p1_index_color = step_slot(&slots, self.p1_index_color, action == CK::F);
+            }
+            if self.p1_selected && self.p1_color == 0 && action == CK::MK {
+                self.p1_socd = self.p1_socd.next();
+            }
+        }
+
+        if let Some(action) = p2_action {
+            if action == CK::LK {
+                if !self.p2_selected {
+                    self.p2_selected = true;
+                    self.p2_index_color = self.first_slot(self.p2_selected_index);
+                } else if self.p2_color == 0 {
+                    self.p2_color = self.p2_index_color;
+                    self.ticks = 0;
                 }
             }
-            Key::Escape => {
-                self.event_sender
-                    .send(Event::ChangeScreen(ScreenType::Main))
-                    .unwrap();
+            if self.p2_selected && self.p2_color == 0 && (action == CK::B || action == CK::F) {
+                let slots = self.get_slots(self.p2_selected_index);
+                self.p2_index_color = step_slot(&slots, self.p2_index_color, action == CK::F);
+            }
+            if self.p2_selected && self.p2_color == 0 && action == CK::MK {
+                self.p2_socd = self.p2_socd.next();
             }
-            _ => (),
         }
     }
 
@@ -193,6 +264,7 @@ impl Screen for RosterScreen {
                 true,
                 self.p1_color > 0,
             );
+            gui::draw_socd(c, g, device, &mut preloads, self.p1_socd, true);
         }
 
         if self.p2_selected {
@@ -206,6 +278,7 @@ impl Screen for RosterScreen {
                 false,
                 self.p2_color > 0,
             );
+            gui::draw_socd(c, g, device, &mut preloads, self.p2_socd, false);
         }
     }
 }
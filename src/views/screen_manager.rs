@@ -8,9 +8,15 @@ use graphics::Context;
 use piston::Key;
 use piston_window::{G2d, PistonWindow};
 
-use crate::{error::pop_up::show_error_popup, preloader::preloader::Preloads};
+use crate::{
+    config::game_config::GameConfig, error::pop_up::show_error_popup, player::input::manager::Socd,
+    preloader::preloader::Preloads,
+};
 
-use super::{screen::Screen, FightScreen, LoadingScreen, MainScreen, RosterScreen};
+use super::{
+    screen::Screen, ControlsScreen, FightScreen, LoadingScreen, MainScreen, OptionsScreen,
+    RosterScreen,
+};
 
 /// Enumera los tipos de pantalla disponibles en el juego.
 #[derive(PartialEq)]
@@ -21,6 +27,10 @@ pub enum ScreenType {
     Roster,
     /// Pantalla de combate.
     Fight,
+    /// Pantalla de asignación de teclas.
+    Controls,
+    /// Pantalla de opciones del juego.
+    Options,
 }
 
 /// Enumera los tipos de eventos que pueden ocurrir en el juego.
@@ -31,6 +41,10 @@ pub enum Event {
     SetPalettes([usize; 2]),
     /// Establece los nombres de los personajes.
     SetCharacters([String; 2]),
+    /// Establece las políticas SOCD de los jugadores.
+    SetSocd([Socd; 2]),
+    /// Establece las rondas ganadas por los jugadores en la pelea en curso.
+    SetWins([u32; 2]),
     /// Cambia la pantalla actual a otro tipo de pantalla.
     ChangeScreen(ScreenType),
 }
@@ -47,6 +61,10 @@ pub struct ScreenManager {
     current_characters: [String; 2],
     /// Paletas actualmenteen uso por jugador 1 y 2
     current_palettes: [usize; 2],
+    /// Políticas SOCD de los jugadores 1 y 2
+    current_socd: [Socd; 2],
+    /// Rondas ganadas por jugador 1 y 2 en la pelea en curso
+    current_wins: [u32; 2],
     /// Ventana a la que cambiar
     switch_screen: ScreenType,
     /// Archivos precargados
@@ -60,12 +78,17 @@ impl ScreenManager {
     ///
     /// * `screen_type` - El tipo de pantalla inicial.
     /// * `window` - La ventana de Piston para el juego.
+    /// * `game_config` - La configuración del juego.
     ///
     /// # Retorna
     ///
     /// Una nueva instancia de `ScreenManager`.
-    pub fn new(screen_type: ScreenType, window: &mut PistonWindow) -> Self {
-        let preloads = match Preloads::new(window) {
+    pub fn new(
+        screen_type: ScreenType,
+        window: &mut PistonWindow,
+        game_config: GameConfig,
+    ) -> Self {
+        let preloads = match Preloads::new(window, game_config) {
             Ok(_preloads) => _preloads,
             Err(err) => {
                 show_error_popup(&err);
@@ -78,6 +101,8 @@ impl ScreenManager {
         let cloned_sender = tx.clone();
         let current_characters = [String::new(), String::new()];
         let current_palettes = [0; 2];
+        let current_socd = [Socd::default(); 2];
+        let current_wins = [0; 2];
 
         let screen = match screen_type {
             ScreenType::Main => {
@@ -86,11 +111,19 @@ impl ScreenManager {
             ScreenType::Roster => {
                 Box::new(RosterScreen::new(cloned_sender, cloned_preloads)) as Box<dyn Screen>
             }
+            ScreenType::Controls => {
+                Box::new(ControlsScreen::new(cloned_sender, cloned_preloads)) as Box<dyn Screen>
+            }
+            ScreenType::Options => {
+                Box::new(OptionsScreen::new(cloned_sender, cloned_preloads)) as Box<dyn Screen>
+            }
             ScreenType::Fight => Box::new(FightScreen::new(
                 window,
                 cloned_sender,
                 &current_characters,
                 current_palettes,
+                current_socd,
+                current_wins,
                 cloned_preloads,
             )) as Box<dyn Screen>,
         };
@@ -101,6 +134,8 @@ impl ScreenManager {
             event_receiver: rx,
             current_characters,
             current_palettes,
+            current_socd,
+            current_wins,
             switch_screen: ScreenType::Main,
             preloads,
         }
@@ -121,11 +156,21 @@ impl ScreenManager {
                 cloned_sender,
                 self.preloads.clone(),
             )) as Box<dyn Screen>),
+            ScreenType::Controls => Some(Box::new(ControlsScreen::new(
+                cloned_sender,
+                self.preloads.clone(),
+            )) as Box<dyn Screen>),
+            ScreenType::Options => Some(Box::new(OptionsScreen::new(
+                cloned_sender,
+                self.preloads.clone(),
+            )) as Box<dyn Screen>),
             ScreenType::Fight => Some(Box::new(FightScreen::new(
                 window,
                 cloned_sender,
                 &self.current_characters,
                 self.current_palettes,
+                self.current_socd,
+                self.current_wins,
                 self.preloads.clone(),
             )) as Box<dyn Screen>),
         };
@@ -143,6 +188,10 @@ impl ScreenManager {
         while let Ok(event) = self.event_receiver.try_recv() {
             match event {
                 Event::ChangeScreen(screen_type) => {
+                    // Las rondas ganadas solo se conservan entre rondas de la misma pelea
+                    if screen_type != ScreenType::Fight {
+                        self.current_wins = [0; 2];
+                    }
                     self.current_screen = Some(Box::new(LoadingScreen::new(
                         self.event_sender.clone(),
                         self.preloads.clone(),
@@ -152,6 +201,12 @@ impl ScreenManager {
                 Event::SetPalettes(palettes) => {
                     self.current_palettes = palettes;
                 }
+                Event::SetSocd(socd) => {
+                    self.current_socd = socd;
+                }
+                Event::SetWins(wins) => {
+                    self.current_wins = wins;
+                }
                 Event::ScreenReady() => {
                     self.switch_screen(window);
                 }